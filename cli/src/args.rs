@@ -0,0 +1,115 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+
+pub const USAGE: &str = "\
+Usage: dsv-cli [options] <command>
+
+Commands:
+  read <addr|symbol> <length>               Print memory as hex
+  write <addr|symbol> <hexbytes>            Write bytes to memory
+  watch <addr|symbol> <length> [--hz <rate>] [--count <n>]
+                                            Print memory repeatedly, one line per read
+  type <TypeName> <addr|symbol>             Print an object using types from the project
+
+Options:
+  --config <path>     Project config, as saved by dsv (default: config.toml if it exists)
+  --address <addr>    GDB stub address (default: from config, or 127.0.0.1:3333)
+  --game <name>       Only look up symbols in this game's config
+  --json              Print JSON instead of plain text";
+
+pub struct Args {
+    pub config: Option<PathBuf>,
+    pub address: Option<String>,
+    pub game: Option<String>,
+    pub json: bool,
+    pub command: Command,
+}
+
+pub enum Command {
+    Read { location: String, length: usize },
+    Write { location: String, data: Vec<u8> },
+    Watch { location: String, length: usize, hz: f32, count: Option<usize> },
+    Type { type_name: String, location: String },
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut config = None;
+        let mut address = None;
+        let mut game = None;
+        let mut json = false;
+        let mut hz = None;
+        let mut count = None;
+        let mut positional = Vec::new();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value =
+                |name: &str| args.next().with_context(|| format!("Missing value for {name}"));
+            match arg.as_str() {
+                "--config" => config = Some(PathBuf::from(value("--config")?)),
+                "--address" => address = Some(value("--address")?),
+                "--game" => game = Some(value("--game")?),
+                "--json" => json = true,
+                "--hz" => hz = Some(value("--hz")?.parse().context("Invalid --hz")?),
+                "--count" => count = Some(value("--count")?.parse().context("Invalid --count")?),
+                "-h" | "--help" => bail!("{USAGE}"),
+                _ if arg.starts_with("--") => bail!("Unknown option '{arg}'\n\n{USAGE}"),
+                _ => positional.push(arg),
+            }
+        }
+
+        let command = match positional.as_slice() {
+            [command, location, length] if command == "read" => {
+                Command::Read { location: location.clone(), length: parse_length(length)? }
+            }
+            [command, location, data] if command == "write" => {
+                Command::Write { location: location.clone(), data: parse_hex_bytes(data)? }
+            }
+            [command, location, length] if command == "watch" => Command::Watch {
+                location: location.clone(),
+                length: parse_length(length)?,
+                hz: hz.unwrap_or(10.0),
+                count,
+            },
+            [command, type_name, location] if command == "type" => {
+                Command::Type { type_name: type_name.clone(), location: location.clone() }
+            }
+            _ => bail!("{USAGE}"),
+        };
+        if hz.is_some_and(|hz: f32| hz <= 0.0) {
+            bail!("--hz must be positive");
+        }
+
+        Ok(Args { config, address, game, json, command })
+    }
+}
+
+/// Parses a decimal number or a hex number prefixed with `0x`.
+pub fn parse_number(text: &str) -> Option<u32> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+fn parse_length(text: &str) -> Result<usize> {
+    parse_number(text)
+        .map(|length| length as usize)
+        .with_context(|| format!("Invalid length '{text}'"))
+}
+
+fn parse_hex_bytes(text: &str) -> Result<Vec<u8>> {
+    let text = text.strip_prefix("0x").unwrap_or(text);
+    if !text.len().is_multiple_of(2) {
+        bail!("Hex bytes must have even length");
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&text[i..i + 2], 16)
+                .with_context(|| format!("Invalid hex bytes '{text}'"))
+        })
+        .collect()
+}
@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use toml::Table;
+
+use crate::args::parse_number;
+
+/// The parts of the dsv project config used by the CLI. The GUI owns the format, see its
+/// `config.rs`.
+#[derive(Deserialize)]
+pub struct Config {
+    pub gdb: GdbConfig,
+    pub types: TypesConfig,
+    #[serde(default)]
+    pub games: Table,
+}
+
+#[derive(Deserialize)]
+pub struct GdbConfig {
+    pub address: String,
+}
+
+#[derive(Deserialize)]
+pub struct TypesConfig {
+    pub project_root: String,
+    pub include_paths: Vec<String>,
+    pub ignore_paths: Vec<String>,
+    #[serde(default)]
+    pub short_enums: bool,
+}
+
+impl Config {
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let toml_string = std::fs::read_to_string(path).context("Failed to read config file")?;
+        let config: Config = toml::from_str(&toml_string).context("Failed to parse config")?;
+        Ok(config)
+    }
+
+    /// Looks up a symbol in the `symbols` table of each game (symbol name → address), or only in
+    /// the given game.
+    pub fn symbol(&self, game: Option<&str>, name: &str) -> Option<u32> {
+        self.games
+            .iter()
+            .filter(|(game_name, _)| game.is_none_or(|game| game == *game_name))
+            .filter_map(|(_, game_config)| game_config.get("symbols")?.get(name))
+            .find_map(|address| match address {
+                toml::Value::String(address) => parse_number(address),
+                toml::Value::Integer(address) => u32::try_from(*address).ok(),
+                _ => None,
+            })
+    }
+}
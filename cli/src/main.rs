@@ -0,0 +1,161 @@
+mod args;
+mod config;
+mod output;
+mod types;
+
+use std::{
+    borrow::Cow,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, bail};
+use dsv_core::{
+    gdb::client::GdbClient,
+    state::State,
+    typed::{TypeInstance, TypeInstanceOptions},
+};
+
+use crate::{
+    args::{Args, Command, parse_number},
+    config::Config,
+    output::{Value, hex, hex_dump, write_json_string},
+};
+
+const DEFAULT_CONFIG_PATH: &str = "config.toml";
+const DEFAULT_GDB_ADDRESS: &str = "127.0.0.1:3333";
+
+fn main() {
+    env_logger::builder()
+        .filter_level(log::LevelFilter::Warn)
+        .format_timestamp(None)
+        .format_target(true)
+        .init();
+
+    let result = Args::parse(std::env::args().skip(1)).and_then(|args| run(&args));
+    if let Err(err) = result {
+        eprintln!("{err:#}");
+        std::process::exit(1);
+    }
+}
+
+fn run(args: &Args) -> Result<()> {
+    let config = match &args.config {
+        Some(path) => Some(Config::load_from_file(path)?),
+        None if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+            Some(Config::load_from_file(DEFAULT_CONFIG_PATH)?)
+        }
+        None => None,
+    };
+    let resolve =
+        |location: &str| resolve_location(config.as_ref(), args.game.as_deref(), location);
+
+    let gdb_address = args
+        .address
+        .as_deref()
+        .or(config.as_ref().map(|config| config.gdb.address.as_str()))
+        .unwrap_or(DEFAULT_GDB_ADDRESS);
+    let mut client = GdbClient::new();
+    client
+        .connect(gdb_address)
+        .with_context(|| format!("Failed to connect to GDB stub at {gdb_address}"))?;
+
+    let result = match &args.command {
+        Command::Read { location, length } => {
+            let address = resolve(location)?;
+            let mut data = vec![0; *length];
+            client.read_slice(address, &mut data)?;
+            print_data(args.json, address, &data);
+            Ok(())
+        }
+        Command::Write { location, data } => {
+            let address = resolve(location)?;
+            client.write_slice(address, data)?;
+            Ok(())
+        }
+        Command::Watch { location, length, hz, count } => {
+            watch(&mut client, args.json, resolve(location)?, *length, *hz, *count)
+        }
+        Command::Type { type_name, location } => {
+            let address = resolve(location)?;
+            let Some(config) = &config else {
+                bail!("The type command needs a config with a project root");
+            };
+            let types = types::load_types(&config.types)?;
+            let Some(ty) = types.get(type_name) else {
+                bail!("Type '{type_name}' not found");
+            };
+            let mut data = vec![0; ty.size(&types)];
+            client.read_slice(address, &mut data)?;
+            let instance = TypeInstance::new(TypeInstanceOptions {
+                ty,
+                address,
+                bit_field_range: None,
+                data: Cow::Owned(data),
+            });
+            let value = types::format_value(&types, &instance);
+            if args.json {
+                println!("{}", value.to_json());
+            } else {
+                println!("{type_name} @ {address:#010x}:{}", value.to_text());
+            }
+            Ok(())
+        }
+    };
+    client.disconnect()?;
+    result
+}
+
+fn resolve_location(config: Option<&Config>, game: Option<&str>, location: &str) -> Result<u32> {
+    if let Some(address) = parse_number(location) {
+        return Ok(address);
+    }
+    let Some(config) = config else {
+        bail!("'{location}' is not an address, and no config was loaded to look up symbols");
+    };
+    config.symbol(game, location).with_context(|| format!("Symbol '{location}' not found"))
+}
+
+fn watch(
+    client: &mut GdbClient,
+    json: bool,
+    address: u32,
+    length: usize,
+    hz: f32,
+    count: Option<usize>,
+) -> Result<()> {
+    let interval = Duration::from_secs_f32(1.0 / hz);
+    let start = Instant::now();
+    let mut state = State::default();
+    let mut reads = 0;
+    while count.is_none_or(|count| reads < count) {
+        let next_read = Instant::now() + interval;
+        state.request(address, length);
+        state.update(client)?;
+        let data = state.get_data(address).unwrap_or_default();
+        let time = start.elapsed().as_secs_f64();
+        if json {
+            let mut line = format!("{{\"time\":{time:.3},\"address\":{address},\"data\":");
+            write_json_string(&mut line, &hex(data));
+            line.push('}');
+            println!("{line}");
+        } else {
+            println!("{time:.3} {address:08x}: {}", hex(data));
+        }
+        reads += 1;
+        std::thread::sleep(next_read.saturating_duration_since(Instant::now()));
+    }
+    Ok(())
+}
+
+fn print_data(json: bool, address: u32, data: &[u8]) {
+    if json {
+        let value = Value::Struct(vec![
+            ("address".into(), Value::Int(address as i64)),
+            ("data".into(), Value::Bytes(data.to_vec())),
+        ]);
+        println!("{}", value.to_json());
+    } else {
+        println!("{}", hex_dump(address, data));
+    }
+}
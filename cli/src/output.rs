@@ -0,0 +1,126 @@
+use std::fmt::Write;
+
+/// A formatted value, printed as plain text or JSON.
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Pointer(u32),
+    Enum {
+        name: Option<String>,
+        value: i64,
+    },
+    Bytes(Vec<u8>),
+    Array(Vec<Value>),
+    Struct(Vec<(String, Value)>),
+    /// A value which can't be formatted, with the reason
+    Unsupported(String),
+}
+
+impl Value {
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        self.write_text(&mut text, 0);
+        text
+    }
+
+    fn write_text(&self, out: &mut String, indent: usize) {
+        match self {
+            Value::Int(value) => write!(out, "{value}").unwrap(),
+            Value::Float(value) => write!(out, "{value}").unwrap(),
+            Value::Bool(value) => write!(out, "{value}").unwrap(),
+            Value::Pointer(address) => write!(out, "{address:#010x}").unwrap(),
+            Value::Enum { name: Some(name), value } => write!(out, "{name} ({value})").unwrap(),
+            Value::Enum { name: None, value } => write!(out, "{value}").unwrap(),
+            Value::Bytes(data) => out.push_str(&hex(data)),
+            Value::Array(elements) => {
+                for (i, element) in elements.iter().enumerate() {
+                    write!(out, "\n{:indent$}[{i}]: ", "", indent = indent + 2).unwrap();
+                    element.write_text(out, indent + 2);
+                }
+            }
+            Value::Struct(fields) => {
+                for (name, field) in fields {
+                    write!(out, "\n{:indent$}{name}: ", "", indent = indent + 2).unwrap();
+                    field.write_text(out, indent + 2);
+                }
+            }
+            Value::Unsupported(reason) => write!(out, "<{reason}>").unwrap(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(&mut json);
+        json
+    }
+
+    fn write_json(&self, out: &mut String) {
+        match self {
+            Value::Int(value) => write!(out, "{value}").unwrap(),
+            Value::Float(value) if value.is_finite() => write!(out, "{value}").unwrap(),
+            Value::Float(_) => out.push_str("null"),
+            Value::Bool(value) => write!(out, "{value}").unwrap(),
+            Value::Pointer(address) => write!(out, "{address}").unwrap(),
+            Value::Enum { name: Some(name), .. } => write_json_string(out, name),
+            Value::Enum { name: None, value } => write!(out, "{value}").unwrap(),
+            Value::Bytes(data) => write_json_string(out, &hex(data)),
+            Value::Array(elements) => {
+                out.push('[');
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    element.write_json(out);
+                }
+                out.push(']');
+            }
+            Value::Struct(fields) => {
+                out.push('{');
+                for (i, (name, field)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_json_string(out, name);
+                    out.push(':');
+                    field.write_json(out);
+                }
+                out.push('}');
+            }
+            Value::Unsupported(_) => out.push_str("null"),
+        }
+    }
+}
+
+pub fn hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub fn write_json_string(out: &mut String, text: &str) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Formats memory as lines of 16 bytes prefixed with their address.
+pub fn hex_dump(address: u32, data: &[u8]) -> String {
+    let mut text = String::new();
+    for (i, line) in data.chunks(16).enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        write!(text, "{:08x}:", address + i as u32 * 16).unwrap();
+        for byte in line {
+            write!(text, " {byte:02x}").unwrap();
+        }
+    }
+    text
+}
@@ -0,0 +1,203 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use dsv_core::{
+    headers::{create_crawler, find_header_files},
+    typed::{TypeInstance, layout::base_types},
+};
+use type_crawler::{TypeKind, Types};
+
+use crate::{config::TypesConfig, output::Value};
+
+/// Loads types from the project headers like the GUI's type loading task, but on this thread.
+pub fn load_types(config: &TypesConfig) -> Result<Types> {
+    let mut crawler = create_crawler(&config.include_paths, config.short_enums)?;
+    let ignore_paths: Vec<PathBuf> = config.ignore_paths.iter().map(PathBuf::from).collect();
+    let mut types = Types::new();
+    for header in find_header_files(Path::new(&config.project_root), &ignore_paths)? {
+        log::debug!("Parsing {}", header.display());
+        let new_types = crawler
+            .parse_file(&header)
+            .with_context(|| format!("Failed to parse {}", header.display()))?;
+        types.extend(new_types).map_err(|err| anyhow::anyhow!("Error extending types: {err}"))?;
+    }
+    Ok(types)
+}
+
+/// Formats an instance as a value, reading it the same way as the GUI. Base types are nested
+/// under their names. Pointers are printed as addresses and not followed.
+pub fn format_value(types: &Types, instance: &TypeInstance) -> Value {
+    if instance.is_truncated() {
+        return Value::Unsupported("out of bounds".into());
+    }
+    match instance.ty() {
+        TypeKind::USize { .. }
+        | TypeKind::U64
+        | TypeKind::U32
+        | TypeKind::U16
+        | TypeKind::U8
+        | TypeKind::SSize { .. }
+        | TypeKind::S64
+        | TypeKind::S32
+        | TypeKind::S16
+        | TypeKind::S8 => match instance.as_int::<i64>(types) {
+            Some(value) => Value::Int(value),
+            None => Value::Bytes(instance.data().to_vec()),
+        },
+        TypeKind::F32 => match instance.data()[..].try_into() {
+            Ok(bytes) => Value::Float(f32::from_le_bytes(bytes) as f64),
+            Err(_) => Value::Bytes(instance.data().to_vec()),
+        },
+        TypeKind::F64 => match instance.data()[..].try_into() {
+            Ok(bytes) => Value::Float(f64::from_le_bytes(bytes)),
+            Err(_) => Value::Bytes(instance.data().to_vec()),
+        },
+        TypeKind::Bool => Value::Bool(instance.data_i64() != 0),
+        TypeKind::Reference { .. }
+        | TypeKind::Pointer { .. }
+        | TypeKind::MemberPointer { .. }
+        | TypeKind::Function { .. } => Value::Pointer(instance.data_i64() as u32),
+        TypeKind::Array { element_type, size: Some(size) } => {
+            let stride = element_type.stride(types).max(1);
+            let elements = (0..*size)
+                .map(|i| {
+                    format_value(types, &instance.slice(types, element_type, i * stride, None))
+                })
+                .collect();
+            Value::Array(elements)
+        }
+        TypeKind::Struct(struct_decl) | TypeKind::Class(struct_decl) => {
+            let mut fields = Vec::new();
+            for base_type in base_types(struct_decl, types) {
+                let value = match base_type.ty {
+                    Some(ty) => {
+                        format_value(types, &instance.slice(types, ty, base_type.range.start, None))
+                    }
+                    None => Value::Unsupported(format!("base type '{}' not found", base_type.name)),
+                };
+                fields.push((base_type.name.to_string(), value));
+            }
+            for field in struct_decl.fields() {
+                let offset = field.offset_bytes();
+                let bit_field_range = field.bit_field_width().map(|width| {
+                    let start = (field.offset_bits() - offset * 8) as u8;
+                    start..start + width
+                });
+                let field_instance = instance.slice(types, field.kind(), offset, bit_field_range);
+                let name = field.name().unwrap_or("").to_string();
+                fields.push((name, format_value(types, &field_instance)));
+            }
+            Value::Struct(fields)
+        }
+        TypeKind::Union(union_decl) => Value::Struct(
+            union_decl
+                .fields()
+                .iter()
+                .map(|field| {
+                    let bit_field_range = field.bit_field_width().map(|width| 0..width);
+                    let field_instance = instance.slice(types, field.kind(), 0, bit_field_range);
+                    let name = field.name().unwrap_or("").to_string();
+                    (name, format_value(types, &field_instance))
+                })
+                .collect(),
+        ),
+        TypeKind::Enum(enum_decl) => {
+            let value = instance.as_int::<i64>(types).unwrap_or_default();
+            let name = enum_decl.get_by_value(value).map(|constant| constant.name().to_string());
+            Value::Enum { name, value }
+        }
+        TypeKind::Typedef(typedef) => {
+            format_value(types, &instance.borrowed().with_type(typedef.underlying_type()))
+        }
+        TypeKind::Named(name) => match types.get(name) {
+            Some(ty) => format_value(types, &instance.borrowed().with_type(ty)),
+            None => Value::Unsupported(format!("type '{name}' not found")),
+        },
+        TypeKind::LongDouble { .. }
+        | TypeKind::Char16
+        | TypeKind::Char32
+        | TypeKind::WChar { .. }
+        | TypeKind::Array { size: None, .. } => Value::Bytes(instance.data().to_vec()),
+        TypeKind::Void => Value::Unsupported("void".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, ops::Range};
+
+    use dsv_core::{test_support::parse_header, typed::TypeInstanceOptions};
+
+    use super::*;
+
+    fn format(types: &Types, ty: &TypeKind, data: &[u8], bits: Option<Range<u8>>) -> String {
+        let instance = TypeInstance::new(TypeInstanceOptions {
+            ty,
+            address: 0x02000000,
+            bit_field_range: bits,
+            data: Cow::Borrowed(data),
+        });
+        format_value(types, &instance).to_json()
+    }
+
+    #[test]
+    fn test_format_int() {
+        let types = Types::new();
+        assert_eq!(format(&types, &TypeKind::S16, &[0xfe, 0xff], None), "-2");
+        assert_eq!(format(&types, &TypeKind::U16, &[0xfe, 0xff], None), "65534");
+        // Signed bit-fields are sign-extended from their width
+        assert_eq!(format(&types, &TypeKind::S32, &[0b1110_0000], Some(3..8)), "-4");
+        assert_eq!(format(&types, &TypeKind::U32, &[0b1110_0000], Some(3..8)), "28");
+        assert_eq!(format(&types, &TypeKind::S32, &[0b0110_0000], Some(3..8)), "12");
+    }
+
+    #[test]
+    fn test_format_scalars() {
+        let types = Types::new();
+        assert_eq!(format(&types, &TypeKind::Bool, &[2], None), "true");
+        assert_eq!(format(&types, &TypeKind::F32, &1.5f32.to_le_bytes(), None), "1.5");
+        let pointer = TypeKind::Pointer { pointee_type: Box::new(TypeKind::U8), size: 4 };
+        assert_eq!(format(&types, &pointer, &[0x34, 0x12, 0x00, 0x02], None), "33559092");
+    }
+
+    #[test]
+    fn test_format_array() {
+        let types = Types::new();
+        let array = TypeKind::Array { element_type: Box::new(TypeKind::S16), size: Some(3) };
+        assert_eq!(format(&types, &array, &[1, 0, 0xff, 0xff, 3, 0], None), "[1,-1,3]");
+        assert_eq!(format(&types, &array, &[1, 0, 0xff, 0xff], None), "[1,-1,null]");
+    }
+
+    #[test]
+    fn test_format_struct() {
+        let types = parse_header(
+            "fixture.hpp",
+            "
+            struct Position {
+                int x;
+                int y;
+            };
+
+            struct Flags {
+                unsigned short flags;
+            };
+
+            class Player : public Position, public Flags {
+                signed char health;
+                int speed : 4;
+            };
+            ",
+        );
+        let player = types.get("Player").expect("fixture should define Player");
+        let mut data = Vec::new();
+        data.extend(1i32.to_le_bytes());
+        data.extend((-2i32).to_le_bytes());
+        data.extend(0xabcd_u16.to_le_bytes());
+        data.extend([0xff, 0]);
+        data.extend(0xfu32.to_le_bytes());
+        assert_eq!(
+            format(&types, player, &data, None),
+            r#"{"Position":{"x":1,"y":-2},"Flags":{"flags":43981},"health":-1,"speed":-1}"#
+        );
+    }
+}
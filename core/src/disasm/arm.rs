@@ -0,0 +1,313 @@
+use super::{Decoded, condition, imm, number, reg, reg_list};
+
+const DATA_PROCESSING: [&str; 16] = [
+    "and", "eor", "sub", "rsb", "add", "adc", "sbc", "rsc", "tst", "teq", "cmp", "cmn", "orr",
+    "mov", "bic", "mvn",
+];
+
+fn bit(raw: u32, index: u32) -> bool {
+    raw & (1 << index) != 0
+}
+
+pub(super) fn decode(address: u32, raw: u32) -> Decoded {
+    let cond = raw >> 28;
+    if cond == 0xf {
+        return decode_unconditional(address, raw);
+    }
+    let c = condition(cond);
+    match (raw >> 25) & 7 {
+        0b000 => decode_register_operand(address, raw, c),
+        0b001 => decode_immediate_operand(raw, c),
+        0b010 | 0b011 => decode_load_store(address, raw, c),
+        0b100 => decode_block_transfer(raw, c),
+        0b101 => {
+            // Signed 24-bit word offset from the instruction after next
+            let offset = ((raw << 8) as i32 >> 6) as u32;
+            let target = address.wrapping_add(8).wrapping_add(offset);
+            let mnemonic = if bit(raw, 24) { "bl" } else { "b" };
+            Decoded::branch(target, format!("{mnemonic}{c}"))
+        }
+        0b110 => decode_coprocessor_transfer(address, raw, c),
+        _ => decode_coprocessor(raw, c),
+    }
+}
+
+fn decode_unconditional(address: u32, raw: u32) -> Decoded {
+    if (raw >> 25) & 7 == 0b101 {
+        let offset = ((raw << 8) as i32 >> 6) as u32;
+        let halfword = (raw >> 23) & 2;
+        let target = address.wrapping_add(8).wrapping_add(offset) | halfword;
+        return Decoded::branch(target, "blx");
+    }
+    if raw & 0x0d70f000 == 0x0550f000 {
+        let offset = Offset::Immediate(raw & 0xfff);
+        let (operand, _) = address_operand(address, raw, offset);
+        return Decoded::new("pld", operand);
+    }
+    Decoded::unknown(raw, 4)
+}
+
+fn decode_register_operand(address: u32, raw: u32, c: &str) -> Decoded {
+    if raw & 0x0fc000f0 == 0x00000090 {
+        return decode_multiply(raw, c);
+    }
+    if raw & 0x0f8000f0 == 0x00800090 {
+        let names = ["umull", "umlal", "smull", "smlal"];
+        let s = if bit(raw, 20) { "s" } else { "" };
+        let operands =
+            format!("{}, {}, {}, {}", reg(raw >> 12), reg(raw >> 16), reg(raw), reg(raw >> 8));
+        return Decoded::new(format!("{}{s}{c}", names[((raw >> 21) & 3) as usize]), operands);
+    }
+    if raw & 0x0fb00ff0 == 0x01000090 {
+        let b = if bit(raw, 22) { "b" } else { "" };
+        let operands = format!("{}, {}, [{}]", reg(raw >> 12), reg(raw), reg(raw >> 16));
+        return Decoded::new(format!("swp{b}{c}"), operands);
+    }
+    if raw & 0x90 == 0x90 && raw & 0x60 != 0 {
+        return decode_halfword_transfer(address, raw, c);
+    }
+    // Comparisons without the S bit are used for other instructions
+    if raw & 0x01900000 == 0x01000000 {
+        return decode_misc(raw, c);
+    }
+    decode_data_processing(raw, c, shifted_register(raw))
+}
+
+fn decode_immediate_operand(raw: u32, c: &str) -> Decoded {
+    if raw & 0x01900000 == 0x01000000 {
+        if raw & 0x0fb0f000 == 0x0320f000 {
+            let value = (raw & 0xff).rotate_right(((raw >> 8) & 0xf) * 2);
+            return Decoded::new(format!("msr{c}"), format!("{}, {}", psr_fields(raw), imm(value)));
+        }
+        return Decoded::unknown(raw, 4);
+    }
+    let value = (raw & 0xff).rotate_right(((raw >> 8) & 0xf) * 2);
+    decode_data_processing(raw, c, imm(value))
+}
+
+fn decode_data_processing(raw: u32, c: &str, operand2: String) -> Decoded {
+    let opcode = (raw >> 21) & 0xf;
+    let mnemonic = DATA_PROCESSING[opcode as usize];
+    let s = if bit(raw, 20) { "s" } else { "" };
+    let rd = reg(raw >> 12);
+    let rn = reg(raw >> 16);
+    match opcode {
+        // Comparisons always set the flags
+        8..=11 => Decoded::new(format!("{mnemonic}{c}"), format!("{rn}, {operand2}")),
+        13 | 15 => Decoded::new(format!("{mnemonic}{s}{c}"), format!("{rd}, {operand2}")),
+        _ => Decoded::new(format!("{mnemonic}{s}{c}"), format!("{rd}, {rn}, {operand2}")),
+    }
+}
+
+/// Formats a register operand with its shift, e.g. `r1, lsl #2`.
+fn shifted_register(raw: u32) -> String {
+    let rm = reg(raw);
+    let kind = (raw >> 5) & 3;
+    let name = ["lsl", "lsr", "asr", "ror"][kind as usize];
+    if bit(raw, 4) {
+        return format!("{rm}, {name} {}", reg(raw >> 8));
+    }
+    match (kind, (raw >> 7) & 0x1f) {
+        (0, 0) => rm.to_string(),
+        (3, 0) => format!("{rm}, rrx"),
+        (_, 0) => format!("{rm}, {name} #32"),
+        (_, amount) => format!("{rm}, {name} #{amount}"),
+    }
+}
+
+fn decode_multiply(raw: u32, c: &str) -> Decoded {
+    let s = if bit(raw, 20) { "s" } else { "" };
+    let (rd, rn, rs, rm) = (reg(raw >> 16), reg(raw >> 12), reg(raw >> 8), reg(raw));
+    if bit(raw, 21) {
+        Decoded::new(format!("mla{s}{c}"), format!("{rd}, {rm}, {rs}, {rn}"))
+    } else {
+        Decoded::new(format!("mul{s}{c}"), format!("{rd}, {rm}, {rs}"))
+    }
+}
+
+fn decode_misc(raw: u32, c: &str) -> Decoded {
+    if raw & 0x0fbf0fff == 0x010f0000 {
+        let psr = if bit(raw, 22) { "spsr" } else { "cpsr" };
+        return Decoded::new(format!("mrs{c}"), format!("{}, {psr}", reg(raw >> 12)));
+    }
+    if raw & 0x0fb0fff0 == 0x0120f000 {
+        return Decoded::new(format!("msr{c}"), format!("{}, {}", psr_fields(raw), reg(raw)));
+    }
+    if raw & 0x0ffffff0 == 0x012fff10 {
+        return Decoded::new(format!("bx{c}"), reg(raw));
+    }
+    if raw & 0x0ffffff0 == 0x012fff30 {
+        return Decoded::new(format!("blx{c}"), reg(raw));
+    }
+    if raw & 0x0fff0ff0 == 0x016f0f10 {
+        return Decoded::new(format!("clz{c}"), format!("{}, {}", reg(raw >> 12), reg(raw)));
+    }
+    if raw & 0x0f900ff0 == 0x01000050 {
+        let names = ["qadd", "qsub", "qdadd", "qdsub"];
+        let operands = format!("{}, {}, {}", reg(raw >> 12), reg(raw), reg(raw >> 16));
+        return Decoded::new(format!("{}{c}", names[((raw >> 21) & 3) as usize]), operands);
+    }
+    if raw & 0x0ff000f0 == 0x01200070 {
+        let value = (raw >> 4) & 0xfff0 | raw & 0xf;
+        return Decoded::new("bkpt", format!("{value:#x}"));
+    }
+    if raw & 0x0f900090 == 0x01000080 {
+        return decode_signed_multiply(raw, c);
+    }
+    Decoded::unknown(raw, 4)
+}
+
+/// Decodes the 16-bit multiplies of ARMv5TE, like `smulbb`.
+fn decode_signed_multiply(raw: u32, c: &str) -> Decoded {
+    let x = if bit(raw, 5) { "t" } else { "b" };
+    let y = if bit(raw, 6) { "t" } else { "b" };
+    let (rd, rn, rs, rm) = (reg(raw >> 16), reg(raw >> 12), reg(raw >> 8), reg(raw));
+    match (raw >> 21) & 3 {
+        0 => Decoded::new(format!("smla{x}{y}{c}"), format!("{rd}, {rm}, {rs}, {rn}")),
+        1 if bit(raw, 5) => Decoded::new(format!("smulw{y}{c}"), format!("{rd}, {rm}, {rs}")),
+        1 => Decoded::new(format!("smlaw{y}{c}"), format!("{rd}, {rm}, {rs}, {rn}")),
+        2 => Decoded::new(format!("smlal{x}{y}{c}"), format!("{rn}, {rd}, {rm}, {rs}")),
+        _ => Decoded::new(format!("smul{x}{y}{c}"), format!("{rd}, {rm}, {rs}")),
+    }
+}
+
+/// Formats the status register and field mask written by `msr`, e.g. `cpsr_fc`.
+fn psr_fields(raw: u32) -> String {
+    let psr = if bit(raw, 22) { "spsr" } else { "cpsr" };
+    let fields: String = [(19, 'f'), (18, 's'), (17, 'x'), (16, 'c')]
+        .into_iter()
+        .filter(|&(index, _)| bit(raw, index))
+        .map(|(_, field)| field)
+        .collect();
+    format!("{psr}_{fields}")
+}
+
+enum Offset {
+    Immediate(u32),
+    Register(String),
+}
+
+/// Formats the address of a load or store from its P, U and W bits. Returns the address which is
+/// read if it's relative to the PC without writeback.
+fn address_operand(address: u32, raw: u32, offset: Offset) -> (String, Option<u32>) {
+    let rn_index = (raw >> 16) & 0xf;
+    let rn = reg(rn_index);
+    let pre = bit(raw, 24);
+    let up = bit(raw, 23);
+    let writeback = if pre && bit(raw, 21) { "!" } else { "" };
+    let sign = if up { "" } else { "-" };
+    let offset_text = match &offset {
+        Offset::Immediate(0) => None,
+        Offset::Immediate(value) => Some(format!("#{sign}{}", number(*value))),
+        Offset::Register(register) => Some(format!("{sign}{register}")),
+    };
+    let text = match (pre, offset_text) {
+        (true, None) => format!("[{rn}]{writeback}"),
+        (true, Some(offset)) => format!("[{rn}, {offset}]{writeback}"),
+        (false, None) => format!("[{rn}]"),
+        (false, Some(offset)) => format!("[{rn}], {offset}"),
+    };
+    let literal = match offset {
+        Offset::Immediate(value) if rn_index == 15 && pre && writeback.is_empty() => {
+            let pc = address.wrapping_add(8);
+            Some(if up { pc.wrapping_add(value) } else { pc.wrapping_sub(value) })
+        }
+        _ => None,
+    };
+    (text, literal)
+}
+
+fn decode_load_store(address: u32, raw: u32, c: &str) -> Decoded {
+    let register_offset = bit(raw, 25);
+    if register_offset && bit(raw, 4) {
+        return Decoded::unknown(raw, 4);
+    }
+    let base = if bit(raw, 20) { "ldr" } else { "str" };
+    let b = if bit(raw, 22) { "b" } else { "" };
+    // Post-indexed with writeback accesses memory as user mode
+    let t = if !bit(raw, 24) && bit(raw, 21) { "t" } else { "" };
+    let offset = if register_offset {
+        Offset::Register(shifted_register(raw))
+    } else {
+        Offset::Immediate(raw & 0xfff)
+    };
+    let (operand, literal) = address_operand(address, raw, offset);
+    Decoded::new(format!("{base}{b}{t}{c}"), format!("{}, {operand}", reg(raw >> 12)))
+        .literal(literal)
+}
+
+fn decode_halfword_transfer(address: u32, raw: u32, c: &str) -> Decoded {
+    let name = match (bit(raw, 20), (raw >> 5) & 3) {
+        (true, 1) => "ldrh",
+        (true, 2) => "ldrsb",
+        (true, _) => "ldrsh",
+        (false, 1) => "strh",
+        (false, 2) => "ldrd",
+        (false, _) => "strd",
+    };
+    let offset = if bit(raw, 22) {
+        Offset::Immediate((raw >> 4) & 0xf0 | raw & 0xf)
+    } else {
+        Offset::Register(reg(raw).into())
+    };
+    let (operand, literal) = address_operand(address, raw, offset);
+    Decoded::new(format!("{name}{c}"), format!("{}, {operand}", reg(raw >> 12))).literal(literal)
+}
+
+fn decode_block_transfer(raw: u32, c: &str) -> Decoded {
+    let load = bit(raw, 20);
+    let pre = bit(raw, 24);
+    let up = bit(raw, 23);
+    let user = if bit(raw, 22) { "^" } else { "" };
+    let writeback = bit(raw, 21);
+    let list = reg_list(raw & 0xffff);
+    let rn_index = (raw >> 16) & 0xf;
+    if rn_index == 13 && writeback && user.is_empty() {
+        match (load, pre, up) {
+            (true, false, true) => return Decoded::new(format!("pop{c}"), list),
+            (false, true, false) => return Decoded::new(format!("push{c}"), list),
+            _ => {}
+        }
+    }
+    let mode = match (pre, up) {
+        (false, false) => "da",
+        (false, true) => "ia",
+        (true, false) => "db",
+        (true, true) => "ib",
+    };
+    let base = if load { "ldm" } else { "stm" };
+    let writeback = if writeback { "!" } else { "" };
+    Decoded::new(format!("{base}{mode}{c}"), format!("{}{writeback}, {list}{user}", reg(raw >> 16)))
+}
+
+fn decode_coprocessor_transfer(address: u32, raw: u32, c: &str) -> Decoded {
+    let base = if bit(raw, 20) { "ldc" } else { "stc" };
+    let long = if bit(raw, 22) { "l" } else { "" };
+    let (operand, _) = address_operand(address, raw, Offset::Immediate((raw & 0xff) * 4));
+    let operands = format!("p{}, c{}, {operand}", (raw >> 8) & 0xf, (raw >> 12) & 0xf);
+    Decoded::new(format!("{base}{long}{c}"), operands)
+}
+
+fn decode_coprocessor(raw: u32, c: &str) -> Decoded {
+    if bit(raw, 24) {
+        return Decoded::new(format!("swi{c}"), format!("{:#x}", raw & 0xffffff));
+    }
+    let coprocessor = (raw >> 8) & 0xf;
+    let (crn, crm, opcode2) = ((raw >> 16) & 0xf, raw & 0xf, (raw >> 5) & 7);
+    if bit(raw, 4) {
+        let base = if bit(raw, 20) { "mrc" } else { "mcr" };
+        let operands = format!(
+            "p{coprocessor}, {}, {}, c{crn}, c{crm}, {opcode2}",
+            (raw >> 21) & 7,
+            reg(raw >> 12),
+        );
+        Decoded::new(format!("{base}{c}"), operands)
+    } else {
+        let operands = format!(
+            "p{coprocessor}, {}, c{}, c{crn}, c{crm}, {opcode2}",
+            (raw >> 20) & 0xf,
+            (raw >> 12) & 0xf,
+        );
+        Decoded::new(format!("cdp{c}"), operands)
+    }
+}
@@ -0,0 +1,180 @@
+//! Disassembler for the ARMv5TE instruction set of the ARM9, in ARM and Thumb state. Only meant
+//! for peeking at code, so coprocessor and DSP instructions are decoded loosely.
+
+mod arm;
+mod thumb;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    Arm,
+    Thumb,
+}
+
+impl Mode {
+    /// Returns the state which a function pointer or branch target runs in, where bit 0 is set for
+    /// Thumb code.
+    pub fn of_code_pointer(pointer: u32) -> Self {
+        if pointer & 1 != 0 { Mode::Thumb } else { Mode::Arm }
+    }
+
+    pub fn instruction_size(self) -> usize {
+        match self {
+            Mode::Arm => 4,
+            Mode::Thumb => 2,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Instruction {
+    pub address: u32,
+    /// Encoding of the instruction. A Thumb `bl` pair has its first halfword in the upper bits.
+    pub raw: u32,
+    /// Size in bytes
+    pub size: u32,
+    pub mnemonic: String,
+    pub operands: String,
+    /// Destination of an immediate branch
+    pub branch_target: Option<u32>,
+    /// Address loaded from by a PC-relative load, usually in a literal pool
+    pub literal_address: Option<u32>,
+}
+
+/// Decodes the instructions in `data`, which was read from `address`. Trailing bytes which don't
+/// make up a whole instruction are ignored, and invalid encodings become `.word` or `.hword`.
+pub fn disassemble(data: &[u8], address: u32, mode: Mode) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut offset = 0;
+    match mode {
+        Mode::Arm => {
+            while let Some(bytes) = data.get(offset..offset + 4) {
+                let raw = u32::from_le_bytes(bytes.try_into().unwrap());
+                let address = address.wrapping_add(offset as u32);
+                instructions.push(arm::decode(address, raw).into_instruction(address, raw, 4));
+                offset += 4;
+            }
+        }
+        Mode::Thumb => {
+            while let Some(bytes) = data.get(offset..offset + 2) {
+                let raw = u16::from_le_bytes(bytes.try_into().unwrap());
+                let address = address.wrapping_add(offset as u32);
+                let next = data
+                    .get(offset + 2..offset + 4)
+                    .map(|bytes| u16::from_le_bytes(bytes.try_into().unwrap()));
+                if let Some(low) = next
+                    && thumb::is_long_branch_pair(raw, low)
+                {
+                    let decoded = thumb::decode_long_branch(address, raw, low);
+                    let raw = (raw as u32) << 16 | low as u32;
+                    instructions.push(decoded.into_instruction(address, raw, 4));
+                    offset += 4;
+                } else {
+                    let decoded = thumb::decode(address, raw);
+                    instructions.push(decoded.into_instruction(address, raw as u32, 2));
+                    offset += 2;
+                }
+            }
+        }
+    }
+    instructions
+}
+
+/// Mnemonic and operands of an instruction, before its address and encoding are known.
+#[derive(Default)]
+struct Decoded {
+    mnemonic: String,
+    operands: String,
+    branch_target: Option<u32>,
+    literal_address: Option<u32>,
+}
+
+impl Decoded {
+    fn new(mnemonic: impl Into<String>, operands: impl Into<String>) -> Self {
+        Self {
+            mnemonic: mnemonic.into(),
+            operands: operands.into(),
+            ..Default::default()
+        }
+    }
+
+    fn unknown(raw: u32, size: u32) -> Self {
+        match size {
+            2 => Self::new(".hword", format!("{raw:#06x}")),
+            _ => Self::new(".word", format!("{raw:#010x}")),
+        }
+    }
+
+    fn branch(target: u32, mnemonic: impl Into<String>) -> Self {
+        Self {
+            branch_target: Some(target),
+            ..Self::new(mnemonic, format!("{target:#010x}"))
+        }
+    }
+
+    fn literal(mut self, address: Option<u32>) -> Self {
+        self.literal_address = address;
+        self
+    }
+
+    fn into_instruction(self, address: u32, raw: u32, size: u32) -> Instruction {
+        Instruction {
+            address,
+            raw,
+            size,
+            mnemonic: self.mnemonic,
+            operands: self.operands,
+            branch_target: self.branch_target,
+            literal_address: self.literal_address,
+        }
+    }
+}
+
+const REGISTERS: [&str; 16] = [
+    "r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7", "r8", "r9", "r10", "r11", "r12", "sp", "lr",
+    "pc",
+];
+
+fn reg(index: u32) -> &'static str {
+    REGISTERS[(index & 0xf) as usize]
+}
+
+/// Returns the suffix of condition code `cond`, which is empty for "always".
+fn condition(cond: u32) -> &'static str {
+    const CONDITIONS: [&str; 16] = [
+        "eq", "ne", "cs", "cc", "mi", "pl", "vs", "vc", "hi", "ls", "ge", "lt", "gt", "le", "",
+        "nv",
+    ];
+    CONDITIONS[(cond & 0xf) as usize]
+}
+
+/// Formats a number like disassemblers do, in decimal below 10 and in hex otherwise.
+fn number(value: u32) -> String {
+    if value < 10 { value.to_string() } else { format!("{value:#x}") }
+}
+
+fn imm(value: u32) -> String {
+    format!("#{}", number(value))
+}
+
+/// Formats a register list, with runs of three or more registers as ranges, e.g. `{r4-r7, lr}`.
+fn reg_list(mask: u32) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let mut index = 0;
+    while index < 16 {
+        if mask & (1 << index) == 0 {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        while index + 1 < 16 && mask & (1 << (index + 1)) != 0 {
+            index += 1;
+        }
+        match index - start {
+            0 => parts.push(reg(start).into()),
+            1 => parts.extend([reg(start).into(), reg(index).into()]),
+            _ => parts.push(format!("{}-{}", reg(start), reg(index))),
+        }
+        index += 1;
+    }
+    format!("{{{}}}", parts.join(", "))
+}
@@ -0,0 +1,162 @@
+use super::{Decoded, condition, imm, reg, reg_list};
+
+const ALU: [&str; 16] = [
+    "ands", "eors", "lsls", "lsrs", "asrs", "adcs", "sbcs", "rors", "tst", "negs", "cmp", "cmn",
+    "orrs", "muls", "bics", "mvns",
+];
+
+const REGISTER_OFFSET: [&str; 8] = ["str", "strh", "strb", "ldrsb", "ldr", "ldrh", "ldrb", "ldrsh"];
+
+fn bit(raw: u32, index: u32) -> bool {
+    raw & (1 << index) != 0
+}
+
+/// Returns true if `high` and `low` are the two halves of a `bl` or `blx` to an immediate.
+pub(super) fn is_long_branch_pair(high: u16, low: u16) -> bool {
+    high & 0xf800 == 0xf000 && matches!(low & 0xf800, 0xf800 | 0xe800)
+}
+
+pub(super) fn decode_long_branch(address: u32, high: u16, low: u16) -> Decoded {
+    let offset_high = (((high as u32) << 21) as i32 >> 9) as u32;
+    let offset = offset_high.wrapping_add((low as u32 & 0x7ff) << 1);
+    let target = address.wrapping_add(4).wrapping_add(offset);
+    if low & 0xf800 == 0xe800 {
+        Decoded::branch(target & !3, "blx")
+    } else {
+        Decoded::branch(target, "bl")
+    }
+}
+
+pub(super) fn decode(address: u32, raw: u16) -> Decoded {
+    let raw = raw as u32;
+    let low = |shift: u32| reg((raw >> shift) & 7);
+    match raw >> 13 {
+        0b000 => {
+            let op = (raw >> 11) & 3;
+            if op == 3 {
+                let mnemonic = if bit(raw, 9) { "subs" } else { "adds" };
+                let field = (raw >> 6) & 7;
+                let operand = if bit(raw, 10) { imm(field) } else { reg(field).into() };
+                return Decoded::new(mnemonic, format!("{}, {}, {operand}", low(0), low(3)));
+            }
+            let amount = (raw >> 6) & 0x1f;
+            if op == 0 && amount == 0 {
+                return Decoded::new("movs", format!("{}, {}", low(0), low(3)));
+            }
+            let amount = if amount == 0 { 32 } else { amount };
+            let mnemonic = ["lsls", "lsrs", "asrs"][op as usize];
+            Decoded::new(mnemonic, format!("{}, {}, #{amount}", low(0), low(3)))
+        }
+        0b001 => {
+            let mnemonic = ["movs", "cmp", "adds", "subs"][((raw >> 11) & 3) as usize];
+            Decoded::new(mnemonic, format!("{}, {}", low(8), imm(raw & 0xff)))
+        }
+        0b010 => {
+            if raw & 0xfc00 == 0x4000 {
+                let mnemonic = ALU[((raw >> 6) & 0xf) as usize];
+                Decoded::new(mnemonic, format!("{}, {}", low(0), low(3)))
+            } else if raw & 0xfc00 == 0x4400 {
+                decode_high_register(raw)
+            } else if raw & 0xf800 == 0x4800 {
+                let offset = (raw & 0xff) * 4;
+                let literal = (address.wrapping_add(4) & !3).wrapping_add(offset);
+                let operands = format!("{}, [pc, {}]", low(8), imm(offset));
+                Decoded::new("ldr", operands).literal(Some(literal))
+            } else {
+                let mnemonic = REGISTER_OFFSET[((raw >> 9) & 7) as usize];
+                Decoded::new(mnemonic, format!("{}, [{}, {}]", low(0), low(3), low(6)))
+            }
+        }
+        0b011 => {
+            let byte = bit(raw, 12);
+            let mnemonic = match (bit(raw, 11), byte) {
+                (false, false) => "str",
+                (false, true) => "strb",
+                (true, false) => "ldr",
+                (true, true) => "ldrb",
+            };
+            let offset = ((raw >> 6) & 0x1f) * if byte { 1 } else { 4 };
+            Decoded::new(mnemonic, format!("{}, [{}, {}]", low(0), low(3), imm(offset)))
+        }
+        0b100 => {
+            let load = bit(raw, 11);
+            if bit(raw, 12) {
+                let mnemonic = if load { "ldr" } else { "str" };
+                Decoded::new(mnemonic, format!("{}, [sp, {}]", low(8), imm((raw & 0xff) * 4)))
+            } else {
+                let mnemonic = if load { "ldrh" } else { "strh" };
+                let offset = ((raw >> 6) & 0x1f) * 2;
+                Decoded::new(mnemonic, format!("{}, [{}, {}]", low(0), low(3), imm(offset)))
+            }
+        }
+        0b101 => decode_misc(raw),
+        0b110 => {
+            if !bit(raw, 12) {
+                let load = bit(raw, 11);
+                let rb = (raw >> 8) & 7;
+                // Loading the base register replaces the written back address
+                let writeback = if load && bit(raw, rb) { "" } else { "!" };
+                let mnemonic = if load { "ldmia" } else { "stmia" };
+                return Decoded::new(
+                    mnemonic,
+                    format!("{}{writeback}, {}", reg(rb), reg_list(raw & 0xff)),
+                );
+            }
+            match (raw >> 8) & 0xf {
+                0xe => Decoded::unknown(raw, 2),
+                0xf => Decoded::new("swi", format!("{:#x}", raw & 0xff)),
+                cond => {
+                    let offset = ((raw << 24) as i32 >> 23) as u32;
+                    let target = address.wrapping_add(4).wrapping_add(offset);
+                    Decoded::branch(target, format!("b{}", condition(cond)))
+                }
+            }
+        }
+        _ => {
+            if (raw >> 11) & 3 == 0 {
+                let offset = ((raw << 21) as i32 >> 20) as u32;
+                Decoded::branch(address.wrapping_add(4).wrapping_add(offset), "b")
+            } else {
+                // Half of a long branch without its other half
+                Decoded::unknown(raw, 2)
+            }
+        }
+    }
+}
+
+/// Decodes the instructions which use or move to the registers r8 to pc.
+fn decode_high_register(raw: u32) -> Decoded {
+    let rd = reg((raw >> 4) & 8 | raw & 7);
+    let rs = reg((raw >> 3) & 0xf);
+    match (raw >> 8) & 3 {
+        0 => Decoded::new("add", format!("{rd}, {rs}")),
+        1 => Decoded::new("cmp", format!("{rd}, {rs}")),
+        2 => Decoded::new("mov", format!("{rd}, {rs}")),
+        _ if bit(raw, 7) => Decoded::new("blx", rs),
+        _ => Decoded::new("bx", rs),
+    }
+}
+
+fn decode_misc(raw: u32) -> Decoded {
+    let low = |shift: u32| reg((raw >> shift) & 7);
+    if !bit(raw, 12) {
+        let base = if bit(raw, 11) { "sp" } else { "pc" };
+        return Decoded::new("add", format!("{}, {base}, {}", low(8), imm((raw & 0xff) * 4)));
+    }
+    match (raw >> 8) & 0xf {
+        0b0000 => {
+            let mnemonic = if bit(raw, 7) { "sub" } else { "add" };
+            Decoded::new(mnemonic, format!("sp, {}", imm((raw & 0x7f) * 4)))
+        }
+        0b0100 | 0b0101 => {
+            let lr = if bit(raw, 8) { 1 << 14 } else { 0 };
+            Decoded::new("push", reg_list(raw & 0xff | lr))
+        }
+        0b1100 | 0b1101 => {
+            let pc = if bit(raw, 8) { 1 << 15 } else { 0 };
+            Decoded::new("pop", reg_list(raw & 0xff | pc))
+        }
+        0b1110 => Decoded::new("bkpt", format!("{:#x}", raw & 0xff)),
+        _ => Decoded::unknown(raw, 2),
+    }
+}
@@ -1,21 +1,141 @@
 use std::net::ToSocketAddrs;
 
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, bail};
 
-use crate::{gdb::stream::GdbStream, hex_char_to_byte};
+use crate::{
+    gdb::{
+        crc::crc32,
+        quirks::{StubInfo, StubKind, StubQuirks, Support, Unsupported},
+        stats::ConnectionStats,
+        stream::GdbStream,
+    },
+    hex,
+    memory::read_cstring_chunked,
+};
 
 #[derive(Default)]
 pub struct GdbClient {
     stream: GdbStream,
+    quirks: StubQuirks,
+    stub_kind: StubKind,
+    /// Reply to the `version` monitor command when connecting
+    version: Option<String>,
 }
 
 impl GdbClient {
+    /// Packet size assumed for writes if the stub didn't report one, about the size of the reply to
+    /// a read of [`Self::FALLBACK_TRANSFER_LENGTH`] bytes. Writes are limited by the whole packet
+    /// since it also contains the header and the data may be hex encoded.
+    const FALLBACK_PACKET_SIZE: usize = 0x800;
+    /// Bytes transferred per packet if the stub didn't report a packet size, small enough for the
+    /// least capable stubs
+    const FALLBACK_TRANSFER_LENGTH: usize = 0x3ff;
+    /// Mirror of the cartridge header's gamecode field in main RAM
+    const GAMECODE_ADDRESS: u32 = 0x023ffe0c;
+
     pub fn new() -> Self {
-        GdbClient { stream: GdbStream::new() }
+        GdbClient {
+            stream: GdbStream::new(),
+            quirks: StubQuirks::default(),
+            stub_kind: StubKind::Unknown,
+            version: None,
+        }
     }
 
     pub fn connect<A: ToSocketAddrs>(&mut self, address: A) -> Result<()> {
-        self.stream.connect(address)
+        self.stream.connect(address)?;
+        self.stub_kind = self.detect_stub_kind();
+        log::info!("Connected to {}", self.stub_kind);
+        self.quirks = self.stub_kind.initial_quirks();
+        self.quirks.binary_read = if self.stream.supports("binary-upload") {
+            Support::Supported
+        } else {
+            Support::Unsupported
+        };
+        self.start_no_ack_mode()?;
+        // Compare the requests before and after switching, instead of sending extra ones
+        let acked_latency = self.stream.last_round_trip();
+        self.version = self.probe_version();
+        if self.quirks.no_ack == Support::Supported
+            && let (Some(before), Some(after)) = (acked_latency, self.stream.last_round_trip())
+        {
+            log::info!("Enabled no-ACK mode, request latency went from {before:?} to {after:?}");
+        }
+        Ok(())
+    }
+
+    /// Asks the stub for its version. Stubs differ in which monitor commands they have, so a
+    /// failed query only leaves the version unknown, and doesn't mark `qRcmd` as unsupported.
+    fn probe_version(&mut self) -> Option<String> {
+        let rcmd = self.quirks.rcmd;
+        let version = self.monitor("version");
+        if version.is_err() {
+            self.quirks.rcmd = rcmd;
+        }
+        match version {
+            Ok(version) if !version.trim().is_empty() => {
+                log::info!("GDB server version: {}", version.trim());
+                Some(version.trim().to_string())
+            }
+            Ok(_) => None,
+            Err(e) => {
+                log::debug!("GDB server did not report a version: {e}");
+                None
+            }
+        }
+    }
+
+    fn detect_stub_kind(&self) -> StubKind {
+        if self.stream.supported_reply_is_empty() {
+            StubKind::DeSmuMe
+        } else if self.stream.supports("qXfer:features:read") && self.stream.supports("hwbreak") {
+            StubKind::MelonDs
+        } else {
+            StubKind::Unknown
+        }
+    }
+
+    /// Asks the stub to stop exchanging ACKs, which saves a round trip per packet.
+    fn start_no_ack_mode(&mut self) -> Result<()> {
+        if !self.stream.supports("QStartNoAckMode") {
+            self.quirks.no_ack = Support::Unsupported;
+            return Ok(());
+        }
+        self.stream.send_packet("QStartNoAckMode")?;
+        self.stream.receive_ack()?;
+        let response = self.stream.receive_reply()?;
+        self.stream.send_ack()?;
+        if response != "OK" {
+            log::info!("GDB server does not support no-ACK mode");
+            self.quirks.no_ack = Support::Unsupported;
+            return Ok(());
+        }
+        self.stream.set_no_ack(true);
+        self.quirks.no_ack = Support::Supported;
+        Ok(())
+    }
+
+    pub fn quirks(&self) -> &StubQuirks {
+        &self.quirks
+    }
+
+    pub fn stub_kind(&self) -> StubKind {
+        self.stub_kind
+    }
+
+    /// Returns what is known about the stub, with limitations based on the packets used so far.
+    pub fn stub_info(&self) -> StubInfo {
+        StubInfo {
+            kind: self.stub_kind,
+            version: self.version.clone(),
+            features: self.stream.features().map(str::to_string).collect(),
+            packet_size: self.stream.packet_size(),
+            limitations: StubInfo::limitations(&self.quirks, self.stream.packet_size()),
+        }
+    }
+
+    pub fn stats(&self) -> &ConnectionStats {
+        self.stream.stats()
     }
 
     pub fn disconnect(&mut self) -> Result<()> {
@@ -26,7 +146,12 @@ impl GdbClient {
         self.stream.is_connected()
     }
 
-    fn handle_error(&self, response: &str) -> Result<()> {
+    /// Checks a reply to `packet` for errors. An empty reply means the stub doesn't implement the
+    /// packet and is returned as [`Unsupported`].
+    fn handle_error(&self, packet: &'static str, response: &str) -> Result<()> {
+        if response.is_empty() {
+            return Err(Unsupported { packet }.into());
+        }
         if response.starts_with("E") {
             bail!("Error from GDB server: {}", response);
         }
@@ -37,16 +162,19 @@ impl GdbClient {
         let packet = format!("m {address:x},{:x}", buf.len());
         self.stream.send_packet(&packet)?;
         self.stream.receive_ack()?;
-        let response = self.stream.receive_packet()?;
+        let response = self.stream.receive_reply()?;
         self.stream.send_ack()?;
-        self.handle_error(&response)?;
-        Self::hex_decode(&response, buf)?;
+        self.handle_error("m", &response)?;
+        hex::decode_into(response.as_bytes(), buf)?;
         Ok(())
     }
 
     pub fn read_slice(&mut self, mut address: u32, buf: &mut [u8]) -> Result<()> {
-        // Exclude $#(checksum) and divide by 2 for hex encoding
-        let max_read_length = (self.stream.packet_size().unwrap_or(usize::MAX) - 4) / 2;
+        let max_read_length = match self.stream.packet_size() {
+            // Exclude $#(checksum) and divide by 2 for hex encoding
+            Some(packet_size) => (packet_size.saturating_sub(4) / 2).max(1),
+            None => Self::FALLBACK_TRANSFER_LENGTH,
+        };
         let mut read_buf = buf;
         while !read_buf.is_empty() {
             let end = read_buf.len().min(max_read_length);
@@ -57,29 +185,205 @@ impl GdbClient {
         Ok(())
     }
 
-    pub fn read_u32(&mut self, address: u32) -> Result<u32> {
-        let mut buf = [0; 4];
+    fn read_array<const N: usize>(&mut self, address: u32) -> Result<[u8; N]> {
+        let mut buf = [0; N];
         self.read_slice(address, &mut buf)?;
-        Ok(u32::from_le_bytes(buf))
+        Ok(buf)
+    }
+
+    pub fn read_u64(&mut self, address: u32) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.read_array(address)?))
+    }
+
+    pub fn read_u32(&mut self, address: u32) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.read_array(address)?))
     }
 
     pub fn read_u16(&mut self, address: u32) -> Result<u16> {
-        let mut buf = [0; 2];
-        self.read_slice(address, &mut buf)?;
-        Ok(u16::from_le_bytes(buf))
+        Ok(u16::from_le_bytes(self.read_array(address)?))
+    }
+
+    pub fn read_i32(&mut self, address: u32) -> Result<i32> {
+        Ok(i32::from_le_bytes(self.read_array(address)?))
     }
 
-    pub fn write_slice(&mut self, address: u32, buf: &[u8]) -> Result<()> {
+    pub fn read_i16(&mut self, address: u32) -> Result<i16> {
+        Ok(i16::from_le_bytes(self.read_array(address)?))
+    }
+
+    pub fn read_i8(&mut self, address: u32) -> Result<i8> {
+        Ok(i8::from_le_bytes(self.read_array(address)?))
+    }
+
+    /// Reads a NUL-terminated string of at most `max_len` bytes, excluding the NUL.
+    pub fn read_cstring(&mut self, address: u32, max_len: usize) -> Result<Vec<u8>> {
+        read_cstring_chunked(address, max_len, |address, buf| self.read_slice_binary(address, buf))
+    }
+
+    fn write_slice_part(&mut self, address: u32, buf: &[u8]) -> Result<()> {
         let length = buf.len();
-        let data = Self::hex_encode(buf);
+        let data = hex::encode(buf);
         self.stream.send_packet(&format!("M {address:x},{length:x}:{data}"))?;
         self.stream.receive_ack()?;
-        let response = self.stream.receive_packet()?;
-        self.handle_error(&response)?;
+        let response = self.stream.receive_reply()?;
+        self.handle_error("M", &response)?;
         self.stream.send_ack()?;
         Ok(())
     }
 
+    pub fn write_slice(&mut self, mut address: u32, buf: &[u8]) -> Result<()> {
+        // Exclude $#(checksum) and the "M addr,len:" header with at most 8 hex digits per number,
+        // then divide by 2 for hex encoding
+        const HEADER_LENGTH: usize = "M ,:".len() + 8 + 8;
+        let packet_size = self.stream.packet_size().unwrap_or(Self::FALLBACK_PACKET_SIZE);
+        let max_write_length = (packet_size.saturating_sub(4 + HEADER_LENGTH) / 2).max(1);
+        for chunk in buf.chunks(max_write_length) {
+            self.write_slice_part(address, chunk)?;
+            address += chunk.len() as u32;
+        }
+        Ok(())
+    }
+
+    pub fn write_u64(&mut self, address: u32, value: u64) -> Result<()> {
+        self.write_slice(address, &value.to_le_bytes())
+    }
+
+    pub fn write_u32(&mut self, address: u32, value: u32) -> Result<()> {
+        self.write_slice(address, &value.to_le_bytes())
+    }
+
+    pub fn write_u16(&mut self, address: u32, value: u16) -> Result<()> {
+        self.write_slice(address, &value.to_le_bytes())
+    }
+
+    pub fn write_u8(&mut self, address: u32, value: u8) -> Result<()> {
+        self.write_slice(address, &[value])
+    }
+
+    /// Returns the number of bytes read, which may be fewer than requested.
+    fn read_slice_binary_part(&mut self, address: u32, buf: &mut [u8]) -> Result<usize> {
+        self.stream.send_packet(&format!("x {address:x},{:x}", buf.len()))?;
+        self.stream.receive_ack()?;
+        let response = self.stream.receive_reply_bytes()?;
+        self.stream.send_ack()?;
+        let Some(data) = response.strip_prefix(b"b") else {
+            let response = String::from_utf8_lossy(&response);
+            self.handle_error("x", &response)?;
+            bail!("Unexpected reply to binary read: {response}");
+        };
+        let data = GdbStream::unescape_binary(data);
+        if data.is_empty() || data.len() > buf.len() {
+            bail!("Expected up to {} bytes, got {}", buf.len(), data.len());
+        }
+        buf[..data.len()].copy_from_slice(&data);
+        Ok(data.len())
+    }
+
+    /// Reads memory with binary `x` packets, or falls back to [`Self::read_slice`] if the stub
+    /// doesn't support them.
+    pub fn read_slice_binary(&mut self, mut address: u32, buf: &mut [u8]) -> Result<()> {
+        if self.quirks.binary_read != Support::Supported {
+            return self.read_slice(address, buf);
+        }
+        let max_read_length = match self.stream.packet_size() {
+            // Exclude $b#(checksum) and assume every byte may be escaped
+            Some(packet_size) => (packet_size.saturating_sub(5) / 2).max(1),
+            None => Self::FALLBACK_TRANSFER_LENGTH,
+        };
+        let mut read_buf = buf;
+        while !read_buf.is_empty() {
+            let end = read_buf.len().min(max_read_length);
+            let length = self.read_slice_binary_part(address, &mut read_buf[..end])?;
+            address += length as u32;
+            read_buf = &mut read_buf[length..];
+        }
+        Ok(())
+    }
+
+    /// Writes memory with binary `X` packets, or falls back to [`Self::write_slice`] if the stub
+    /// doesn't support them.
+    pub fn write_slice_binary(&mut self, mut address: u32, buf: &[u8]) -> Result<()> {
+        // Exclude $#(checksum) and the "X addr,len:" header with at most 8 hex digits per number
+        const HEADER_LENGTH: usize = "X ,:".len() + 8 + 8;
+        let packet_size = self.stream.packet_size().unwrap_or(Self::FALLBACK_PACKET_SIZE);
+        let max_data_length = packet_size.saturating_sub(4 + HEADER_LENGTH).max(2);
+
+        let mut remaining = buf;
+        while !remaining.is_empty() {
+            if self.quirks.binary_write == Support::Unsupported {
+                return self.write_slice(address, remaining);
+            }
+
+            let mut data_length = 0;
+            let length = remaining
+                .iter()
+                .take_while(|&&byte| {
+                    data_length += if GdbStream::needs_escape(byte) { 2 } else { 1 };
+                    data_length <= max_data_length
+                })
+                .count();
+
+            let mut packet = format!("X {address:x},{length:x}:").into_bytes();
+            GdbStream::escape_binary(&remaining[..length], &mut packet);
+            self.stream.send_packet_bytes(&packet)?;
+            self.stream.receive_ack()?;
+            let response = self.stream.receive_reply()?;
+            self.stream.send_ack()?;
+            if response.is_empty() {
+                log::info!("GDB server does not support binary writes, using hex writes");
+                self.quirks.binary_write = Support::Unsupported;
+                continue;
+            }
+            self.handle_error("X", &response)?;
+            self.quirks.binary_write = Support::Supported;
+
+            address += length as u32;
+            remaining = &remaining[length..];
+        }
+        Ok(())
+    }
+
+    /// Asks the stub for the CRC of a memory range with `qCRC`, see [`crc32`].
+    pub fn crc(&mut self, address: u32, length: usize) -> Result<u32> {
+        if self.quirks.crc == Support::Unsupported {
+            return Err(Unsupported { packet: "qCRC" }.into());
+        }
+        self.stream.send_packet(&format!("qCRC:{address:x},{length:x}"))?;
+        self.stream.receive_ack()?;
+        let response = self.stream.receive_reply()?;
+        self.stream.send_ack()?;
+        if response.is_empty() {
+            log::info!("GDB server does not support qCRC, reading memory in full");
+            self.quirks.crc = Support::Unsupported;
+        }
+        self.handle_error("qCRC", &response)?;
+        let Some(crc) =
+            response.strip_prefix('C').and_then(|crc| u32::from_str_radix(crc, 16).ok())
+        else {
+            bail!("Unexpected reply to qCRC: {response}");
+        };
+        self.quirks.crc = Support::Supported;
+        Ok(crc)
+    }
+
+    /// Reads memory into `buf` unless the stub reports the same CRC as for `cached`, in which case
+    /// `buf` is left as is. Returns true if memory was read. Stubs without `qCRC` are always read.
+    pub fn read_if_changed(&mut self, address: u32, cached: &[u8], buf: &mut [u8]) -> Result<bool> {
+        if self.quirks.crc != Support::Unsupported && cached.len() == buf.len() {
+            match self.crc(address, buf.len()) {
+                Ok(crc) if crc == crc32(cached) => {
+                    self.stream.stats_mut().crc_hits += 1;
+                    return Ok(false);
+                }
+                Ok(_) => self.stream.stats_mut().crc_misses += 1,
+                Err(e) if e.is::<Unsupported>() => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.read_slice_binary(address, buf)?;
+        Ok(true)
+    }
+
     pub fn continue_execution(&mut self) -> Result<()> {
         self.stream.send_packet("c")?;
         self.stream.receive_ack()?;
@@ -89,52 +393,111 @@ impl GdbClient {
     pub fn stop_execution(&mut self) -> Result<()> {
         self.stream.send_packet("s")?;
         self.stream.receive_ack()?;
-        let response = self.stream.receive_packet()?;
-        self.handle_error(&response)?;
+        let response = self.stream.receive_reply()?;
+        self.handle_error("s", &response)?;
         self.stream.send_ack()?;
         Ok(())
     }
 
-    pub fn get_gamecode(&mut self) -> Result<String> {
-        let rcmd = Self::hex_encode(b"gamecode");
-        self.stream.send_packet(&format!("qRcmd,{}", rcmd))?;
+    /// Reads register `index` in GDB's numbering for ARM, where 15 is the PC and 25 the CPSR.
+    /// Stubs without `p` packets are asked for every register with `g` instead, which only
+    /// includes the general purpose registers reliably.
+    pub fn read_register(&mut self, index: usize) -> Result<u32> {
+        if self.quirks.read_register != Support::Unsupported {
+            self.stream.send_packet(&format!("p{index:x}"))?;
+            self.stream.receive_ack()?;
+            let response = self.stream.receive_reply()?;
+            self.stream.send_ack()?;
+            if !response.is_empty() {
+                self.handle_error("p", &response)?;
+                self.quirks.read_register = Support::Supported;
+                let mut buf = [0; 4];
+                hex::decode_into(&response.as_bytes()[..8.min(response.len())], &mut buf)?;
+                return Ok(u32::from_le_bytes(buf));
+            }
+            log::info!("GDB server does not support register reads, reading all registers");
+            self.quirks.read_register = Support::Unsupported;
+        }
+
+        self.stream.send_packet("g")?;
         self.stream.receive_ack()?;
-        let response = self.stream.receive_packet()?;
+        let response = self.stream.receive_reply()?;
         self.stream.send_ack()?;
-        self.handle_error(&response)?;
-        Self::hex_decode_string(&response)
+        self.handle_error("g", &response)?;
+        let hex = response
+            .as_bytes()
+            .get(index * 8..index * 8 + 8)
+            .with_context(|| format!("Register {index} is missing from the register dump"))?;
+        let mut buf = [0; 4];
+        hex::decode_into(hex, &mut buf)?;
+        Ok(u32::from_le_bytes(buf))
     }
 
-    fn hex_encode(data: &[u8]) -> String {
-        let mut encoded = String::with_capacity(data.len() * 2);
-        for &byte in data {
-            encoded.push_str(&format!("{:02x}", byte));
-        }
-        encoded
+    /// Returns console output received since the last call, see [`GdbStream::receive_reply`].
+    pub fn take_console_output(&mut self) -> Vec<String> {
+        self.stream.take_console_output()
     }
 
-    fn hex_decode(data: &str, buf: &mut [u8]) -> Result<()> {
-        if data.len() != buf.len() * 2 {
-            bail!("Expected {} bytes, got {}", buf.len() * 2, data.len());
+    /// Queries the gamecode with a monitor command, falling back to reading it from memory for
+    /// stubs like DeSmuME's which don't implement it.
+    pub fn get_gamecode(&mut self) -> Result<String> {
+        match self.monitor("gamecode") {
+            Ok(reply) => match normalize_gamecode(&reply) {
+                Some(gamecode) => return Ok(gamecode),
+                None => log::info!("Gamecode query returned {reply:?}, reading it from memory"),
+            },
+            Err(e) => log::info!("Gamecode query failed, reading it from memory: {e}"),
         }
-        for (i, chunk) in data.as_bytes().chunks(2).enumerate() {
-            let high = hex_char_to_byte(chunk[0] as char);
-            let low = hex_char_to_byte(chunk[1] as char);
-            buf[i] = (high << 4) | low;
+        self.read_gamecode()
+    }
+
+    fn read_gamecode(&mut self) -> Result<String> {
+        let mut buf = [0; 4];
+        self.read_slice(Self::GAMECODE_ADDRESS, &mut buf)?;
+        if !buf.iter().all(|b| b.is_ascii_alphanumeric()) {
+            bail!("Invalid gamecode in memory: {buf:02x?}");
         }
-        Ok(())
+        Ok(String::from_utf8_lossy(&buf).into_owned())
     }
 
-    fn hex_decode_string(data: &str) -> Result<String> {
-        if data.len() % 2 != 0 {
-            bail!("Hex string must have even length");
+    /// Sends a monitor command (`qRcmd`) and returns the decoded reply. Console output sent as
+    /// `O` packets before the final reply is concatenated into the result.
+    pub fn monitor(&mut self, command: &str) -> Result<String> {
+        if self.quirks.rcmd == Support::Unsupported {
+            return Err(Unsupported { packet: "qRcmd" }.into());
         }
-        let mut bytes = Vec::with_capacity(data.len() / 2);
-        for chunk in data.as_bytes().chunks(2) {
-            let high = hex_char_to_byte(chunk[0] as char);
-            let low = hex_char_to_byte(chunk[1] as char);
-            bytes.push((high << 4) | low);
+        let rcmd = hex::encode(command.as_bytes());
+        self.stream.send_packet(&format!("qRcmd,{rcmd}"))?;
+        self.stream.receive_ack()?;
+        let mut output = String::new();
+        loop {
+            let response = self.stream.receive_packet()?;
+            self.stream.send_ack()?;
+            self.quirks.rcmd = if response.is_empty() {
+                Support::Unsupported
+            } else {
+                Support::Supported
+            };
+            self.handle_error("qRcmd", &response)?;
+            if response == "OK" {
+                break;
+            }
+            if let Some(console_output) = GdbStream::parse_console_output(&response) {
+                output.push_str(&console_output);
+                continue;
+            }
+            output.push_str(&hex::decode_string(&response)?);
+            break;
         }
-        Ok(String::from_utf8(bytes)?)
+        Ok(output)
     }
 }
+
+/// Extracts the gamecode from a monitor command reply. Stubs may pad the code with whitespace or
+/// NULs, add a newline or follow it with the two-character maker code, so the code is taken from
+/// the first word of the reply. Returns `None` if that word doesn't look like a gamecode.
+pub fn normalize_gamecode(reply: &str) -> Option<String> {
+    let word = reply.split(|c: char| c.is_whitespace() || c == '\0').find(|w| !w.is_empty())?;
+    let valid = matches!(word.len(), 4 | 6) && word.chars().all(|c| c.is_ascii_alphanumeric());
+    valid.then(|| word[..4].to_ascii_uppercase())
+}
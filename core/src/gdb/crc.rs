@@ -0,0 +1,23 @@
+/// CRC-32 lookup table for the polynomial 0x04c11db7, most significant bit first.
+const TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = (i as u32) << 24;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x80000000 != 0 { (crc << 1) ^ 0x04c11db7 } else { crc << 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Computes the CRC which GDB stubs reply with to `qCRC` packets: CRC-32 starting from
+/// 0xffffffff, most significant bit first and without a final XOR.
+pub fn crc32(data: &[u8]) -> u32 {
+    data.iter()
+        .fold(0xffffffff, |crc, &byte| (crc << 8) ^ TABLE[((crc >> 24) as u8 ^ byte) as usize])
+}
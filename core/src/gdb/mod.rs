@@ -1,2 +1,5 @@
 pub mod client;
+pub mod crc;
+pub mod quirks;
+pub mod stats;
 pub mod stream;
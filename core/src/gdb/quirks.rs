@@ -0,0 +1,125 @@
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Support {
+    #[default]
+    Unknown,
+    Supported,
+    Unsupported,
+}
+
+/// Optional packets which differ between GDB stubs, e.g. DeSmuME has no `qRcmd` while melonDS
+/// does. Each entry starts out unknown and is updated as packets are used on a connection.
+#[derive(Default, Clone, Debug)]
+pub struct StubQuirks {
+    /// `QStartNoAckMode`
+    pub no_ack: Support,
+    /// `qRcmd` monitor commands
+    pub rcmd: Support,
+    /// `x` binary memory reads, advertised as `binary-upload+`
+    pub binary_read: Support,
+    /// `X` binary memory writes, probed on first use
+    pub binary_write: Support,
+    /// `Z`/`z` breakpoints and watchpoints
+    pub breakpoints: Support,
+    /// `qCRC` checksums of memory ranges, probed on first use
+    pub crc: Support,
+    /// `p` single register reads, probed on first use
+    pub read_register: Support,
+}
+
+/// The emulator behind a GDB stub, guessed from its `qSupported` reply when connecting.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StubKind {
+    #[default]
+    Unknown,
+    /// Advertises `qXfer:features:read` and hardware breakpoints
+    MelonDs,
+    /// Replies to `qSupported` with an empty packet
+    DeSmuMe,
+}
+
+impl StubKind {
+    /// Returns the quirks known before using any packets. Packets left unknown are probed on first
+    /// use, which some stubs don't handle gracefully.
+    pub fn initial_quirks(self) -> StubQuirks {
+        match self {
+            StubKind::DeSmuMe => StubQuirks { rcmd: Support::Unsupported, ..Default::default() },
+            StubKind::MelonDs | StubKind::Unknown => StubQuirks::default(),
+        }
+    }
+}
+
+impl std::fmt::Display for StubKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StubKind::Unknown => write!(f, "Unknown stub"),
+            StubKind::MelonDs => write!(f, "melonDS"),
+            StubKind::DeSmuMe => write!(f, "DeSmuME"),
+        }
+    }
+}
+
+/// What a GDB stub told about itself when connecting, for bug reports and for warning about
+/// emulators which limit what dsv can do. See [`crate::gdb::client::GdbClient::stub_info`].
+#[derive(Default, Clone, Debug)]
+pub struct StubInfo {
+    pub kind: StubKind,
+    /// Reply to the `version` monitor command, if the stub has one
+    pub version: Option<String>,
+    /// Features advertised in the `qSupported` reply
+    pub features: Vec<String>,
+    pub packet_size: Option<usize>,
+    /// Features of dsv which work worse with this stub, see [`StubInfo::limitations`]
+    pub limitations: Vec<&'static str>,
+}
+
+impl StubInfo {
+    /// Packet sizes below this split typical objects into several reads.
+    const SMALL_PACKET_SIZE: usize = 0x800;
+
+    /// Returns the dsv features which the stub degrades, judging by its quirks so far.
+    pub fn limitations(quirks: &StubQuirks, packet_size: Option<usize>) -> Vec<&'static str> {
+        let mut limitations = Vec::new();
+        if quirks.rcmd == Support::Unsupported {
+            limitations.push("The gamecode is read from memory, so games may not be detected");
+            limitations.push("Monitor commands are unavailable");
+        }
+        if packet_size.is_none_or(|size| size < Self::SMALL_PACKET_SIZE) {
+            limitations.push("Memory is read in small packets, so large windows update slowly");
+        }
+        if quirks.binary_read == Support::Unsupported {
+            limitations.push("Memory is read as hex, which doubles the transferred bytes");
+        }
+        if quirks.no_ack == Support::Unsupported {
+            limitations.push("Every packet is acknowledged, which adds latency to each read");
+        }
+        limitations
+    }
+
+    /// Describes the stub in one line, e.g. `melonDS 1.0, PacketSize=0x1000, hwbreak+`.
+    pub fn identification(&self) -> String {
+        let mut parts = vec![match &self.version {
+            Some(version) => format!("{} {version}", self.kind),
+            None => self.kind.to_string(),
+        }];
+        if let Some(packet_size) = self.packet_size {
+            parts.push(format!("PacketSize={packet_size:#x}"));
+        }
+        parts.extend(self.features.iter().map(|feature| format!("{feature}+")));
+        parts.join(", ")
+    }
+}
+
+/// Error for packets the stub replied to with an empty packet, which means it doesn't implement
+/// them. Callers can detect it with [`anyhow::Error::downcast_ref`].
+#[derive(Debug)]
+pub struct Unsupported {
+    pub packet: &'static str,
+}
+
+impl std::fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GDB server does not support '{}' packets", self.packet)
+    }
+}
+
+impl std::error::Error for Unsupported {}
@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+#[derive(Default, Clone, Debug)]
+pub struct ConnectionStats {
+    pub packets_sent: u64,
+    pub packets_received: u64,
+    /// Packets resent because the stub replied with a NAK
+    pub retransmits: u64,
+    /// NAKs sent because a reply failed its checksum
+    pub naks_sent: u64,
+    /// Reads skipped because the `qCRC` of the range matched the last data
+    pub crc_hits: u64,
+    /// Reads done after the `qCRC` of the range differed from the last data
+    pub crc_misses: u64,
+    /// Requests whose reply has arrived
+    pub round_trips: u64,
+    /// Total time from sending those requests to receiving their replies
+    pub round_trip_time: Duration,
+}
+
+impl ConnectionStats {
+    pub fn average_round_trip(&self) -> Option<Duration> {
+        let round_trips = u32::try_from(self.round_trips).ok().filter(|&n| n > 0)?;
+        Some(self.round_trip_time / round_trips)
+    }
+}
@@ -1,30 +1,132 @@
 use std::{
+    collections::{BTreeSet, VecDeque},
     io::{ErrorKind, Read, Write},
     net::{Shutdown, ToSocketAddrs},
+    time::{Duration, Instant},
 };
 
 use anyhow::{Context, Result, bail};
-use mio::net::TcpStream;
+use mio::{Events, Interest, Poll, Token, net::TcpStream};
 
-use crate::hex_char_to_byte;
+use crate::{gdb::stats::ConnectionStats, hex};
+
+/// A non-blocking TCP stream which blocks on a [`Poll`] until it is ready, instead of spinning.
+struct Connection {
+    stream: TcpStream,
+    poll: Poll,
+    events: Events,
+}
+
+impl Connection {
+    /// How long to wait for the GDB server before giving up on a read or write
+    const TIMEOUT: Duration = Duration::from_secs(5);
+    const TOKEN: Token = Token(0);
+
+    fn connect(address: std::net::SocketAddr) -> Result<Self> {
+        let mut stream = TcpStream::connect(address).context("Failed to open TCP connection")?;
+        let poll = Poll::new().context("Failed to create poll")?;
+        poll.registry()
+            .register(&mut stream, Self::TOKEN, Interest::READABLE | Interest::WRITABLE)
+            .context("Failed to register TCP connection")?;
+        let mut connection = Connection { stream, poll, events: Events::with_capacity(4) };
+
+        // The connection is established once the stream becomes writable
+        loop {
+            connection.wait()?;
+            if connection.events.iter().any(|event| event.is_writable()) {
+                break;
+            }
+        }
+        if let Some(err) = connection.stream.take_error()? {
+            bail!("Failed to open TCP connection: {err}");
+        }
+        connection.stream.set_nodelay(true)?;
+        Ok(connection)
+    }
+
+    /// Blocks until the stream may have become readable or writable.
+    fn wait(&mut self) -> Result<()> {
+        loop {
+            match self.poll.poll(&mut self.events, Some(Self::TIMEOUT)) {
+                Ok(()) => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => bail!("Failed to poll GDB connection: {e}"),
+            }
+        }
+        if self.events.is_empty() {
+            bail!("Timed out waiting for GDB server");
+        }
+        Ok(())
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        loop {
+            match self.stream.read(buf) {
+                Ok(n) => return Ok(n),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => self.wait()?,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => bail!("Failed to read from GDB server: {e}"),
+            }
+        }
+    }
+
+    fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+        while !buf.is_empty() {
+            match self.stream.write(buf) {
+                Ok(0) => bail!("Connection closed by GDB server"),
+                Ok(n) => buf = &buf[n..],
+                Err(e) if e.kind() == ErrorKind::WouldBlock => self.wait()?,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => bail!("Failed to write to GDB server: {e}"),
+            }
+        }
+        Ok(())
+    }
+}
 
 #[derive(Default)]
 pub struct GdbStream {
-    stream: Option<TcpStream>,
+    stream: Option<Connection>,
+    read_buffer: VecDeque<u8>,
     packet_size: Option<usize>,
+    features: BTreeSet<String>,
+    no_ack: bool,
+    /// Last packet sent including framing, kept for retransmission
+    last_packet: Vec<u8>,
+    /// When the last packet was sent, until the first packet after it arrives
+    sent_at: Option<Instant>,
+    last_round_trip: Option<Duration>,
+    stats: ConnectionStats,
+    console_output: Vec<String>,
 }
 
 impl GdbStream {
+    const MAX_RETRANSMITS: usize = 3;
+
     pub fn new() -> Self {
-        GdbStream { stream: None, packet_size: None }
+        GdbStream {
+            stream: None,
+            read_buffer: VecDeque::new(),
+            packet_size: None,
+            features: BTreeSet::new(),
+            no_ack: false,
+            last_packet: Vec::new(),
+            sent_at: None,
+            last_round_trip: None,
+            stats: ConnectionStats::default(),
+            console_output: Vec::new(),
+        }
     }
 
     pub fn connect<A: ToSocketAddrs>(&mut self, address: A) -> Result<()> {
         let addr = address.to_socket_addrs()?.next().context("No socket address found")?;
 
-        let stream = TcpStream::connect(addr).context("Failed to open TCP connection")?;
-        stream.set_nodelay(true)?;
-        self.stream = Some(stream);
+        self.stream = Some(Connection::connect(addr)?);
+        self.read_buffer.clear();
+        self.no_ack = false;
+        self.stats = ConnectionStats::default();
+        self.sent_at = None;
+        self.last_round_trip = None;
         self.send_ack().context("Failed to send initial ACK")?;
         self.receive_ack().context("Failed to receive initial ACK")?;
 
@@ -33,7 +135,13 @@ impl GdbStream {
         let response = self.receive_packet().context("Failed to receive qSupported response")?;
         self.send_ack().context("Failed to send ACK after qSupported")?;
 
+        self.features.clear();
+        self.packet_size = None;
         for feature in response.split(';') {
+            if let Some(name) = feature.strip_suffix('+') {
+                self.features.insert(name.to_string());
+                continue;
+            }
             let (name, value) = feature.split_once('=').unwrap_or((feature, ""));
             if name == "PacketSize" {
                 let value =
@@ -46,8 +154,8 @@ impl GdbStream {
     }
 
     pub fn disconnect(&mut self) -> Result<()> {
-        if let Some(stream) = self.stream.take() {
-            stream.shutdown(Shutdown::Both)?;
+        if let Some(connection) = self.stream.take() {
+            connection.stream.shutdown(Shutdown::Both)?;
         }
         Ok(())
     }
@@ -57,6 +165,9 @@ impl GdbStream {
     }
 
     pub fn send_ack(&mut self) -> Result<()> {
+        if self.no_ack {
+            return Ok(());
+        }
         let Some(ref mut stream) = self.stream else {
             bail!("Not connected to GDB server");
         };
@@ -66,33 +177,72 @@ impl GdbStream {
     }
 
     pub fn receive_ack(&mut self) -> Result<()> {
-        let Some(ref mut stream) = self.stream else {
-            bail!("Not connected to GDB server");
-        };
-        let mut buf = [0; 1];
-        loop {
-            let Err(e) = stream.read_exact(&mut buf) else {
-                break;
+        if self.no_ack {
+            return Ok(());
+        }
+        for _ in 1..=Self::MAX_RETRANSMITS {
+            if self.read_ack_reply()? {
+                return Ok(());
+            }
+            self.stats.retransmits += 1;
+            log::warn!("GDB server requested a retransmit ({} so far)", self.stats.retransmits);
+            let Some(ref mut stream) = self.stream else {
+                bail!("Not connected to GDB server");
             };
-            let kind = e.kind();
-            match kind {
-                ErrorKind::WouldBlock => {
-                    continue;
-                }
-                _ => {
-                    bail!("Failed to read ACK from GDB server: {kind}");
-                }
+            stream.write_all(&self.last_packet).context("Failed to resend packet")?;
+        }
+        // Read the reply to the last resend, so that it isn't mistaken for the next reply
+        if self.read_ack_reply()? {
+            return Ok(());
+        }
+        bail!("GDB server rejected packet after {} retransmits", Self::MAX_RETRANSMITS)
+    }
+
+    /// Returns true on an ACK and false on a NAK.
+    fn read_ack_reply(&mut self) -> Result<bool> {
+        match self.read_byte().context("Failed to read ACK from GDB server")? {
+            b'+' => {
+                log::debug!("Received ACK from GDB server");
+                Ok(true)
             }
+            b'-' => Ok(false),
+            byte => bail!("Failed to receive ACK from GDB server, got: {}", byte as char),
         }
-        if buf[0] != b'+' {
-            bail!("Failed to receive ACK from GDB server, got: {}", buf[0] as char);
+    }
+
+    /// Stops sending and expecting ACKs, after the stub has accepted `QStartNoAckMode`.
+    pub fn set_no_ack(&mut self, no_ack: bool) {
+        self.no_ack = no_ack;
+    }
+
+    pub fn is_no_ack(&self) -> bool {
+        self.no_ack
+    }
+
+    /// Returns the time from sending the last request to receiving its reply.
+    pub fn last_round_trip(&self) -> Option<Duration> {
+        self.last_round_trip
+    }
+
+    fn read_byte(&mut self) -> Result<u8> {
+        if let Some(byte) = self.read_buffer.pop_front() {
+            return Ok(byte);
         }
-        log::debug!("Received ACK from GDB server");
-        Ok(())
+        let Some(ref mut stream) = self.stream else {
+            bail!("Not connected to GDB server");
+        };
+        let mut buf = [0; 128];
+        let bytes_read = stream.read(&mut buf)?;
+        if bytes_read == 0 {
+            bail!("Connection closed by GDB server");
+        }
+        // Keep the rest, the stub may send several packets at once when ACKs are disabled
+        self.read_buffer.extend(&buf[1..bytes_read]);
+        Ok(buf[0])
     }
 
     pub fn receive_ok(&mut self) -> Result<()> {
-        let response = self.receive_packet()?;
+        let response = self.receive_reply()?;
         if response != "OK" {
             bail!("Expected 'OK' response, got: {}", response);
         }
@@ -101,78 +251,185 @@ impl GdbStream {
     }
 
     pub fn send_packet(&mut self, packet: &str) -> Result<()> {
+        self.send_packet_bytes(packet.as_bytes())
+    }
+
+    pub fn send_packet_bytes(&mut self, packet: &[u8]) -> Result<()> {
         let Some(ref mut stream) = self.stream else {
             bail!("Not connected to GDB server");
         };
 
-        log::debug!("Sending packet: {packet}");
+        log::debug!("Sending packet: {}", String::from_utf8_lossy(packet));
 
-        let checksum = packet.as_bytes().iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
-        let packet_with_checksum = format!("${packet}#{checksum:02x}");
-        stream.write_all(packet_with_checksum.as_bytes()).context("Failed to send packet")?;
+        let checksum = packet.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        self.last_packet.clear();
+        self.last_packet.push(b'$');
+        self.last_packet.extend_from_slice(packet);
+        self.last_packet.extend_from_slice(format!("#{checksum:02x}").as_bytes());
+        stream.write_all(&self.last_packet).context("Failed to send packet")?;
+        self.stats.packets_sent += 1;
+        self.sent_at = Some(Instant::now());
 
         Ok(())
     }
 
     pub fn receive_packet(&mut self) -> Result<String> {
-        let Some(ref mut stream) = self.stream else {
-            bail!("Not connected to GDB server");
-        };
+        let packet = self.receive_packet_bytes()?;
+        let response = String::from_utf8(packet).context("Failed to parse GDB response")?;
+        log::debug!("Received packet: {response}");
+        Ok(response)
+    }
 
-        let mut buf = [0; 128];
-        let mut vec = Vec::new();
-        loop {
-            let bytes_read = loop {
-                match stream.read(&mut buf) {
-                    Ok(n) => break n,
-                    Err(e) => match e.kind() {
-                        ErrorKind::WouldBlock => continue,
-                        _ => {
-                            bail!("Failed to read from GDB server: {e}");
-                        }
-                    },
+    pub fn receive_packet_bytes(&mut self) -> Result<Vec<u8>> {
+        for _ in 0..=Self::MAX_RETRANSMITS {
+            if let Some(packet) = self.read_packet()? {
+                self.stats.packets_received += 1;
+                if let Some(sent_at) = self.sent_at.take() {
+                    let round_trip = sent_at.elapsed();
+                    self.last_round_trip = Some(round_trip);
+                    self.stats.round_trips += 1;
+                    self.stats.round_trip_time += round_trip;
                 }
-            };
-            // let bytes_read = stream.read(&mut buf).context("Failed to read from GDB server")?;
-            if bytes_read == 0 {
-                bail!("Connection closed by GDB server");
+                return Ok(packet);
             }
-            vec.extend_from_slice(&buf[..bytes_read]);
-            if vec[0] != b'$' {
-                self.disconnect()?;
-                bail!("Response did not start with '$', got: {}", String::from_utf8_lossy(&vec));
-            }
-            let len = vec.len();
-            if vec[len - 3] == b'#'
-                && vec[len - 2].is_ascii_hexdigit()
-                && vec[len - 1].is_ascii_hexdigit()
-            {
+            // NAKs are not allowed in no-ACK mode, so the stream can't recover
+            if self.no_ack {
                 break;
             }
-            if bytes_read == buf.len() {
-                continue;
-            } else {
-                self.disconnect()?;
-                bail!("Response did not end with checksum, got: {}", String::from_utf8_lossy(&vec));
+            self.stats.naks_sent += 1;
+            log::warn!("Requesting a retransmit from GDB server ({} so far)", self.stats.naks_sent);
+            let Some(ref mut stream) = self.stream else {
+                bail!("Not connected to GDB server");
+            };
+            stream.write_all(b"-")?;
+        }
+        self.disconnect()?;
+        bail!("Failed to receive a packet with a valid checksum");
+    }
+
+    /// Returns `None` if the packet failed its checksum.
+    fn read_packet(&mut self) -> Result<Option<Vec<u8>>> {
+        let start = self.read_byte()?;
+        if start != b'$' {
+            self.disconnect()?;
+            bail!("Response did not start with '$', got: {}", start as char);
+        }
+        let mut packet = Vec::new();
+        loop {
+            match self.read_byte()? {
+                b'#' => break,
+                byte => packet.push(byte),
             }
         }
+        let checksum = [self.read_byte()?, self.read_byte()?];
+        if !checksum.iter().all(|b| b.is_ascii_hexdigit()) {
+            self.disconnect()?;
+            bail!("Response did not end with checksum, got: {}", String::from_utf8_lossy(&packet));
+        }
 
-        let len = vec.len();
-        let packet = &vec[1..len - 3];
         let expected_checksum = packet.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
-        let actual_checksum =
-            hex_char_to_byte(vec[len - 2] as char) << 4 | hex_char_to_byte(vec[len - 1] as char);
+        let actual_checksum = hex::nibble(checksum[0]) << 4 | hex::nibble(checksum[1]);
         if expected_checksum != actual_checksum {
-            self.disconnect()?;
-            bail!("Checksum mismatch: expected {expected_checksum:02x}, got {actual_checksum:02x}");
+            log::warn!(
+                "Checksum mismatch: expected {expected_checksum:02x}, got {actual_checksum:02x}"
+            );
+            return Ok(None);
         }
 
-        let response =
-            String::from_utf8(packet.to_vec()).context("Failed to parse GDB response")?;
+        Ok(Some(packet))
+    }
+
+    /// Receives the next reply, skipping any console output (`O` packets) sent before it. Skipped
+    /// output is acknowledged and buffered until taken with [`Self::take_console_output`].
+    pub fn receive_reply(&mut self) -> Result<String> {
+        let response = self.receive_reply_bytes()?;
+        let response = String::from_utf8(response).context("Failed to parse GDB response")?;
         log::debug!("Received packet: {response}");
         Ok(response)
     }
 
+    /// Like [`Self::receive_reply`], but for replies which may contain binary data.
+    pub fn receive_reply_bytes(&mut self) -> Result<Vec<u8>> {
+        loop {
+            let response = self.receive_packet_bytes()?;
+            let Some(output) = Self::parse_console_output(&String::from_utf8_lossy(&response))
+            else {
+                return Ok(response);
+            };
+            self.send_ack()?;
+            log::info!("GDB console: {}", output.trim_end());
+            self.console_output.push(output);
+        }
+    }
+
+    /// Decodes the payload of an `O` packet. Returns `None` for other packets, including `OK`.
+    pub fn parse_console_output(packet: &str) -> Option<String> {
+        let payload = packet.strip_prefix('O')?;
+        if payload.is_empty() || !payload.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        hex::decode_string(payload).ok()
+    }
+
+    /// Escapes binary data for `X` packets, each of `#$}*` becomes `}` followed by the byte XOR
+    /// 0x20.
+    pub fn escape_binary(data: &[u8], out: &mut Vec<u8>) {
+        for &byte in data {
+            if Self::needs_escape(byte) {
+                out.extend_from_slice(&[b'}', byte ^ 0x20]);
+            } else {
+                out.push(byte);
+            }
+        }
+    }
+
+    pub fn needs_escape(byte: u8) -> bool {
+        matches!(byte, b'#' | b'$' | b'}' | b'*')
+    }
+
+    pub fn unescape_binary(data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(data.len());
+        let mut bytes = data.iter();
+        while let Some(&byte) = bytes.next() {
+            if byte == b'}' {
+                if let Some(&escaped) = bytes.next() {
+                    out.push(escaped ^ 0x20);
+                }
+            } else {
+                out.push(byte);
+            }
+        }
+        out
+    }
+
+    /// Returns true if the stub replied to `qSupported` with neither features nor a packet size,
+    /// like stubs which don't implement it.
+    pub fn supported_reply_is_empty(&self) -> bool {
+        self.features.is_empty() && self.packet_size.is_none()
+    }
+
+    /// Returns whether the stub advertised `<feature>+` in its `qSupported` reply.
+    pub fn supports(&self, feature: &str) -> bool {
+        self.features.contains(feature)
+    }
+
+    /// Returns the features advertised as `<feature>+` in the `qSupported` reply.
+    pub fn features(&self) -> impl Iterator<Item = &str> {
+        self.features.iter().map(String::as_str)
+    }
+
+    pub fn take_console_output(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.console_output)
+    }
+
+    pub fn stats(&self) -> &ConnectionStats {
+        &self.stats
+    }
+
+    pub(crate) fn stats_mut(&mut self) -> &mut ConnectionStats {
+        &mut self.stats
+    }
+
     pub fn packet_size(&self) -> Option<usize> {
         self.packet_size
     }
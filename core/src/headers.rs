@@ -0,0 +1,37 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use type_crawler::{Env, EnvOptions, TypeCrawler, WordSize};
+
+/// Creates a type crawler for the DS, a 32-bit target whose `char` is signed.
+pub fn create_crawler<P: AsRef<Path>>(
+    include_paths: &[P],
+    short_enums: bool,
+) -> Result<TypeCrawler> {
+    let env = Env::new(EnvOptions { word_size: WordSize::Size32, short_enums, signed_char: true });
+    let mut crawler = TypeCrawler::new(env).context("Failed to create type crawler")?;
+    for path in include_paths {
+        crawler.add_include_path(path).context("Failed to add include path")?;
+    }
+    Ok(crawler)
+}
+
+/// Returns the `.h` and `.hpp` files in `dir` and its subdirectories, except for directories
+/// containing one of `ignore_paths`.
+pub fn find_header_files(dir: &Path, ignore_paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    if ignore_paths.iter().any(|p| p.starts_with(dir)) {
+        return Ok(Vec::new());
+    }
+    let mut header_files = Vec::new();
+    if dir.is_dir() {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                header_files.extend(find_header_files(&path, ignore_paths)?);
+            } else if path.extension().is_some_and(|ext| ext == "hpp" || ext == "h") {
+                header_files.push(path);
+            }
+        }
+    }
+    Ok(header_files)
+}
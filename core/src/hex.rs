@@ -0,0 +1,73 @@
+use anyhow::{Result, bail};
+
+const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Lowercase hex digits of every byte, high nibble first.
+const ENCODE: [[u8; 2]; 256] = {
+    let mut table = [[0; 2]; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = [DIGITS[i >> 4], DIGITS[i & 0xf]];
+        i += 1;
+    }
+    table
+};
+
+/// Value of every hex digit, zero for other characters.
+const DECODE: [u8; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = match i as u8 {
+            c @ b'0'..=b'9' => c - b'0',
+            c @ b'a'..=b'f' => c - b'a' + 10,
+            c @ b'A'..=b'F' => c - b'A' + 10,
+            _ => 0,
+        };
+        i += 1;
+    }
+    table
+};
+
+/// Returns the value of a hex digit, or zero if `c` is not one.
+pub fn nibble(c: u8) -> u8 {
+    DECODE[c as usize]
+}
+
+/// Appends `data` to `out` as lowercase hex.
+pub fn encode_into(data: &[u8], out: &mut String) {
+    out.reserve(data.len() * 2);
+    for &byte in data {
+        let [high, low] = ENCODE[byte as usize];
+        out.push(high as char);
+        out.push(low as char);
+    }
+}
+
+pub fn encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len() * 2);
+    encode_into(data, &mut encoded);
+    encoded
+}
+
+/// Decodes `data` into `buf`, which must be exactly half as long. Characters which are not hex
+/// digits decode as zero.
+pub fn decode_into(data: &[u8], buf: &mut [u8]) -> Result<()> {
+    if data.len() != buf.len() * 2 {
+        bail!("Expected {} bytes, got {}", buf.len() * 2, data.len());
+    }
+    for (byte, pair) in buf.iter_mut().zip(data.chunks_exact(2)) {
+        *byte = (DECODE[pair[0] as usize] << 4) | DECODE[pair[1] as usize];
+    }
+    Ok(())
+}
+
+/// Decodes hex into a UTF-8 string, like the output of monitor commands.
+pub fn decode_string(data: &str) -> Result<String> {
+    if !data.len().is_multiple_of(2) {
+        bail!("Hex string must have even length");
+    }
+    let mut bytes = vec![0; data.len() / 2];
+    decode_into(data.as_bytes(), &mut bytes)?;
+    Ok(String::from_utf8(bytes)?)
+}
@@ -1,12 +1,11 @@
+pub mod disasm;
 pub mod gdb;
+pub mod headers;
+pub mod hex;
+pub mod memory;
 pub mod state;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+pub mod trace;
+pub mod typed;
 pub mod types;
-
-pub(crate) fn hex_char_to_byte(c: char) -> u8 {
-    match c {
-        '0'..='9' => c as u8 - b'0',
-        'a'..='f' => c as u8 - b'a' + 10,
-        'A'..='F' => c as u8 - b'A' + 10,
-        _ => 0,
-    }
-}
@@ -0,0 +1,57 @@
+use std::{fmt::Display, ops::Range};
+
+/// An address in the memory of the target. The DS is 32-bit, so this is `u32`, but code which
+/// names it instead of `u32` won't need changes for targets with wider addresses.
+pub type Addr = u32;
+
+/// The 4 MB of main RAM of a retail DS.
+pub const DS_MAIN_RAM: Range<Addr> = 0x02000000..0x02400000;
+/// Data TCM where games commonly map it, which holds the stack and many global pointers.
+pub const DS_DTCM: Range<Addr> = 0x027e0000..0x027e4000;
+
+/// A reason why an object is unlikely to be at an address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AddressWarning {
+    Null,
+    Misaligned { alignment: usize },
+    OutOfRange,
+}
+
+impl Display for AddressWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddressWarning::Null => write!(f, "Address is NULL"),
+            AddressWarning::Misaligned { alignment } => {
+                write!(f, "Address is not aligned to {alignment} bytes")
+            }
+            AddressWarning::OutOfRange => {
+                write!(f, "Address is outside of the known memory ranges")
+            }
+        }
+    }
+}
+
+/// Checks that an object of `size` bytes and `alignment` fits at `address`, inside one of
+/// `ranges`. A NULL address gets no other warnings.
+pub fn check_address(
+    address: Addr,
+    size: usize,
+    alignment: usize,
+    ranges: &[Range<Addr>],
+) -> Vec<AddressWarning> {
+    if address == 0 {
+        return vec![AddressWarning::Null];
+    }
+    let mut warnings = Vec::new();
+    if alignment > 1 && !address.is_multiple_of(alignment as Addr) {
+        warnings.push(AddressWarning::Misaligned { alignment });
+    }
+    let end = address as u64 + size as u64;
+    let in_range = ranges
+        .iter()
+        .any(|range| range.start <= address && end <= range.end as u64 && address < range.end);
+    if !in_range {
+        warnings.push(AddressWarning::OutOfRange);
+    }
+    warnings
+}
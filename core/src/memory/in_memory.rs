@@ -0,0 +1,78 @@
+use anyhow::{Result, bail};
+
+use crate::memory::{MemoryBackend, address::Addr};
+
+/// A backend serving a single contiguous block of memory, useful for tests and memory dumps.
+pub struct InMemoryBackend {
+    base: Addr,
+    data: Vec<u8>,
+    connected: bool,
+    running: bool,
+}
+
+impl InMemoryBackend {
+    pub fn new(base: Addr, size: usize) -> Self {
+        Self::from_bytes(base, vec![0; size])
+    }
+
+    pub fn from_bytes(base: Addr, data: Vec<u8>) -> Self {
+        Self { base, data, connected: true, running: true }
+    }
+
+    pub fn base(&self) -> Addr {
+        self.base
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    fn range(&self, address: Addr, length: usize) -> Result<std::ops::Range<usize>> {
+        let start = address.checked_sub(self.base).map(|offset| offset as usize);
+        match start {
+            Some(start) if start + length <= self.data.len() => Ok(start..start + length),
+            _ => bail!(
+                "Address range {:#x}..{:#x} is out of bounds",
+                address,
+                address as usize + length
+            ),
+        }
+    }
+}
+
+impl MemoryBackend for InMemoryBackend {
+    fn read(&mut self, address: Addr, buf: &mut [u8]) -> Result<()> {
+        let range = self.range(address, buf.len())?;
+        buf.copy_from_slice(&self.data[range]);
+        Ok(())
+    }
+
+    fn write(&mut self, address: Addr, buf: &[u8]) -> Result<()> {
+        let range = self.range(address, buf.len())?;
+        self.data[range].copy_from_slice(buf);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.running = false;
+        Ok(())
+    }
+
+    fn resume(&mut self) -> Result<()> {
+        self.running = true;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        self.connected = false;
+        Ok(())
+    }
+}
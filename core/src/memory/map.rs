@@ -0,0 +1,127 @@
+use std::{fmt::Display, ops::Range};
+
+use super::address::{DS_DTCM, DS_MAIN_RAM};
+
+/// What the CPU may do with a memory region.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Access {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+}
+
+impl Access {
+    pub const ALL: Self = Self { read: true, write: true, execute: true };
+    pub const READ_EXECUTE: Self = Self { read: true, write: false, execute: true };
+    pub const READ_ONLY: Self = Self { read: true, write: false, execute: false };
+    pub const READ_WRITE: Self = Self { read: true, write: true, execute: false };
+
+    /// Parses permissions like `rw` or `r-x`, where every letter is optional.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut access = Self { read: false, write: false, execute: false };
+        for c in text.chars() {
+            match c {
+                'r' => access.read = true,
+                'w' => access.write = true,
+                'x' => access.execute = true,
+                '-' => {}
+                _ => return None,
+            }
+        }
+        Some(access)
+    }
+}
+
+impl Display for Access {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let flag = |set: bool, c: char| if set { c } else { '-' };
+        write!(f, "{}{}{}", flag(self.read, 'r'), flag(self.write, 'w'), flag(self.execute, 'x'))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryRegion {
+    pub name: String,
+    pub range: Range<u32>,
+    pub access: Access,
+    /// Set for hardware registers, where reads can have side effects and no objects are stored
+    pub io: bool,
+}
+
+impl MemoryRegion {
+    pub fn new(name: impl Into<String>, range: Range<u32>, access: Access) -> Self {
+        Self { name: name.into(), range, access, io: false }
+    }
+
+    pub fn io(mut self) -> Self {
+        self.io = true;
+        self
+    }
+
+    pub fn contains(&self, address: u32) -> bool {
+        self.range.contains(&address)
+    }
+}
+
+/// The named regions of an address space, used to tell which addresses are worth reading.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryMap {
+    /// Sorted by start address
+    regions: Vec<MemoryRegion>,
+}
+
+impl Default for MemoryMap {
+    fn default() -> Self {
+        Self::ds_arm9()
+    }
+}
+
+impl MemoryMap {
+    pub fn new(mut regions: Vec<MemoryRegion>) -> Self {
+        regions.sort_by_key(|region| (region.range.start, region.range.end));
+        Self { regions }
+    }
+
+    /// The address space as seen from the ARM9, with the TCMs where retail games map them.
+    pub fn ds_arm9() -> Self {
+        Self::new(vec![
+            MemoryRegion::new("ITCM", 0x01ff8000..0x02000000, Access::ALL),
+            MemoryRegion::new("Main RAM", DS_MAIN_RAM, Access::ALL),
+            MemoryRegion::new("DTCM", DS_DTCM, Access::READ_WRITE),
+            MemoryRegion::new("Shared WRAM", 0x03000000..0x03008000, Access::READ_WRITE),
+            MemoryRegion::new("I/O registers", 0x04000000..0x04001100, Access::READ_WRITE).io(),
+            MemoryRegion::new("IPC and cartridge I/O", 0x04100000..0x04100014, Access::READ_WRITE)
+                .io(),
+            MemoryRegion::new("Palettes", 0x05000000..0x05000800, Access::READ_WRITE),
+            MemoryRegion::new("VRAM engine A BG", 0x06000000..0x06080000, Access::READ_WRITE),
+            MemoryRegion::new("VRAM engine B BG", 0x06200000..0x06220000, Access::READ_WRITE),
+            MemoryRegion::new("VRAM engine A OBJ", 0x06400000..0x06440000, Access::READ_WRITE),
+            MemoryRegion::new("VRAM engine B OBJ", 0x06600000..0x06620000, Access::READ_WRITE),
+            MemoryRegion::new("VRAM LCDC", 0x06800000..0x068a4000, Access::READ_WRITE),
+            MemoryRegion::new("OAM", 0x07000000..0x07000800, Access::READ_WRITE),
+            MemoryRegion::new("BIOS", 0xffff0000..0xffff8000, Access::READ_EXECUTE),
+        ])
+    }
+
+    pub fn regions(&self) -> &[MemoryRegion] {
+        &self.regions
+    }
+
+    pub fn region_at(&self, address: u32) -> Option<&MemoryRegion> {
+        self.regions.iter().find(|region| region.contains(address))
+    }
+
+    /// Returns true if `address` is in a readable region which isn't I/O.
+    pub fn is_readable(&self, address: u32) -> bool {
+        self.region_at(address).is_some_and(|region| region.access.read && !region.io)
+    }
+
+    /// Ranges which objects can be read from, for [`super::address::check_address`].
+    pub fn object_ranges(&self) -> Vec<Range<u32>> {
+        self.regions
+            .iter()
+            .filter(|region| region.access.read && !region.io)
+            .map(|region| region.range.clone())
+            .collect()
+    }
+}
@@ -0,0 +1,143 @@
+use anyhow::{Result, bail};
+
+use self::address::Addr;
+use crate::gdb::{client::GdbClient, stats::ConnectionStats};
+
+pub mod address;
+pub mod in_memory;
+pub mod map;
+pub mod savestate;
+pub mod scan;
+
+/// Kept for callers written against the original read/write-only trait.
+pub use self::MemoryBackend as MemorySource;
+
+/// A target whose memory [`State`](crate::state::State) can read from and write to.
+pub trait MemoryBackend {
+    fn read(&mut self, address: Addr, buf: &mut [u8]) -> Result<()>;
+
+    fn write(&mut self, address: Addr, buf: &[u8]) -> Result<()>;
+
+    /// Reads memory into `buf` unless the backend can tell that it still holds `cached`, which was
+    /// read from the same range before. Returns true if `buf` was read.
+    fn read_if_changed(&mut self, address: Addr, _cached: &[u8], buf: &mut [u8]) -> Result<bool> {
+        self.read(address, buf)?;
+        Ok(true)
+    }
+
+    /// Reads a NUL-terminated string of at most `max_len` bytes, excluding the NUL.
+    fn read_cstring(&mut self, address: Addr, max_len: usize) -> Result<Vec<u8>> {
+        read_cstring_chunked(address, max_len, |address, buf| self.read(address, buf))
+    }
+
+    /// Halts the target so a consistent snapshot can be read.
+    fn stop(&mut self) -> Result<()>;
+
+    fn resume(&mut self) -> Result<()>;
+
+    fn is_connected(&self) -> bool;
+
+    fn disconnect(&mut self) -> Result<()>;
+
+    fn monitor(&mut self, _command: &str) -> Result<String> {
+        bail!("Monitor commands are not supported by this backend")
+    }
+
+    /// Reads a register of a stopped target, numbered like GDB does for ARM.
+    fn read_register(&mut self, _index: usize) -> Result<u32> {
+        bail!("Registers are not available from this backend")
+    }
+
+    /// Returns console output printed by the target since the last call.
+    fn take_console_output(&mut self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Returns protocol statistics for backends talking to a remote target.
+    fn connection_stats(&self) -> Option<ConnectionStats> {
+        None
+    }
+
+    /// Moves to a frame of a recorded trace, see [`crate::trace::TraceReplay`].
+    fn seek_frame(&mut self, _frame: usize) -> Result<()> {
+        bail!("Cannot seek while connected to a live target")
+    }
+}
+
+/// Chunks are aligned to their length, so a string near the end of a memory region is read
+/// without touching the next region.
+const CSTRING_CHUNK_LENGTH: u32 = 32;
+
+/// Reads a string forward in small chunks until a NUL byte or `max_len` bytes.
+pub(crate) fn read_cstring_chunked(
+    mut address: Addr,
+    max_len: usize,
+    mut read: impl FnMut(Addr, &mut [u8]) -> Result<()>,
+) -> Result<Vec<u8>> {
+    let mut string = Vec::new();
+    let mut chunk = [0; CSTRING_CHUNK_LENGTH as usize];
+    while string.len() < max_len {
+        let chunk_length = (CSTRING_CHUNK_LENGTH - address % CSTRING_CHUNK_LENGTH) as usize;
+        let chunk = &mut chunk[..chunk_length.min(max_len - string.len())];
+        read(address, chunk)?;
+        match chunk.iter().position(|&byte| byte == 0) {
+            Some(end) => {
+                string.extend_from_slice(&chunk[..end]);
+                break;
+            }
+            None => string.extend_from_slice(chunk),
+        }
+        address += chunk.len() as Addr;
+    }
+    Ok(string)
+}
+
+impl MemoryBackend for GdbClient {
+    fn read(&mut self, address: Addr, buf: &mut [u8]) -> Result<()> {
+        self.read_slice_binary(address, buf)
+    }
+
+    fn read_if_changed(&mut self, address: Addr, cached: &[u8], buf: &mut [u8]) -> Result<bool> {
+        GdbClient::read_if_changed(self, address, cached, buf)
+    }
+
+    fn read_cstring(&mut self, address: Addr, max_len: usize) -> Result<Vec<u8>> {
+        GdbClient::read_cstring(self, address, max_len)
+    }
+
+    fn write(&mut self, address: Addr, buf: &[u8]) -> Result<()> {
+        self.write_slice_binary(address, buf)
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.stop_execution()
+    }
+
+    fn resume(&mut self) -> Result<()> {
+        self.continue_execution()
+    }
+
+    fn is_connected(&self) -> bool {
+        GdbClient::is_connected(self)
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        GdbClient::disconnect(self)
+    }
+
+    fn monitor(&mut self, command: &str) -> Result<String> {
+        GdbClient::monitor(self, command)
+    }
+
+    fn read_register(&mut self, index: usize) -> Result<u32> {
+        GdbClient::read_register(self, index)
+    }
+
+    fn take_console_output(&mut self) -> Vec<String> {
+        GdbClient::take_console_output(self)
+    }
+
+    fn connection_stats(&self) -> Option<ConnectionStats> {
+        Some(self.stats().clone())
+    }
+}
@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+
+use crate::memory::{MemoryBackend, address::Addr, in_memory::InMemoryBackend};
+
+/// Reads memory from a file instead of a running emulator.
+///
+/// Only raw main RAM dumps are supported for now. Emulator-specific savestate formats are not
+/// parsed yet, so such files are rejected by their size.
+pub struct SavestateBackend {
+    memory: InMemoryBackend,
+}
+
+impl SavestateBackend {
+    pub const MAIN_RAM_BASE: u32 = 0x02000000;
+    pub const MAIN_RAM_SIZE: usize = 0x400000;
+
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let data = std::fs::read(path).context("Failed to read savestate file")?;
+        if data.len() != Self::MAIN_RAM_SIZE {
+            bail!(
+                "Expected a raw main RAM dump of {:#x} bytes, got {:#x} bytes",
+                Self::MAIN_RAM_SIZE,
+                data.len()
+            );
+        }
+        Ok(Self { memory: InMemoryBackend::from_bytes(Self::MAIN_RAM_BASE, data) })
+    }
+}
+
+impl MemoryBackend for SavestateBackend {
+    fn read(&mut self, address: Addr, buf: &mut [u8]) -> Result<()> {
+        self.memory.read(address, buf)
+    }
+
+    fn write(&mut self, _address: Addr, _buf: &[u8]) -> Result<()> {
+        bail!("Savestate files are read-only")
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn resume(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.memory.is_connected()
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        self.memory.disconnect()
+    }
+}
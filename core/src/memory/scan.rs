@@ -0,0 +1,262 @@
+use std::ops::Range;
+
+use crate::{
+    memory::address::Addr,
+    state::{SnapshotId, State},
+};
+
+/// A word in memory which points to or near the target of a [`PointerScan`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PointerMatch {
+    pub address: Addr,
+    /// Distance from the target to where the word points
+    pub offset: i32,
+}
+
+/// Appends the 4-byte aligned words in `data` which point within `tolerance` bytes of `target`.
+/// `base` is the address of `data`.
+pub fn find_pointers(
+    data: &[u8],
+    base: Addr,
+    target: Addr,
+    tolerance: u32,
+    matches: &mut Vec<PointerMatch>,
+) {
+    let skip = (base.next_multiple_of(4) - base) as usize;
+    let Some(data) = data.get(skip..) else {
+        return;
+    };
+    for (index, word) in data.chunks_exact(4).enumerate() {
+        let value = u32::from_le_bytes(word.try_into().unwrap());
+        let offset = value as i64 - target as i64;
+        if offset.unsigned_abs() <= tolerance as u64 {
+            let address = base + skip as Addr + index as Addr * 4;
+            matches.push(PointerMatch { address, offset: offset as i32 });
+        }
+    }
+}
+
+/// Appends the base addresses of objects which have `value` in the word at `offset`. Bases are
+/// aligned to `stride`, and only words at 4-byte aligned addresses are considered. `base` is the
+/// address of `data`.
+pub fn find_values(
+    data: &[u8],
+    base: Addr,
+    value: u32,
+    offset: u32,
+    stride: u32,
+    matches: &mut Vec<Addr>,
+) {
+    let skip = (base.next_multiple_of(4) - base) as usize;
+    let Some(data) = data.get(skip..) else {
+        return;
+    };
+    for (index, word) in data.chunks_exact(4).enumerate() {
+        if u32::from_le_bytes(word.try_into().unwrap()) != value {
+            continue;
+        }
+        let address = base + skip as Addr + index as Addr * 4;
+        if let Some(object) = address.checked_sub(offset)
+            && object.is_multiple_of(stride.max(1))
+        {
+            matches.push(object);
+        }
+    }
+}
+
+/// Reads a range of memory in chunks through [`State`], one chunk per call to [`Self::step`], so
+/// that a scan can run across frames.
+struct ChunkedRead {
+    range: Range<Addr>,
+    /// Start of the next chunk to request
+    next: Addr,
+    /// Chunk waiting to be read, its snapshot and how many steps it has waited
+    pending: Option<(Range<Addr>, SnapshotId, usize)>,
+    failed_chunks: usize,
+}
+
+impl ChunkedRead {
+    const CHUNK_SIZE: u32 = 0x4000;
+    /// Steps to wait for a chunk before giving up on it
+    const MAX_WAIT_STEPS: usize = 60;
+
+    fn new(range: Range<Addr>) -> Self {
+        let start = range.start.next_multiple_of(4);
+        Self {
+            range: start..range.end.max(start),
+            next: start,
+            pending: None,
+            failed_chunks: 0,
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.pending.is_none() && self.next >= self.range.end
+    }
+
+    fn progress(&self) -> f32 {
+        let length = self.range.len().max(1) as f32;
+        (self.next - self.range.start) as f32 / length
+    }
+
+    /// Passes the chunk requested by the previous step to `scan` if it has been read, and
+    /// requests the next one unless `scan` returns true to stop. Returns true once there are no
+    /// more chunks.
+    fn step(&mut self, state: &mut State, scan: impl FnOnce(&[u8], Addr) -> bool) -> bool {
+        if let Some((chunk, snapshot, waited)) = self.pending.take() {
+            if let Some(data) = state.get_snapshot(snapshot) {
+                if scan(data, chunk.start) {
+                    self.next = self.range.end;
+                }
+            } else if state.snapshot_failed(snapshot) || waited >= Self::MAX_WAIT_STEPS {
+                log::debug!("Skipping unreadable chunk {:#x}..{:#x}", chunk.start, chunk.end);
+                self.failed_chunks += 1;
+            } else {
+                self.pending = Some((chunk, snapshot, waited + 1));
+                return false;
+            }
+            state.release_snapshot(snapshot);
+        }
+        if self.is_done() {
+            return true;
+        }
+        let end = self.next.saturating_add(Self::CHUNK_SIZE).min(self.range.end);
+        let chunk = self.next..end;
+        self.next = end;
+        let snapshot = state.request_snapshot(chunk.start, chunk.len());
+        self.pending = Some((chunk, snapshot, 0));
+        false
+    }
+
+    fn cancel(&mut self, state: &mut State) {
+        if let Some((_, snapshot, _)) = self.pending.take() {
+            state.release_snapshot(snapshot);
+        }
+        self.next = self.range.end;
+    }
+}
+
+/// Searches a range of memory for pointers to an address, see [`find_pointers`].
+pub struct PointerScan {
+    target: Addr,
+    tolerance: u32,
+    chunks: ChunkedRead,
+    matches: Vec<PointerMatch>,
+}
+
+impl PointerScan {
+    /// Stop collecting matches past this many, as the target is then likely a common value
+    pub const MAX_MATCHES: usize = 10_000;
+
+    pub fn new(target: Addr, tolerance: u32, range: Range<Addr>) -> Self {
+        Self {
+            target,
+            tolerance,
+            chunks: ChunkedRead::new(range),
+            matches: Vec::new(),
+        }
+    }
+
+    pub fn target(&self) -> Addr {
+        self.target
+    }
+
+    pub fn tolerance(&self) -> u32 {
+        self.tolerance
+    }
+
+    pub fn matches(&self) -> &[PointerMatch] {
+        &self.matches
+    }
+
+    /// Number of chunks which couldn't be read and were skipped
+    pub fn failed_chunks(&self) -> usize {
+        self.chunks.failed_chunks
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.chunks.is_done()
+    }
+
+    /// Fraction of the range which has been scanned, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        self.chunks.progress()
+    }
+
+    /// Scans the chunk requested by the previous step if it has been read, and requests the next
+    /// one. Returns true once the whole range has been scanned.
+    pub fn step(&mut self, state: &mut State) -> bool {
+        let (target, tolerance, matches) = (self.target, self.tolerance, &mut self.matches);
+        self.chunks.step(state, |data, base| {
+            find_pointers(data, base, target, tolerance, matches);
+            matches.truncate(Self::MAX_MATCHES);
+            matches.len() >= Self::MAX_MATCHES
+        })
+    }
+
+    /// Stops the scan and drops the request of the chunk being read.
+    pub fn cancel(&mut self, state: &mut State) {
+        self.chunks.cancel(state);
+    }
+}
+
+/// Searches a range of memory for objects with a known value at an offset, like a vtable address
+/// or a magic number, see [`find_values`].
+pub struct ValueScan {
+    value: u32,
+    offset: u32,
+    stride: u32,
+    chunks: ChunkedRead,
+    matches: Vec<Addr>,
+}
+
+impl ValueScan {
+    /// Stop collecting matches past this many, as the value is then likely not unique to the type
+    pub const MAX_MATCHES: usize = 10_000;
+
+    pub fn new(value: u32, offset: u32, stride: u32, range: Range<Addr>) -> Self {
+        Self {
+            value,
+            offset,
+            stride,
+            chunks: ChunkedRead::new(range),
+            matches: Vec::new(),
+        }
+    }
+
+    /// Base addresses of the objects found so far
+    pub fn matches(&self) -> &[Addr] {
+        &self.matches
+    }
+
+    /// Number of chunks which couldn't be read and were skipped
+    pub fn failed_chunks(&self) -> usize {
+        self.chunks.failed_chunks
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.chunks.is_done()
+    }
+
+    /// Fraction of the range which has been scanned, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        self.chunks.progress()
+    }
+
+    /// Scans the chunk requested by the previous step if it has been read, and requests the next
+    /// one. Returns true once the whole range has been scanned.
+    pub fn step(&mut self, state: &mut State) -> bool {
+        let (value, offset, stride) = (self.value, self.offset, self.stride);
+        let matches = &mut self.matches;
+        self.chunks.step(state, |data, base| {
+            find_values(data, base, value, offset, stride, matches);
+            matches.truncate(Self::MAX_MATCHES);
+            matches.len() >= Self::MAX_MATCHES
+        })
+    }
+
+    /// Stops the scan and drops the request of the chunk being read.
+    pub fn cancel(&mut self, state: &mut State) {
+        self.chunks.cancel(state);
+    }
+}
@@ -1,40 +1,646 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    time::{Duration, Instant},
+};
 
 use anyhow::Result;
 
-use crate::gdb::client::GdbClient;
+use crate::memory::{MemoryBackend, address::Addr};
+
+pub mod watch;
+
+use self::watch::{Watch, WatchEvent};
+
+/// How often a requested range is read from memory. Writes through [`State::request_write`] make
+/// the requests they overlap read again in the same update, whatever their refresh rate.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum RefreshRate {
+    /// Read on every update
+    #[default]
+    Live,
+    /// Read once the interval has passed since the last read
+    Interval(Duration),
+    /// Read once, then keep the last data
+    Paused,
+}
+
+impl RefreshRate {
+    pub const SLOW: Self = Self::Interval(Duration::from_secs(1));
+
+    /// Returns whichever of the two rates reads more often.
+    pub fn fastest(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Live, _) | (_, Self::Live) => Self::Live,
+            (Self::Interval(a), Self::Interval(b)) => Self::Interval(a.min(b)),
+            (Self::Interval(interval), Self::Paused) | (Self::Paused, Self::Interval(interval)) => {
+                Self::Interval(interval)
+            }
+            (Self::Paused, Self::Paused) => Self::Paused,
+        }
+    }
+
+    fn is_due(self, last_read: Option<Instant>, now: Instant) -> bool {
+        match (self, last_read) {
+            (_, None) | (Self::Live, _) => true,
+            (Self::Interval(interval), Some(last_read)) => now - last_read >= interval,
+            (Self::Paused, Some(_)) => false,
+        }
+    }
+}
+
+struct Request {
+    length: u32,
+    refresh_rate: RefreshRate,
+    /// Update count when the request was last renewed
+    generation: u64,
+    last_read: Option<Instant>,
+    /// Set if the last read failed, in which case the data is from `last_read`
+    failed: bool,
+    /// Requests of the same group are always read in the same update, see [`State::begin_group`]
+    group: Option<u64>,
+}
+
+impl Request {
+    /// Renews the request at `address`, or adds it if there is none.
+    fn renew(
+        requests: &mut BTreeMap<Addr, Request>,
+        address: Addr,
+        length: u32,
+        refresh_rate: RefreshRate,
+        generation: u64,
+        group: Option<u64>,
+    ) {
+        let request = requests.entry(address).or_insert(Request {
+            length,
+            refresh_rate,
+            generation,
+            last_read: None,
+            failed: false,
+            group,
+        });
+        if request.length != length {
+            request.length = length;
+            request.last_read = None;
+        }
+        if request.generation == generation {
+            request.refresh_rate = request.refresh_rate.fastest(refresh_rate);
+            request.group = request.group.or(group);
+        } else {
+            request.refresh_rate = refresh_rate;
+            request.group = group;
+        }
+        request.generation = generation;
+    }
+
+    fn finish_read<T, E>(&mut self, result: &std::result::Result<T, E>, now: Instant) {
+        self.failed = result.is_err();
+        if result.is_ok() {
+            self.last_read = Some(now);
+        }
+    }
+
+    fn contains(&self, start: Addr, address: Addr) -> bool {
+        (address as u64) < start as u64 + self.length.max(1) as u64
+    }
+}
+
+/// How recent the data of the requests made while tracking is, see [`State::track_freshness`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Freshness {
+    /// Age of the oldest data, `None` if nothing has been read yet
+    pub oldest: Option<Duration>,
+    /// Number of requests whose last read failed
+    pub failed: usize,
+    pub requests: usize,
+}
+
+impl Freshness {
+    fn add(&mut self, request: &Request, now: Instant) {
+        self.requests += 1;
+        if request.failed {
+            self.failed += 1;
+        }
+        if let Some(last_read) = request.last_read {
+            let age = now.saturating_duration_since(last_read);
+            self.oldest = Some(self.oldest.map_or(age, |oldest| oldest.max(age)));
+        }
+    }
+}
+
+/// Identifies a one-off read requested with [`State::request_snapshot`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SnapshotId(u64);
+
+/// A range read once for a single caller, apart from the shared requests.
+struct Snapshot {
+    address: Addr,
+    length: usize,
+    /// `None` until the range has been read
+    data: Option<Vec<u8>>,
+    failed: bool,
+}
 
 #[derive(Default)]
 pub struct State {
-    data_objects: BTreeMap<u32, Vec<u8>>,
-    requests: BTreeMap<u32, u32>,
-    writes: Vec<(u32, Vec<u8>)>,
+    data_objects: BTreeMap<Addr, Vec<u8>>,
+    requests: BTreeMap<Addr, Request>,
+    /// NUL-terminated strings by address, read up to the request length
+    cstrings: BTreeMap<Addr, Vec<u8>>,
+    cstring_requests: BTreeMap<Addr, Request>,
+    /// Queued writes by start address. Ranges never overlap or touch, see [`Self::request_write`].
+    writes: BTreeMap<Addr, Vec<u8>>,
+    write_conflicts: u64,
+    refresh_rate: RefreshRate,
+    generation: u64,
+    /// Number of updates which changed any data
+    changes: u64,
+    /// Bytes of the requests read in the last update, see [`Self::bytes_per_update`]
+    update_bytes: usize,
+    watches: Vec<Watch>,
+    /// Most recent changes to watched ranges, oldest first
+    watch_events: VecDeque<WatchEvent>,
+    halt_on_change: bool,
+    halted: bool,
+    /// Drops every write so that memory can't be modified by accident
+    read_only: bool,
+    freshness: Option<Freshness>,
+    /// Group of the requests made from now on
+    group: Option<u64>,
+    /// Number of [`State::begin_group`] calls without a matching [`State::end_group`]
+    group_depth: u32,
+    next_group: u64,
+    /// Most bytes to read in one update, see [`State::set_read_budget`]
+    read_budget: Option<usize>,
+    snapshots: BTreeMap<SnapshotId, Snapshot>,
+    next_snapshot: u64,
 }
 
 impl State {
-    pub fn update(&mut self, gdb: &mut GdbClient) -> Result<()> {
-        for (address, data) in self.writes.drain(..) {
-            gdb.write_slice(address, &data)?;
+    /// Requests of at least this many bytes are only read again if the backend reports that they
+    /// changed, see [`MemoryBackend::read_if_changed`]. Smaller ones are cheaper to read than to
+    /// check.
+    pub const CRC_MIN_LENGTH: usize = 256;
+    const MAX_WATCH_EVENTS: usize = 1000;
+
+    pub fn update<B: MemoryBackend>(&mut self, backend: &mut B) -> Result<()> {
+        for (address, data) in std::mem::take(&mut self.writes) {
+            backend.write(address, &data)?;
+            self.invalidate(address, data.len());
         }
 
-        for (&address, &length) in self.requests.iter() {
+        // Keep reading after a failed request so one bad range doesn't stall every other window
+        let mut result = Ok(());
+        let mut changed = false;
+        let now = Instant::now();
+        let mut scratch = Vec::new();
+        self.update_bytes = 0;
+        for address in self.due_requests(now) {
+            let request = self.requests.get_mut(&address).unwrap();
+            self.update_bytes += request.length as usize;
             let buffer = self.data_objects.entry(address).or_default();
-            buffer.resize(length as usize, 0);
-            gdb.read_slice(address, buffer)?;
+            let has_data = request.last_read.is_some() && buffer.len() == request.length as usize;
+            if buffer.len() != request.length as usize {
+                buffer.resize(request.length as usize, 0);
+                changed = true;
+            }
+            scratch.clear();
+            scratch.resize(buffer.len(), 0);
+            let read = if has_data && buffer.len() >= Self::CRC_MIN_LENGTH {
+                backend.read_if_changed(address, buffer, &mut scratch)
+            } else {
+                backend.read(address, &mut scratch).map(|()| true)
+            };
+            // A failed read keeps the last data
+            if let Ok(true) = read
+                && *buffer != scratch
+            {
+                std::mem::swap(buffer, &mut scratch);
+                changed = true;
+            }
+            request.finish_read(&read, now);
+            if let Err(e) = read
+                && result.is_ok()
+            {
+                result = Err(e);
+            }
+        }
+        for snapshot in self.snapshots.values_mut() {
+            if snapshot.data.is_some() || snapshot.failed {
+                continue;
+            }
+            // Read after the due requests, so snapshots over the budget wait for a later update
+            if let Some(budget) = self.read_budget
+                && self.update_bytes > 0
+                && self.update_bytes + snapshot.length > budget
+            {
+                continue;
+            }
+            self.update_bytes += snapshot.length;
+            let mut data = vec![0; snapshot.length];
+            match backend.read(snapshot.address, &mut data) {
+                Ok(()) => {
+                    snapshot.data = Some(data);
+                    changed = true;
+                }
+                Err(e) => {
+                    snapshot.failed = true;
+                    if result.is_ok() {
+                        result = Err(e);
+                    }
+                }
+            }
+        }
+        for (&address, request) in self.cstring_requests.iter_mut() {
+            if !request.refresh_rate.is_due(request.last_read, now) {
+                continue;
+            }
+            let read = backend.read_cstring(address, request.length as usize).map(|string| {
+                if self.cstrings.get(&address) != Some(&string) {
+                    self.cstrings.insert(address, string);
+                    changed = true;
+                }
+            });
+            request.finish_read(&read, now);
+            if let Err(e) = read
+                && result.is_ok()
+            {
+                result = Err(e);
+            }
+        }
+        for watch in &mut self.watches {
+            let mut data = vec![0; watch.size];
+            if let Err(e) = backend.read(watch.address, &mut data) {
+                if result.is_ok() {
+                    result = Err(e);
+                }
+                continue;
+            }
+            if let Some(previous) = &watch.previous
+                && *previous != data
+            {
+                if self.watch_events.len() >= Self::MAX_WATCH_EVENTS {
+                    self.watch_events.pop_front();
+                }
+                self.watch_events.push_back(WatchEvent {
+                    time: now,
+                    address: watch.address,
+                    kind: watch.kind,
+                    label: watch.label.clone(),
+                    window: watch.window.clone(),
+                    old: previous.clone(),
+                    new: data.clone(),
+                });
+                self.halted |= self.halt_on_change;
+                changed = true;
+            }
+            watch.previous = Some(data);
+        }
+        self.generation += 1;
+        if changed {
+            self.changes += 1;
+        }
+
+        result
+    }
+
+    /// Marks the requests which overlap a written range as unread, so that paused and slow
+    /// requests don't keep showing the data from before the write.
+    fn invalidate(&mut self, address: Addr, length: usize) {
+        let last = (address as u64 + length.max(1) as u64 - 1).min(Addr::MAX as u64) as Addr;
+        for requests in [&mut self.requests, &mut self.cstring_requests] {
+            let overlapping = requests
+                .range_mut(..=last)
+                .filter(|(start, request)| request.contains(**start, address));
+            for (_, request) in overlapping {
+                request.last_read = None;
+            }
+        }
+    }
+
+    /// Returns the addresses of the requests to read in this update, oldest data first. A group is
+    /// read as a whole once any of its requests is due, except for paused requests which were read
+    /// before, and is left for a later update as a whole if it doesn't fit in the read budget.
+    fn due_requests(&self, now: Instant) -> Vec<Addr> {
+        let due_groups: BTreeSet<u64> = self
+            .requests
+            .values()
+            .filter(|request| request.refresh_rate.is_due(request.last_read, now))
+            .filter_map(|request| request.group)
+            .collect();
+        let mut groups: BTreeMap<u64, Vec<Addr>> = BTreeMap::new();
+        let mut units = Vec::new();
+        for (&address, request) in &self.requests {
+            match request.group {
+                Some(group)
+                    if due_groups.contains(&group)
+                        && (request.refresh_rate != RefreshRate::Paused
+                            || request.last_read.is_none()) =>
+                {
+                    groups.entry(group).or_default().push(address)
+                }
+                _ if request.refresh_rate.is_due(request.last_read, now) => {
+                    units.push(vec![address])
+                }
+                _ => {}
+            }
+        }
+        units.extend(groups.into_values());
+        // Requests which were never read come first, as `None` is less than any time
+        units.sort_by_key(|unit| unit.iter().map(|address| self.requests[address].last_read).min());
+
+        let mut bytes = 0;
+        let mut due = Vec::new();
+        for unit in units {
+            let unit_bytes: usize =
+                unit.iter().map(|address| self.requests[address].length as usize).sum();
+            // The first unit is always read so that a group larger than the budget isn't stuck
+            if let Some(budget) = self.read_budget
+                && bytes > 0
+                && bytes + unit_bytes > budget
+            {
+                continue;
+            }
+            bytes += unit_bytes;
+            due.extend(unit);
         }
+        due
+    }
+
+    /// Groups the requests made until [`Self::end_group`], so that they are read in the same
+    /// update even if their refresh rates differ. Use this for objects which must agree with each
+    /// other, like a list header and its elements. Nested groups join the outer group.
+    pub fn begin_group(&mut self) {
+        if self.group_depth == 0 {
+            self.group = Some(self.next_group);
+            self.next_group += 1;
+        }
+        self.group_depth += 1;
+    }
+
+    /// Ends the group of the matching [`Self::begin_group`]. Requests made after ending a nested
+    /// group still belong to the outer group.
+    pub fn end_group(&mut self) {
+        self.group_depth = self.group_depth.saturating_sub(1);
+        if self.group_depth == 0 {
+            self.group = None;
+        }
+    }
+
+    /// Limits how many bytes of requests are read in one update, or removes the limit. Requests
+    /// over the budget are read in a later update.
+    pub fn set_read_budget(&mut self, read_budget: Option<usize>) {
+        self.read_budget = read_budget;
+    }
+
+    /// Returns the number of updates which changed the data of any request or watch. Readers can
+    /// compare it to a previous count to only redraw when something changed.
+    pub fn changes(&self) -> u64 {
+        self.changes
+    }
+
+    /// Returns the number of bytes of the requests which were due in the last update, including
+    /// reads which were skipped because the CRC of the range hadn't changed.
+    pub fn bytes_per_update(&self) -> usize {
+        self.update_bytes
+    }
 
-        Ok(())
+    /// Requests `length` bytes at `address` to be read on every update, or as often as the current
+    /// refresh rate says. If several callers request the same address between two updates, the
+    /// fastest refresh rate is used.
+    pub fn request(&mut self, address: Addr, length: usize) {
+        Request::renew(
+            &mut self.requests,
+            address,
+            length as u32,
+            self.refresh_rate,
+            self.generation,
+            self.group,
+        );
+        if let Some(freshness) = &mut self.freshness {
+            freshness.add(&self.requests[&address], Instant::now());
+        }
     }
 
-    pub fn request(&mut self, address: u32, length: usize) {
-        self.requests.insert(address, length as u32);
+    /// Like [`Self::request`], but reads a NUL-terminated string of at most `max_len` bytes. Use
+    /// [`Self::get_cstring`] to get the result.
+    pub fn request_cstring(&mut self, address: Addr, max_len: usize) {
+        Request::renew(
+            &mut self.cstring_requests,
+            address,
+            max_len as u32,
+            self.refresh_rate,
+            self.generation,
+            None,
+        );
+        if let Some(freshness) = &mut self.freshness {
+            freshness.add(&self.cstring_requests[&address], Instant::now());
+        }
     }
 
-    pub fn request_write(&mut self, address: u32, data: Vec<u8>) {
-        self.writes.push((address, data));
+    /// Returns the start and length of every requested range, including strings.
+    pub fn requested_ranges(&self) -> impl Iterator<Item = (Addr, usize)> {
+        self.requests
+            .iter()
+            .chain(self.cstring_requests.iter())
+            .map(|(&address, request)| (address, request.length as usize))
     }
 
-    pub fn get_data(&self, address: u32) -> Option<&[u8]> {
+    /// Requests `length` bytes at `address` to be read once, for data which is only needed once
+    /// like the chunks of a scan. Unlike [`Self::request`], the read belongs to the caller alone,
+    /// so it doesn't change the requests of windows showing the same range.
+    pub fn request_snapshot(&mut self, address: Addr, length: usize) -> SnapshotId {
+        let id = SnapshotId(self.next_snapshot);
+        self.next_snapshot += 1;
+        self.snapshots.insert(id, Snapshot { address, length, data: None, failed: false });
+        id
+    }
+
+    /// Returns the data of a snapshot once it has been read.
+    pub fn get_snapshot(&self, id: SnapshotId) -> Option<&[u8]> {
+        self.snapshots.get(&id)?.data.as_deref()
+    }
+
+    /// Returns true if reading a snapshot failed, in which case it isn't read again.
+    pub fn snapshot_failed(&self, id: SnapshotId) -> bool {
+        self.snapshots.get(&id).is_some_and(|snapshot| snapshot.failed)
+    }
+
+    /// Drops a snapshot and its data, whether or not it has been read.
+    pub fn release_snapshot(&mut self, id: SnapshotId) {
+        self.snapshots.remove(&id);
+    }
+
+    /// Starts summarizing the requests made from now on if `freshness` is set, and returns the
+    /// summary so far. Like [`Self::set_refresh_rate`], windows swap in their own summary while
+    /// they are rendered.
+    pub fn track_freshness(&mut self, freshness: Option<Freshness>) -> Option<Freshness> {
+        std::mem::replace(&mut self.freshness, freshness)
+    }
+
+    /// Returns how long ago the data at `address` was last read, by the request which contains
+    /// it. Returns `None` if the address isn't requested or hasn't been read yet.
+    pub fn get_age(&self, address: Addr) -> Option<Duration> {
+        let last_read = self.find_request(address)?.last_read?;
+        Some(Instant::now().saturating_duration_since(last_read))
+    }
+
+    /// Returns true if the last read of the request containing `address` failed.
+    pub fn read_failed(&self, address: Addr) -> bool {
+        self.find_request(address).is_some_and(|request| request.failed)
+    }
+
+    fn find_request(&self, address: Addr) -> Option<&Request> {
+        fn find(requests: &BTreeMap<Addr, Request>, address: Addr) -> Option<&Request> {
+            requests
+                .range(..=address)
+                .rev()
+                .find_map(|(&start, request)| request.contains(start, address).then_some(request))
+        }
+        find(&self.requests, address).or_else(|| find(&self.cstring_requests, address))
+    }
+
+    /// Sets the refresh rate of subsequent requests and returns the previous one, so that windows
+    /// can apply their refresh rate to every request made while rendering them.
+    pub fn set_refresh_rate(&mut self, refresh_rate: RefreshRate) -> RefreshRate {
+        std::mem::replace(&mut self.refresh_rate, refresh_rate)
+    }
+
+    /// Queues a write for the next update. Writes which overlap or touch queued writes are merged
+    /// into one, and the latest write wins where they overlap. Overlaps are counted as conflicts.
+    pub fn request_write(&mut self, address: Addr, data: Vec<u8>) {
+        if data.is_empty() {
+            return;
+        }
+        if self.read_only {
+            log::debug!("Dropped write to {address:#x} in read-only mode");
+            return;
+        }
+        let start = address as u64;
+        let end = start + data.len() as u64;
+
+        let mut merged_start = start;
+        let mut merged_end = end;
+        let mut conflict = false;
+        let touching: Vec<Addr> = self
+            .writes
+            .range(..=end.min(Addr::MAX as u64) as Addr)
+            .filter(|&(&queued, queued_data)| queued as u64 + queued_data.len() as u64 >= start)
+            .map(|(&queued, _)| queued)
+            .collect();
+        let queued_writes: Vec<(Addr, Vec<u8>)> =
+            touching.into_iter().filter_map(|queued| self.writes.remove_entry(&queued)).collect();
+        for (queued, queued_data) in &queued_writes {
+            let queued_start = *queued as u64;
+            let queued_end = queued_start + queued_data.len() as u64;
+            conflict |= queued_start < end && queued_end > start;
+            merged_start = merged_start.min(queued_start);
+            merged_end = merged_end.max(queued_end);
+        }
+
+        let mut merged = vec![0; (merged_end - merged_start) as usize];
+        for (queued, queued_data) in &queued_writes {
+            let offset = (*queued as u64 - merged_start) as usize;
+            merged[offset..offset + queued_data.len()].copy_from_slice(queued_data);
+        }
+        let offset = (start - merged_start) as usize;
+        merged[offset..offset + data.len()].copy_from_slice(&data);
+        self.writes.insert(merged_start as Addr, merged);
+
+        if conflict {
+            log::warn!("Conflicting writes to {address:#x}..{end:#x}, keeping the latest");
+            self.write_conflicts += 1;
+        }
+    }
+
+    pub fn pending_writes(&self) -> impl Iterator<Item = (Addr, &[u8])> {
+        self.writes.iter().map(|(&address, data)| (address, data.as_slice()))
+    }
+
+    /// Makes [`Self::request_write`] drop every write. Writes which are already queued are dropped
+    /// as well when enabling it.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+        if read_only {
+            self.writes.clear();
+        }
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Number of writes which overlapped a queued write since the state was created.
+    pub fn write_conflicts(&self) -> u64 {
+        self.write_conflicts
+    }
+
+    /// Adds a range to compare after every update, replacing any watch of the same range.
+    pub fn add_watch(&mut self, watch: Watch) {
+        self.remove_watch(watch.address, watch.size);
+        self.watches.push(watch);
+    }
+
+    pub fn remove_watch(&mut self, address: Addr, size: usize) {
+        self.watches.retain(|watch| watch.address != address || watch.size != size);
+    }
+
+    pub fn is_watched(&self, address: Addr, size: usize) -> bool {
+        self.watches.iter().any(|watch| watch.address == address && watch.size == size)
+    }
+
+    pub fn watches(&self) -> &[Watch] {
+        &self.watches
+    }
+
+    /// Removes every watch and lets updates continue if a watch had halted them.
+    pub fn clear_watches(&mut self) {
+        self.watches.clear();
+        self.halted = false;
+    }
+
+    pub fn watch_events(&self) -> impl DoubleEndedIterator<Item = &WatchEvent> {
+        self.watch_events.iter()
+    }
+
+    pub fn clear_watch_events(&mut self) {
+        self.watch_events.clear();
+    }
+
+    /// Whether a changed watch should halt updates, see [`Self::is_halted`].
+    pub fn set_halt_on_change(&mut self, halt_on_change: bool) {
+        self.halt_on_change = halt_on_change;
+    }
+
+    pub fn halt_on_change(&self) -> bool {
+        self.halt_on_change
+    }
+
+    /// Returns true if a watch changed while halting on change was enabled. The update loop
+    /// should then leave the target stopped until [`Self::continue_updates`] is called.
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    pub fn continue_updates(&mut self) {
+        self.halted = false;
+    }
+
+    pub fn get_data(&self, address: Addr) -> Option<&[u8]> {
         self.data_objects.get(&address).map(|v| v.as_slice())
     }
+
+    /// Returns `length` bytes at `address` from any read block which contains them, so that a part
+    /// of a requested object can be read without requesting it separately.
+    pub fn get_data_range(&self, address: Addr, length: usize) -> Option<&[u8]> {
+        let end = address as u64 + length as u64;
+        self.data_objects.range(..=address).rev().find_map(|(&start, data)| {
+            let offset = (address - start) as usize;
+            (start as u64 + data.len() as u64 >= end).then(|| &data[offset..offset + length])
+        })
+    }
+
+    /// Returns the last string read at `address`, excluding the NUL.
+    pub fn get_cstring(&self, address: Addr) -> Option<&[u8]> {
+        self.cstrings.get(&address).map(|v| v.as_slice())
+    }
 }
@@ -0,0 +1,67 @@
+use std::time::Instant;
+
+use crate::memory::address::Addr;
+
+/// How the bytes of a watched range are shown in events.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WatchKind {
+    Unsigned,
+    Signed,
+    Float,
+    Bool,
+}
+
+impl WatchKind {
+    pub fn format(self, data: &[u8]) -> String {
+        let mut buf = [0; 8];
+        let length = data.len().min(8);
+        buf[..length].copy_from_slice(&data[..length]);
+        let value = u64::from_le_bytes(buf);
+        match self {
+            WatchKind::Unsigned => format!("{value} ({value:#x})"),
+            WatchKind::Signed => {
+                let shift = 64 - length.max(1) as u32 * 8;
+                format!("{}", ((value << shift) as i64) >> shift)
+            }
+            WatchKind::Float if length == 4 => format!("{}", f32::from_bits(value as u32)),
+            WatchKind::Float if length == 8 => format!("{}", f64::from_bits(value)),
+            WatchKind::Float => format!("{value:#x}"),
+            WatchKind::Bool => format!("{}", value != 0),
+        }
+    }
+}
+
+/// A memory range which is compared to its previous contents after every update.
+#[derive(Clone, Debug)]
+pub struct Watch {
+    pub address: Addr,
+    pub size: usize,
+    pub kind: WatchKind,
+    pub label: String,
+    /// Key of the window the watch was added from, if any
+    pub window: Option<String>,
+    pub(super) previous: Option<Vec<u8>>,
+}
+
+impl Watch {
+    pub fn new(address: Addr, size: usize, kind: WatchKind, label: String) -> Self {
+        Self { address, size, kind, label, window: None, previous: None }
+    }
+
+    pub fn with_window(mut self, window: &str) -> Self {
+        self.window = Some(window.to_string());
+        self
+    }
+}
+
+/// A change to a watched range, seen between two updates.
+#[derive(Clone, Debug)]
+pub struct WatchEvent {
+    pub time: Instant,
+    pub address: Addr,
+    pub kind: WatchKind,
+    pub label: String,
+    pub window: Option<String>,
+    pub old: Vec<u8>,
+    pub new: Vec<u8>,
+}
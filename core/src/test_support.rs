@@ -0,0 +1,14 @@
+use type_crawler::Types;
+
+use crate::headers::create_crawler;
+
+/// Parses `source` as a header named `file_name` for a 32-bit target like the DS. The header is
+/// written to a temporary directory of its own, so tests running in parallel don't share it.
+pub fn parse_header(file_name: &str, source: &str) -> Types {
+    let dir = tempfile::tempdir().unwrap();
+    let header = dir.path().join(file_name);
+    std::fs::write(&header, source).unwrap();
+
+    let mut crawler = create_crawler::<&str>(&[], false).unwrap();
+    crawler.parse_file(&header).unwrap()
+}
@@ -0,0 +1,304 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, bail};
+
+use crate::{
+    gdb::stats::ConnectionStats,
+    memory::{MemoryBackend, address::Addr},
+};
+
+const MAGIC: &[u8; 4] = b"DSVT";
+const VERSION: u8 = 1;
+
+const TAG_FRAME: u8 = 0;
+const TAG_READ: u8 = 1;
+const TAG_WRITE: u8 = 2;
+
+/// Appends memory reads and writes to a compact binary trace.
+///
+/// A trace starts with a header containing the gamecode, followed by a stream of records. Each
+/// frame record carries a timestamp and is followed by the reads and writes made in that frame.
+pub struct TraceWriter<W: Write = BufWriter<File>> {
+    writer: W,
+    start: Instant,
+}
+
+impl TraceWriter {
+    pub fn create<P: AsRef<Path>>(path: P, gamecode: &str) -> Result<Self> {
+        let file = File::create(path).context("Failed to create trace file")?;
+        Self::new(BufWriter::new(file), gamecode)
+    }
+}
+
+impl<W: Write> TraceWriter<W> {
+    pub fn new(mut writer: W, gamecode: &str) -> Result<Self> {
+        let gamecode_len: u8 = gamecode.len().try_into().context("Gamecode is too long")?;
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION, gamecode_len])?;
+        writer.write_all(gamecode.as_bytes())?;
+        Ok(Self { writer, start: Instant::now() })
+    }
+
+    pub fn begin_frame(&mut self) -> Result<()> {
+        let timestamp = self.start.elapsed().as_micros() as u64;
+        self.writer.write_all(&[TAG_FRAME])?;
+        self.writer.write_all(&timestamp.to_le_bytes())?;
+        Ok(())
+    }
+
+    pub fn record_read(&mut self, address: u32, data: &[u8]) -> Result<()> {
+        self.record(TAG_READ, address, data)
+    }
+
+    pub fn record_write(&mut self, address: u32, data: &[u8]) -> Result<()> {
+        self.record(TAG_WRITE, address, data)
+    }
+
+    fn record(&mut self, tag: u8, address: u32, data: &[u8]) -> Result<()> {
+        self.writer.write_all(&[tag])?;
+        self.writer.write_all(&address.to_le_bytes())?;
+        self.writer.write_all(&(data.len() as u32).to_le_bytes())?;
+        self.writer.write_all(data)?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush().context("Failed to flush trace")
+    }
+
+    pub fn into_inner(mut self) -> Result<W> {
+        self.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Forwards memory accesses to another backend while recording them to a trace.
+pub struct RecordingBackend<'a, B: MemoryBackend, W: Write> {
+    pub backend: &'a mut B,
+    pub writer: &'a mut TraceWriter<W>,
+}
+
+impl<B: MemoryBackend, W: Write> MemoryBackend for RecordingBackend<'_, B, W> {
+    fn read(&mut self, address: Addr, buf: &mut [u8]) -> Result<()> {
+        self.backend.read(address, buf)?;
+        self.writer.record_read(address, buf)
+    }
+
+    /// Unchanged ranges aren't recorded, a replay serves them from the frame which last read them.
+    fn read_if_changed(&mut self, address: Addr, cached: &[u8], buf: &mut [u8]) -> Result<bool> {
+        let read = self.backend.read_if_changed(address, cached, buf)?;
+        if read {
+            self.writer.record_read(address, buf)?;
+        }
+        Ok(read)
+    }
+
+    fn write(&mut self, address: Addr, buf: &[u8]) -> Result<()> {
+        self.backend.write(address, buf)?;
+        self.writer.record_write(address, buf)
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.backend.stop()
+    }
+
+    fn resume(&mut self) -> Result<()> {
+        self.backend.resume()
+    }
+
+    fn is_connected(&self) -> bool {
+        self.backend.is_connected()
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        self.backend.disconnect()
+    }
+
+    fn monitor(&mut self, command: &str) -> Result<String> {
+        self.backend.monitor(command)
+    }
+
+    fn read_register(&mut self, index: usize) -> Result<u32> {
+        self.backend.read_register(index)
+    }
+
+    fn take_console_output(&mut self) -> Vec<String> {
+        self.backend.take_console_output()
+    }
+
+    fn connection_stats(&self) -> Option<ConnectionStats> {
+        self.backend.connection_stats()
+    }
+
+    fn seek_frame(&mut self, frame: usize) -> Result<()> {
+        self.backend.seek_frame(frame)
+    }
+}
+
+pub struct TraceFrame {
+    pub timestamp: Duration,
+    pub reads: Vec<(u32, Vec<u8>)>,
+    pub writes: Vec<(u32, Vec<u8>)>,
+}
+
+/// Serves memory reads from a recorded trace. Reads are answered from the current frame, or
+/// from the latest earlier frame that recorded the requested range.
+pub struct TraceReplay {
+    gamecode: String,
+    frames: Vec<TraceFrame>,
+    current: usize,
+    connected: bool,
+}
+
+impl TraceReplay {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path).context("Failed to open trace file")?;
+        Self::from_reader(BufReader::new(file))
+    }
+
+    pub fn from_reader<R: Read + Seek>(mut reader: R) -> Result<Self> {
+        let start = reader.stream_position()?;
+        let end = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(start))?;
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic).context("Failed to read trace header")?;
+        if &magic != MAGIC {
+            bail!("Not a dsv trace file");
+        }
+        let mut header = [0; 2];
+        reader.read_exact(&mut header).context("Failed to read trace header")?;
+        let [version, gamecode_len] = header;
+        if version != VERSION {
+            bail!("Unsupported trace version {version}");
+        }
+        let mut gamecode = vec![0; gamecode_len as usize];
+        reader.read_exact(&mut gamecode).context("Failed to read trace gamecode")?;
+        let gamecode = String::from_utf8(gamecode).context("Invalid trace gamecode")?;
+
+        let mut frames: Vec<TraceFrame> = Vec::new();
+        loop {
+            let mut tag = [0; 1];
+            match reader.read_exact(&mut tag) {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e).context("Failed to read trace record"),
+            }
+            let record = match tag[0] {
+                TAG_FRAME => Self::read_u64(&mut reader).map(|timestamp| {
+                    frames.push(TraceFrame {
+                        timestamp: Duration::from_micros(timestamp),
+                        reads: Vec::new(),
+                        writes: Vec::new(),
+                    });
+                }),
+                TAG_READ | TAG_WRITE => Self::read_access(&mut reader, end).and_then(|access| {
+                    let Some(frame) = frames.last_mut() else {
+                        bail!("Trace record found before the first frame");
+                    };
+                    if tag[0] == TAG_READ {
+                        frame.reads.push(access);
+                    } else {
+                        frame.writes.push(access);
+                    }
+                    Ok(())
+                }),
+                tag => bail!("Unknown trace record tag {tag:#x}"),
+            };
+            if let Err(e) = record {
+                // Recordings cut off by a crash end in a partial record, keep what we have
+                log::warn!("Trace ended with an incomplete record: {e}");
+                break;
+            }
+        }
+
+        Ok(Self { gamecode, frames, current: 0, connected: true })
+    }
+
+    fn read_u64<R: Read>(reader: &mut R) -> Result<u64> {
+        let mut buf = [0; 8];
+        reader.read_exact(&mut buf)?;
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    /// `end` is the length of the trace, which bounds the length of the record.
+    fn read_access<R: Read + Seek>(reader: &mut R, end: u64) -> Result<(u32, Vec<u8>)> {
+        let mut buf = [0; 8];
+        reader.read_exact(&mut buf)?;
+        let address = u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]);
+        let length = u32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]);
+        // Don't trust the length of a corrupt record with the allocation
+        let left = end.saturating_sub(reader.stream_position()?);
+        if length as u64 > left {
+            bail!("Record of {length:#x} bytes is longer than the rest of the trace");
+        }
+        let mut data = vec![0; length as usize];
+        reader.read_exact(&mut data)?;
+        Ok((address, data))
+    }
+
+    pub fn gamecode(&self) -> &str {
+        &self.gamecode
+    }
+
+    pub fn frames(&self) -> &[TraceFrame] {
+        &self.frames
+    }
+
+    pub fn current_frame(&self) -> usize {
+        self.current
+    }
+
+    pub fn seek(&mut self, frame: usize) {
+        self.current = frame.min(self.frames.len().saturating_sub(1));
+    }
+}
+
+impl MemoryBackend for TraceReplay {
+    fn read(&mut self, address: Addr, buf: &mut [u8]) -> Result<()> {
+        let end = address as u64 + buf.len() as u64;
+        let frames = &self.frames[..(self.current + 1).min(self.frames.len())];
+        for frame in frames.iter().rev() {
+            let read = frame.reads.iter().rev().find(|(read_address, data)| {
+                *read_address <= address && *read_address as u64 + data.len() as u64 >= end
+            });
+            if let Some((read_address, data)) = read {
+                let start = (address - read_address) as usize;
+                buf.copy_from_slice(&data[start..start + buf.len()]);
+                return Ok(());
+            }
+        }
+        bail!("No recorded data for {:#x}..{:#x}", address, end)
+    }
+
+    fn write(&mut self, _address: u32, _buf: &[u8]) -> Result<()> {
+        bail!("Cannot write to a replayed trace")
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn resume(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        self.connected = false;
+        Ok(())
+    }
+
+    fn seek_frame(&mut self, frame: usize) -> Result<()> {
+        self.seek(frame);
+        Ok(())
+    }
+}
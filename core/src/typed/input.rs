@@ -0,0 +1,60 @@
+use std::ops::RangeInclusive;
+
+/// Parses an integer typed by the user into the little-endian bytes of a `size`-byte integer.
+/// Input with a `0x` prefix is the raw value, other input is decimal and may be negative if
+/// `signed` is set. Returns `None` if the text doesn't parse or the value doesn't fit.
+pub fn parse_int_input(text: &str, size: usize, signed: bool) -> Option<Vec<u8>> {
+    if !(1..=8).contains(&size) {
+        return None;
+    }
+    let bits = size as u32 * 8;
+    let max_unsigned = u64::MAX >> (64 - bits);
+    let text = text.trim();
+    let value = if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        u64::from_str_radix(hex, 16).ok().filter(|&value| value <= max_unsigned)?
+    } else if signed {
+        let value = text.parse::<i64>().ok()?;
+        let min = i64::MIN >> (64 - bits);
+        let max = i64::MAX >> (64 - bits);
+        if !(min..=max).contains(&value) {
+            return None;
+        }
+        value as u64
+    } else {
+        text.parse::<u64>().ok().filter(|&value| value <= max_unsigned)?
+    };
+    Some(value.to_le_bytes()[..size].to_vec())
+}
+
+/// Returns the values that a `size`-byte integer can hold, or `None` for sizes above 8 bytes.
+pub fn int_range(size: usize, signed: bool) -> Option<RangeInclusive<i128>> {
+    if !(1..=8).contains(&size) {
+        return None;
+    }
+    let bits = size as u32 * 8;
+    Some(if signed {
+        -(1i128 << (bits - 1))..=(1i128 << (bits - 1)) - 1
+    } else {
+        0..=(1i128 << bits) - 1
+    })
+}
+
+/// Formats the little-endian bytes of an integer as decimal, which is negative if `signed` is set
+/// and the sign bit is, or as hex of the raw value. Returns `None` for sizes above 8 bytes.
+pub fn format_int(data: &[u8], signed: bool, hex: bool) -> Option<String> {
+    if !(1..=8).contains(&data.len()) {
+        return None;
+    }
+    let mut bytes = [0; 8];
+    bytes[..data.len()].copy_from_slice(data);
+    let value = u64::from_le_bytes(bytes);
+    let shift = 64 - data.len() as u32 * 8;
+    Some(if hex {
+        format!("{value:#x}")
+    } else if signed {
+        // Shift the sign bit to the top and back to sign-extend it
+        (((value << shift) as i64) >> shift).to_string()
+    } else {
+        value.to_string()
+    })
+}
@@ -0,0 +1,150 @@
+use std::ops::Range;
+
+use type_crawler::Types;
+
+/// Bytes of a struct which no field covers, and fields which overlap each other.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StructLayout {
+    /// Byte ranges which no field covers, in ascending order
+    pub holes: Vec<Range<usize>>,
+    /// Indices of fields which overlap another field, in ascending order
+    pub overlapping: Vec<usize>,
+}
+
+impl StructLayout {
+    /// `fields` are the bit ranges of the fields and base classes of a struct which is `size`
+    /// bytes long. Bytes are only holes if none of their bits belong to a field, so unused bits
+    /// of a bit-field unit aren't reported.
+    pub fn new(fields: &[Range<usize>], size: usize) -> Self {
+        let mut order: Vec<usize> =
+            (0..fields.len()).filter(|&index| !fields[index].is_empty()).collect();
+        order.sort_by_key(|&index| (fields[index].start, fields[index].end));
+
+        let mut holes = Vec::new();
+        let mut overlapping = Vec::new();
+        let mut covered_bytes = 0;
+        // Field which ends last so far, by bit
+        let mut last: Option<usize> = None;
+        for index in order {
+            let bits = &fields[index];
+            let start_byte = bits.start / 8;
+            if start_byte > covered_bytes {
+                holes.push(covered_bytes..start_byte);
+            }
+            covered_bytes = covered_bytes.max(bits.end.div_ceil(8));
+
+            match last {
+                Some(last_index) if fields[last_index].end > bits.start => {
+                    overlapping.push(last_index);
+                    overlapping.push(index);
+                    if bits.end > fields[last_index].end {
+                        last = Some(index);
+                    }
+                }
+                _ => last = Some(index),
+            }
+        }
+        if size > covered_bytes {
+            holes.push(covered_bytes..size);
+        }
+        overlapping.sort_unstable();
+        overlapping.dedup();
+
+        Self { holes, overlapping }
+    }
+
+    pub fn is_overlapping(&self, index: usize) -> bool {
+        self.overlapping.binary_search(&index).is_ok()
+    }
+}
+
+pub trait NaturalAlignment {
+    fn natural_alignment(&self, types: &Types) -> usize;
+}
+
+impl NaturalAlignment for type_crawler::TypeKind {
+    fn natural_alignment(&self, types: &Types) -> usize {
+        match self {
+            type_crawler::TypeKind::USize { .. }
+            | type_crawler::TypeKind::SSize { .. }
+            | type_crawler::TypeKind::U64
+            | type_crawler::TypeKind::U32
+            | type_crawler::TypeKind::U16
+            | type_crawler::TypeKind::U8
+            | type_crawler::TypeKind::S64
+            | type_crawler::TypeKind::S32
+            | type_crawler::TypeKind::S16
+            | type_crawler::TypeKind::S8
+            | type_crawler::TypeKind::F32
+            | type_crawler::TypeKind::F64
+            | type_crawler::TypeKind::LongDouble { .. }
+            | type_crawler::TypeKind::Char16
+            | type_crawler::TypeKind::Char32
+            | type_crawler::TypeKind::WChar { .. }
+            | type_crawler::TypeKind::Bool
+            | type_crawler::TypeKind::Reference { .. }
+            | type_crawler::TypeKind::Pointer { .. }
+            | type_crawler::TypeKind::MemberPointer { .. } => self.size(types).max(1),
+            type_crawler::TypeKind::Void => 1,
+            type_crawler::TypeKind::Function { .. } => 1,
+            type_crawler::TypeKind::Array { element_type, .. } => {
+                element_type.natural_alignment(types)
+            }
+            type_crawler::TypeKind::Struct(struct_decl)
+            | type_crawler::TypeKind::Class(struct_decl) => {
+                let base_alignment = struct_decl
+                    .base_types()
+                    .iter()
+                    .filter_map(|base_type| types.get(base_type))
+                    .map(|base_type| base_type.natural_alignment(types));
+                let field_alignment =
+                    struct_decl.fields().iter().map(|field| field.kind().natural_alignment(types));
+                base_alignment.chain(field_alignment).max().unwrap_or(1)
+            }
+            type_crawler::TypeKind::Union(union_decl) => union_decl
+                .fields()
+                .iter()
+                .map(|field| field.kind().natural_alignment(types))
+                .max()
+                .unwrap_or(1),
+            type_crawler::TypeKind::Enum(enum_decl) => enum_decl.size().max(1),
+            type_crawler::TypeKind::Typedef(typedef) => {
+                typedef.underlying_type().natural_alignment(types)
+            }
+            type_crawler::TypeKind::Named(name) => {
+                types.get(name).map(|ty| ty.natural_alignment(types)).unwrap_or(1)
+            }
+        }
+    }
+}
+
+pub struct BaseType<'t> {
+    pub name: &'t str,
+    pub ty: Option<&'t type_crawler::TypeKind>,
+    /// Byte range in the derived struct, empty if the type wasn't found
+    pub range: Range<usize>,
+}
+
+/// Returns the base types of a struct, which are laid out one after another from the start.
+pub fn base_types<'t>(
+    struct_decl: &'t type_crawler::StructDecl,
+    types: &'t Types,
+) -> Vec<BaseType<'t>> {
+    let mut end: usize = 0;
+    struct_decl
+        .base_types()
+        .iter()
+        .map(|name| {
+            let ty = types.get(name);
+            let range = match ty {
+                Some(ty) => {
+                    let start = end.next_multiple_of(ty.natural_alignment(types));
+                    end = start + ty.size(types);
+                    start..end
+                }
+                None => end..end,
+            };
+            BaseType { name, ty, range }
+        })
+        .collect()
+}
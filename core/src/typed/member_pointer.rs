@@ -0,0 +1,102 @@
+use std::fmt::Display;
+
+/// A decoded pointer-to-member. Member pointers are offsets into an object rather than
+/// addresses, so they can't be dereferenced on their own.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MemberPointer {
+    Null,
+    /// Offset of a data member from the start of the object
+    Data {
+        offset: u32,
+    },
+    /// A member function, called on the object adjusted by `this_delta` bytes
+    Function {
+        this_delta: i32,
+        target: MemberFunction,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MemberFunction {
+    Address(u32),
+    /// Called through the vtable pointer at `vptr_offset` in the adjusted object
+    Virtual {
+        vptr_offset: i32,
+        entry_offset: i32,
+    },
+}
+
+impl MemberPointer {
+    /// Decodes a member pointer by its size:
+    /// - 4 bytes: data member as stored by Metrowerks, the offset plus one so that NULL is zero
+    /// - 8 bytes: member function in the ARM C++ ABI, `{ ptr, adj }` where the low bit of `adj`
+    ///   marks `ptr` as a vtable offset
+    /// - 12 bytes: member function as stored by Metrowerks, `{ this_delta, vtbl_offset, func }`
+    ///   where a negative `vtbl_offset` marks `func` as an address
+    ///
+    /// Returns `None` for other sizes.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let word = |index: usize| -> i32 {
+            let bytes = data[index * 4..index * 4 + 4].try_into().unwrap();
+            i32::from_le_bytes(bytes)
+        };
+        let member_pointer = match data.len() {
+            4 => match word(0) {
+                0 => Self::Null,
+                value => Self::Data { offset: value as u32 - 1 },
+            },
+            8 => {
+                let (ptr, adj) = (word(0), word(1));
+                if adj & 1 != 0 {
+                    let target = MemberFunction::Virtual { vptr_offset: 0, entry_offset: ptr };
+                    Self::Function { this_delta: adj >> 1, target }
+                } else if ptr == 0 {
+                    Self::Null
+                } else {
+                    let target = MemberFunction::Address(ptr as u32);
+                    Self::Function { this_delta: adj >> 1, target }
+                }
+            }
+            12 => {
+                let (this_delta, vtbl_offset, func) = (word(0), word(1), word(2));
+                if vtbl_offset >= 0 {
+                    let target =
+                        MemberFunction::Virtual { vptr_offset: vtbl_offset, entry_offset: func };
+                    Self::Function { this_delta, target }
+                } else if func == 0 {
+                    Self::Null
+                } else {
+                    Self::Function { this_delta, target: MemberFunction::Address(func as u32) }
+                }
+            }
+            _ => return None,
+        };
+        Some(member_pointer)
+    }
+}
+
+impl Display for MemberPointer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemberPointer::Null => write!(f, "NULL"),
+            MemberPointer::Data { offset } => write!(f, "+{offset:#x}"),
+            MemberPointer::Function { this_delta, target } => {
+                match target {
+                    MemberFunction::Address(address) => write!(f, "{address:#010x}")?,
+                    MemberFunction::Virtual { vptr_offset, entry_offset } => {
+                        write!(f, "vtable[{entry_offset:#x}]")?;
+                        if *vptr_offset != 0 {
+                            write!(f, " at +{vptr_offset:#x}")?;
+                        }
+                    }
+                }
+                match this_delta {
+                    0 => {}
+                    1.. => write!(f, ", this+{this_delta:#x}")?,
+                    _ => write!(f, ", this-{:#x}", this_delta.unsigned_abs())?,
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -3,12 +3,14 @@ use std::fmt::Display;
 use anyhow::Result;
 use bytemuck::{Pod, Zeroable};
 
-use crate::gdb::client::GdbClient;
+use crate::{gdb::client::GdbClient, types::mirror::mirrored_type};
 
 #[repr(C)]
 #[derive(Default, Clone, Copy, Pod, Zeroable)]
 pub struct Fx16(pub i16);
 
+mirrored_type!(Fx16 = "fx16" {});
+
 impl Fx16 {
     pub fn to_f32(&self) -> f32 {
         self.0 as f32 / 4096.0
@@ -25,6 +27,8 @@ impl Display for Fx16 {
 #[derive(Default, Clone, Copy, Pod, Zeroable)]
 pub struct Fx32(pub i32);
 
+mirrored_type!(Fx32 = "fx32" {});
+
 impl Fx32 {
     pub fn to_f32(&self) -> f32 {
         self.0 as f32 / 4096.0
@@ -45,6 +49,8 @@ pub struct Vec3p {
     pub z: Fx32,
 }
 
+mirrored_type!(Vec3p = "Vec3p" { x, y, z });
+
 impl Vec3p {
     pub fn read(&mut self, gdb: &mut GdbClient, address: u32) -> Result<()> {
         let mut buf = [0u8; 12];
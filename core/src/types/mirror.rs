@@ -0,0 +1,115 @@
+//! Checks that the POD structs of this crate still match the types in the game's headers which
+//! they mirror, so that drift between the two is noticed.
+
+use std::fmt::Display;
+
+use bytemuck::Pod;
+
+/// A POD struct which mirrors a type in the game's headers, declared with [`mirrored_type!`].
+pub trait MirroredType: Pod {
+    /// Name of the mirrored type in the headers
+    const TYPE_NAME: &'static str;
+    /// Byte offsets of the fields which must match, by field name
+    const FIELDS: &'static [(&'static str, usize)];
+}
+
+/// Implements [`MirroredType`] for a struct, taking the offsets of the listed fields from the
+/// struct itself, e.g. `mirrored_type!(Vec3p = "Vec3p" { x, y, z });`.
+macro_rules! mirrored_type {
+    ($ty:ty = $type_name:literal { $($field:ident),* $(,)? }) => {
+        impl $crate::types::mirror::MirroredType for $ty {
+            const TYPE_NAME: &'static str = $type_name;
+            const FIELDS: &'static [(&'static str, usize)] =
+                &[$((stringify!($field), std::mem::offset_of!($ty, $field))),*];
+        }
+    };
+}
+pub(crate) use mirrored_type;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LayoutMismatch {
+    Size { expected: usize, actual: usize },
+    MissingField(&'static str),
+    FieldOffset { field: &'static str, expected: usize, actual: usize },
+}
+
+impl Display for LayoutMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutMismatch::Size { expected, actual } => {
+                write!(f, "Size is {actual:#x} instead of {expected:#x}")
+            }
+            LayoutMismatch::MissingField(field) => write!(f, "Field '{field}' is missing"),
+            LayoutMismatch::FieldOffset { field, expected, actual } => {
+                write!(f, "Field '{field}' is at {actual:#x} instead of {expected:#x}")
+            }
+        }
+    }
+}
+
+/// Outcome of comparing a POD struct to the type it mirrors, see [`check_layout`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutReport {
+    /// Name of the Rust struct
+    pub rust_name: &'static str,
+    pub type_name: &'static str,
+    /// False if the headers have no such type, in which case nothing was compared
+    pub found: bool,
+    pub mismatches: Vec<LayoutMismatch>,
+}
+
+impl LayoutReport {
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Compares the size and field offsets of a struct to the `actual_size` of the mirrored type,
+/// where `actual_offset` returns the offset of a field of the mirrored type by name.
+pub fn compare_layout(
+    size: usize,
+    fields: &[(&'static str, usize)],
+    actual_size: usize,
+    actual_offset: impl Fn(&str) -> Option<usize>,
+) -> Vec<LayoutMismatch> {
+    let mut mismatches = Vec::new();
+    if size != actual_size {
+        mismatches.push(LayoutMismatch::Size { expected: size, actual: actual_size });
+    }
+    for &(field, expected) in fields {
+        match actual_offset(field) {
+            Some(actual) if actual != expected => {
+                mismatches.push(LayoutMismatch::FieldOffset { field, expected, actual })
+            }
+            Some(_) => {}
+            None => mismatches.push(LayoutMismatch::MissingField(field)),
+        }
+    }
+    mismatches
+}
+
+/// Compares `T` to the type it mirrors in `types`.
+pub fn check_layout<T: MirroredType>(types: &type_crawler::Types) -> LayoutReport {
+    let rust_name = std::any::type_name::<T>().rsplit("::").next().unwrap_or_default();
+    let mut report = LayoutReport {
+        rust_name,
+        type_name: T::TYPE_NAME,
+        found: false,
+        mismatches: Vec::new(),
+    };
+    let Some(ty) = types.get(T::TYPE_NAME) else {
+        return report;
+    };
+    report.found = true;
+    let struct_decl = ty.as_struct(types);
+    report.mismatches = compare_layout(size_of::<T>(), T::FIELDS, ty.size(types), |name| {
+        Some(struct_decl?.get_field(types, name)?.offset_bytes())
+    });
+    report
+}
+
+/// Checks every POD struct of this crate which mirrors a type in the headers.
+pub fn check_layouts(types: &type_crawler::Types) -> Vec<LayoutReport> {
+    use super::fx32::{Fx16, Fx32, Vec3p};
+    vec![check_layout::<Fx16>(types), check_layout::<Fx32>(types), check_layout::<Vec3p>(types)]
+}
@@ -1,2 +1,3 @@
 pub mod fx32;
+pub mod mirror;
 pub mod pod;
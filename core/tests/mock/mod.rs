@@ -0,0 +1,153 @@
+#![allow(dead_code)]
+
+use std::{
+    collections::VecDeque,
+    io::{Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    thread::JoinHandle,
+};
+
+/// A minimal GDB stub serving a single connection. `qSupported` is answered with `supported`, and
+/// every other packet is acknowledged and passed to the handler, whose replies are sent one at a
+/// time, waiting for an ACK in between. `QStartNoAckMode` is accepted if `supported` advertises it.
+pub struct MockGdbServer {
+    pub address: SocketAddr,
+    thread: Option<JoinHandle<MockSession>>,
+}
+
+#[derive(Default)]
+pub struct MockOptions {
+    /// Number of replies to send with a bad checksum, including retransmissions
+    pub corrupt_replies: usize,
+    /// Number of received packets to reject with a NAK
+    pub nak_packets: usize,
+    /// Reject `QStartNoAckMode` even if `supported` advertises it
+    pub reject_no_ack: bool,
+}
+
+#[derive(Default)]
+pub struct MockSession {
+    /// Every packet received, in order
+    pub packets: Vec<String>,
+    /// ACKs received after no-ACK mode was enabled, which a client should never send
+    pub unexpected_acks: usize,
+    pub naks_received: usize,
+    pub no_ack: bool,
+}
+
+impl MockGdbServer {
+    pub fn start<F>(supported: &str, handler: F) -> Self
+    where
+        F: FnMut(&str) -> Vec<String> + Send + 'static,
+    {
+        Self::start_with_options(supported, MockOptions::default(), handler)
+    }
+
+    pub fn start_with_options<F>(supported: &str, mut options: MockOptions, mut handler: F) -> Self
+    where
+        F: FnMut(&str) -> Vec<String> + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let supported = supported.to_string();
+        let thread = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.set_nodelay(true).unwrap();
+            let mut session = MockSession::default();
+            let mut pending = VecDeque::new();
+            let mut last_reply = String::new();
+            let mut awaiting_ack = false;
+            let mut enter_no_ack = false;
+
+            let mut handshake = [0; 1];
+            stream.read_exact(&mut handshake).unwrap();
+            stream.write_all(b"+").unwrap();
+
+            while let Some(byte) = read_byte(&mut stream) {
+                match byte {
+                    b'+' if awaiting_ack => {
+                        awaiting_ack = false;
+                        session.no_ack |= std::mem::take(&mut enter_no_ack);
+                    }
+                    b'+' if session.no_ack => {
+                        session.unexpected_acks += 1;
+                    }
+                    b'-' if awaiting_ack => {
+                        session.naks_received += 1;
+                        send_packet(&mut stream, &last_reply, &mut options.corrupt_replies);
+                    }
+                    b'$' => {
+                        let packet = read_packet(&mut stream);
+                        if options.nak_packets > 0 {
+                            options.nak_packets -= 1;
+                            stream.write_all(b"-").unwrap();
+                            continue;
+                        }
+                        if !session.no_ack {
+                            stream.write_all(b"+").unwrap();
+                        }
+                        if packet.starts_with("qSupported") {
+                            pending.push_back(supported.clone());
+                        } else if packet == "QStartNoAckMode"
+                            && supported.contains("QStartNoAckMode+")
+                            && !options.reject_no_ack
+                        {
+                            pending.push_back("OK".into());
+                            enter_no_ack = true;
+                        } else {
+                            pending.extend(handler(&packet));
+                        }
+                        session.packets.push(packet);
+                    }
+                    _ => {}
+                }
+                while !awaiting_ack && let Some(reply) = pending.pop_front() {
+                    send_packet(&mut stream, &reply, &mut options.corrupt_replies);
+                    last_reply = reply;
+                    awaiting_ack = !session.no_ack;
+                }
+            }
+            session
+        });
+        Self { address, thread: Some(thread) }
+    }
+
+    /// Waits for the client to disconnect.
+    pub fn join(mut self) -> MockSession {
+        self.thread.take().unwrap().join().unwrap()
+    }
+}
+
+pub fn hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn read_byte(stream: &mut TcpStream) -> Option<u8> {
+    let mut buf = [0; 1];
+    match stream.read(&mut buf) {
+        Ok(1) => Some(buf[0]),
+        _ => None,
+    }
+}
+
+fn read_packet(stream: &mut TcpStream) -> String {
+    let mut packet = Vec::new();
+    while let Some(byte) = read_byte(stream) {
+        if byte == b'#' {
+            read_byte(stream);
+            read_byte(stream);
+            break;
+        }
+        packet.push(byte);
+    }
+    String::from_utf8(packet).unwrap()
+}
+
+fn send_packet(stream: &mut TcpStream, packet: &str, corrupt: &mut usize) {
+    let mut checksum = packet.bytes().fold(0u8, |acc, b| acc.wrapping_add(b));
+    if *corrupt > 0 {
+        *corrupt -= 1;
+        checksum = checksum.wrapping_add(1);
+    }
+    stream.write_all(format!("${packet}#{checksum:02x}").as_bytes()).unwrap();
+}
@@ -0,0 +1,48 @@
+mod tests {
+    use dsv_core::memory::address::{AddressWarning, DS_DTCM, DS_MAIN_RAM, check_address};
+
+    #[test]
+    fn test_valid_address() {
+        assert!(check_address(0x02000000, 4, 4, &[DS_MAIN_RAM]).is_empty());
+        assert!(check_address(0x023ffffc, 4, 4, &[DS_MAIN_RAM]).is_empty());
+        assert!(check_address(0x0211c5a1, 1, 1, &[DS_MAIN_RAM]).is_empty());
+    }
+
+    #[test]
+    fn test_null_address() {
+        assert_eq!(check_address(0, 12, 4, &[DS_MAIN_RAM]), vec![AddressWarning::Null]);
+    }
+
+    #[test]
+    fn test_misaligned_address() {
+        assert_eq!(check_address(0x02000002, 4, 4, &[DS_MAIN_RAM]), vec![
+            AddressWarning::Misaligned { alignment: 4 }
+        ]);
+        assert!(check_address(0x02000002, 2, 2, &[DS_MAIN_RAM]).is_empty());
+    }
+
+    #[test]
+    fn test_out_of_range_address() {
+        assert_eq!(check_address(0x01ff0000, 4, 4, &[DS_MAIN_RAM]), vec![
+            AddressWarning::OutOfRange
+        ]);
+        assert_eq!(check_address(0x02400000, 4, 4, &[DS_MAIN_RAM]), vec![
+            AddressWarning::OutOfRange
+        ]);
+        // Starts inside but ends past the range
+        assert_eq!(check_address(0x023ffffc, 8, 4, &[DS_MAIN_RAM]), vec![
+            AddressWarning::OutOfRange
+        ]);
+        assert_eq!(check_address(0x027e0f93, 4, 4, &[DS_MAIN_RAM]), vec![
+            AddressWarning::Misaligned { alignment: 4 },
+            AddressWarning::OutOfRange
+        ]);
+    }
+
+    #[test]
+    fn test_custom_ranges() {
+        let ranges = [DS_MAIN_RAM, DS_DTCM];
+        assert!(check_address(0x027e0f94, 12, 4, &ranges).is_empty());
+        assert_eq!(check_address(0x027e0f94, 12, 4, &[]), vec![AddressWarning::OutOfRange]);
+    }
+}
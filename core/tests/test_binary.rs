@@ -0,0 +1,124 @@
+mod mock;
+
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use anyhow::Result;
+    use dsv_core::gdb::{client::GdbClient, quirks::Support};
+
+    use crate::mock::{MockGdbServer, hex};
+
+    const DATA: &[u8] = b"a#b$c}d*";
+    const ESCAPED: &str = "a}\x03b}\x04c}]d}\x0a";
+
+    #[test]
+    fn test_binary_read_and_write() -> Result<()> {
+        let writes = Arc::new(Mutex::new(Vec::new()));
+        let server = {
+            let writes = writes.clone();
+            MockGdbServer::start("PacketSize=1000;binary-upload+", move |packet| {
+                if packet == "x 2000000,8" {
+                    vec![format!("b{ESCAPED}")]
+                } else if let Some(write) = packet.strip_prefix("X ") {
+                    writes.lock().unwrap().push(write.to_string());
+                    vec!["OK".into()]
+                } else {
+                    vec!["E01".into()]
+                }
+            })
+        };
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        assert_eq!(client.quirks().binary_read, Support::Supported);
+
+        let mut buf = [0; 8];
+        client.read_slice_binary(0x02000000, &mut buf)?;
+        assert_eq!(&buf, DATA);
+
+        client.write_slice_binary(0x02000000, DATA)?;
+        assert_eq!(client.quirks().binary_write, Support::Supported);
+        assert_eq!(*writes.lock().unwrap(), vec![format!("2000000,8:{ESCAPED}")]);
+        client.disconnect()?;
+        server.join();
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_fallback() -> Result<()> {
+        let server = MockGdbServer::start("PacketSize=1000", |packet| {
+            if packet == "m 2000000,8" {
+                vec![hex(DATA)]
+            } else if packet.starts_with("M ") {
+                vec!["OK".into()]
+            } else {
+                vec![String::new()]
+            }
+        });
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        assert_eq!(client.quirks().binary_read, Support::Unsupported);
+
+        let mut buf = [0; 8];
+        client.read_slice_binary(0x02000000, &mut buf)?;
+        assert_eq!(&buf, DATA);
+
+        client.write_slice_binary(0x02000000, DATA)?;
+        client.write_slice_binary(0x02000000, DATA)?;
+        assert_eq!(client.quirks().binary_write, Support::Unsupported);
+        client.disconnect()?;
+
+        let received = server.join().packets;
+        let count = |prefix: &str| received.iter().filter(|p| p.starts_with(prefix)).count();
+        assert_eq!(count("x "), 0);
+        assert_eq!(count("X "), 1);
+        assert_eq!(count("M "), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_length_without_packet_size() -> Result<()> {
+        let server = MockGdbServer::start("", |packet| {
+            let Some((_, length)) = packet.strip_prefix("m ").and_then(|p| p.split_once(','))
+            else {
+                return vec![String::new()];
+            };
+            let length = usize::from_str_radix(length, 16).unwrap();
+            vec![hex(&vec![0x55; length])]
+        });
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+
+        let mut buf = vec![0; 0x1000];
+        client.read_slice_binary(0x02000000, &mut buf)?;
+        assert!(buf.iter().all(|&b| b == 0x55));
+        client.disconnect()?;
+
+        let received = server.join().packets;
+        let reads: Vec<_> = received.iter().filter(|p| p.starts_with("m ")).collect();
+        assert_eq!(reads.len(), 5);
+        assert_eq!(reads[0], "m 2000000,3ff");
+        Ok(())
+    }
+    #[test]
+    fn test_write_length_without_packet_size() -> Result<()> {
+        let server = MockGdbServer::start("", |packet| {
+            if packet.starts_with("M ") {
+                vec!["OK".into()]
+            } else {
+                vec![String::new()]
+            }
+        });
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        client.write_slice(0x02000000, &[0x55; 0x1000])?;
+        client.disconnect()?;
+
+        let received = server.join().packets;
+        let writes: Vec<_> = received.iter().filter(|p| p.starts_with("M ")).collect();
+        assert_eq!(writes.len(), 5);
+        assert!(writes[0].starts_with("M 2000000,3f4:"));
+        // Fits in the fallback packet size with $#(checksum)
+        assert!(writes.iter().all(|p| p.len() + 4 <= 0x800));
+        Ok(())
+    }
+}
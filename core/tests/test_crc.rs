@@ -0,0 +1,114 @@
+mod mock;
+
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use anyhow::Result;
+    use dsv_core::{
+        gdb::{client::GdbClient, crc::crc32, quirks::Support},
+        state::State,
+    };
+
+    use crate::mock::{MockGdbServer, hex};
+
+    const BASE: u32 = 0x02000000;
+    const LENGTH: usize = State::CRC_MIN_LENGTH * 2;
+
+    /// Serves `m` and, if `crc` is set, `qCRC` packets from `memory` at [`BASE`].
+    fn serve(memory: &Mutex<Vec<u8>>, packet: &str, crc: bool) -> Vec<String> {
+        let memory = memory.lock().unwrap();
+        let range = |args: &str| {
+            let (address, length) = args.split_once(',').unwrap();
+            let start = (u32::from_str_radix(address, 16).unwrap() - BASE) as usize;
+            start..start + usize::from_str_radix(length, 16).unwrap()
+        };
+        if let Some(args) = packet.strip_prefix("m ") {
+            vec![hex(&memory[range(args)])]
+        } else if let Some(args) = packet.strip_prefix("qCRC:")
+            && crc
+        {
+            vec![format!("C{:x}", crc32(&memory[range(args)]))]
+        } else {
+            vec![String::new()]
+        }
+    }
+
+    fn count(packets: &[String], prefix: &str) -> usize {
+        packets.iter().filter(|packet| packet.starts_with(prefix)).count()
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0xffffffff);
+        assert_eq!(crc32(b"123456789"), 0x0376e6e7);
+    }
+
+    #[test]
+    fn test_crc_skips_unchanged_reads() -> Result<()> {
+        let memory = Arc::new(Mutex::new(vec![0x11; LENGTH]));
+        let server_memory = memory.clone();
+        let server = MockGdbServer::start("PacketSize=4000", move |packet| {
+            serve(&server_memory, packet, true)
+        });
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        let mut state = State::default();
+
+        // The first read has nothing to compare to
+        state.request(BASE, LENGTH);
+        state.update(&mut client)?;
+        assert_eq!(client.quirks().crc, Support::Unknown);
+
+        state.request(BASE, LENGTH);
+        state.update(&mut client)?;
+        assert_eq!(client.quirks().crc, Support::Supported);
+        assert_eq!(client.stats().crc_hits, 1);
+
+        // A change of one byte is detected and read
+        memory.lock().unwrap()[LENGTH - 1] = 0x22;
+        state.request(BASE, LENGTH);
+        state.update(&mut client)?;
+        assert_eq!(client.stats().crc_misses, 1);
+        assert_eq!(state.get_data(BASE).unwrap()[LENGTH - 1], 0x22);
+
+        // Small requests are read without checking
+        state.request(BASE, 4);
+        state.update(&mut client)?;
+        state.request(BASE, 4);
+        state.update(&mut client)?;
+        client.disconnect()?;
+
+        let packets = server.join().packets;
+        assert_eq!(count(&packets, "qCRC"), 2);
+        assert_eq!(count(&packets, "m "), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_crc_unsupported() -> Result<()> {
+        let memory = Arc::new(Mutex::new(vec![0x11; LENGTH]));
+        let server_memory = memory.clone();
+        let server = MockGdbServer::start("PacketSize=4000", move |packet| {
+            serve(&server_memory, packet, false)
+        });
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        let mut state = State::default();
+
+        for value in [0x11, 0x22, 0x33] {
+            memory.lock().unwrap().fill(value);
+            state.request(BASE, LENGTH);
+            state.update(&mut client)?;
+            assert_eq!(state.get_data(BASE), Some(vec![value; LENGTH].as_slice()));
+        }
+        assert_eq!(client.quirks().crc, Support::Unsupported);
+        assert_eq!(client.stats().crc_hits + client.stats().crc_misses, 0);
+        client.disconnect()?;
+
+        // Probed once, then every read is in full
+        let packets = server.join().packets;
+        assert_eq!(count(&packets, "qCRC"), 1);
+        assert_eq!(count(&packets, "m "), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+mod tests {
+    use dsv_core::disasm::{Instruction, Mode, disassemble};
+
+    const BASE: u32 = 0x02000000;
+
+    fn arm(raw: u32) -> Instruction {
+        disassemble(&raw.to_le_bytes(), BASE, Mode::Arm).remove(0)
+    }
+
+    fn thumb(halfwords: &[u16]) -> Instruction {
+        let data: Vec<u8> = halfwords.iter().flat_map(|halfword| halfword.to_le_bytes()).collect();
+        disassemble(&data, BASE, Mode::Thumb).remove(0)
+    }
+
+    fn text(instruction: &Instruction) -> String {
+        format!("{} {}", instruction.mnemonic, instruction.operands).trim_end().to_string()
+    }
+
+    #[test]
+    fn test_arm() {
+        assert_eq!(text(&arm(0xe92d4010)), "push {r4, lr}");
+        assert_eq!(text(&arm(0xe8bd80f0)), "pop {r4-r7, pc}");
+        assert_eq!(text(&arm(0xe3a00001)), "mov r0, #1");
+        assert_eq!(text(&arm(0x10810002)), "addne r0, r1, r2");
+        assert_eq!(text(&arm(0xe1b00100)), "movs r0, r0, lsl #2");
+        assert_eq!(text(&arm(0xe1d000b2)), "ldrh r0, [r0, #2]");
+        assert_eq!(text(&arm(0xe5310010)), "ldr r0, [r1, #-0x10]!");
+        assert_eq!(text(&arm(0xe0010392)), "mul r1, r2, r3");
+        assert_eq!(text(&arm(0xe12fff1e)), "bx lr");
+        assert_eq!(text(&arm(0xee070f9a)), "mcr p15, 0, r0, c7, c10, 4");
+        assert_eq!(text(&arm(0xe7f000f0)), ".word 0xe7f000f0");
+    }
+
+    #[test]
+    fn test_arm_targets() {
+        let branch = arm(0xeb000000);
+        assert_eq!(text(&branch), "bl 0x02000008");
+        assert_eq!(branch.branch_target, Some(BASE + 8));
+        // Branch to itself
+        assert_eq!(arm(0xeafffffe).branch_target, Some(BASE));
+        assert_eq!(arm(0xfb000000).branch_target, Some(BASE + 10));
+
+        let load = arm(0xe59f0004);
+        assert_eq!(text(&load), "ldr r0, [pc, #4]");
+        assert_eq!(load.literal_address, Some(BASE + 12));
+    }
+
+    #[test]
+    fn test_thumb() {
+        assert_eq!(text(&thumb(&[0xb510])), "push {r4, lr}");
+        assert_eq!(text(&thumb(&[0xbd10])), "pop {r4, pc}");
+        assert_eq!(text(&thumb(&[0x2001])), "movs r0, #1");
+        assert_eq!(text(&thumb(&[0x4770])), "bx lr");
+        assert_eq!(text(&thumb(&[0x4640])), "mov r0, r8");
+        assert_eq!(text(&thumb(&[0x6848])), "ldr r0, [r1, #4]");
+        assert_eq!(text(&thumb(&[0xc90c])), "ldmia r1!, {r2, r3}");
+        assert_eq!(text(&thumb(&[0xc903])), "ldmia r1, {r0, r1}");
+        assert_eq!(text(&thumb(&[0xb082])), "sub sp, #8");
+    }
+
+    #[test]
+    fn test_thumb_targets() {
+        let branch = thumb(&[0xf000, 0xf802]);
+        assert_eq!(text(&branch), "bl 0x02000008");
+        assert_eq!((branch.size, branch.raw), (4, 0xf000f802));
+        assert_eq!(thumb(&[0xf7ff, 0xeffe]).branch_target, Some(BASE));
+        assert_eq!(thumb(&[0xd0fe]).branch_target, Some(BASE));
+        assert_eq!(text(&thumb(&[0xd0fe])), "beq 0x02000000");
+
+        let data: Vec<u8> = [0x0000u16, 0x4801].iter().flat_map(|h| h.to_le_bytes()).collect();
+        let load = &disassemble(&data, BASE, Mode::Thumb)[1];
+        assert_eq!(text(load), "ldr r0, [pc, #4]");
+        assert_eq!(load.literal_address, Some(BASE + 8));
+
+        // A lone half of a long branch
+        assert_eq!(text(&thumb(&[0xf000])), ".hword 0xf000");
+    }
+}
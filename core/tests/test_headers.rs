@@ -0,0 +1,23 @@
+mod tests {
+    use anyhow::Result;
+    use dsv_core::headers::find_header_files;
+
+    #[test]
+    fn test_find_header_files() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("include/actor"))?;
+        for file in ["main.h", "include/actor/Actor.hpp", "include/notes.txt", "main.c"] {
+            std::fs::write(root.join(file), "")?;
+        }
+
+        let mut headers = find_header_files(root, &[])?;
+        headers.sort();
+        assert_eq!(headers, [root.join("include/actor/Actor.hpp"), root.join("main.h")]);
+
+        assert!(find_header_files(&root.join("missing"), &[])?.is_empty());
+        let ignored = [root.to_path_buf()];
+        assert!(find_header_files(root, &ignored)?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+mod mock;
+
+mod tests {
+    use std::time::Instant;
+
+    use anyhow::Result;
+    use dsv_core::{gdb::client::GdbClient, hex};
+
+    use crate::mock::MockGdbServer;
+
+    /// The per-nibble decoding which the lookup table replaced.
+    fn reference_nibble(c: char) -> u8 {
+        match c {
+            '0'..='9' => c as u8 - b'0',
+            'a'..='f' => c as u8 - b'a' + 10,
+            'A'..='F' => c as u8 - b'A' + 10,
+            _ => 0,
+        }
+    }
+
+    fn reference_encode(data: &[u8]) -> String {
+        let mut encoded = String::with_capacity(data.len() * 2);
+        for &byte in data {
+            encoded.push_str(&format!("{:02x}", byte));
+        }
+        encoded
+    }
+
+    fn reference_decode(data: &str, buf: &mut [u8]) {
+        for (i, chunk) in data.as_bytes().chunks(2).enumerate() {
+            let high = reference_nibble(chunk[0] as char);
+            let low = reference_nibble(chunk[1] as char);
+            buf[i] = (high << 4) | low;
+        }
+    }
+
+    #[test]
+    fn test_encode_all_bytes() {
+        let data: Vec<u8> = (0..=255).collect();
+        assert_eq!(hex::encode(&data), reference_encode(&data));
+
+        let mut encoded = String::from("M 0,1:");
+        hex::encode_into(&[0xab], &mut encoded);
+        assert_eq!(encoded, "M 0,1:ab");
+    }
+
+    #[test]
+    fn test_decode_all_chars() {
+        for c in 0..=255u8 {
+            assert_eq!(hex::nibble(c), reference_nibble(c as char), "char {c:#04x}");
+        }
+        let data: Vec<u8> = (0..=255).collect();
+        let mut buf = [0; 256];
+        hex::decode_into(hex::encode(&data).as_bytes(), &mut buf).unwrap();
+        assert_eq!(buf.as_slice(), data.as_slice());
+        hex::decode_into(hex::encode(&data).to_uppercase().as_bytes(), &mut buf).unwrap();
+        assert_eq!(buf.as_slice(), data.as_slice());
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        let mut buf = [0xff; 2];
+        hex::decode_into(b"g1zZ", &mut buf).unwrap();
+        let mut expected = [0; 2];
+        reference_decode("g1zZ", &mut expected);
+        assert_eq!(buf, expected);
+
+        assert!(hex::decode_into(b"123", &mut buf).is_err());
+        assert!(hex::decode_into(b"123456", &mut buf).is_err());
+        assert!(hex::decode_string("abc").is_err());
+        assert_eq!(hex::decode_string("48690a").unwrap(), "Hi\n");
+    }
+
+    /// Times a large `read_slice` and the codec itself against the reference implementation. This
+    /// is a benchmark, run it with `--ignored --nocapture` to see the results.
+    #[test]
+    #[ignore = "benchmark"]
+    fn test_codec_timing() -> Result<()> {
+        const LENGTH: usize = 0x100000;
+        let data: Vec<u8> = (0..LENGTH).map(|i| (i * 7) as u8).collect();
+        let encoded = hex::encode(&data);
+
+        let start = Instant::now();
+        let reference = reference_encode(&data);
+        let reference_encode_time = start.elapsed();
+        let start = Instant::now();
+        let table = hex::encode(&data);
+        let table_encode_time = start.elapsed();
+        assert_eq!(table, reference);
+
+        let mut buf = vec![0; LENGTH];
+        let start = Instant::now();
+        reference_decode(&encoded, &mut buf);
+        let reference_decode_time = start.elapsed();
+        let start = Instant::now();
+        hex::decode_into(encoded.as_bytes(), &mut buf)?;
+        let table_decode_time = start.elapsed();
+        assert_eq!(buf, data);
+
+        println!("encode {LENGTH:#x} bytes: {reference_encode_time:?} -> {table_encode_time:?}");
+        println!("decode {LENGTH:#x} bytes: {reference_decode_time:?} -> {table_decode_time:?}");
+
+        let reply = encoded.clone();
+        let server = MockGdbServer::start("PacketSize=200010", move |packet| {
+            let Some((address, length)) = packet.strip_prefix("m ").and_then(|p| p.split_once(','))
+            else {
+                return vec![String::new()];
+            };
+            let offset = usize::from_str_radix(address, 16).unwrap() - 0x02000000;
+            let length = usize::from_str_radix(length, 16).unwrap();
+            vec![reply[offset * 2..(offset + length) * 2].to_string()]
+        });
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        let mut buf = vec![0; LENGTH];
+        let start = Instant::now();
+        client.read_slice(0x02000000, &mut buf)?;
+        println!("read_slice {LENGTH:#x} bytes: {:?}", start.elapsed());
+        assert_eq!(buf, data);
+        client.disconnect()?;
+        server.join();
+        Ok(())
+    }
+}
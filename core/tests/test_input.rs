@@ -0,0 +1,94 @@
+mod tests {
+    use dsv_core::typed::input::{format_int, int_range, parse_int_input};
+
+    #[test]
+    fn test_negative_input() {
+        assert_eq!(parse_int_input("-1", 1, true), Some(vec![0xff]));
+        assert_eq!(parse_int_input("-1", 2, true), Some(vec![0xff, 0xff]));
+        assert_eq!(parse_int_input("-2", 4, true), Some(vec![0xfe, 0xff, 0xff, 0xff]));
+        assert_eq!(parse_int_input("-1", 8, true), Some(vec![0xff; 8]));
+        assert_eq!(parse_int_input("-128", 1, true), Some(vec![0x80]));
+        assert_eq!(parse_int_input("-32768", 2, true), Some(vec![0x00, 0x80]));
+
+        // Out of range or unsigned
+        assert_eq!(parse_int_input("-129", 1, true), None);
+        assert_eq!(parse_int_input("-32769", 2, true), None);
+        assert_eq!(parse_int_input("-1", 4, false), None);
+    }
+
+    #[test]
+    fn test_positive_input() {
+        assert_eq!(parse_int_input("127", 1, true), Some(vec![0x7f]));
+        assert_eq!(parse_int_input("128", 1, true), None);
+        assert_eq!(parse_int_input("255", 1, false), Some(vec![0xff]));
+        assert_eq!(parse_int_input("256", 1, false), None);
+        assert_eq!(parse_int_input(" 4096 ", 4, false), Some(vec![0x00, 0x10, 0x00, 0x00]));
+        assert_eq!(parse_int_input("18446744073709551615", 8, false), Some(vec![0xff; 8]));
+    }
+
+    #[test]
+    fn test_hex_input() {
+        // Hex is the raw value, so it may set the sign bit of signed fields
+        assert_eq!(parse_int_input("0xffff", 2, true), Some(vec![0xff, 0xff]));
+        assert_eq!(parse_int_input("0x12345", 2, false), None);
+        assert_eq!(parse_int_input("0X1f", 1, false), Some(vec![0x1f]));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(parse_int_input("", 4, true), None);
+        assert_eq!(parse_int_input("1.5", 4, true), None);
+        assert_eq!(parse_int_input("0x", 4, false), None);
+        assert_eq!(parse_int_input("1", 3, false), Some(vec![1, 0, 0]));
+        assert_eq!(parse_int_input("1", 0, false), None);
+    }
+
+    #[test]
+    fn test_int_range() {
+        assert_eq!(int_range(1, false), Some(0..=0xff));
+        assert_eq!(int_range(1, true), Some(-0x80..=0x7f));
+        assert_eq!(int_range(2, true), Some(-0x8000..=0x7fff));
+        assert_eq!(int_range(4, false), Some(0..=0xffffffff));
+        assert_eq!(int_range(8, true), Some(i64::MIN as i128..=i64::MAX as i128));
+        assert_eq!(int_range(8, false), Some(0..=u64::MAX as i128));
+        assert_eq!(int_range(16, false), None);
+    }
+
+    #[test]
+    fn test_format_int() {
+        assert_eq!(format_int(&[0xff], true, false).as_deref(), Some("-1"));
+        assert_eq!(format_int(&[0xff], false, false).as_deref(), Some("255"));
+        assert_eq!(format_int(&[0xff], true, true).as_deref(), Some("0xff"));
+        assert_eq!(format_int(&[0x00, 0x80], true, false).as_deref(), Some("-32768"));
+        assert_eq!(format_int(&[0xfe, 0xff, 0xff, 0xff], true, false).as_deref(), Some("-2"));
+        assert_eq!(
+            format_int(&[0xfe, 0xff, 0xff, 0xff], false, true).as_deref(),
+            Some("0xfffffffe")
+        );
+        assert_eq!(format_int(&[0xff; 8], false, false).as_deref(), Some("18446744073709551615"));
+        assert_eq!(format_int(&[0xff; 8], true, false).as_deref(), Some("-1"));
+        assert_eq!(format_int(&[], false, false), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for size in [1, 2, 4, 8] {
+            for signed in [false, true] {
+                let range = int_range(size, signed).unwrap();
+                for value in [*range.start(), *range.end(), 0, 1] {
+                    let text = value.to_string();
+                    let bytes = parse_int_input(&text, size, signed).unwrap();
+                    assert_eq!(bytes.len(), size);
+                    assert_eq!(format_int(&bytes, signed, false), Some(text));
+
+                    let hex = format_int(&bytes, signed, true).unwrap();
+                    assert_eq!(parse_int_input(&hex, size, signed), Some(bytes));
+                }
+                let below = (*range.start() - 1).to_string();
+                let above = (*range.end() + 1).to_string();
+                assert_eq!(parse_int_input(&below, size, signed), None);
+                assert_eq!(parse_int_input(&above, size, signed), None);
+            }
+        }
+    }
+}
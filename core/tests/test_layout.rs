@@ -0,0 +1,59 @@
+mod tests {
+    use dsv_core::typed::layout::StructLayout;
+
+    /// Bit range of a field which isn't a bit-field.
+    fn bytes(offset: usize, size: usize) -> std::ops::Range<usize> {
+        offset * 8..(offset + size) * 8
+    }
+
+    #[test]
+    fn test_holes_between_fields_and_bit_fields() {
+        // struct { u8 kind; u16 id; u32 active : 1, state : 5, frames : 12; Vec3p pos; u32 value; }
+        let fields = [bytes(0, 1), bytes(2, 2), 32..33, 33..38, 38..50, bytes(8, 12), bytes(20, 4)];
+        let layout = StructLayout::new(&fields, 24);
+        // Byte 7 is left over from the bit-field unit, while the unused bits in byte 6 are not
+        // reported
+        assert_eq!(layout.holes, vec![1..2, 7..8]);
+        assert!(layout.overlapping.is_empty());
+    }
+
+    #[test]
+    fn test_holes_after_base() {
+        // struct Derived : Base { u8 flag; u32 value; }, where Base is 8 bytes
+        let fields = [bytes(0, 8), bytes(8, 1), bytes(12, 4)];
+        let layout = StructLayout::new(&fields, 16);
+        assert_eq!(layout.holes, vec![9..12]);
+        assert!(layout.overlapping.is_empty());
+    }
+
+    #[test]
+    fn test_tail_padding() {
+        // struct { u32 a; u8 b; }, aligned to 4 bytes
+        let layout = StructLayout::new(&[bytes(0, 4), bytes(4, 1)], 8);
+        assert_eq!(layout.holes, vec![5..8]);
+
+        let empty = StructLayout::new(&[], 4);
+        assert_eq!(empty.holes, vec![0..4]);
+    }
+
+    #[test]
+    fn test_zero_sized_fields() {
+        // struct { u32 count; u8 data[]; }
+        let layout = StructLayout::new(&[bytes(0, 4), bytes(4, 0)], 4);
+        assert!(layout.holes.is_empty());
+        assert!(layout.overlapping.is_empty());
+    }
+
+    #[test]
+    fn test_overlapping_fields() {
+        let fields = [bytes(0, 4), bytes(2, 2), bytes(4, 4), bytes(8, 2), bytes(6, 4)];
+        let layout = StructLayout::new(&fields, 12);
+        assert_eq!(layout.overlapping, vec![0, 1, 2, 3, 4]);
+        assert!(layout.is_overlapping(1));
+        assert_eq!(layout.holes, vec![10..12]);
+
+        let fields = [bytes(0, 8), bytes(0, 4), bytes(4, 4)];
+        let layout = StructLayout::new(&fields, 8);
+        assert_eq!(layout.overlapping, vec![0, 1, 2]);
+    }
+}
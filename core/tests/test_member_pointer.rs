@@ -0,0 +1,55 @@
+mod tests {
+    use dsv_core::typed::member_pointer::{MemberFunction, MemberPointer};
+
+    fn words(words: &[i32]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_data_member() {
+        assert_eq!(MemberPointer::decode(&words(&[0])), Some(MemberPointer::Null));
+        // The first member is at offset 0, stored as 1
+        assert_eq!(MemberPointer::decode(&words(&[1])), Some(MemberPointer::Data { offset: 0 }));
+        let member_pointer = MemberPointer::decode(&words(&[0x15])).unwrap();
+        assert_eq!(member_pointer, MemberPointer::Data { offset: 0x14 });
+        assert_eq!(member_pointer.to_string(), "+0x14");
+    }
+
+    #[test]
+    fn test_arm_member_function() {
+        assert_eq!(MemberPointer::decode(&words(&[0, 0])), Some(MemberPointer::Null));
+        let member_pointer = MemberPointer::decode(&words(&[0x02001234, 8])).unwrap();
+        assert_eq!(member_pointer, MemberPointer::Function {
+            this_delta: 4,
+            target: MemberFunction::Address(0x02001234)
+        });
+        assert_eq!(member_pointer.to_string(), "0x02001234, this+0x4");
+
+        let member_pointer = MemberPointer::decode(&words(&[0xc, 1])).unwrap();
+        assert_eq!(member_pointer, MemberPointer::Function {
+            this_delta: 0,
+            target: MemberFunction::Virtual { vptr_offset: 0, entry_offset: 0xc }
+        });
+        assert_eq!(member_pointer.to_string(), "vtable[0xc]");
+    }
+
+    #[test]
+    fn test_metrowerks_member_function() {
+        assert_eq!(MemberPointer::decode(&words(&[0, -1, 0])), Some(MemberPointer::Null));
+        let member_pointer = MemberPointer::decode(&words(&[-8, -1, 0x02004000])).unwrap();
+        assert_eq!(member_pointer, MemberPointer::Function {
+            this_delta: -8,
+            target: MemberFunction::Address(0x02004000)
+        });
+        assert_eq!(member_pointer.to_string(), "0x02004000, this-0x8");
+
+        let member_pointer = MemberPointer::decode(&words(&[0, 0x10, 0x8])).unwrap();
+        assert_eq!(member_pointer.to_string(), "vtable[0x8] at +0x10");
+    }
+
+    #[test]
+    fn test_unknown_size() {
+        assert_eq!(MemberPointer::decode(&[0; 2]), None);
+        assert_eq!(MemberPointer::decode(&[0; 16]), None);
+    }
+}
@@ -0,0 +1,81 @@
+mod mock;
+
+mod tests {
+    use anyhow::Result;
+    use dsv_core::gdb::client::GdbClient;
+
+    use crate::mock::{MockGdbServer, hex};
+
+    const DATA: [u8; 8] = [0xf0, 0xde, 0xbc, 0x9a, 0x78, 0x56, 0x34, 0x12];
+
+    #[test]
+    fn test_read_helpers() -> Result<()> {
+        let server = MockGdbServer::start("PacketSize=1000", |packet| {
+            let Some((_, length)) = packet.strip_prefix("m ").and_then(|p| p.split_once(','))
+            else {
+                return vec![String::new()];
+            };
+            let length = usize::from_str_radix(length, 16).unwrap();
+            vec![hex(&DATA[..length])]
+        });
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+
+        assert_eq!(client.read_u64(0x02000000)?, 0x123456789abcdef0);
+        assert_eq!(client.read_u32(0x02000000)?, 0x9abcdef0);
+        assert_eq!(client.read_u16(0x02000000)?, 0xdef0);
+        assert_eq!(client.read_i32(0x02000000)?, 0x9abcdef0_u32 as i32);
+        assert_eq!(client.read_i16(0x02000000)?, -0x2110);
+        assert_eq!(client.read_i8(0x02000000)?, -0x10);
+        client.disconnect()?;
+        server.join();
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_cstring() -> Result<()> {
+        let server = MockGdbServer::start("PacketSize=1000", |packet| match packet {
+            "m 2000010,10" => vec![hex(b"Phantom Hourglas")],
+            "m 2000010,7" => vec![hex(b"Phantom")],
+            "m 2000020,20" => vec![hex(b"s\0 and some more data afterwards")],
+            _ => vec!["E01".into()],
+        });
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+
+        assert_eq!(client.read_cstring(0x02000010, 0x100)?, b"Phantom Hourglass");
+        assert_eq!(client.read_cstring(0x02000010, 7)?, b"Phantom");
+        client.disconnect()?;
+        server.join();
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_helpers() -> Result<()> {
+        let server = MockGdbServer::start("PacketSize=1000", |packet| {
+            if packet.starts_with("M ") {
+                vec!["OK".into()]
+            } else {
+                vec![String::new()]
+            }
+        });
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+
+        client.write_u64(0x02000000, 0x123456789abcdef0)?;
+        client.write_u32(0x02000000, 0x9abcdef0)?;
+        client.write_u16(0x02000000, 0xdef0)?;
+        client.write_u8(0x02000000, 0xf0)?;
+        client.disconnect()?;
+
+        let received = server.join().packets;
+        let writes: Vec<_> = received.iter().filter(|p| p.starts_with("M ")).collect();
+        assert_eq!(writes, [
+            "M 2000000,8:f0debc9a78563412",
+            "M 2000000,4:f0debc9a",
+            "M 2000000,2:f0de",
+            "M 2000000,1:f0",
+        ]);
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+mod tests {
+    use dsv_core::memory::map::{Access, MemoryMap, MemoryRegion};
+
+    #[test]
+    fn test_ds_arm9_regions() {
+        let map = MemoryMap::ds_arm9();
+        assert_eq!(map.region_at(0x02100000).map(|r| r.name.as_str()), Some("Main RAM"));
+        assert_eq!(map.region_at(0x027e0010).map(|r| r.name.as_str()), Some("DTCM"));
+        assert_eq!(map.region_at(0x02400000), None);
+        assert!(map.is_readable(0x02000000));
+        assert!(!map.is_readable(0x00001000));
+        // I/O registers are readable by the CPU, but not as objects
+        assert!(!map.is_readable(0x04000000));
+        assert!(!map.object_ranges().contains(&(0x04000000..0x04001100)));
+    }
+
+    #[test]
+    fn test_custom_regions_are_sorted() {
+        let map = MemoryMap::new(vec![
+            MemoryRegion::new("ARM7 WRAM", 0x03800000..0x03810000, Access::READ_WRITE),
+            MemoryRegion::new("Main RAM", 0x02000000..0x02400000, Access::ALL),
+            MemoryRegion::new("BIOS", 0x00000000..0x00004000, Access::parse("r-x").unwrap()),
+        ]);
+        let names: Vec<&str> = map.regions().iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["BIOS", "Main RAM", "ARM7 WRAM"]);
+        assert_eq!(map.object_ranges().len(), 3);
+        assert!(map.is_readable(0x03800000));
+    }
+
+    #[test]
+    fn test_access() {
+        assert_eq!(Access::parse("rw"), Some(Access::READ_WRITE));
+        assert_eq!(Access::parse("r-x"), Some(Access::READ_EXECUTE));
+        assert_eq!(Access::parse("rwz"), None);
+        assert_eq!(Access::ALL.to_string(), "rwx");
+        assert_eq!(Access::READ_ONLY.to_string(), "r--");
+    }
+}
@@ -0,0 +1,50 @@
+mod tests {
+    use dsv_core::types::{
+        fx32::Vec3p,
+        mirror::{LayoutMismatch, MirroredType, check_layout, compare_layout},
+    };
+    use type_crawler::Types;
+
+    #[test]
+    fn test_mirrored_fields() {
+        assert_eq!(Vec3p::TYPE_NAME, "Vec3p");
+        assert_eq!(Vec3p::FIELDS, [("x", 0), ("y", 4), ("z", 8)]);
+    }
+
+    #[test]
+    fn test_matching_layout() {
+        let offsets = |name: &str| match name {
+            "x" => Some(0),
+            "y" => Some(4),
+            "z" => Some(8),
+            _ => None,
+        };
+        assert!(compare_layout(12, Vec3p::FIELDS, 12, offsets).is_empty());
+    }
+
+    #[test]
+    fn test_mismatching_layout() {
+        // struct Vec3p { int x; short pad; int y; } after a header change
+        let offsets = |name: &str| match name {
+            "x" => Some(0),
+            "y" => Some(8),
+            _ => None,
+        };
+        assert_eq!(compare_layout(12, Vec3p::FIELDS, 12, offsets), [
+            LayoutMismatch::FieldOffset { field: "y", expected: 4, actual: 8 },
+            LayoutMismatch::MissingField("z"),
+        ]);
+        assert_eq!(compare_layout(12, &[], 16, |_| None), [LayoutMismatch::Size {
+            expected: 12,
+            actual: 16
+        }]);
+    }
+
+    #[test]
+    fn test_missing_type() {
+        let report = check_layout::<Vec3p>(&Types::new());
+        assert_eq!(report.rust_name, "Vec3p");
+        assert!(!report.found);
+        assert!(report.is_ok());
+    }
+}
@@ -0,0 +1,74 @@
+mod mock;
+
+mod tests {
+    use anyhow::Result;
+    use dsv_core::gdb::{client::GdbClient, quirks::Support};
+
+    use crate::mock::{MockGdbServer, MockOptions, hex};
+
+    fn handler(packet: &str) -> Vec<String> {
+        match packet {
+            "m 2000000,4" => vec![hex(&[1, 2, 3, 4])],
+            // Console output followed by the final reply, sent back to back without ACKs
+            "qRcmd,68656c6c6f" => vec![format!("O{}", hex(b"hello ")), hex(b"world")],
+            _ => vec![String::new()],
+        }
+    }
+
+    #[test]
+    fn test_no_ack_mode_accepted() -> Result<()> {
+        let server = MockGdbServer::start("PacketSize=1000;QStartNoAckMode+", handler);
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        assert_eq!(client.quirks().no_ack, Support::Supported);
+
+        assert_eq!(client.read_u32(0x02000000)?, 0x04030201);
+        assert_eq!(client.monitor("hello")?, "hello world");
+        assert_eq!(client.read_u32(0x02000000)?, 0x04030201);
+        assert_eq!(client.stats().round_trips, client.stats().packets_sent);
+        assert!(client.stats().average_round_trip().is_some());
+        client.disconnect()?;
+
+        let session = server.join();
+        assert!(session.no_ack);
+        assert_eq!(session.unexpected_acks, 0);
+        // Latency is measured from the requests made anyway
+        assert_eq!(session.packets[..2], ["qSupported:multiprocess", "QStartNoAckMode"]);
+        assert!(!session.packets.contains(&"qAttached".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_ack_mode_not_advertised() -> Result<()> {
+        let server = MockGdbServer::start("PacketSize=1000", handler);
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        assert_eq!(client.quirks().no_ack, Support::Unsupported);
+        assert_eq!(client.read_u32(0x02000000)?, 0x04030201);
+        client.disconnect()?;
+
+        let session = server.join();
+        assert!(!session.no_ack);
+        assert!(!session.packets.contains(&"QStartNoAckMode".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_ack_mode_rejected() -> Result<()> {
+        let options = MockOptions { reject_no_ack: true, ..Default::default() };
+        let server =
+            MockGdbServer::start_with_options("PacketSize=1000;QStartNoAckMode+", options, handler);
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        assert_eq!(client.quirks().no_ack, Support::Unsupported);
+
+        assert_eq!(client.read_u32(0x02000000)?, 0x04030201);
+        assert_eq!(client.monitor("hello")?, "hello world");
+        client.disconnect()?;
+
+        let session = server.join();
+        assert!(!session.no_ack);
+        assert!(session.packets.contains(&"QStartNoAckMode".to_string()));
+        Ok(())
+    }
+}
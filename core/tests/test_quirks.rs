@@ -0,0 +1,164 @@
+mod mock;
+
+mod tests {
+    use anyhow::Result;
+    use dsv_core::gdb::{
+        client::{GdbClient, normalize_gamecode},
+        quirks::{StubKind, Support, Unsupported},
+    };
+
+    use crate::mock::{MockGdbServer, hex};
+
+    const GAMECODE_RCMD: &str = "qRcmd,67616d65636f6465";
+    const VERSION_RCMD: &str = "qRcmd,76657273696f6e";
+
+    #[test]
+    fn test_gamecode_from_monitor_command() -> Result<()> {
+        let server = MockGdbServer::start("PacketSize=1000", |packet| match packet {
+            GAMECODE_RCMD => vec![hex(b"AZEE")],
+            _ => vec!["E01".into()],
+        });
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        assert_eq!(client.get_gamecode()?, "AZEE");
+        assert_eq!(client.quirks().rcmd, Support::Supported);
+        client.disconnect()?;
+
+        let received = server.join().packets;
+        assert!(!received.iter().any(|packet| packet.starts_with('m')));
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_gamecode() {
+        assert_eq!(normalize_gamecode("BKIJ").as_deref(), Some("BKIJ"));
+        assert_eq!(normalize_gamecode("  BKIJ\r\n").as_deref(), Some("BKIJ"));
+        assert_eq!(normalize_gamecode("\0AZEE\0\0").as_deref(), Some("AZEE"));
+        assert_eq!(normalize_gamecode("azee01").as_deref(), Some("AZEE"));
+        assert_eq!(normalize_gamecode("BKI"), None);
+        assert_eq!(normalize_gamecode("Unknown command"), None);
+        assert_eq!(normalize_gamecode("\n"), None);
+    }
+
+    #[test]
+    fn test_padded_gamecode_reply() -> Result<()> {
+        let replies = [
+            vec![hex(b"BKIJ\n")],
+            vec![hex(b" BKIJ \0")],
+            // Sent as console output before the final reply
+            vec![format!("O{}", hex(b"BKIJ\r\n")), "OK".into()],
+        ];
+        for reply in replies {
+            let server = MockGdbServer::start("PacketSize=1000", move |packet| match packet {
+                GAMECODE_RCMD => reply.clone(),
+                _ => vec!["E01".into()],
+            });
+            let mut client = GdbClient::new();
+            client.connect(server.address)?;
+            assert_eq!(client.get_gamecode()?, "BKIJ");
+            client.disconnect()?;
+            server.join();
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_gamecode_fallback_to_memory() -> Result<()> {
+        let server = MockGdbServer::start("PacketSize=1000", |packet| match packet {
+            "m 23ffe0c,4" => vec![hex(b"BKIE")],
+            // Unsupported packets get an empty reply
+            _ => vec![String::new()],
+        });
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        assert_eq!(client.get_gamecode()?, "BKIE");
+        assert_eq!(client.quirks().rcmd, Support::Unsupported);
+        assert!(client.monitor("help").is_err());
+        client.disconnect()?;
+
+        let received = server.join().packets;
+        let rcmd_count = received.iter().filter(|packet| *packet == GAMECODE_RCMD).count();
+        assert_eq!(rcmd_count, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_reply_is_unsupported() -> Result<()> {
+        let server = MockGdbServer::start("PacketSize=1000", |_| vec![String::new()]);
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+
+        let mut buf = [0; 4];
+        let error = client.read_slice(0x02000000, &mut buf).unwrap_err();
+        assert_eq!(error.downcast_ref::<Unsupported>().map(|e| e.packet), Some("m"));
+        let error = client.get_gamecode().unwrap_err();
+        assert_eq!(error.downcast_ref::<Unsupported>().map(|e| e.packet), Some("m"));
+        let error = client.monitor("help").unwrap_err();
+        assert_eq!(error.downcast_ref::<Unsupported>().map(|e| e.packet), Some("qRcmd"));
+        client.disconnect()?;
+        server.join();
+        Ok(())
+    }
+
+    #[test]
+    fn test_stub_kind() -> Result<()> {
+        let cases = [
+            ("", StubKind::DeSmuMe),
+            ("PacketSize=1000;qXfer:features:read+;swbreak-;hwbreak+", StubKind::MelonDs),
+            ("PacketSize=1000", StubKind::Unknown),
+        ];
+        for (supported, expected) in cases {
+            let server = MockGdbServer::start(supported, |packet| match packet {
+                "m 23ffe0c,4" => vec![hex(b"AZEE")],
+                _ => vec![String::new()],
+            });
+            let mut client = GdbClient::new();
+            client.connect(server.address)?;
+            assert_eq!(client.stub_kind(), expected, "qSupported reply '{supported}'");
+            assert_eq!(client.get_gamecode()?, "AZEE");
+            client.disconnect()?;
+
+            // Stubs known to lack monitor commands don't get any
+            let received = server.join().packets;
+            let rcmd_count = received.iter().filter(|packet| packet.starts_with("qRcmd")).count();
+            // The version probe and the gamecode query
+            assert_eq!(rcmd_count, if expected == StubKind::DeSmuMe { 0 } else { 2 });
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_stub_info() -> Result<()> {
+        let server =
+            MockGdbServer::start("PacketSize=1000;binary-upload+", |packet| match packet {
+                VERSION_RCMD => vec![hex(b"1.0\n")],
+                GAMECODE_RCMD => vec![hex(b"AZEE")],
+                _ => vec![String::new()],
+            });
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        let info = client.stub_info();
+        assert_eq!(info.version.as_deref(), Some("1.0"));
+        assert_eq!(info.identification(), "Unknown stub 1.0, PacketSize=0x1000, binary-upload+");
+        assert_eq!(info.limitations, [
+            "Every packet is acknowledged, which adds latency to each read"
+        ]);
+        client.disconnect()?;
+        server.join();
+
+        // A stub without monitor commands is still connected to, and its limitations are listed
+        let server = MockGdbServer::start("", |packet| match packet {
+            "m 23ffe0c,4" => vec![hex(b"AZEE")],
+            _ => vec![String::new()],
+        });
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        assert_eq!(client.get_gamecode()?, "AZEE");
+        let info = client.stub_info();
+        assert_eq!(info.identification(), "DeSmuME");
+        assert!(info.limitations.contains(&"Monitor commands are unavailable"));
+        client.disconnect()?;
+        server.join();
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+mod mock;
+
+mod tests {
+    use anyhow::Result;
+    use dsv_core::gdb::{client::GdbClient, quirks::Support};
+
+    use crate::mock::{MockGdbServer, hex};
+
+    /// Register dump of r0-r15 where each register holds its own index, and r15 is the PC.
+    fn register_dump() -> String {
+        (0..15u32).chain([0x02001234]).map(|value| hex(&value.to_le_bytes())).collect()
+    }
+
+    #[test]
+    fn test_read_register() -> Result<()> {
+        let server = MockGdbServer::start("PacketSize=4000", |packet| match packet {
+            "pf" => vec!["34120002".into()],
+            "p19" => vec!["1f000060".into()],
+            _ => vec![String::new()],
+        });
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        assert_eq!(client.read_register(15)?, 0x02001234);
+        assert_eq!(client.read_register(25)?, 0x6000001f);
+        assert_eq!(client.quirks().read_register, Support::Supported);
+        client.disconnect()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_register_fallback() -> Result<()> {
+        let server = MockGdbServer::start("PacketSize=4000", |packet| match packet {
+            "g" => vec![register_dump()],
+            _ => vec![String::new()],
+        });
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        assert_eq!(client.read_register(15)?, 0x02001234);
+        assert_eq!(client.read_register(3)?, 3);
+        assert_eq!(client.quirks().read_register, Support::Unsupported);
+        assert!(client.read_register(25).is_err());
+        client.disconnect()?;
+
+        // Probed once
+        let packets = server.join().packets;
+        assert_eq!(packets.iter().filter(|packet| packet.starts_with('p')).count(), 1);
+        Ok(())
+    }
+}
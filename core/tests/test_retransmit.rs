@@ -0,0 +1,67 @@
+mod mock;
+
+mod tests {
+    use anyhow::Result;
+    use dsv_core::gdb::client::GdbClient;
+
+    use crate::mock::{MockGdbServer, MockOptions, hex};
+
+    fn handler(packet: &str) -> Vec<String> {
+        match packet {
+            "m 2000000,4" => vec![hex(&[1, 2, 3, 4])],
+            _ => vec![String::new()],
+        }
+    }
+
+    #[test]
+    fn test_corrupted_replies_are_retransmitted() -> Result<()> {
+        let options = MockOptions { corrupt_replies: 2, ..Default::default() };
+        let server = MockGdbServer::start_with_options("PacketSize=1000", options, handler);
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        assert_eq!(client.read_u32(0x02000000)?, 0x04030201);
+        assert_eq!(client.stats().naks_sent, 2);
+        client.disconnect()?;
+
+        assert_eq!(server.join().naks_received, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejected_packets_are_resent() -> Result<()> {
+        let options = MockOptions { nak_packets: 2, ..Default::default() };
+        let server = MockGdbServer::start_with_options("PacketSize=1000", options, handler);
+        let mut client = GdbClient::new();
+        client.connect(server.address)?;
+        assert_eq!(client.read_u32(0x02000000)?, 0x04030201);
+        assert_eq!(client.stats().retransmits, 2);
+        client.disconnect()?;
+        server.join();
+        Ok(())
+    }
+
+    #[test]
+    fn test_gives_up_after_repeated_rejections() -> Result<()> {
+        // The first send and all three resends are rejected
+        let options = MockOptions { nak_packets: 4, ..Default::default() };
+        let server = MockGdbServer::start_with_options("PacketSize=1000", options, handler);
+        let mut client = GdbClient::new();
+        let error = client.connect(server.address).unwrap_err();
+        assert!(format!("{error:#}").contains("after 3 retransmits"));
+        assert_eq!(client.stats().retransmits, 3);
+        client.disconnect()?;
+        assert!(server.join().packets.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_gives_up_after_repeated_corruption() -> Result<()> {
+        let options = MockOptions { corrupt_replies: usize::MAX, ..Default::default() };
+        let server = MockGdbServer::start_with_options("PacketSize=1000", options, handler);
+        let mut client = GdbClient::new();
+        assert!(client.connect(server.address).is_err());
+        assert!(!client.is_connected());
+        server.join();
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+mod tests {
+    use dsv_core::{
+        memory::{
+            MemoryBackend,
+            in_memory::InMemoryBackend,
+            scan::{PointerMatch, PointerScan, ValueScan, find_pointers, find_values},
+        },
+        state::{RefreshRate, State},
+    };
+
+    #[test]
+    fn test_find_pointers() {
+        let words: [u32; 5] = [0x02001000, 0x02001010, 0x02000ff0, 0x02001100, 0x1234];
+        let data: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        let mut matches = Vec::new();
+        find_pointers(&data, 0x02000000, 0x02001000, 0, &mut matches);
+        assert_eq!(matches, vec![PointerMatch { address: 0x02000000, offset: 0 }]);
+
+        matches.clear();
+        find_pointers(&data, 0x02000000, 0x02001000, 0x10, &mut matches);
+        assert_eq!(matches, vec![
+            PointerMatch { address: 0x02000000, offset: 0 },
+            PointerMatch { address: 0x02000004, offset: 0x10 },
+            PointerMatch { address: 0x02000008, offset: -0x10 },
+        ]);
+    }
+
+    #[test]
+    fn test_find_pointers_unaligned_base() {
+        // Only whole words at aligned addresses are considered
+        let mut data = vec![0xff; 2];
+        data.extend(0x02001000u32.to_le_bytes());
+        data.extend([0x00, 0x10]);
+        let mut matches = Vec::new();
+        find_pointers(&data, 0x02000002, 0x02001000, 0, &mut matches);
+        assert_eq!(matches, vec![PointerMatch { address: 0x02000004, offset: 0 }]);
+    }
+
+    #[test]
+    fn test_pointer_scan() {
+        let mut backend = InMemoryBackend::new(0x02000000, 0x10000);
+        backend.write(0x02000100, &0x02008000u32.to_le_bytes()).unwrap();
+        backend.write(0x0200c004, &0x02008020u32.to_le_bytes()).unwrap();
+        let mut state = State::default();
+        // Past the end of the backend, so the last chunk fails
+        let mut scan = PointerScan::new(0x02008000, 0x20, 0x02000000..0x02014000);
+        let mut steps = 0;
+        while !scan.step(&mut state) {
+            state.update(&mut backend).ok();
+            steps += 1;
+            assert!(steps < 100);
+        }
+        assert_eq!(scan.matches(), &[
+            PointerMatch { address: 0x02000100, offset: 0 },
+            PointerMatch { address: 0x0200c004, offset: 0x20 },
+        ]);
+        assert_eq!(scan.failed_chunks(), 1);
+        assert_eq!(scan.progress(), 1.0);
+        // Chunks are released once scanned
+        state.update(&mut backend).unwrap();
+        assert_eq!(state.bytes_per_update(), 0);
+    }
+
+    #[test]
+    fn test_scan_keeps_window_requests() {
+        let mut backend = InMemoryBackend::new(0x02000000, 0x10000);
+        backend.write(0x02000000, &0x02008000u32.to_le_bytes()).unwrap();
+        let mut state = State::default();
+        // A paused window showing the start of the scanned range
+        state.set_refresh_rate(RefreshRate::Paused);
+        state.request(0x02000000, 4);
+        state.update(&mut backend).unwrap();
+
+        let mut scan = PointerScan::new(0x02008000, 0, 0x02000000..0x02004000);
+        while !scan.step(&mut state) {
+            state.request(0x02000000, 4);
+            state.update(&mut backend).unwrap();
+        }
+        assert_eq!(scan.matches(), &[PointerMatch { address: 0x02000000, offset: 0 }]);
+        // The window's request is neither dropped nor read again
+        state.request(0x02000000, 4);
+        state.update(&mut backend).unwrap();
+        assert_eq!(state.get_data(0x02000000), Some(0x02008000u32.to_le_bytes().as_slice()));
+        assert_eq!(state.bytes_per_update(), 0);
+    }
+
+    #[test]
+    fn test_find_values() {
+        let words: [u32; 6] = [0x020c1234, 0, 0, 0x020c1234, 0x020c1234, 7];
+        let data: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        let mut matches = Vec::new();
+        find_values(&data, 0x02000000, 0x020c1234, 0, 4, &mut matches);
+        assert_eq!(matches, [0x02000000, 0x0200000c, 0x02000010]);
+
+        // Objects aligned to 8 with the value at offset 4
+        matches.clear();
+        find_values(&data, 0x02000000, 0x020c1234, 4, 8, &mut matches);
+        assert_eq!(matches, [0x02000008]);
+
+        // No object can start before address 0
+        matches.clear();
+        find_values(&data, 0, 0x020c1234, 8, 4, &mut matches);
+        assert_eq!(matches, [0x4, 0x8]);
+    }
+
+    #[test]
+    fn test_value_scan() {
+        let mut backend = InMemoryBackend::new(0x02000000, 0x10000);
+        backend.write(0x02000100, &0x020c1234u32.to_le_bytes()).unwrap();
+        backend.write(0x0200a010, &0x020c1234u32.to_le_bytes()).unwrap();
+        let mut state = State::default();
+        let mut scan = ValueScan::new(0x020c1234, 0x10, 4, 0x02000000..0x02010000);
+        let mut steps = 0;
+        while !scan.step(&mut state) {
+            state.update(&mut backend).ok();
+            steps += 1;
+            assert!(steps < 100);
+        }
+        assert_eq!(scan.matches(), [0x020000f0, 0x0200a000]);
+        assert_eq!(scan.failed_chunks(), 0);
+    }
+}
@@ -0,0 +1,487 @@
+mod tests {
+    use anyhow::Result;
+    use dsv_core::{
+        memory::{MemoryBackend, MemorySource, in_memory::InMemoryBackend},
+        state::{
+            Freshness, RefreshRate, State,
+            watch::{Watch, WatchKind},
+        },
+    };
+
+    #[test]
+    fn test_update_reads_and_writes() -> Result<()> {
+        let mut backend = InMemoryBackend::from_bytes(0x02000000, (0..32).collect());
+        let mut state = State::default();
+        state.request(0x02000008, 4);
+        state.request_write(0x02000008, vec![0xff]);
+        state.update(&mut backend)?;
+
+        assert_eq!(state.get_data(0x02000008), Some([0xff, 9, 10, 11].as_slice()));
+        assert_eq!(backend.data()[8], 0xff);
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_continues_after_failed_read() {
+        let mut backend = InMemoryBackend::new(0x02000000, 16);
+        backend.write(0x02000000, &[1, 2]).unwrap();
+        let mut state = State::default();
+        state.request(0x01000000, 4);
+        state.request(0x02000000, 2);
+
+        assert!(state.update(&mut backend).is_err());
+        assert_eq!(state.get_data(0x02000000), Some([1, 2].as_slice()));
+    }
+
+    #[test]
+    fn test_request_cstring() -> Result<()> {
+        let mut data = vec![b'x'; 0x40];
+        data[..6].copy_from_slice(b"Link\0x");
+        data[0x3e] = b'!';
+        let mut backend = InMemoryBackend::from_bytes(0x02000000, data);
+        let mut state = State::default();
+        state.request_cstring(0x02000000, 0x100);
+        state.request_cstring(0x02000010, 4);
+        state.request_cstring(0x0200003e, 0x100);
+        assert!(state.update(&mut backend).is_err());
+
+        assert_eq!(state.get_cstring(0x02000000), Some(b"Link".as_slice()));
+        assert_eq!(state.get_cstring(0x02000010), Some(b"xxxx".as_slice()));
+        assert_eq!(state.get_cstring(0x0200003e), None);
+        assert_eq!(state.get_data(0x02000000), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_in_memory_backend_bounds() {
+        let mut backend = InMemoryBackend::new(0x02000000, 16);
+        let mut buf = [0; 4];
+        assert!(backend.read(0x0200000c, &mut buf).is_ok());
+        assert!(backend.read(0x0200000d, &mut buf).is_err());
+        assert!(backend.read(0x01fffffc, &mut buf).is_err());
+
+        backend.stop().unwrap();
+        assert!(!backend.is_running());
+        backend.disconnect().unwrap();
+        assert!(!backend.is_connected());
+    }
+
+    #[test]
+    fn test_memory_source_alias() -> Result<()> {
+        fn read_u8<S: MemorySource>(source: &mut S, address: u32) -> Result<u8> {
+            let mut buf = [0];
+            source.read(address, &mut buf)?;
+            Ok(buf[0])
+        }
+
+        let mut backend = InMemoryBackend::from_bytes(0x02000000, vec![1, 2, 3]);
+        assert_eq!(read_u8(&mut backend, 0x02000002)?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_request_write_merges() {
+        struct Case {
+            name: &'static str,
+            writes: &'static [(u32, &'static [u8])],
+            expected: &'static [(u32, &'static [u8])],
+            conflicts: u64,
+        }
+        let cases = [
+            Case {
+                name: "separate",
+                writes: &[(0x10, &[1, 2]), (0x20, &[3])],
+                expected: &[(0x10, &[1, 2]), (0x20, &[3])],
+                conflicts: 0,
+            },
+            Case {
+                name: "adjacent",
+                writes: &[(0x10, &[1, 2]), (0x12, &[3]), (0x0f, &[4])],
+                expected: &[(0x0f, &[4, 1, 2, 3])],
+                conflicts: 0,
+            },
+            Case {
+                name: "overlap keeps latest",
+                writes: &[(0x10, &[1, 2, 3, 4]), (0x11, &[5, 6])],
+                expected: &[(0x10, &[1, 5, 6, 4])],
+                conflicts: 1,
+            },
+            Case {
+                name: "overlap extends",
+                writes: &[(0x10, &[1, 2]), (0x11, &[3, 4, 5])],
+                expected: &[(0x10, &[1, 3, 4, 5])],
+                conflicts: 1,
+            },
+            Case {
+                name: "bridges two writes",
+                writes: &[(0x10, &[1]), (0x13, &[2]), (0x11, &[3, 4])],
+                expected: &[(0x10, &[1, 3, 4, 2])],
+                conflicts: 0,
+            },
+            Case {
+                name: "covers several writes",
+                writes: &[(0x10, &[1]), (0x12, &[2]), (0x0f, &[3, 4, 5, 6, 7])],
+                expected: &[(0x0f, &[3, 4, 5, 6, 7])],
+                conflicts: 1,
+            },
+            Case {
+                name: "empty write",
+                writes: &[(0x10, &[1]), (0x10, &[])],
+                expected: &[(0x10, &[1])],
+                conflicts: 0,
+            },
+        ];
+
+        for case in cases {
+            let mut state = State::default();
+            for &(address, data) in case.writes {
+                state.request_write(address, data.to_vec());
+            }
+            let writes: Vec<_> = state.pending_writes().collect();
+            assert_eq!(writes, case.expected, "{}", case.name);
+            assert_eq!(state.write_conflicts(), case.conflicts, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_update_sends_merged_writes() -> Result<()> {
+        let mut backend = InMemoryBackend::new(0x02000000, 16);
+        let mut state = State::default();
+        state.request_write(0x02000000, vec![1, 2, 3, 4]);
+        state.request_write(0x02000002, vec![5]);
+        state.update(&mut backend)?;
+
+        assert_eq!(&backend.data()[..4], &[1, 2, 5, 4]);
+        assert_eq!(state.pending_writes().count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_only_drops_writes() -> Result<()> {
+        let mut backend = InMemoryBackend::new(0x02000000, 16);
+        let mut state = State::default();
+        state.request_write(0x02000000, vec![1]);
+        state.set_read_only(true);
+        assert_eq!(state.pending_writes().count(), 0);
+
+        state.request_write(0x02000004, vec![2]);
+        state.update(&mut backend)?;
+        assert_eq!(&backend.data()[..8], &[0; 8]);
+
+        state.set_read_only(false);
+        state.request_write(0x02000004, vec![3]);
+        state.update(&mut backend)?;
+        assert_eq!(backend.data()[4], 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_refresh_rates() -> Result<()> {
+        let mut backend = InMemoryBackend::new(0x02000000, 16);
+        let mut state = State::default();
+        let update = |state: &mut State, backend: &mut InMemoryBackend, value: u8| {
+            backend.write(0x02000000, &[value; 12]).unwrap();
+            state.set_refresh_rate(RefreshRate::Paused);
+            state.request(0x02000000, 4);
+            state.set_refresh_rate(RefreshRate::SLOW);
+            state.request(0x02000004, 4);
+            // Requested by a paused and a live window, the live one wins
+            state.set_refresh_rate(RefreshRate::Paused);
+            state.request(0x02000008, 4);
+            state.set_refresh_rate(RefreshRate::Live);
+            state.request(0x02000008, 4);
+            state.update(backend)
+        };
+
+        update(&mut state, &mut backend, 1)?;
+        update(&mut state, &mut backend, 2)?;
+        assert_eq!(state.get_data(0x02000000), Some([1; 4].as_slice()));
+        assert_eq!(state.get_data(0x02000004), Some([1; 4].as_slice()));
+        assert_eq!(state.get_data(0x02000008), Some([2; 4].as_slice()));
+
+        // Only the live request was read
+        assert_eq!(state.bytes_per_update(), 4);
+
+        // Changing the length renews a paused request
+        state.set_refresh_rate(RefreshRate::Paused);
+        state.request(0x02000000, 2);
+        state.update(&mut backend)?;
+        assert_eq!(state.get_data(0x02000000), Some([2; 2].as_slice()));
+        assert_eq!(state.bytes_per_update(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_invalidates_requests() -> Result<()> {
+        let mut backend = InMemoryBackend::new(0x02000000, 16);
+        let mut state = State::default();
+        let request = |state: &mut State| {
+            state.set_refresh_rate(RefreshRate::Paused);
+            state.request(0x02000000, 4);
+            state.request(0x02000004, 4);
+            state.request_cstring(0x02000008, 4);
+        };
+        request(&mut state);
+        state.update(&mut backend)?;
+
+        // Changes by the game stay hidden from paused requests, but writes through the state show
+        backend.write(0x02000000, b"abcdefgh")?;
+        request(&mut state);
+        state.request_write(0x02000006, b"XYZ".to_vec());
+        state.update(&mut backend)?;
+        assert_eq!(state.get_data(0x02000000), Some([0; 4].as_slice()));
+        assert_eq!(state.get_data(0x02000004), Some(b"efXY".as_slice()));
+        assert_eq!(state.get_cstring(0x02000008), Some(b"Z".as_slice()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_changes() -> Result<()> {
+        let mut backend = InMemoryBackend::from_bytes(0x02000000, (0..32).collect());
+        let mut state = State::default();
+        state.request(0x02000000, 4);
+        state.request_cstring(0x02000010, 4);
+        state.update(&mut backend)?;
+        assert_eq!(state.changes(), 1);
+
+        // Same data again
+        state.update(&mut backend)?;
+        assert_eq!(state.changes(), 1);
+
+        backend.write(0x02000002, &[0xff])?;
+        state.update(&mut backend)?;
+        assert_eq!(state.changes(), 2);
+
+        backend.write(0x02000010, b"a")?;
+        state.update(&mut backend)?;
+        assert_eq!(state.changes(), 3);
+
+        // A failed read keeps the last data, which is no change
+        state.request(0x01000000, 4);
+        assert!(state.update(&mut backend).is_err());
+        assert_eq!(state.changes(), 4);
+        assert!(state.update(&mut backend).is_err());
+        assert_eq!(state.changes(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_request_groups() -> Result<()> {
+        let mut backend = InMemoryBackend::new(0x02000000, 16);
+        let mut state = State::default();
+        let update = |state: &mut State, backend: &mut InMemoryBackend, value: u8| {
+            backend.write(0x02000000, &[value; 16]).unwrap();
+            state.begin_group();
+            state.set_refresh_rate(RefreshRate::SLOW);
+            state.request(0x02000000, 4);
+            state.set_refresh_rate(RefreshRate::Live);
+            state.request(0x02000004, 4);
+            state.end_group();
+            state.set_refresh_rate(RefreshRate::SLOW);
+            state.request(0x02000008, 4);
+            state.update(backend)
+        };
+
+        update(&mut state, &mut backend, 1)?;
+        update(&mut state, &mut backend, 2)?;
+        // The slow request is read with the live request of its group
+        assert_eq!(state.get_data(0x02000000), Some([2; 4].as_slice()));
+        assert_eq!(state.get_data(0x02000004), Some([2; 4].as_slice()));
+        assert_eq!(state.get_data(0x02000008), Some([1; 4].as_slice()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_paused_request_in_group() -> Result<()> {
+        let mut backend = InMemoryBackend::new(0x02000000, 16);
+        let mut state = State::default();
+        let update = |state: &mut State, backend: &mut InMemoryBackend, value: u8| {
+            backend.write(0x02000000, &[value; 16]).unwrap();
+            state.begin_group();
+            state.set_refresh_rate(RefreshRate::Live);
+            state.request(0x02000000, 4);
+            state.set_refresh_rate(RefreshRate::Paused);
+            state.request(0x02000004, 4);
+            state.end_group();
+            state.update(backend)
+        };
+
+        update(&mut state, &mut backend, 1)?;
+        update(&mut state, &mut backend, 2)?;
+        // The paused request was read once with its group, but isn't read again with it
+        assert_eq!(state.get_data(0x02000000), Some([2; 4].as_slice()));
+        assert_eq!(state.get_data(0x02000004), Some([1; 4].as_slice()));
+        assert_eq!(state.bytes_per_update(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_nested_request_groups() -> Result<()> {
+        let mut backend = InMemoryBackend::new(0x02000000, 16);
+        let mut state = State::default();
+        let update = |state: &mut State, backend: &mut InMemoryBackend, value: u8| {
+            backend.write(0x02000000, &[value; 16]).unwrap();
+            state.begin_group();
+            state.set_refresh_rate(RefreshRate::Live);
+            state.request(0x02000000, 4);
+            state.set_refresh_rate(RefreshRate::SLOW);
+            state.begin_group();
+            state.request(0x02000004, 4);
+            state.end_group();
+            // Still in the outer group after the nested group ended
+            state.request(0x02000008, 4);
+            state.end_group();
+            state.request(0x0200000c, 4);
+            state.update(backend)
+        };
+
+        update(&mut state, &mut backend, 1)?;
+        update(&mut state, &mut backend, 2)?;
+        assert_eq!(state.get_data(0x02000000), Some([2; 4].as_slice()));
+        assert_eq!(state.get_data(0x02000004), Some([2; 4].as_slice()));
+        assert_eq!(state.get_data(0x02000008), Some([2; 4].as_slice()));
+        assert_eq!(state.get_data(0x0200000c), Some([1; 4].as_slice()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_budget() -> Result<()> {
+        let mut backend = InMemoryBackend::new(0x02000000, 16);
+        let mut state = State::default();
+        state.set_read_budget(Some(8));
+        let update = |state: &mut State, backend: &mut InMemoryBackend, value: u8| {
+            backend.write(0x02000000, &[value; 16]).unwrap();
+            state.begin_group();
+            state.request(0x02000000, 4);
+            state.request(0x02000004, 4);
+            state.end_group();
+            state.request(0x02000008, 4);
+            state.update(backend)
+        };
+
+        // Both were never read, the group doesn't fit after the single request
+        update(&mut state, &mut backend, 1)?;
+        assert_eq!(state.get_data(0x02000000), None);
+        assert_eq!(state.get_data(0x02000004), None);
+        assert_eq!(state.get_data(0x02000008), Some([1; 4].as_slice()));
+
+        // The group was never read, so it goes first and fills the budget
+        update(&mut state, &mut backend, 2)?;
+        assert_eq!(state.get_data(0x02000000), Some([2; 4].as_slice()));
+        assert_eq!(state.get_data(0x02000004), Some([2; 4].as_slice()));
+        assert_eq!(state.get_data(0x02000008), Some([1; 4].as_slice()));
+
+        // The group is never split, even though half of it would fit
+        update(&mut state, &mut backend, 3)?;
+        assert_eq!(state.get_data(0x02000000), Some([2; 4].as_slice()));
+        assert_eq!(state.get_data(0x02000004), Some([2; 4].as_slice()));
+        assert_eq!(state.get_data(0x02000008), Some([3; 4].as_slice()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_data_range() -> Result<()> {
+        let mut backend = InMemoryBackend::from_bytes(0x02000000, (0..32).collect());
+        let mut state = State::default();
+        state.request(0x02000000, 16);
+        state.request(0x02000008, 2);
+        state.update(&mut backend)?;
+
+        // The closest block is too short, so the enclosing one is used
+        assert_eq!(state.get_data_range(0x02000009, 4), Some([9, 10, 11, 12].as_slice()));
+        assert_eq!(state.get_data_range(0x02000008, 2), Some([8, 9].as_slice()));
+        assert_eq!(state.get_data_range(0x0200000c, 4), Some([12, 13, 14, 15].as_slice()));
+        assert_eq!(state.get_data_range(0x0200000e, 4), None);
+        assert_eq!(state.get_data_range(0x01ffffff, 1), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_freshness() {
+        let mut backend = InMemoryBackend::new(0x02000000, 16);
+        let mut state = State::default();
+        state.request(0x02000000, 8);
+        assert_eq!(state.get_age(0x02000004), None);
+
+        state.update(&mut backend).unwrap();
+        assert!(state.get_age(0x02000004).is_some());
+        assert!(!state.read_failed(0x02000004));
+        assert_eq!(state.get_age(0x02000008), None);
+
+        // A failed read keeps the age of the last successful read
+        let previous = state.track_freshness(Some(Freshness::default()));
+        assert_eq!(previous, None);
+        state.request(0x02000000, 8);
+        state.request(0x02000010, 4);
+        state.update(&mut backend).unwrap_err();
+        let freshness = state.track_freshness(None).unwrap();
+        assert_eq!(freshness.requests, 2);
+        assert_eq!(freshness.failed, 0);
+        assert!(freshness.oldest.is_some());
+        assert!(state.read_failed(0x02000010));
+        assert_eq!(state.get_age(0x02000010), None);
+
+        state.track_freshness(Some(Freshness::default()));
+        state.request(0x02000010, 4);
+        state.request(0x02000000, 4);
+        let freshness = state.track_freshness(None).unwrap();
+        assert_eq!(freshness.failed, 1);
+        assert_eq!(freshness.requests, 2);
+    }
+
+    #[test]
+    fn test_watch_events() -> Result<()> {
+        let mut backend = InMemoryBackend::new(0x02000000, 16);
+        let mut state = State::default();
+        state.add_watch(Watch::new(0x02000004, 2, WatchKind::Unsigned, "hp".into()));
+        state.update(&mut backend)?;
+        state.update(&mut backend)?;
+        assert_eq!(state.watch_events().count(), 0);
+
+        backend.write(0x02000004, &[3, 0])?;
+        state.update(&mut backend)?;
+        let events: Vec<_> = state.watch_events().collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].label, "hp");
+        assert_eq!(events[0].old, [0, 0]);
+        assert_eq!(events[0].new, [3, 0]);
+        assert!(!state.is_halted());
+
+        state.remove_watch(0x02000004, 2);
+        backend.write(0x02000004, &[4, 0])?;
+        state.update(&mut backend)?;
+        assert_eq!(state.watch_events().count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_watch_halt_on_change() -> Result<()> {
+        let mut backend = InMemoryBackend::new(0x02000000, 16);
+        let mut state = State::default();
+        state.set_halt_on_change(true);
+        state.add_watch(Watch::new(0x02000000, 1, WatchKind::Bool, "flag".into()));
+        state.update(&mut backend)?;
+        assert!(!state.is_halted());
+
+        backend.write(0x02000000, &[1])?;
+        state.update(&mut backend)?;
+        assert!(state.is_halted());
+        state.continue_updates();
+        assert!(!state.is_halted());
+
+        backend.write(0x02000000, &[0])?;
+        state.update(&mut backend)?;
+        state.clear_watches();
+        assert!(!state.is_halted());
+        assert!(state.watches().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_watch_kind_format() {
+        assert_eq!(WatchKind::Unsigned.format(&[0xff, 0]), "255 (0xff)");
+        assert_eq!(WatchKind::Signed.format(&[0xfe]), "-2");
+        assert_eq!(WatchKind::Float.format(&1.5f32.to_le_bytes()), "1.5");
+        assert_eq!(WatchKind::Bool.format(&[0]), "false");
+    }
+}
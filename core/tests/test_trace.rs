@@ -0,0 +1,95 @@
+mod tests {
+    use std::io::Cursor;
+
+    use anyhow::Result;
+    use dsv_core::{
+        memory::{MemoryBackend, in_memory::InMemoryBackend},
+        state::State,
+        trace::{RecordingBackend, TraceReplay, TraceWriter},
+    };
+
+    #[test]
+    fn test_record_and_replay() -> Result<()> {
+        let mut ram = InMemoryBackend::from_bytes(0x02000000, (0..64).collect());
+        let mut writer = TraceWriter::new(Vec::new(), "AZEE")?;
+        let mut state = State::default();
+        state.request(0x02000000, 16);
+
+        writer.begin_frame()?;
+        state.update(&mut RecordingBackend { backend: &mut ram, writer: &mut writer })?;
+
+        state.request_write(0x02000004, vec![0xaa, 0xbb]);
+        writer.begin_frame()?;
+        state.update(&mut RecordingBackend { backend: &mut ram, writer: &mut writer })?;
+
+        let trace = writer.into_inner()?;
+        let mut replay = TraceReplay::from_reader(Cursor::new(trace))?;
+        assert_eq!(replay.gamecode(), "AZEE");
+        assert_eq!(replay.frames().len(), 2);
+        assert_eq!(replay.frames()[1].writes, vec![(0x02000004, vec![0xaa, 0xbb])]);
+
+        let mut buf = [0; 4];
+        replay.seek(0);
+        replay.read(0x02000004, &mut buf)?;
+        assert_eq!(buf, [4, 5, 6, 7]);
+
+        replay.seek(1);
+        replay.read(0x02000004, &mut buf)?;
+        assert_eq!(buf, [0xaa, 0xbb, 6, 7]);
+
+        assert!(replay.read(0x02000020, &mut buf).is_err());
+        assert!(replay.write(0x02000004, &buf).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_truncated_trace() -> Result<()> {
+        let mut writer = TraceWriter::new(Vec::new(), "BKIE")?;
+        writer.begin_frame()?;
+        writer.record_read(0x02000000, &[1, 2, 3, 4])?;
+        let mut trace = writer.into_inner()?;
+        trace.truncate(trace.len() - 2);
+
+        let replay = TraceReplay::from_reader(Cursor::new(trace))?;
+        assert_eq!(replay.frames().len(), 1);
+        assert!(replay.frames()[0].reads.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_oversized_record() -> Result<()> {
+        let mut writer = TraceWriter::new(Vec::new(), "BKIE")?;
+        writer.begin_frame()?;
+        let mut trace = writer.into_inner()?;
+        // A read record claiming 4 GiB of data, followed by only a few bytes
+        trace.push(1);
+        trace.extend(0x02000000_u32.to_le_bytes());
+        trace.extend(u32::MAX.to_le_bytes());
+        trace.extend([1, 2, 3, 4]);
+
+        let replay = TraceReplay::from_reader(Cursor::new(trace))?;
+        assert_eq!(replay.frames().len(), 1);
+        assert!(replay.frames()[0].reads.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_seek_frame() -> Result<()> {
+        let mut writer = TraceWriter::new(Vec::new(), "AZEE")?;
+        writer.begin_frame()?;
+        writer.record_read(0x02000000, &[1, 2, 3, 4])?;
+        writer.begin_frame()?;
+        writer.record_read(0x02000000, &[5, 6, 7, 8])?;
+        let mut replay = TraceReplay::from_reader(Cursor::new(writer.into_inner()?))?;
+
+        let mut buf = [0; 4];
+        replay.seek_frame(1)?;
+        replay.read(0x02000000, &mut buf)?;
+        assert_eq!(buf, [5, 6, 7, 8]);
+
+        replay.disconnect()?;
+        assert!(!replay.is_connected());
+        assert!(InMemoryBackend::from_bytes(0x02000000, vec![0; 4]).seek_frame(0).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,238 @@
+mod tests {
+    use std::{borrow::Cow, ops::Range};
+
+    use dsv_core::{
+        state::State,
+        test_support::parse_header,
+        typed::{TypeInstance, TypeInstanceOptions},
+    };
+    use type_crawler::Types;
+
+    const FIXTURE_HEADER: &str = "
+        typedef struct Vec3p {
+            int x;
+            int y;
+            int z;
+        } Vec3p;
+
+        union Value {
+            unsigned int word;
+            unsigned short half;
+            unsigned char byte;
+        };
+
+        struct Actor {
+            unsigned char kind;
+            unsigned short id;
+            unsigned int active : 1;
+            unsigned int state : 5;
+            unsigned int frames : 12;
+            Vec3p pos;
+            union Value value;
+        };
+    ";
+
+    fn fixture_types() -> Types {
+        parse_header("fixture.h", FIXTURE_HEADER)
+    }
+
+    fn bit_field<'a>(
+        ty: &'a type_crawler::TypeKind,
+        parent: &'a TypeInstance<'a>,
+        offset: usize,
+        range: Range<u8>,
+    ) -> TypeInstance<'a> {
+        parent.slice(&Types::new(), ty, offset, Some(range))
+    }
+    #[test]
+    fn test_slice_past_end() {
+        let types = type_crawler::Types::new();
+        let ty = type_crawler::TypeKind::U32;
+        let instance = TypeInstance::new(TypeInstanceOptions {
+            ty: &ty,
+            address: 0x02000000,
+            bit_field_range: None,
+            data: Cow::Owned(vec![0x11, 0x22, 0x33, 0x44, 0x55, 0x66]),
+        });
+        assert!(!instance.is_truncated());
+
+        let inside = instance.slice(&types, &ty, 0, None);
+        assert_eq!(&inside.data()[..], &[0x11, 0x22, 0x33, 0x44]);
+        assert!(!inside.is_truncated());
+
+        let partial = instance.slice(&types, &ty, 4, None);
+        assert_eq!(partial.address(), 0x02000004);
+        assert_eq!(&partial.data()[..], &[0x55, 0x66, 0, 0]);
+        assert!(partial.is_truncated());
+
+        let outside = instance.slice(&types, &ty, 8, None);
+        assert_eq!(&outside.data()[..], &[0; 4]);
+        assert!(outside.is_truncated());
+    }
+
+    #[test]
+    fn test_borrowed() {
+        let types = type_crawler::Types::new();
+        let ty = type_crawler::TypeKind::U32;
+        let instance = TypeInstance::new(TypeInstanceOptions {
+            ty: &ty,
+            address: 0x02000000,
+            bit_field_range: None,
+            data: Cow::Owned(vec![0x11, 0x22, 0x33, 0x44, 0x55, 0x66]),
+        });
+        let borrowed = instance.borrowed();
+        assert_eq!(borrowed.address(), 0x02000000);
+        assert_eq!(borrowed.data().as_ptr(), instance.data().as_ptr());
+
+        let partial = instance.slice(&types, &ty, 4, None);
+        let borrowed = partial.borrowed();
+        assert_eq!(&borrowed.data()[..], &[0x55, 0x66, 0, 0]);
+        assert!(borrowed.is_truncated());
+    }
+
+    #[test]
+    fn test_bit_field_read() {
+        // Data, offset, bit range, expected value
+        type Case = (&'static [u8], usize, Range<u8>, i64);
+        let cases: &[Case] = &[
+            (&[0b0000_0101, 0], 0, 0..3, 5),
+            (&[0b1110_0000, 0], 0, 3..8, 0b11100),
+            (&[0b1100_0000, 0b0000_0111], 0, 6..11, 0b11111),
+            (&[0b1010_0000, 0b0000_0101], 0, 5..11, 0b101101),
+            (&[0b0000_0000, 0b1000_0000, 0xff, 0b0000_0001], 1, 7..17, 0x3ff),
+            (&[0xff, 0b0110_1111, 0xff, 0xff], 1, 4..7, 0b110),
+            (&[0xff, 0b1111_1110, 0b0000_1111, 0xff], 0, 9..20, 0x7ff),
+            (&[0, 0, 0b1000_0000, 0b0101_0101], 1, 15..24, 0b1010_1011),
+        ];
+        let ty = type_crawler::TypeKind::U32;
+        for (data, offset, range, expected) in cases {
+            let parent = TypeInstance::new(TypeInstanceOptions {
+                ty: &ty,
+                address: 0x02000000,
+                bit_field_range: None,
+                data: Cow::Borrowed(data),
+            });
+            let field = bit_field(&ty, &parent, *offset, range.clone());
+            assert_eq!(field.data_i64(), *expected, "bits {range:?} at offset {offset}");
+        }
+    }
+
+    #[test]
+    fn test_signed_bit_field() {
+        let types = Types::new();
+        let data: &[u8] = &[0b1110_0000, 0b0000_0001];
+        // Type, bit range, expected value
+        let cases = [
+            (type_crawler::TypeKind::S32, 3..8, -4),
+            (type_crawler::TypeKind::U32, 3..8, 0b11100),
+            (type_crawler::TypeKind::S16, 5..9, -1),
+            (type_crawler::TypeKind::S8, 4..8, -2),
+            (type_crawler::TypeKind::S32, 7..9, -1),
+        ];
+        for (ty, range, expected) in cases {
+            let parent = TypeInstance::new(TypeInstanceOptions {
+                ty: &ty,
+                address: 0x02000000,
+                bit_field_range: None,
+                data: Cow::Borrowed(data),
+            });
+            let field = bit_field(&ty, &parent, 0, range.clone());
+            assert_eq!(field.as_int::<i64>(&types), Some(expected), "{ty:?} bits {range:?}");
+        }
+    }
+
+    #[test]
+    fn test_bit_field_write() {
+        // Data, bit range, written value, expected data
+        type Case = (&'static [u8], Range<u8>, u32, &'static [u8]);
+        let cases: &[Case] = &[
+            (&[0xff], 0..3, 0, &[0b1111_1000]),
+            (&[0x00], 3..8, 0b10101, &[0b1010_1000]),
+            (&[0xff, 0xff], 6..11, 0, &[0b0011_1111, 0b1111_1000]),
+            (&[0x00, 0x00], 6..11, 0b10101, &[0b0100_0000, 0b0000_0101]),
+            (&[0x00, 0x00, 0x00], 7..17, 0x3ff, &[0b1000_0000, 0xff, 0b0000_0001]),
+            (&[0xff, 0xff, 0xff], 9..20, 0, &[0b0000_0001, 0b1111_0000]),
+            // Bits outside of the field are ignored
+            (&[0x00, 0x00], 4..10, 0xffff_ffff, &[0b1111_0000, 0b0000_0011]),
+        ];
+        let ty = type_crawler::TypeKind::U32;
+        for (data, range, value, expected) in cases {
+            let parent = TypeInstance::new(TypeInstanceOptions {
+                ty: &ty,
+                address: 0x02000000,
+                bit_field_range: None,
+                data: Cow::Borrowed(data),
+            });
+            let field = bit_field(&ty, &parent, 0, range.clone());
+            let mut state = State::default();
+            field.write(&mut state, value.to_le_bytes().to_vec());
+            let writes: Vec<_> = state.pending_writes().collect();
+            let address = 0x02000000 + range.start as u32 / 8;
+            assert_eq!(writes, vec![(address, *expected)], "bits {range:?}");
+        }
+    }
+
+    fn actor_data() -> Vec<u8> {
+        let bits: u32 = 1 | (22 << 1) | (0x5a5 << 6);
+        let mut data = vec![7, 0, 0x34, 0x12];
+        data.extend(bits.to_le_bytes());
+        for coord in [-1i32, 2, 3] {
+            data.extend(coord.to_le_bytes());
+        }
+        data.extend(0xdeadbeef_u32.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_read_field() {
+        let types = fixture_types();
+        let actor_type = types.get("Actor").expect("fixture should define Actor");
+        let actor = TypeInstance::new(TypeInstanceOptions {
+            ty: actor_type,
+            address: 0x02000000,
+            bit_field_range: None,
+            data: Cow::Owned(actor_data()),
+        });
+
+        // Name, expected value
+        let cases: &[(&str, i64)] =
+            &[("kind", 7), ("id", 0x1234), ("active", 1), ("state", 22), ("frames", 0x5a5)];
+        for (name, expected) in cases {
+            assert_eq!(actor.read_int_field::<i64>(&types, name), Some(*expected), "{name}");
+        }
+        assert!(actor.read_field(&types, "missing").is_none());
+
+        // Typedef'd struct
+        let pos = actor.read_field(&types, "pos").unwrap();
+        assert_eq!(pos.address(), 0x02000008);
+        assert_eq!(pos.read_int_field::<i32>(&types, "x"), Some(-1));
+        assert_eq!(pos.read_int_field::<i32>(&types, "z"), Some(3));
+
+        // Union members all start at the union
+        let value = actor.read_field(&types, "value").unwrap();
+        assert_eq!(value.read_int_field::<u32>(&types, "word"), Some(0xdeadbeef));
+        assert_eq!(value.read_int_field::<u16>(&types, "half"), Some(0xbeef));
+        assert_eq!(value.read_int_field::<u8>(&types, "byte"), Some(0xef));
+    }
+
+    #[test]
+    fn test_read_field_past_end() {
+        let types = fixture_types();
+        let actor_type = types.get("Actor").expect("fixture should define Actor");
+        let actor = TypeInstance::new(TypeInstanceOptions {
+            ty: actor_type,
+            address: 0x02000000,
+            bit_field_range: None,
+            data: Cow::Owned(actor_data()[..12].to_vec()),
+        });
+
+        let pos = actor.read_field(&types, "pos").unwrap();
+        assert!(pos.is_truncated());
+        let x = pos.read_field(&types, "x").unwrap();
+        assert!(!x.is_truncated());
+        assert_eq!(x.as_int::<i32>(&types), Some(-1));
+        let z = pos.read_field(&types, "z").unwrap();
+        assert!(z.is_truncated());
+        assert_eq!(z.as_int::<i32>(&types), Some(0));
+    }
+}
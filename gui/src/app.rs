@@ -1,29 +1,113 @@
 use std::{
-    net::ToSocketAddrs,
+    net::{SocketAddr, ToSocketAddrs},
     path::PathBuf,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use anyhow::{Context, Result};
-use dsv_core::gdb::client::GdbClient;
+use dsv_core::{
+    gdb::{client::GdbClient, quirks::StubInfo},
+    trace::TraceReplay,
+    types::mirror::check_layouts,
+};
 use eframe::egui::{self, Color32};
 
 use crate::{
+    client::{Client, Command},
     config::Config,
-    tasks::load_types::{LoadTypesTask, LoadTypesTaskOptions},
-    ui::text_field_list::TextFieldList,
-    views::{View, ph, st},
+    tasks::{
+        launch_emulator::EmulatorProcess,
+        load_types::{LoadTypesTask, LoadTypesTaskOptions, LoadedTypes},
+    },
+    ui::{
+        dock::set_safe_mode,
+        text_field_list::TextFieldList,
+        type_decl::{
+            forget_array_ranges, set_flatten_base_types, set_float_precision, set_raw_bytes_tooltip,
+        },
+    },
+    util::{
+        keybindings::{self, COMMAND_PALETTE},
+        log_buffer,
+    },
+    views::{
+        View,
+        game::{GameProfile, GameView, PROFILES},
+    },
+    windows::{
+        connection::ConnectionWindow,
+        events::{EventsWindow, flash_windows},
+        execution::ExecutionWindow,
+        keybindings::KeybindingsWindow,
+        layout_checks::LayoutChecksWindow,
+        log::LogWindow,
+        monitor::MonitorWindow,
+        palette::{CommandPalette, PaletteAction},
+        types::TypesWindow,
+    },
 };
 
+/// Longest time between repaints while connected, for things like connection stats which don't
+/// repaint on their own.
+const REPAINT_KEEPALIVE: Duration = Duration::from_secs(1);
+/// Time between repaints while the status of a task is shown.
+const TASK_STATUS_INTERVAL: Duration = Duration::from_millis(200);
+
 pub struct DsvApp {
     config_path: Option<PathBuf>,
     config: Config,
 
     project_modal_open: bool,
-    types: Arc<Mutex<type_crawler::Types>>,
+    types: Arc<Mutex<LoadedTypes>>,
+    /// Generation of the types which the views last saw, see [`LoadedTypes::generation`]
+    types_generation: u64,
+    /// Shown in the status bar after new types were loaded
+    types_refreshed: Option<String>,
+    type_names: Arc<Mutex<Vec<String>>>,
     load_types_task: Option<LoadTypesTask>,
 
-    view: Option<Box<dyn View>>,
+    sessions: Vec<Session>,
+    pending_connection: Option<PendingConnection>,
+    /// Stub whose limitations are shown in a notice
+    stub_notice: Option<StubInfo>,
+    /// Emulator started with the Launch button
+    emulator: Option<EmulatorProcess>,
+    /// Index of the session shown in the central and side panels
+    active_session: usize,
+    monitor_window: MonitorWindow,
+    connection_window: ConnectionWindow,
+    events_window: EventsWindow,
+    execution_window: ExecutionWindow,
+    types_window: TypesWindow,
+    log_window: LogWindow,
+    keybindings_window: KeybindingsWindow,
+    layout_checks_window: LayoutChecksWindow,
+    command_palette: CommandPalette,
+}
+
+/// A connection to an emulator or an opened trace, shown as a tab.
+struct Session {
+    view: Box<dyn View>,
+    /// GDB address or trace file name
+    source: String,
+    /// Emulator behind the GDB stub, `None` for traces
+    stub: Option<StubInfo>,
+    /// Game chosen by hand instead of detected from the gamecode
+    chosen_game: Option<&'static GameProfile>,
+    replay_frame: usize,
+}
+
+/// A connection whose game couldn't be detected, waiting for the user to choose one.
+struct PendingConnection {
+    client: GdbClient,
+    gamecode: Option<String>,
+}
+
+impl Session {
+    fn title(&self) -> String {
+        format!("{} @ {}", self.view.client().gamecode(), self.source)
+    }
 }
 
 impl Default for DsvApp {
@@ -33,17 +117,41 @@ impl Default for DsvApp {
             config: Config::new(),
 
             project_modal_open: false,
-            types: Arc::new(Mutex::new(type_crawler::Types::new())),
+            types: Arc::new(Mutex::new(LoadedTypes::new())),
+            types_generation: 0,
+            types_refreshed: None,
+            type_names: Arc::new(Mutex::new(Vec::new())),
             load_types_task: None,
 
-            view: None,
+            sessions: Vec::new(),
+            pending_connection: None,
+            stub_notice: None,
+            emulator: None,
+            active_session: 0,
+            monitor_window: MonitorWindow::default(),
+            connection_window: ConnectionWindow::default(),
+            events_window: EventsWindow::default(),
+            execution_window: ExecutionWindow::default(),
+            types_window: TypesWindow::default(),
+            log_window: LogWindow::default(),
+            keybindings_window: KeybindingsWindow::default(),
+            layout_checks_window: LayoutChecksWindow::default(),
+            command_palette: CommandPalette::default(),
         }
     }
 }
 
 impl eframe::App for DsvApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        ctx.request_repaint();
+        // Clients repaint when their data changes, input repaints on its own
+        if !self.sessions.is_empty() {
+            ctx.request_repaint_after(REPAINT_KEEPALIVE);
+        }
+        if self.load_types_task.as_ref().is_some_and(|task| task.is_running()) {
+            ctx.request_repaint_after(TASK_STATUS_INTERVAL);
+        }
+        self.poll_emulator(ctx);
+        self.run_shortcuts(ctx);
 
         egui::TopBottomPanel::top("dsv_top_panel")
             .frame(egui::Frame::new().inner_margin(4).fill(Color32::from_gray(20)))
@@ -68,18 +176,107 @@ impl eframe::App for DsvApp {
                     {
                         self.save_config();
                     }
-                    if self.view.is_none() {
-                        if ui.button("Connect").clicked()
-                            && let Err(e) = self.connect()
+                    let game_name = |key: &Option<String>| match key {
+                        Some(key) => GameProfile::for_config_key(key).map_or("Unknown", |p| p.name),
+                        None => "Auto-detect",
+                    };
+                    let mut game = self.config.gdb.game.clone();
+                    egui::ComboBox::from_id_salt("dsv_game")
+                        .selected_text(game_name(&game))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut game, None, "Auto-detect");
+                            for profile in PROFILES {
+                                let key = Some(profile.config_key.to_string());
+                                ui.selectable_value(&mut game, key, profile.name);
+                            }
+                        })
+                        .response
+                        .on_hover_text("Game to open on connecting");
+                    if game != self.config.gdb.game {
+                        self.config.gdb.game = game;
+                        self.save_config();
+                    }
+                    if ui.button("Connect").on_hover_text("Open a new session tab").clicked()
+                        && let Err(e) = self.connect(ctx)
+                    {
+                        log::error!("Failed to connect: {e}");
+                    }
+                    if self.emulator.as_ref().is_some_and(|emulator| emulator.is_waiting()) {
+                        ui.spinner();
+                        ui.label("Waiting for emulator");
+                    } else if !self.config.emulator.executable.is_empty()
+                        && ui
+                            .add_enabled(self.emulator.is_none(), egui::Button::new("Launch"))
+                            .on_hover_text("Start the emulator and connect once it's ready")
+                            .on_disabled_hover_text("The emulator is already running")
+                            .clicked()
+                        && let Err(e) = self.launch_emulator()
+                    {
+                        log::error!("Failed to launch emulator: {e}");
+                    }
+                    if ui.button("Open trace").clicked() {
+                        self.pick_trace(ctx);
+                    }
+                    if !self.sessions.is_empty() {
+                        ui.separator();
+                        if ui.button("Disconnect").clicked() {
+                            self.disconnect();
+                        }
+                    }
+                    if let Some(session) = self.sessions.get(self.active_session) {
+                        let view = &session.view;
+                        if let Some(profile) = session.chosen_game {
+                            let gamecode = view.client().gamecode();
+                            if profile.matches(gamecode) {
+                                ui.label(profile.name).on_hover_text("Chosen instead of detected");
+                            } else {
+                                ui.colored_label(Color32::ORANGE, profile.name).on_hover_text(
+                                    format!("Chosen, but gamecode {gamecode} is another game"),
+                                );
+                            }
+                        }
+                        ui.toggle_value(&mut self.monitor_window.open, "Monitor");
+                        ui.toggle_value(&mut self.connection_window.open, "Connection");
+                        ui.toggle_value(&mut self.events_window.open, "Events");
+                        let client = view.client();
+                        if client.replay().is_none() {
+                            ui.toggle_value(&mut self.execution_window.open, "Execution");
+                            if client.is_paused() {
+                                ui.colored_label(Color32::ORANGE, "Paused");
+                            }
+                        }
+                        let mut state = client.state.lock().unwrap();
+                        if state.is_halted() {
+                            ui.colored_label(Color32::ORANGE, "Halted by watch");
+                            if ui.button("Continue").clicked() {
+                                state.continue_updates();
+                            }
+                        }
+                        let mut safe_mode = state.is_read_only();
+                        if ui
+                            .toggle_value(&mut safe_mode, "Safe mode")
+                            .on_hover_text("Block all writes to memory")
+                            .changed()
                         {
-                            log::error!("Failed to connect: {e}");
+                            state.set_read_only(safe_mode);
                         }
-                    } else if ui.button("Disconnect").clicked()
-                        && let Some(view) = &mut self.view
-                    {
-                        match view.exit() {
-                            Ok(_) => self.view = None,
-                            Err(e) => log::error!("Failed to disconnect: {e}"),
+                        drop(state);
+                        if client.replay().is_none() {
+                            if client.is_recording() {
+                                if ui.button("Stop recording").clicked() {
+                                    client.send_command(Command::StopRecording).unwrap_or_else(
+                                        |e| log::error!("Failed to stop recording: {e}"),
+                                    );
+                                }
+                            } else if ui.button("Record").clicked()
+                                && let Some(file) = rfd::FileDialog::new()
+                                    .add_filter("dsv trace", &["dsvt"])
+                                    .save_file()
+                            {
+                                client.send_command(Command::StartRecording(file)).unwrap_or_else(
+                                    |e| log::error!("Failed to start recording: {e}"),
+                                );
+                            }
                         }
                     }
 
@@ -88,28 +285,22 @@ impl eframe::App for DsvApp {
                         self.project_modal_open = true;
                     }
                     if ui.button("Load types").clicked() {
-                        if let Some(mut task) = self.load_types_task.take() {
-                            task.terminate();
-                        }
-                        let project_root = self.config.types.project_root.clone().into();
-                        let include_paths =
-                            self.config.types.include_paths.iter().map(|s| s.into()).collect();
-                        let ignore_paths =
-                            self.config.types.ignore_paths.iter().map(|s| s.into()).collect();
-                        let options = LoadTypesTaskOptions {
-                            project_root,
-                            types: self.types.clone(),
-                            include_paths,
-                            ignore_paths,
-                            short_enums: self.config.types.short_enums,
-                        };
-                        let mut task = LoadTypesTask::new(options);
-                        if let Err(e) = task.run() {
-                            log::error!("Failed to start type loading task: {e}");
-                        } else {
-                            self.load_types_task = Some(task);
-                        }
+                        self.load_types(None);
                     }
+                    ui.toggle_value(&mut self.types_window.open, "Types");
+                    let mismatches = check_layouts(&self.types.lock().unwrap())
+                        .iter()
+                        .filter(|report| !report.is_ok())
+                        .count();
+                    let layout_text = if mismatches > 0 {
+                        egui::RichText::new(format!("Layout checks ({mismatches})"))
+                            .color(ui.visuals().error_fg_color)
+                    } else {
+                        egui::RichText::new("Layout checks")
+                    };
+                    ui.toggle_value(&mut self.layout_checks_window.open, layout_text)
+                        .on_hover_text("Structs built into dsv which differ from the headers");
+                    ui.toggle_value(&mut self.keybindings_window.open, "Shortcuts");
                 });
             });
 
@@ -117,11 +308,33 @@ impl eframe::App for DsvApp {
             .frame(egui::Frame::new().inner_margin(4).fill(Color32::from_gray(20)))
             .show(ctx, |ui| {
                 ui.horizontal(|ui| {
+                    let errors = log_buffer::unseen_errors();
+                    let log_text = if errors > 0 {
+                        egui::RichText::new(format!("Log ({errors})"))
+                            .color(Color32::WHITE)
+                            .background_color(ui.visuals().error_fg_color)
+                    } else {
+                        egui::RichText::new("Log")
+                    };
+                    ui.toggle_value(&mut self.log_window.open, log_text)
+                        .on_hover_text("Errors since the log was last viewed");
+                    ui.separator();
+                    if let Some(session) = self.sessions.get(self.active_session)
+                        && let Some(stub) = &session.stub
+                    {
+                        ui.label(stub.kind.to_string())
+                            .on_hover_text(format!("GDB stub: {}", stub.identification()));
+                        ui.separator();
+                    }
                     if let Some(task) = &self.load_types_task {
                         ui.label(format!("Status: {}", task.status()));
                     } else {
                         ui.label("No type loading task running");
                     }
+                    if let Some(text) = &self.types_refreshed {
+                        ui.separator();
+                        ui.label(text);
+                    }
                     if ui.button("Cancel").clicked()
                         && let Some(mut task) = self.load_types_task.take()
                     {
@@ -130,11 +343,57 @@ impl eframe::App for DsvApp {
                 });
             });
 
+        if !self.sessions.is_empty() {
+            egui::TopBottomPanel::top("dsv_session_tabs")
+                .frame(egui::Frame::new().inner_margin(4).fill(Color32::from_gray(20)))
+                .show(ctx, |ui| {
+                    ui.horizontal_wrapped(|ui| {
+                        for (index, session) in self.sessions.iter().enumerate() {
+                            ui.selectable_value(&mut self.active_session, index, session.title());
+                        }
+                    });
+                });
+        }
+
+        if let Some(session) = self.sessions.get_mut(self.active_session)
+            && let Some(replay) = session.view.client().replay()
+        {
+            egui::TopBottomPanel::bottom("dsv_replay_panel")
+                .frame(egui::Frame::new().inner_margin(4).fill(Color32::from_gray(20)))
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        let last_frame = replay.frame_timestamps.len().saturating_sub(1);
+                        let timestamp = replay
+                            .frame_timestamps
+                            .get(session.replay_frame)
+                            .copied()
+                            .unwrap_or_default();
+                        ui.label(format!("{:.3}s", timestamp.as_secs_f32()));
+                        ui.spacing_mut().slider_width = ui.available_width() - 100.0;
+                        if ui
+                            .add(
+                                egui::Slider::new(&mut session.replay_frame, 0..=last_frame)
+                                    .text("Frame"),
+                            )
+                            .changed()
+                        {
+                            session
+                                .view
+                                .client()
+                                .send_command(Command::SeekFrame(session.replay_frame))
+                                .unwrap_or_else(|e| log::error!("Failed to seek replay: {e}"));
+                        }
+                    });
+                });
+        }
+
         egui::SidePanel::right("dsv_side_panel")
             .frame(egui::Frame::new().inner_margin(4).fill(Color32::from_gray(20)))
             .show(ctx, |ui| {
-                if let Some(view) = &mut self.view {
-                    view.render_side_panel(ctx, ui, &self.types.lock().unwrap(), &mut self.config)
+                if let Some(session) = self.sessions.get_mut(self.active_session) {
+                    session
+                        .view
+                        .render_side_panel(ctx, ui, &self.types.lock().unwrap(), &mut self.config)
                         .unwrap_or_else(|e| {
                             log::error!("Failed to render side panel: {e}");
                         });
@@ -176,6 +435,47 @@ impl eframe::App for DsvApp {
                     if ui.checkbox(&mut self.config.types.short_enums, "Short enums").changed() {
                         self.save_config();
                     }
+                    if ui
+                        .checkbox(&mut self.config.ui.raw_bytes_tooltip, "Show raw bytes on hover")
+                        .changed()
+                    {
+                        self.save_config();
+                    }
+                    if ui
+                        .checkbox(&mut self.config.ui.flatten_base_types, "Flatten base classes")
+                        .on_hover_text("List inherited fields without a section per base class")
+                        .changed()
+                    {
+                        self.save_config();
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("Default pointer list length");
+                        let length = egui::DragValue::new(&mut self.config.ui.default_list_length)
+                            .range(1..=usize::MAX);
+                        if ui.add(length).changed() {
+                            self.save_config();
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Float precision");
+                        let precision = egui::DragValue::new(&mut self.config.ui.float_precision)
+                            .range(0..=9)
+                            .suffix(" decimals");
+                        if ui.add(precision).changed() {
+                            self.save_config();
+                        }
+                    });
+                    if ui
+                        .checkbox(&mut self.config.ui.safe_mode, "Connect in safe mode")
+                        .on_hover_text("Block all writes to memory when connecting")
+                        .changed()
+                    {
+                        self.save_config();
+                    }
+                    ui.separator();
+                    if self.render_emulator_config(ui) {
+                        self.save_config();
+                    }
                     ui.separator();
                     if ui.button("Save").clicked() {
                         let file =
@@ -189,18 +489,98 @@ impl eframe::App for DsvApp {
                 self.project_modal_open = open;
             }
 
-            if let Some(view) = self.view.as_mut() {
-                view.render_central_panel(ctx, ui, &self.types.lock().unwrap(), &mut self.config)
-                    .unwrap_or_else(|e| {
-                        log::error!("Failed to render central panel: {e}");
-                    });
+            self.check_types_generation(ctx);
+            set_raw_bytes_tooltip(ctx, self.config.ui.raw_bytes_tooltip);
+            set_flatten_base_types(ctx, self.config.ui.flatten_base_types);
+            set_float_precision(ctx, self.config.ui.float_precision);
+            if let Some(session) = self.sessions.get_mut(self.active_session) {
+                let view = &mut session.view;
+                set_safe_mode(ctx, view.client().state.lock().unwrap().is_read_only());
+                view.render_central_panel(
+                    ctx,
+                    ui,
+                    &self.types.lock().unwrap(),
+                    &self.type_names.lock().unwrap(),
+                    &mut self.config,
+                )
+                .unwrap_or_else(|e| {
+                    log::error!("Failed to render central panel: {e}");
+                });
+            }
+
+            self.log_window.render(ctx);
+            let reload = self.types_window.render(
+                ctx,
+                &self.types.lock().unwrap(),
+                &self.type_names.lock().unwrap(),
+                &self.config.types.project_root,
+            );
+            if let Some(header) = reload {
+                self.load_types(Some(header));
+            }
+            if self.layout_checks_window.open {
+                let reports = check_layouts(&self.types.lock().unwrap());
+                self.layout_checks_window.render(ctx, &reports);
+            }
+
+            if self.config.unsaved_changes {
+                self.config.unsaved_changes = false;
+                self.save_config();
+            }
+
+            self.render_game_selection(ctx);
+            self.render_stub_notice(ctx);
+
+            let mut app_actions = 0;
+            let chosen = self.command_palette.render(ctx, || {
+                let (actions, count) =
+                    Self::palette_actions(self.sessions.get(self.active_session));
+                app_actions = count;
+                actions
+            });
+            if let Some(chosen) = chosen {
+                self.run_palette_action(
+                    ctx,
+                    &chosen.action,
+                    chosen.index < app_actions,
+                    chosen.address,
+                );
+            }
+            if self.keybindings_window.open {
+                let (actions, _) = Self::palette_actions(self.sessions.get(self.active_session));
+                self.config.unsaved_changes |=
+                    self.keybindings_window.render(ctx, &actions, &mut self.config.keybindings);
+            }
+
+            if let Some(session) = self.sessions.get(self.active_session) {
+                let view = &session.view;
+                self.monitor_window.render(ctx, view.client());
+                self.connection_window.render(ctx, view.client(), session.stub.as_ref());
+                self.events_window.render(ctx, view.client());
+                if view.client().replay().is_none() {
+                    self.execution_window.render(ctx, view.client(), &mut self.config);
+                }
+                flash_windows(ctx, &view.client().state.lock().unwrap());
             }
         });
     }
 
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
-        if let Some(mut view) = self.view.take() {
-            view.exit().context("Failed to exit view").unwrap();
+        // Save first, in case a connection doesn't close cleanly
+        self.save_config();
+        for mut session in self.sessions.drain(..) {
+            session.view.exit().unwrap_or_else(|e| log::error!("Failed to exit view: {e}"));
+        }
+        if let Some(mut pending) = self.pending_connection.take() {
+            pending
+                .client
+                .disconnect()
+                .unwrap_or_else(|e| log::error!("Failed to disconnect: {e}"));
+        }
+        if self.config.emulator.terminate_on_disconnect
+            && let Some(mut emulator) = self.emulator.take()
+        {
+            emulator.terminate();
         }
     }
 }
@@ -228,31 +608,477 @@ impl DsvApp {
         }
     }
 
-    fn connect(&mut self) -> Result<()> {
+    /// Starts loading every header of the project, or reloading only `header`.
+    fn load_types(&mut self, header: Option<PathBuf>) {
+        if let Some(mut task) = self.load_types_task.take() {
+            task.terminate();
+        }
+        let project_root = self.config.types.project_root.clone().into();
+        let include_paths = self.config.types.include_paths.iter().map(|s| s.into()).collect();
+        let ignore_paths = self.config.types.ignore_paths.iter().map(|s| s.into()).collect();
+        let options = LoadTypesTaskOptions {
+            project_root,
+            types: self.types.clone(),
+            type_names: self.type_names.clone(),
+            include_paths,
+            ignore_paths,
+            short_enums: self.config.types.short_enums,
+        };
+        let mut task = LoadTypesTask::new(options);
+        let result = match header {
+            Some(header) => task.run_file(header),
+            None => task.run(),
+        };
+        if let Err(e) = result {
+            log::error!("Failed to start type loading task: {e}");
+        } else {
+            self.load_types_task = Some(task);
+        }
+    }
+
+    fn connect(&mut self, ctx: &egui::Context) -> Result<()> {
         log::info!("Connecting to GDB server at {}", self.config.gdb.address);
 
-        let addr = self
-            .config
+        let chosen_game = match &self.config.gdb.game {
+            Some(key) => Some(
+                GameProfile::for_config_key(key)
+                    .with_context(|| format!("Unknown game '{key}' chosen in config"))?,
+            ),
+            None => None,
+        };
+        let addr = self.gdb_socket_address()?;
+
+        let mut gdb_client = GdbClient::new();
+        gdb_client.connect(addr)?;
+        gdb_client.continue_execution()?;
+        let gamecode = gdb_client
+            .get_gamecode()
+            .inspect_err(|e| log::warn!("Failed to get gamecode: {e}"))
+            .ok();
+        if let Some(profile) = chosen_game {
+            self.open_connection(gdb_client, gamecode, profile, true, ctx);
+            return Ok(());
+        }
+        match gamecode.as_deref().and_then(GameProfile::for_gamecode) {
+            Some(profile) => self.open_connection(gdb_client, gamecode, profile, false, ctx),
+            None => {
+                log::warn!("Could not detect the game from gamecode {gamecode:?}, asking for it");
+                self.pending_connection = Some(PendingConnection { client: gdb_client, gamecode });
+            }
+        }
+        Ok(())
+    }
+
+    fn gdb_socket_address(&self) -> Result<SocketAddr> {
+        self.config
             .gdb
             .address
             .to_socket_addrs()
             .context("Failed to resolve address")?
             .next()
-            .context("No socket address found")?;
+            .context("No socket address found")
+    }
 
-        let mut gdb_client = GdbClient::new();
-        gdb_client.connect(addr)?;
-        gdb_client.continue_execution()?;
-        let gamecode = gdb_client.get_gamecode()?;
-        let view: Box<dyn View> = match gamecode.as_str() {
-            "BKIJ" | "BKIP" | "BKIE" => Box::new(st::View::new(gdb_client)),
-            "AZEJ" | "AZEP" | "AZEE" => Box::new(ph::View::new(gdb_client)),
-            _ => {
-                gdb_client.disconnect()?;
-                return Err(anyhow::anyhow!("Unsupported game code: {}", gamecode));
+    fn launch_emulator(&mut self) -> Result<()> {
+        let address = self.gdb_socket_address()?;
+        self.emulator = Some(EmulatorProcess::launch(&self.config.emulator, address)?);
+        Ok(())
+    }
+
+    /// Connects to a launched emulator once its GDB port opens, and forgets it once it exits.
+    fn poll_emulator(&mut self, ctx: &egui::Context) {
+        let Some(emulator) = &mut self.emulator else {
+            return;
+        };
+        let waiting = emulator.is_waiting();
+        if waiting {
+            ctx.request_repaint_after(TASK_STATUS_INTERVAL);
+        }
+        match emulator.poll() {
+            Ok(true) => {
+                if let Err(e) = self.connect(ctx) {
+                    log::error!("Failed to connect to emulator: {e}");
+                }
+            }
+            Ok(false) => {}
+            Err(e) if waiting => {
+                log::error!("Failed to launch emulator: {e}");
+                if let Some(mut emulator) = self.emulator.take() {
+                    emulator.terminate();
+                }
+            }
+            Err(e) => {
+                log::info!("{e}");
+                self.emulator = None;
+            }
+        }
+    }
+
+    /// Renders the emulator settings. Returns true if they changed.
+    fn render_emulator_config(&mut self, ui: &mut egui::Ui) -> bool {
+        let emulator = &mut self.config.emulator;
+        let mut changed = false;
+        ui.strong("Emulator");
+        egui::Grid::new("dsv_emulator_config").num_columns(2).show(ui, |ui| {
+            ui.label("Executable");
+            ui.horizontal(|ui| {
+                let response = egui::TextEdit::singleline(&mut emulator.executable)
+                    .desired_width(200.0)
+                    .hint_text("Path to emulator")
+                    .show(ui)
+                    .response;
+                changed |= response.lost_focus();
+                if ui.button("Browse…").clicked()
+                    && let Some(path) = rfd::FileDialog::new().pick_file()
+                {
+                    emulator.executable = path.display().to_string();
+                    changed = true;
+                }
+            });
+            ui.end_row();
+
+            ui.label("Arguments");
+            let response = egui::TextEdit::singleline(&mut emulator.args)
+                .desired_width(200.0)
+                .show(ui)
+                .response
+                .on_hover_text("{rom} is replaced by the ROM path and {port} by the GDB port");
+            changed |= response.lost_focus();
+            ui.end_row();
+
+            ui.label("ROM");
+            ui.horizontal(|ui| {
+                let response = egui::TextEdit::singleline(&mut emulator.rom)
+                    .desired_width(200.0)
+                    .hint_text("Path to ROM")
+                    .show(ui)
+                    .response;
+                changed |= response.lost_focus();
+                if ui.button("Browse…").clicked()
+                    && let Some(path) =
+                        rfd::FileDialog::new().add_filter("NDS ROM", &["nds"]).pick_file()
+                {
+                    emulator.rom = path.display().to_string();
+                    changed = true;
+                }
+            });
+            ui.end_row();
+        });
+        changed |= ui
+            .checkbox(&mut emulator.terminate_on_disconnect, "Close emulator on disconnect")
+            .changed();
+        changed
+    }
+
+    /// Adds a session for a connected GDB client. Without a gamecode, the first gamecode of the
+    /// game is used so that traces recorded from the session can be opened again.
+    fn open_connection(
+        &mut self,
+        gdb_client: GdbClient,
+        gamecode: Option<String>,
+        profile: &'static GameProfile,
+        chosen: bool,
+        ctx: &egui::Context,
+    ) {
+        let stub = gdb_client.stub_info();
+        if !stub.limitations.is_empty() {
+            log::info!("Limitations of {}: {}", stub.kind, stub.limitations.join("; "));
+            // Shown once per emulator rather than on every connection
+            let kind = stub.kind.to_string();
+            if !self.config.ui.stub_notices_shown.contains(&kind) {
+                self.config.ui.stub_notices_shown.push(kind);
+                self.config.unsaved_changes = true;
+                self.stub_notice = Some(stub.clone());
             }
+        }
+        let gamecode = gamecode.unwrap_or_else(|| profile.default_gamecode());
+        log::info!("Opening {} view for {gamecode}", profile.name);
+        let client = Client::new(gdb_client, gamecode, ctx.clone());
+        {
+            let mut state = client.state.lock().unwrap();
+            state.set_read_only(self.config.ui.safe_mode);
+            state.set_read_budget(self.config.memory.read_budget);
+        }
+        client.set_read_interval(self.config.memory.read_interval());
+        self.add_session(Session {
+            view: Box::new(GameView::new(client, profile)),
+            source: self.config.gdb.address.clone(),
+            stub: Some(stub),
+            chosen_game: chosen.then_some(profile),
+            replay_frame: 0,
+        });
+    }
+
+    /// Asks which game to open for a connection whose game couldn't be detected.
+    fn render_game_selection(&mut self, ctx: &egui::Context) {
+        let Some(pending) = &self.pending_connection else {
+            return;
         };
-        self.view = Some(view);
+        let mut chosen = None;
+        let mut cancel = false;
+        egui::Modal::new(egui::Id::new("dsv_game_selection")).show(ctx, |ui| {
+            ui.heading("Choose game");
+            match &pending.gamecode {
+                Some(gamecode) => ui.label(format!("Gamecode {gamecode} is not supported.")),
+                None => ui.label("The gamecode could not be read."),
+            };
+            ui.label("Which game is running?");
+            for &profile in PROFILES {
+                if ui.button(profile.name).clicked() {
+                    chosen = Some(profile);
+                }
+            }
+            ui.separator();
+            cancel = ui.button("Cancel").clicked();
+        });
+        if let Some(profile) = chosen {
+            let pending = self.pending_connection.take().unwrap();
+            self.open_connection(pending.client, pending.gamecode, profile, true, ctx);
+        } else if cancel {
+            let mut pending = self.pending_connection.take().unwrap();
+            pending
+                .client
+                .disconnect()
+                .unwrap_or_else(|e| log::error!("Failed to disconnect: {e}"));
+        }
+    }
+
+    /// Lets the views know when the type loading task swapped in new types, so that windows which
+    /// were opened before the types were loaded don't keep state derived from the old ones.
+    fn check_types_generation(&mut self, ctx: &egui::Context) {
+        let generation = self.types.lock().unwrap().generation();
+        if generation == self.types_generation {
+            return;
+        }
+        self.types_generation = generation;
+        forget_array_ranges(ctx);
+        let refreshed: usize =
+            self.sessions.iter_mut().map(|session| session.view.types_changed(ctx)).sum();
+        self.types_refreshed = Some(format!("Types updated, {refreshed} windows refreshed"));
+    }
+
+    /// Lists what works worse with the emulator which was just connected to.
+    fn render_stub_notice(&mut self, ctx: &egui::Context) {
+        let Some(stub) = &self.stub_notice else {
+            return;
+        };
+        let mut close = false;
+        egui::Modal::new(egui::Id::new("dsv_stub_notice")).show(ctx, |ui| {
+            ui.heading(format!("Connected to {}", stub.kind));
+            ui.label("Some features are limited with this emulator:");
+            for limitation in &stub.limitations {
+                ui.label(format!("• {limitation}"));
+            }
+            ui.weak(stub.identification());
+            ui.separator();
+            close = ui.button("OK").clicked();
+        });
+        if close {
+            self.stub_notice = None;
+        }
+    }
+
+    fn open_trace(&mut self, path: PathBuf, ctx: &egui::Context) -> Result<()> {
+        log::info!("Opening trace {}", path.display());
+        let replay = TraceReplay::open(&path)?;
+        if !Self::is_supported_game(replay.gamecode()) {
+            return Err(anyhow::anyhow!("Unsupported game code: {}", replay.gamecode()));
+        }
+        let client = Client::new_replay(replay, ctx.clone());
+        {
+            let mut state = client.state.lock().unwrap();
+            state.set_read_only(self.config.ui.safe_mode);
+            state.set_read_budget(self.config.memory.read_budget);
+        }
+        client.set_read_interval(self.config.memory.read_interval());
+        let view = Self::create_view(client)?;
+        let source = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        self.add_session(Session { view, source, stub: None, chosen_game: None, replay_frame: 0 });
         Ok(())
     }
+
+    /// Asks for a trace file and opens it in a new session.
+    fn pick_trace(&mut self, ctx: &egui::Context) {
+        if let Some(file) = rfd::FileDialog::new().add_filter("dsv trace", &["dsvt"]).pick_file()
+            && let Err(e) = self.open_trace(file, ctx)
+        {
+            log::error!("Failed to open trace: {e}");
+        }
+    }
+
+    /// Exits the active session and removes its tab.
+    fn disconnect(&mut self) {
+        let Some(session) = self.sessions.get_mut(self.active_session) else {
+            return;
+        };
+        match session.view.exit() {
+            Ok(_) => {
+                let session = self.sessions.remove(self.active_session);
+                self.active_session =
+                    self.active_session.min(self.sessions.len().saturating_sub(1));
+                if self.config.emulator.terminate_on_disconnect
+                    && session.stub.is_some()
+                    && session.source == self.config.gdb.address
+                    && let Some(mut emulator) = self.emulator.take()
+                {
+                    emulator.terminate();
+                }
+            }
+            Err(e) => log::error!("Failed to disconnect: {e}"),
+        }
+    }
+
+    /// Returns the actions of the app and the active view, and how many of them are the app's.
+    /// Shortcuts can be bound to any of them without an address.
+    fn palette_actions(session: Option<&Session>) -> (Vec<PaletteAction>, usize) {
+        let mut actions = Self::actions(session);
+        let app_actions = actions.len();
+        if let Some(session) = session {
+            actions.extend(session.view.actions());
+        }
+        (actions, app_actions)
+    }
+
+    fn run_palette_action(
+        &mut self,
+        ctx: &egui::Context,
+        action: &PaletteAction,
+        app_action: bool,
+        address: Option<u32>,
+    ) {
+        if app_action {
+            self.run_action(ctx, action);
+        } else if let Some(session) = self.sessions.get_mut(self.active_session) {
+            session.view.run_action(ctx, action, address);
+        }
+    }
+
+    /// Runs the actions whose shortcuts were pressed, before the panels can take the keys.
+    fn run_shortcuts(&mut self, ctx: &egui::Context) {
+        if self.keybindings_window.is_capturing() {
+            return;
+        }
+        let mut bindings: Vec<(String, egui::KeyboardShortcut)> =
+            keybindings::bindings(&self.config.keybindings).into_iter().collect();
+        // Try Ctrl+Shift+A before Ctrl+A, which would also match it
+        bindings.sort_by_key(|(_, shortcut)| std::cmp::Reverse(modifier_count(shortcut)));
+        // Keys without modifiers are left to text fields while typing
+        let typing = ctx.wants_keyboard_input();
+        let pressed: Vec<String> = bindings
+            .into_iter()
+            .filter(|(_, shortcut)| !(typing && shortcut.modifiers.is_none()))
+            .filter(|(_, shortcut)| ctx.input_mut(|i| i.consume_shortcut(shortcut)))
+            .map(|(id, _)| id)
+            .collect();
+        if pressed.is_empty() {
+            return;
+        }
+
+        let (actions, app_actions) = Self::palette_actions(self.sessions.get(self.active_session));
+        for id in pressed {
+            if id == COMMAND_PALETTE {
+                self.command_palette.toggle();
+                continue;
+            }
+            let index =
+                actions.iter().position(|action| action.id == id && action.address_hint.is_none());
+            if let Some(index) = index {
+                self.run_palette_action(ctx, &actions[index], index < app_actions, None);
+            }
+        }
+    }
+
+    /// Returns the actions of the app for the command palette, see [`Self::run_action`].
+    fn actions(session: Option<&Session>) -> Vec<PaletteAction> {
+        let mut actions = vec![
+            PaletteAction::new("connect", "Connect"),
+            PaletteAction::new("open_trace", "Open trace…"),
+        ];
+        if let Some(session) = session {
+            actions.push(PaletteAction::new("disconnect", "Disconnect"));
+            actions.push(PaletteAction::new("safe_mode", "Toggle safe mode"));
+            let mut windows = vec!["Monitor", "Connection", "Events"];
+            if session.view.client().replay().is_none() {
+                actions.push(PaletteAction::new("pause", "Pause or resume the target"));
+                windows.push("Execution");
+            }
+            actions.extend(windows.into_iter().map(|title| {
+                PaletteAction::new(format!("window:{title}"), format!("Open window: {title}"))
+            }));
+        }
+        actions.extend([
+            PaletteAction::new("configure_project", "Configure project…"),
+            PaletteAction::new("load_types", "Load types"),
+            PaletteAction::new("window:Types", "Open window: Types"),
+            PaletteAction::new("window:Log", "Open window: Log"),
+            PaletteAction::new("window:Layout checks", "Open window: Layout checks"),
+            PaletteAction::new("window:Keyboard shortcuts", "Open window: Keyboard shortcuts"),
+        ]);
+        actions
+    }
+
+    fn run_action(&mut self, ctx: &egui::Context, action: &PaletteAction) {
+        match action.id.as_str() {
+            "connect" => {
+                if let Err(e) = self.connect(ctx) {
+                    log::error!("Failed to connect: {e}");
+                }
+            }
+            "open_trace" => self.pick_trace(ctx),
+            "disconnect" => self.disconnect(),
+            "safe_mode" => {
+                if let Some(session) = self.sessions.get(self.active_session) {
+                    let mut state = session.view.client().state.lock().unwrap();
+                    let safe_mode = !state.is_read_only();
+                    state.set_read_only(safe_mode);
+                }
+            }
+            "pause" => {
+                if let Some(session) = self.sessions.get(self.active_session) {
+                    let client = session.view.client();
+                    let cmd = if client.is_paused() { Command::Resume } else { Command::Pause };
+                    client.send_command(cmd).unwrap_or_else(|e| {
+                        log::error!("Failed to control execution: {e}");
+                    });
+                }
+            }
+            "configure_project" => self.project_modal_open = true,
+            "load_types" => self.load_types(None),
+            "window:Monitor" => self.monitor_window.open = true,
+            "window:Connection" => self.connection_window.open = true,
+            "window:Events" => self.events_window.open = true,
+            "window:Execution" => self.execution_window.open = true,
+            "window:Types" => self.types_window.open = true,
+            "window:Log" => self.log_window.open = true,
+            "window:Layout checks" => self.layout_checks_window.open = true,
+            "window:Keyboard shortcuts" => self.keybindings_window.open = true,
+            id => log::warn!("Unknown action '{id}'"),
+        }
+    }
+
+    /// Adds a session as a new tab and switches to it.
+    fn add_session(&mut self, session: Session) {
+        self.sessions.push(session);
+        self.active_session = self.sessions.len() - 1;
+    }
+
+    fn is_supported_game(gamecode: &str) -> bool {
+        GameProfile::for_gamecode(gamecode).is_some()
+    }
+
+    fn create_view(client: Client) -> Result<Box<dyn View>> {
+        let Some(profile) = GameProfile::for_gamecode(client.gamecode()) else {
+            return Err(anyhow::anyhow!("Unsupported game code: {}", client.gamecode()));
+        };
+        log::info!("Opening {} view for {}", profile.name, client.gamecode());
+        Ok(Box::new(GameView::new(client, profile)))
+    }
+}
+
+fn modifier_count(shortcut: &egui::KeyboardShortcut) -> usize {
+    let modifiers = shortcut.modifiers;
+    [modifiers.command || modifiers.ctrl, modifiers.shift, modifiers.alt]
+        .into_iter()
+        .filter(|&set| set)
+        .count()
 }
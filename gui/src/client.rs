@@ -1,96 +1,309 @@
 use std::{
+    path::PathBuf,
     sync::{Arc, Mutex, mpsc::Sender},
     thread::JoinHandle,
     time::{Duration, Instant},
 };
 
 use anyhow::{Context, Result, bail};
-use dsv_core::{gdb::client::GdbClient, state::State};
+use dsv_core::{
+    gdb::stats::ConnectionStats,
+    memory::{MemoryBackend, address::Addr},
+    state::State,
+    trace::{RecordingBackend, TraceReplay, TraceWriter},
+};
+use eframe::egui;
 
 pub struct Client {
     running: Arc<Mutex<bool>>,
+    recording: Arc<Mutex<bool>>,
+    paused: Arc<Mutex<bool>>,
+    stopped_at: Arc<Mutex<Option<StoppedAt>>>,
+    /// Time between the starts of two reads, see [`Client::set_read_interval`]
+    read_interval: Arc<Mutex<Duration>>,
     tx: Sender<Command>,
+    gamecode: String,
+    replay: Option<ReplayInfo>,
+    connected_at: Instant,
     pub state: Arc<Mutex<State>>,
+    pub console: Arc<Mutex<Vec<ConsoleEntry>>>,
+    pub connection_stats: Arc<Mutex<Option<ConnectionStats>>>,
     update_thread: Option<JoinHandle<()>>,
 }
 
 #[derive(PartialEq, Eq)]
 pub enum Command {
     Disconnect,
+    Monitor(String),
+    StartRecording(PathBuf),
+    StopRecording,
+    SeekFrame(usize),
+    /// Stops the target until [`Command::Resume`], while memory is still read and written
+    Pause,
+    Resume,
+    /// Runs a paused target for one frame, or until the frame counter at the given address
+    /// increments
+    StepFrame {
+        frame_counter: Option<Addr>,
+    },
+}
+
+/// Where a paused target is executing.
+#[derive(Clone, Copy)]
+pub struct StoppedAt {
+    pub pc: Addr,
+    /// Set if the CPU is in Thumb state, `None` if the stub doesn't report the CPSR
+    pub thumb: Option<bool>,
+    pub sp: Option<Addr>,
+    /// Link register, the return address of a leaf function
+    pub lr: Option<Addr>,
+}
+
+impl StoppedAt {
+    const CPSR: usize = 25;
+    const LR: usize = 14;
+    const PC: usize = 15;
+    const SP: usize = 13;
+    const THUMB_BIT: u32 = 1 << 5;
+
+    fn read<B: MemoryBackend>(backend: &mut B) -> Option<Self> {
+        let pc = backend
+            .read_register(Self::PC)
+            .inspect_err(|e| log::warn!("Failed to read the PC: {e}"))
+            .ok()?;
+        let thumb = backend.read_register(Self::CPSR).ok().map(|cpsr| cpsr & Self::THUMB_BIT != 0);
+        let sp = backend.read_register(Self::SP).ok();
+        let lr = backend.read_register(Self::LR).ok();
+        Some(Self { pc, thumb, sp, lr })
+    }
+}
+
+pub enum ConsoleEntry {
+    Monitor { command: String, result: Result<String, String> },
+    Output(String),
+}
+
+pub struct ReplayInfo {
+    pub frame_timestamps: Vec<Duration>,
 }
 
 impl Client {
+    pub const DEFAULT_READ_INTERVAL: Duration = Self::FRAME_TIME;
+    /// Frame period of the DS, used for stepping frames
     const FRAME_TIME: Duration = Duration::from_nanos(16_666_667);
+    /// Longest time to wait for the update thread to stop when disconnecting
+    pub const JOIN_TIMEOUT: Duration = Duration::from_secs(2);
+    /// Longest time to wait for the frame counter to increment when stepping a frame
+    const MAX_STEP_TIME: Duration = Duration::from_millis(500);
 
-    pub fn new(mut gdb_client: GdbClient) -> Self {
+    /// Repaints `ctx` when an update changes the state or a command was handled.
+    pub fn new<B: MemoryBackend + Send + 'static>(
+        mut backend: B,
+        gamecode: String,
+        ctx: egui::Context,
+    ) -> Self {
         let (tx, rx) = std::sync::mpsc::channel();
 
         let running = Arc::new(Mutex::new(false));
+        let recording = Arc::new(Mutex::new(false));
+        let paused = Arc::new(Mutex::new(false));
+        let stopped_at = Arc::new(Mutex::new(None));
+        let read_interval = Arc::new(Mutex::new(Self::DEFAULT_READ_INTERVAL));
         let state = Arc::new(Mutex::new(State::default()));
+        let console = Arc::new(Mutex::new(Vec::new()));
+        let connection_stats = Arc::new(Mutex::new(None));
         let update_thread = {
             let running = running.clone();
+            let recording = recording.clone();
+            let paused = paused.clone();
+            let stopped_at = stopped_at.clone();
+            let read_interval = read_interval.clone();
             let state = state.clone();
+            let console = console.clone();
+            let connection_stats = connection_stats.clone();
+            let gamecode = gamecode.clone();
             std::thread::spawn(move || {
                 *running.lock().unwrap() = true;
 
                 // Continue execution in case "Break on startup" is enabled
-                gdb_client.continue_execution().unwrap_or_else(|e| {
+                backend.resume().unwrap_or_else(|e| {
                     log::error!("Failed to continue execution: {e}");
                 });
 
-                let mut next_time = Instant::now();
+                let mut recorder = None;
+                // The target is left stopped while paused by the user
+                let mut is_paused = false;
+                let mut read_time = Instant::now();
                 let mut frame_count = 0;
+                let mut changed_frames = 0;
+                let mut last_changes = 0;
+                // Failed updates are only logged when the error changes, a window without data
+                // fails every update, and so does every window opened after a trace was recorded
+                let mut last_error = None;
                 let mut last_fps_report = Instant::now();
-                while gdb_client.is_connected() {
+                while backend.is_connected() {
                     if let Ok(cmd) = rx.try_recv() {
-                        Self::handle_command(cmd, &mut gdb_client).unwrap_or_else(|e| {
+                        Self::handle_command(
+                            cmd,
+                            &mut backend,
+                            &gamecode,
+                            &state,
+                            &console,
+                            &mut recorder,
+                            &mut is_paused,
+                        )
+                        .unwrap_or_else(|e| {
                             log::error!("Failed to handle command: {e}");
                         });
+                        *recording.lock().unwrap() = recorder.is_some();
+                        *paused.lock().unwrap() = is_paused;
+                        // Registers only stay put while the target is paused
+                        *stopped_at.lock().unwrap() =
+                            if is_paused { StoppedAt::read(&mut backend) } else { None };
+                        ctx.request_repaint();
+                        continue;
+                    }
+                    // A watch halted the target, leave it stopped until the user continues
+                    if state.lock().unwrap().is_halted() {
+                        std::thread::sleep(*read_interval.lock().unwrap());
                         continue;
                     }
 
-                    gdb_client.stop_execution().unwrap_or_else(|e| {
-                        log::error!("Failed to stop execution: {e}");
-                    });
-                    {
+                    if !is_paused {
+                        backend.stop().unwrap_or_else(|e| {
+                            log::error!("Failed to stop execution: {e}");
+                        });
+                    }
+                    let (halted, changes) = {
                         let mut state = state.lock().unwrap();
-                        state.update(&mut gdb_client).unwrap_or_else(|e| {
+                        let result = if let Some(writer) = &mut recorder {
+                            writer.begin_frame().and_then(|_| {
+                                state
+                                    .update(&mut RecordingBackend { backend: &mut backend, writer })
+                            })
+                        } else {
+                            state.update(&mut backend)
+                        };
+                        let error = result.err().map(|e| e.to_string());
+                        if let Some(e) = &error
+                            && last_error.as_ref() != Some(e)
+                        {
                             log::error!("Failed to update player: {e}");
+                        }
+                        last_error = error;
+                        (state.is_halted(), state.changes())
+                    };
+                    if changes != last_changes {
+                        last_changes = changes;
+                        changed_frames += 1;
+                        ctx.request_repaint();
+                    }
+                    if halted {
+                        log::info!("Watched memory changed, halting the target");
+                    } else if !is_paused {
+                        backend.resume().unwrap_or_else(|e| {
+                            log::error!("Failed to continue execution: {e}");
                         });
                     }
-                    gdb_client.continue_execution().unwrap_or_else(|e| {
-                        log::error!("Failed to continue execution: {e}");
-                    });
+                    let output = backend.take_console_output();
+                    if !output.is_empty() {
+                        console
+                            .lock()
+                            .unwrap()
+                            .extend(output.into_iter().map(ConsoleEntry::Output));
+                        ctx.request_repaint();
+                    }
+                    *connection_stats.lock().unwrap() = backend.connection_stats();
 
                     frame_count += 1;
                     if last_fps_report.elapsed() >= Duration::from_secs(1) {
-                        log::debug!("FPS: {frame_count}");
+                        log::debug!("FPS: {frame_count}, {changed_frames} with new data");
                         frame_count = 0;
+                        changed_frames = 0;
                         last_fps_report = Instant::now();
                     }
 
-                    let time = Instant::now();
-                    next_time += Duration::from_nanos(
-                        (time - next_time).as_nanos().next_multiple_of(Self::FRAME_TIME.as_nanos())
-                            as u64,
-                    );
-                    std::thread::sleep(next_time - time);
+                    let now = Instant::now();
+                    read_time = next_read_time(read_time, now, *read_interval.lock().unwrap());
+                    std::thread::sleep(read_time.saturating_duration_since(now));
                 }
 
-                gdb_client.disconnect().unwrap_or_else(|e| {
-                    log::error!("Failed to disconnect from GDB server: {e}");
+                if let Some(writer) = recorder.take() {
+                    writer.into_inner().map(|_| ()).unwrap_or_else(|e| {
+                        log::error!("Failed to finish recording: {e}");
+                    });
+                }
+                backend.disconnect().unwrap_or_else(|e| {
+                    log::error!("Failed to disconnect: {e}");
                 });
+                state.lock().unwrap().clear_watches();
                 *running.lock().unwrap() = false;
+                ctx.request_repaint();
             })
         };
 
-        Client { running, tx, state, update_thread: Some(update_thread) }
+        Client {
+            running,
+            recording,
+            paused,
+            stopped_at,
+            read_interval,
+            tx,
+            gamecode,
+            replay: None,
+            connected_at: Instant::now(),
+            state,
+            console,
+            connection_stats,
+            update_thread: Some(update_thread),
+        }
+    }
+
+    /// Creates a client which plays back a recorded trace instead of talking to a GDB server.
+    pub fn new_replay(replay: TraceReplay, ctx: egui::Context) -> Self {
+        let gamecode = replay.gamecode().to_string();
+        let frame_timestamps = replay.frames().iter().map(|frame| frame.timestamp).collect();
+        let mut client = Self::new(replay, gamecode, ctx);
+        client.replay = Some(ReplayInfo { frame_timestamps });
+        client
     }
 
     pub fn is_running(&self) -> bool {
         *self.running.lock().unwrap()
     }
 
+    pub fn is_recording(&self) -> bool {
+        *self.recording.lock().unwrap()
+    }
+
+    /// Returns true if the user paused the target, see [`Command::Pause`].
+    pub fn is_paused(&self) -> bool {
+        *self.paused.lock().unwrap()
+    }
+
+    /// Returns where the target is executing while paused, if the stub reports its registers.
+    pub fn stopped_at(&self) -> Option<StoppedAt> {
+        *self.stopped_at.lock().unwrap()
+    }
+
+    /// Sets how often memory is read. This is independent of the frame rate of the target, reads
+    /// are only as consistent as the target is stopped for them.
+    pub fn set_read_interval(&self, interval: Duration) {
+        *self.read_interval.lock().unwrap() = interval;
+    }
+
+    pub fn gamecode(&self) -> &str {
+        &self.gamecode
+    }
+
+    pub fn connected_at(&self) -> Instant {
+        self.connected_at
+    }
+
+    pub fn replay(&self) -> Option<&ReplayInfo> {
+        self.replay.as_ref()
+    }
+
     pub fn send_command(&self, cmd: Command) -> Result<()> {
         if !self.is_running() {
             bail!("Not connected to GDB server");
@@ -99,15 +312,217 @@ impl Client {
         Ok(())
     }
 
-    pub fn handle_command(cmd: Command, gdb: &mut GdbClient) -> Result<()> {
+    fn handle_command<B: MemoryBackend>(
+        cmd: Command,
+        backend: &mut B,
+        gamecode: &str,
+        state: &Mutex<State>,
+        console: &Mutex<Vec<ConsoleEntry>>,
+        recorder: &mut Option<TraceWriter>,
+        paused: &mut bool,
+    ) -> Result<()> {
         match cmd {
-            Command::Disconnect => gdb.disconnect(),
+            Command::Disconnect => backend.disconnect(),
+            Command::Monitor(command) => {
+                let result = backend.monitor(&command).map_err(|e| e.to_string());
+                console.lock().unwrap().push(ConsoleEntry::Monitor { command, result });
+                Ok(())
+            }
+            Command::StartRecording(path) => {
+                log::info!("Recording trace to {}", path.display());
+                *recorder = Some(TraceWriter::create(path, gamecode)?);
+                Ok(())
+            }
+            Command::StopRecording => {
+                if let Some(writer) = recorder.take() {
+                    writer.into_inner()?;
+                    log::info!("Stopped recording trace");
+                }
+                Ok(())
+            }
+            Command::SeekFrame(frame) => backend.seek_frame(frame),
+            Command::Pause => {
+                // A target halted by a watch is already stopped
+                if !*paused && !state.lock().unwrap().is_halted() {
+                    backend.stop()?;
+                }
+                *paused = true;
+                log::info!("Paused the target");
+                Ok(())
+            }
+            Command::Resume => {
+                if !*paused {
+                    return Ok(());
+                }
+                *paused = false;
+                state.lock().unwrap().continue_updates();
+                backend.resume()?;
+                log::info!("Resumed the target");
+                Ok(())
+            }
+            Command::StepFrame { frame_counter } => {
+                if !*paused {
+                    bail!("Pause the target before stepping a frame");
+                }
+                Self::step_frame(backend, frame_counter)
+            }
+        }
+    }
+
+    /// Runs a stopped target for one frame interval, or until the frame counter changes.
+    fn step_frame<B: MemoryBackend>(backend: &mut B, frame_counter: Option<Addr>) -> Result<()> {
+        let Some(frame_counter) = frame_counter else {
+            backend.resume()?;
+            std::thread::sleep(Self::FRAME_TIME);
+            return backend.stop();
+        };
+        let read_counter = |backend: &mut B| -> Result<u32> {
+            let mut buf = [0; 4];
+            backend.read(frame_counter, &mut buf).context("Failed to read frame counter")?;
+            Ok(u32::from_le_bytes(buf))
+        };
+        let start_count = read_counter(backend)?;
+        let start_time = Instant::now();
+        // Check in short slices to avoid running past the next frame
+        loop {
+            backend.resume()?;
+            std::thread::sleep(Self::FRAME_TIME / 4);
+            backend.stop()?;
+            if read_counter(backend)? != start_count {
+                return Ok(());
+            }
+            if start_time.elapsed() >= Self::MAX_STEP_TIME {
+                bail!("Frame counter at {frame_counter:#010x} did not change");
+            }
+        }
+    }
+
+    /// Waits for the update thread to stop. If it hasn't stopped after `timeout`, for example
+    /// because a read is blocked on a wedged socket, it is detached and false is returned.
+    pub fn join_update_thread(&mut self, timeout: Duration) -> bool {
+        let Some(thread) = self.update_thread.take() else {
+            return true;
+        };
+        let deadline = Instant::now() + timeout;
+        while !thread.is_finished() {
+            if Instant::now() >= deadline {
+                log::warn!("Update thread did not stop within {timeout:?}, detaching it");
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        if thread.join().is_err() {
+            log::error!("Update thread panicked");
         }
+        true
     }
+}
+
+/// Returns when to start the next read, `interval` after `last` was scheduled. After a read which
+/// took longer than the interval, the next one starts right away instead of catching up on missed
+/// reads, so the time until the returned instant is never more than `interval`.
+fn next_read_time(last: Instant, now: Instant, interval: Duration) -> Instant {
+    (last + interval).max(now)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::{Receiver, channel};
+
+    use super::*;
+
+    /// A backend whose connection hangs in [`MemoryBackend::stop`] until `hang` is dropped.
+    struct HungBackend {
+        hang: Option<Receiver<()>>,
+        connected: bool,
+    }
+
+    impl MemoryBackend for HungBackend {
+        fn read(&mut self, _address: Addr, buf: &mut [u8]) -> Result<()> {
+            buf.fill(0);
+            Ok(())
+        }
+
+        fn write(&mut self, _address: Addr, _buf: &[u8]) -> Result<()> {
+            Ok(())
+        }
+
+        fn stop(&mut self) -> Result<()> {
+            if let Some(hang) = &self.hang {
+                hang.recv().ok();
+            }
+            Ok(())
+        }
+
+        fn resume(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        fn is_connected(&self) -> bool {
+            self.connected
+        }
+
+        fn disconnect(&mut self) -> Result<()> {
+            self.connected = false;
+            Ok(())
+        }
+    }
+
+    fn start(hang: Option<Receiver<()>>) -> Client {
+        let backend = HungBackend { hang, connected: true };
+        let client = Client::new(backend, "TEST".into(), egui::Context::default());
+        while !client.is_running() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        client
+    }
+
+    #[test]
+    fn test_join_update_thread() {
+        let mut client = start(None);
+        client.send_command(Command::Disconnect).unwrap();
+        assert!(client.join_update_thread(Duration::from_secs(5)));
+        assert!(!client.is_running());
+    }
+
+    #[test]
+    fn test_join_update_thread_timeout() {
+        let (unhang, hang) = channel();
+        let mut client = start(Some(hang));
+        client.send_command(Command::Disconnect).unwrap();
+
+        let start = Instant::now();
+        assert!(!client.join_update_thread(Duration::from_millis(100)));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(client.is_running());
+        // Joining again has no thread left to wait for
+        assert!(client.join_update_thread(Duration::ZERO));
+        drop(unhang);
+    }
+
+    #[test]
+    fn test_next_read_time() {
+        let interval = Duration::from_millis(16);
+        let start = Instant::now();
+        let sleep = |last: Instant, now: Instant| {
+            next_read_time(last, now, interval).saturating_duration_since(now)
+        };
+        // A fast read sleeps for the rest of the interval
+        assert_eq!(sleep(start, start + Duration::from_millis(5)), Duration::from_millis(11));
+        // A slow read is followed right away, without sleeping for the missed reads
+        assert_eq!(sleep(start, start + Duration::from_secs(3)), Duration::ZERO);
+        assert_eq!(sleep(start, start + Duration::from_millis(17)), Duration::ZERO);
 
-    pub fn join_update_thread(&mut self) {
-        if let Some(thread) = self.update_thread.take() {
-            thread.join().expect("Failed to join update thread");
+        // Reads of varying length keep the cadence and never sleep longer than the interval
+        let mut last = start;
+        let mut now = start;
+        for read_time in [1, 30, 2, 16, 100, 0, 15, 5000, 3].map(Duration::from_millis) {
+            now += read_time;
+            let next = next_read_time(last, now, interval);
+            assert!(next >= now);
+            assert!(next - now <= interval);
+            last = next;
+            now = next;
         }
     }
 }
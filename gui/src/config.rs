@@ -1,20 +1,37 @@
-use std::path::Path;
+use std::{collections::BTreeMap, ops::Range, path::Path, time::Duration};
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use toml::Table;
 
+use crate::client::Client;
+
 #[derive(Serialize, Deserialize)]
 pub struct Config {
     pub gdb: GdbConfig,
     pub types: TypesConfig,
     #[serde(default)]
+    pub ui: UiConfig,
+    #[serde(default)]
+    pub memory: MemoryConfig,
+    #[serde(default)]
+    pub emulator: EmulatorConfig,
+    /// Keyboard shortcuts which differ from the defaults, by action ID
+    #[serde(default)]
+    pub keybindings: BTreeMap<String, String>,
+    #[serde(default)]
     pub games: Table,
+    /// Set by views which edit the config so the app knows to save it
+    #[serde(skip)]
+    pub unsaved_changes: bool,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct GdbConfig {
     pub address: String,
+    /// Config key of the game to open on connecting, instead of detecting it from the gamecode
+    #[serde(default)]
+    pub game: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -26,17 +43,151 @@ pub struct TypesConfig {
     pub short_enums: bool,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct UiConfig {
+    /// Show the raw bytes of a field when hovering its name
+    #[serde(default)]
+    pub raw_bytes_tooltip: bool,
+    /// Start every connection in safe mode, where memory can't be written
+    #[serde(default)]
+    pub safe_mode: bool,
+    /// Number of elements shown behind pointers which have no saved length
+    #[serde(default = "default_list_length")]
+    pub default_list_length: usize,
+    /// List the fields of base classes with the fields of derived classes
+    #[serde(default)]
+    pub flatten_base_types: bool,
+    /// Number of decimals shown for floats and fixed-point values
+    #[serde(default = "default_float_precision")]
+    pub float_precision: usize,
+    /// Emulators whose limitations were already shown when connecting
+    #[serde(default)]
+    pub stub_notices_shown: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MemoryConfig {
+    /// Address ranges which windows may read from on top of the readable regions of the game's
+    /// memory map, as `[start, end)` pairs
+    #[serde(default)]
+    pub valid_ranges: Vec<(u32, u32)>,
+    /// Address of a counter which the game increments every frame, used for frame stepping
+    #[serde(default)]
+    pub frame_counter: Option<u32>,
+    /// Most actor table entries to read, larger tables are treated as garbage
+    #[serde(default = "default_max_actors")]
+    pub max_actors: u32,
+    /// Milliseconds from the start of one read of all windows to the next, independent of the
+    /// frame rate of the game
+    #[serde(default = "default_read_interval_ms")]
+    pub read_interval_ms: f64,
+    /// Most bytes read in one update, so that the target isn't stopped for long on slow
+    /// connections. Unlimited if unset.
+    #[serde(default)]
+    pub read_budget: Option<usize>,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            valid_ranges: Vec::new(),
+            frame_counter: None,
+            max_actors: default_max_actors(),
+            read_interval_ms: default_read_interval_ms(),
+            read_budget: None,
+        }
+    }
+}
+
+fn default_max_actors() -> u32 {
+    256
+}
+
+fn default_read_interval_ms() -> f64 {
+    1000.0 / 60.0
+}
+
+/// Emulator which the app can start before connecting to it.
+#[derive(Serialize, Deserialize)]
+pub struct EmulatorConfig {
+    #[serde(default)]
+    pub executable: String,
+    /// Arguments separated by spaces, where `{rom}` is replaced by [`Self::rom`] and `{port}` by
+    /// the port of the GDB address
+    #[serde(default = "default_emulator_args")]
+    pub args: String,
+    #[serde(default)]
+    pub rom: String,
+    /// Terminate the emulator when its session is disconnected
+    #[serde(default)]
+    pub terminate_on_disconnect: bool,
+}
+
+impl Default for EmulatorConfig {
+    fn default() -> Self {
+        Self {
+            executable: String::new(),
+            args: default_emulator_args(),
+            rom: String::new(),
+            terminate_on_disconnect: false,
+        }
+    }
+}
+
+fn default_emulator_args() -> String {
+    "{rom}".into()
+}
+
+impl MemoryConfig {
+    pub fn valid_ranges(&self) -> Vec<Range<u32>> {
+        self.valid_ranges.iter().map(|&(start, end)| start..end).collect()
+    }
+
+    pub fn read_interval(&self) -> Duration {
+        Duration::try_from_secs_f64(self.read_interval_ms / 1000.0)
+            .ok()
+            .filter(|interval| !interval.is_zero())
+            .unwrap_or(Client::DEFAULT_READ_INTERVAL)
+    }
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            raw_bytes_tooltip: false,
+            safe_mode: false,
+            default_list_length: default_list_length(),
+            flatten_base_types: false,
+            float_precision: default_float_precision(),
+            stub_notices_shown: Vec::new(),
+        }
+    }
+}
+
+fn default_list_length() -> usize {
+    1
+}
+
+fn default_float_precision() -> usize {
+    5
+}
+
 impl Config {
     pub fn new() -> Self {
         Config {
-            gdb: GdbConfig { address: "127.0.0.1:3333".into() },
+            gdb: GdbConfig { address: "127.0.0.1:3333".into(), game: None },
             types: TypesConfig {
                 project_root: String::new(),
                 include_paths: Vec::new(),
                 ignore_paths: Vec::new(),
                 short_enums: false,
             },
+            ui: UiConfig::default(),
+            memory: MemoryConfig::default(),
+            emulator: EmulatorConfig::default(),
+            keybindings: BTreeMap::new(),
             games: Table::new(),
+            unsaved_changes: false,
         }
     }
 
@@ -5,17 +5,19 @@ mod tasks;
 mod ui;
 mod util;
 mod views;
+mod windows;
 
 use eframe::egui;
 
-use crate::app::DsvApp;
+use crate::{app::DsvApp, util::log_buffer};
 
 fn main() -> eframe::Result {
-    env_logger::builder()
+    let logger = env_logger::builder()
         .filter_level(log::LevelFilter::Info)
         .format_timestamp(None)
         .format_target(true)
-        .init();
+        .build();
+    log_buffer::init(logger);
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([640.0, 480.0]),
         ..Default::default()
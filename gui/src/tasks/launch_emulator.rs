@@ -0,0 +1,144 @@
+use std::{
+    io::{BufRead, BufReader, Read},
+    net::{SocketAddr, TcpStream},
+    process::{Child, Command, Stdio},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, bail};
+
+use crate::config::EmulatorConfig;
+
+/// How long a launched emulator has to open its GDB port.
+const PORT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Time between attempts to connect to the GDB port.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// An emulator started from the app, which is connected to once its GDB stub accepts connections.
+pub struct EmulatorProcess {
+    child: Child,
+    /// Receives once the port opens or the wait times out, `None` after that
+    ready_rx: Option<mpsc::Receiver<bool>>,
+    stop_polling: Arc<AtomicBool>,
+}
+
+impl EmulatorProcess {
+    /// Starts the emulator and waits for `address` to accept connections in the background, see
+    /// [`Self::poll`]. The output of the emulator goes to the log.
+    pub fn launch(config: &EmulatorConfig, address: SocketAddr) -> Result<Self> {
+        if config.executable.is_empty() {
+            bail!("No emulator executable set");
+        }
+        let args = expand_args(&config.args, &config.rom, address.port());
+        log::info!("Launching {} {}", config.executable, args.join(" "));
+        let mut child = Command::new(&config.executable)
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to start {}", config.executable))?;
+        if let Some(stdout) = child.stdout.take() {
+            forward_output(stdout, log::Level::Info);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_output(stderr, log::Level::Warn);
+        }
+
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let stop_polling = Arc::new(AtomicBool::new(false));
+        let stop = stop_polling.clone();
+        std::thread::spawn(move || {
+            let deadline = Instant::now() + PORT_TIMEOUT;
+            while !stop.load(Ordering::Relaxed) {
+                if TcpStream::connect_timeout(&address, POLL_INTERVAL).is_ok() {
+                    let _ = ready_tx.send(true);
+                    return;
+                }
+                if Instant::now() >= deadline {
+                    let _ = ready_tx.send(false);
+                    return;
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        });
+        Ok(Self { child, ready_rx: Some(ready_rx), stop_polling })
+    }
+
+    /// Returns true once, when the GDB port has opened. Fails if the emulator exits or the port
+    /// doesn't open in time.
+    pub fn poll(&mut self) -> Result<bool> {
+        if let Some(status) = self.child.try_wait().context("Failed to check emulator")? {
+            self.ready_rx = None;
+            self.stop_polling.store(true, Ordering::Relaxed);
+            bail!("Emulator exited with {status}");
+        }
+        let Some(ready_rx) = &self.ready_rx else {
+            return Ok(false);
+        };
+        match ready_rx.try_recv() {
+            Ok(true) => {
+                self.ready_rx = None;
+                Ok(true)
+            }
+            Ok(false) | Err(mpsc::TryRecvError::Disconnected) => {
+                self.ready_rx = None;
+                bail!("GDB port did not open within {}s", PORT_TIMEOUT.as_secs())
+            }
+            Err(mpsc::TryRecvError::Empty) => Ok(false),
+        }
+    }
+
+    /// Returns true until the GDB port has opened.
+    pub fn is_waiting(&self) -> bool {
+        self.ready_rx.is_some()
+    }
+
+    pub fn terminate(&mut self) {
+        self.stop_polling.store(true, Ordering::Relaxed);
+        if let Err(e) = self.child.kill() {
+            log::warn!("Failed to terminate emulator: {e}");
+            return;
+        }
+        let _ = self.child.wait();
+        log::info!("Terminated emulator");
+    }
+}
+
+/// Splits the arguments template at whitespace, then replaces `{rom}` and `{port}` in each
+/// argument, so that a ROM path with spaces stays one argument.
+fn expand_args(template: &str, rom: &str, port: u16) -> Vec<String> {
+    template
+        .split_whitespace()
+        .map(|arg| arg.replace("{rom}", rom).replace("{port}", &port.to_string()))
+        .collect()
+}
+
+/// Logs each line of the emulator's output.
+fn forward_output(output: impl Read + Send + 'static, level: log::Level) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(output).lines() {
+            let Ok(line) = line else {
+                return;
+            };
+            log::log!(target: "emulator", level, "{line}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_args() {
+        let args = expand_args("--gdb-port={port}  {rom}", "/roms/Phantom Hourglass.nds", 3333);
+        assert_eq!(args, ["--gdb-port=3333", "/roms/Phantom Hourglass.nds"]);
+        assert!(expand_args("", "game.nds", 3333).is_empty());
+    }
+}
@@ -1,15 +1,57 @@
 use std::{
-    path::{Path, PathBuf},
+    ops::Deref,
+    path::PathBuf,
     sync::{Arc, Mutex, mpsc},
     thread::JoinHandle,
     time::Instant,
 };
 
-use anyhow::{Context, Result};
-use type_crawler::{Env, EnvOptions, TypeCrawler, Types, WordSize};
+use anyhow::Result;
+use dsv_core::headers::{create_crawler, find_header_files};
+use type_crawler::{TypeCrawler, TypeKind, Types};
+
+use crate::util::type_names::scan_type_names;
+
+/// The types shared between the app and [`LoadTypesTask`], with a generation which counts how
+/// often the task swapped in new results. Views compare it to notice that types were loaded.
+pub struct LoadedTypes {
+    types: Types,
+    generation: u64,
+}
+
+impl LoadedTypes {
+    pub fn new() -> Self {
+        Self { types: Types::new(), generation: 0 }
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Swaps in new types and returns the old ones.
+    fn replace(&mut self, types: Types) -> Types {
+        self.generation += 1;
+        std::mem::replace(&mut self.types, types)
+    }
+}
+
+impl Default for LoadedTypes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for LoadedTypes {
+    type Target = Types;
+
+    fn deref(&self) -> &Types {
+        &self.types
+    }
+}
 
 pub struct LoadTypesTask {
-    types: Arc<Mutex<type_crawler::Types>>,
+    types: Arc<Mutex<LoadedTypes>>,
+    type_names: Arc<Mutex<Vec<String>>>,
     status: Arc<Mutex<String>>,
     thread_handle: Option<JoinHandle<()>>,
     terminate_tx: Option<mpsc::Sender<()>>,
@@ -21,7 +63,8 @@ pub struct LoadTypesTask {
 }
 
 pub struct LoadTypesTaskOptions {
-    pub types: Arc<Mutex<type_crawler::Types>>,
+    pub types: Arc<Mutex<LoadedTypes>>,
+    pub type_names: Arc<Mutex<Vec<String>>>,
 
     pub project_root: PathBuf,
     pub include_paths: Vec<PathBuf>,
@@ -34,6 +77,7 @@ impl LoadTypesTask {
         LoadTypesTask {
             project_root: options.project_root,
             types: options.types,
+            type_names: options.type_names,
             status: Arc::new(Mutex::new(String::new())),
             thread_handle: None,
             terminate_tx: None,
@@ -50,47 +94,128 @@ impl LoadTypesTask {
         }
 
         let types_result = self.types.clone();
+        let type_names_result = self.type_names.clone();
         let status = self.status.clone();
 
         let include_paths = self.include_paths.to_vec();
-        let headers = self.find_header_files(&self.project_root);
+        let headers = find_header_files(&self.project_root, &self.ignore_paths)?;
         let short_enums = self.short_enums;
 
         let (terminate_tx, terminate_rx) = mpsc::channel();
         self.terminate_tx = Some(terminate_tx);
 
         self.thread_handle = Some(std::thread::spawn(move || {
-            let env = Env::new(EnvOptions {
-                word_size: WordSize::Size32,
-                short_enums,
-                signed_char: true,
-            });
-            let mut crawler =
-                TypeCrawler::new(env).context("Failed to create type crawler").unwrap();
-            include_paths.iter().for_each(|path| {
-                crawler.add_include_path(path).unwrap();
-            });
+            let mut crawler = match create_crawler(&include_paths, short_enums) {
+                Ok(crawler) => crawler,
+                Err(err) => {
+                    *status.lock().unwrap() = format!("{err:#}");
+                    return;
+                }
+            };
+            load_all(
+                &mut crawler,
+                &headers,
+                &terminate_rx,
+                &status,
+                &types_result,
+                &type_names_result,
+            );
+        }));
+        Ok(())
+    }
+
+    /// Reparses one header and merges its types into the loaded types. If a type of the header
+    /// changed its size or field layout, every header is parsed again instead, since types which
+    /// contain it elsewhere were laid out with the old definition.
+    pub fn run_file(&mut self, header: PathBuf) -> Result<()> {
+        if self.thread_handle.is_some() {
+            log::warn!("Type loading task is already running.");
+            return Ok(());
+        }
 
+        let types_result = self.types.clone();
+        let type_names_result = self.type_names.clone();
+        let status = self.status.clone();
+
+        let include_paths = self.include_paths.to_vec();
+        let headers = find_header_files(&self.project_root, &self.ignore_paths)?;
+        let short_enums = self.short_enums;
+
+        let (terminate_tx, terminate_rx) = mpsc::channel();
+        self.terminate_tx = Some(terminate_tx);
+
+        self.thread_handle = Some(std::thread::spawn(move || {
+            let mut crawler = match create_crawler(&include_paths, short_enums) {
+                Ok(crawler) => crawler,
+                Err(err) => {
+                    *status.lock().unwrap() = format!("{err:#}");
+                    return;
+                }
+            };
             let start = Instant::now();
-            let mut types = Types::new();
-            for header in &headers {
-                if terminate_rx.try_recv().is_ok() {
-                    log::info!("Type loading task terminated early.");
+            *status.lock().unwrap() = format!("{}", header.display());
+            let new_types = match crawler.parse_file(&header) {
+                Ok(new_types) => new_types,
+                Err(err) => {
+                    *status.lock().unwrap() =
+                        format!("Failed to parse {}: {err}", header.display());
                     return;
                 }
-
-                *status.lock().unwrap() = format!("{}", header.display());
-                let new_types = crawler.parse_file(header).unwrap();
-                match types.extend(new_types) {
-                    Ok(()) => {}
-                    Err(err) => panic!("Error extending types: {err}"),
+            };
+            let names = match std::fs::read_to_string(&header) {
+                Ok(source) => scan_type_names(&source),
+                Err(err) => {
+                    log::warn!("Failed to scan {}: {err}", header.display());
+                    Vec::new()
                 }
+            };
+
+            let changed_shape = {
+                let types = types_result.lock().unwrap();
+                names
+                    .iter()
+                    .find(|name| match (types.get(name), new_types.get(name)) {
+                        (Some(old), Some(new)) => shape(&types, old) != shape(&new_types, new),
+                        _ => false,
+                    })
+                    .cloned()
+            };
+            if let Some(name) = changed_shape {
+                log::info!("Layout of {name} changed, reloading all headers");
+                load_all(
+                    &mut crawler,
+                    &headers,
+                    &terminate_rx,
+                    &status,
+                    &types_result,
+                    &type_names_result,
+                );
+                return;
             }
-            let end = Instant::now();
-            *status.lock().unwrap() =
-                format!("Loaded {} types in {:.2}s", types.len(), (end - start).as_secs_f32());
 
-            *types_result.lock().unwrap() = types;
+            // The reparsed header goes first so that its definitions win over the old ones
+            let mut types = types_result.lock().unwrap();
+            let old_types = types.replace(new_types);
+            if let Err(err) = types.types.extend(old_types) {
+                drop(types);
+                log::warn!("Failed to merge {}: {err}, reloading all headers", header.display());
+                load_all(
+                    &mut crawler,
+                    &headers,
+                    &terminate_rx,
+                    &status,
+                    &types_result,
+                    &type_names_result,
+                );
+                return;
+            }
+            let mut type_names = type_names_result.lock().unwrap();
+            type_names.extend(names);
+            type_names.retain(|name| types.get(name).is_some());
+            type_names.sort();
+            type_names.dedup();
+            *status.lock().unwrap() =
+                format!("Reloaded {} in {:.2}s", header.display(), start.elapsed().as_secs_f32());
         }));
         Ok(())
     }
@@ -104,27 +229,64 @@ impl LoadTypesTask {
         }
     }
 
+    pub fn is_running(&self) -> bool {
+        self.thread_handle.as_ref().is_some_and(|handle| !handle.is_finished())
+    }
+
     pub fn status(&self) -> String {
         self.status.lock().unwrap().clone()
     }
+}
 
-    fn find_header_files<P: AsRef<Path>>(&self, dir: P) -> Vec<PathBuf> {
-        let dir = dir.as_ref();
-        if self.ignore_paths.iter().any(|p| p.starts_with(dir)) {
-            return Vec::new();
+/// Parses every header and replaces the loaded types, unless the task is terminated first.
+fn load_all(
+    crawler: &mut TypeCrawler,
+    headers: &[PathBuf],
+    terminate_rx: &mpsc::Receiver<()>,
+    status: &Mutex<String>,
+    types_result: &Mutex<LoadedTypes>,
+    type_names_result: &Mutex<Vec<String>>,
+) {
+    let start = Instant::now();
+    let mut types = Types::new();
+    let mut type_names = Vec::new();
+    for header in headers {
+        if terminate_rx.try_recv().is_ok() {
+            log::info!("Type loading task terminated early.");
+            return;
         }
-        let mut header_files = Vec::new();
-        if dir.is_dir() {
-            for entry in std::fs::read_dir(dir).unwrap() {
-                let entry = entry.unwrap();
-                let path = entry.path();
-                if path.is_dir() {
-                    header_files.extend(self.find_header_files(&path));
-                } else if path.extension().is_some_and(|ext| ext == "hpp" || ext == "h") {
-                    header_files.push(path);
-                }
-            }
+
+        *status.lock().unwrap() = format!("{}", header.display());
+        let new_types = crawler.parse_file(header).unwrap();
+        match types.extend(new_types) {
+            Ok(()) => {}
+            Err(err) => panic!("Error extending types: {err}"),
+        }
+        match std::fs::read_to_string(header) {
+            Ok(source) => type_names.extend(scan_type_names(&source)),
+            Err(err) => log::warn!("Failed to scan {}: {err}", header.display()),
         }
-        header_files
     }
+    type_names.retain(|name| types.get(name).is_some());
+    type_names.sort();
+    type_names.dedup();
+    let end = Instant::now();
+    *status.lock().unwrap() =
+        format!("Loaded {} types in {:.2}s", types.len(), (end - start).as_secs_f32());
+
+    types_result.lock().unwrap().replace(types);
+    *type_names_result.lock().unwrap() = type_names;
+}
+
+/// What a reloaded type must keep for the layout of other types to stay valid: its size and the
+/// position and size of each field.
+fn shape(types: &Types, ty: &TypeKind) -> (usize, Vec<(usize, usize)>) {
+    let fields = match ty {
+        TypeKind::Struct(struct_decl) | TypeKind::Class(struct_decl) => struct_decl.fields(),
+        TypeKind::Union(union_decl) => union_decl.fields(),
+        _ => &[],
+    };
+    let fields =
+        fields.iter().map(|field| (field.offset_bits(), field.kind().size(types))).collect();
+    (ty.size(types), fields)
 }
@@ -1 +1,2 @@
+pub mod launch_emulator;
 pub mod load_types;
@@ -1,18 +1,33 @@
 use eframe::egui::{self, Rect, pos2, vec2};
 
-pub fn fixed_columns<R>(
+/// Width of the draggable area between two columns
+const HANDLE_WIDTH: f32 = 6.0;
+const MIN_COLUMN_WIDTH: f32 = 20.0;
+
+pub struct ColumnsResponse {
+    /// True if a column was resized by dragging a handle
+    pub resized: bool,
+    /// Index of the column whose right-hand handle was double-clicked
+    pub double_clicked: Option<usize>,
+}
+
+type AddColumns<'c> = Box<dyn FnOnce(&mut [egui::Ui]) + 'c>;
+
+/// Lays out columns of the given widths, with a resize handle between each column. Dragging a
+/// handle updates `column_widths`.
+pub fn fixed_columns(
     ui: &mut egui::Ui,
-    column_widths: &[f32],
-    add_contents: impl FnOnce(&mut [egui::Ui]) -> R,
-) -> R {
+    column_widths: &mut [f32],
+    add_contents: impl FnOnce(&mut [egui::Ui]),
+) -> ColumnsResponse {
     fixed_columns_dyn(ui, column_widths, Box::new(add_contents))
 }
 
-fn fixed_columns_dyn<'c, R>(
+fn fixed_columns_dyn(
     ui: &mut egui::Ui,
-    column_widths: &[f32],
-    add_contents: Box<dyn FnOnce(&mut [egui::Ui]) -> R + 'c>,
-) -> R {
+    column_widths: &mut [f32],
+    add_contents: AddColumns<'_>,
+) -> ColumnsResponse {
     let spacing = ui.spacing().item_spacing.x;
     let num_columns = column_widths.len();
     let total_spacing = spacing * (num_columns as f32 - 1.0);
@@ -20,7 +35,7 @@ fn fixed_columns_dyn<'c, R>(
 
     let mut columns = Vec::with_capacity(num_columns);
     let mut x = 0.0;
-    for &width in column_widths {
+    for &width in column_widths.iter() {
         let pos = top_left + vec2(x, 0.0);
         let child_rect =
             Rect::from_min_max(pos, pos2(pos.x + width, ui.max_rect().right_bottom().y));
@@ -34,7 +49,7 @@ fn fixed_columns_dyn<'c, R>(
         x += width + spacing;
     }
 
-    let result = add_contents(&mut columns[..]);
+    add_contents(&mut columns[..]);
 
     let mut total_width = 0.0;
     let mut max_height = 0.0;
@@ -43,10 +58,43 @@ fn fixed_columns_dyn<'c, R>(
         max_height = column.min_size().y.max(max_height);
     }
 
+    let mut resized = false;
+    let mut double_clicked = None;
+    let mut handle_x = top_left.x;
+    let handle_count = num_columns.saturating_sub(1);
+    for (i, column_width) in column_widths.iter_mut().take(handle_count).enumerate() {
+        handle_x += *column_width + spacing;
+        let handle_rect = Rect::from_min_size(
+            pos2(handle_x - (spacing + HANDLE_WIDTH) / 2.0, top_left.y),
+            vec2(HANDLE_WIDTH, max_height),
+        );
+        let handle = ui.interact(
+            handle_rect,
+            ui.id().with(("column_handle", i)),
+            egui::Sense::click_and_drag(),
+        );
+        if handle.hovered() || handle.dragged() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeColumn);
+            ui.painter().vline(
+                handle_rect.center().x,
+                handle_rect.y_range(),
+                ui.visuals().widgets.hovered.fg_stroke,
+            );
+        }
+        if handle.dragged() {
+            let width = (*column_width + handle.drag_delta().x).max(MIN_COLUMN_WIDTH);
+            resized |= width != *column_width;
+            *column_width = width;
+        }
+        if handle.double_clicked() {
+            double_clicked = Some(i);
+        }
+    }
+
     // Make sure we fit everything next frame:
     let total_required_width = total_spacing + total_width;
 
     let size = vec2(ui.available_width().max(total_required_width), max_height);
     ui.advance_cursor_after_rect(Rect::from_min_size(top_left, size));
-    result
+    ColumnsResponse { resized, double_clicked }
 }
@@ -0,0 +1,822 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use eframe::egui;
+
+const TAB_STRIP_HEIGHT: f32 = 24.0;
+const SPLITTER_WIDTH: f32 = 6.0;
+
+#[derive(Clone, Copy)]
+enum Side {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// A node of the dock layout, either a group of tabs or a split into two nodes.
+#[derive(Clone)]
+enum DockNode {
+    Leaf {
+        tabs: Vec<String>,
+        active: usize,
+    },
+    Split {
+        /// Children are side by side if true, stacked otherwise
+        horizontal: bool,
+        /// Size of the first child relative to the whole split
+        fraction: f32,
+        first: Box<DockNode>,
+        second: Box<DockNode>,
+    },
+}
+
+impl DockNode {
+    fn empty() -> Self {
+        DockNode::Leaf { tabs: Vec::new(), active: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        matches!(self, DockNode::Leaf { tabs, .. } if tabs.is_empty())
+    }
+
+    fn contains(&self, key: &str) -> bool {
+        match self {
+            DockNode::Leaf { tabs, .. } => tabs.iter().any(|tab| tab == key),
+            DockNode::Split { first, second, .. } => first.contains(key) || second.contains(key),
+        }
+    }
+
+    fn keys<'a>(&'a self, keys: &mut Vec<&'a str>) {
+        match self {
+            DockNode::Leaf { tabs, .. } => keys.extend(tabs.iter().map(String::as_str)),
+            DockNode::Split { first, second, .. } => {
+                first.keys(keys);
+                second.keys(keys);
+            }
+        }
+    }
+
+    fn has_visible(&self, visible: &dyn Fn(&str) -> bool) -> bool {
+        match self {
+            DockNode::Leaf { tabs, .. } => tabs.iter().any(|tab| visible(tab)),
+            DockNode::Split { first, second, .. } => {
+                first.has_visible(visible) || second.has_visible(visible)
+            }
+        }
+    }
+
+    /// Returns the leaf at `index` in depth-first order.
+    fn leaf_mut(&mut self, index: &mut usize) -> Option<&mut DockNode> {
+        match self {
+            DockNode::Leaf { .. } => {
+                if *index == 0 {
+                    return Some(self);
+                }
+                *index -= 1;
+                None
+            }
+            DockNode::Split { first, second, .. } => {
+                first.leaf_mut(index).or_else(|| second.leaf_mut(index))
+            }
+        }
+    }
+
+    /// Adds a tab to the leaf at `index` and makes it active.
+    fn add_tab(&mut self, mut index: usize, key: &str) {
+        if let Some(DockNode::Leaf { tabs, active }) = self.leaf_mut(&mut index)
+            && !tabs.iter().any(|tab| tab == key)
+        {
+            tabs.push(key.to_string());
+            *active = tabs.len() - 1;
+        }
+    }
+
+    /// Removes a tab without collapsing the leaf it was in, see [`Self::collapse`].
+    fn remove_tab(&mut self, key: &str) {
+        match self {
+            DockNode::Leaf { tabs, active } => {
+                if let Some(index) = tabs.iter().position(|tab| tab == key) {
+                    tabs.remove(index);
+                    if index < *active {
+                        *active -= 1;
+                    }
+                    *active = (*active).min(tabs.len().saturating_sub(1));
+                }
+            }
+            DockNode::Split { first, second, .. } => {
+                first.remove_tab(key);
+                second.remove_tab(key);
+            }
+        }
+    }
+
+    /// Replaces splits which have an empty side with their other side.
+    fn collapse(&mut self) {
+        if let DockNode::Split { first, second, .. } = self {
+            first.collapse();
+            second.collapse();
+            if first.is_empty() {
+                let second = std::mem::replace(second.as_mut(), DockNode::empty());
+                *self = second;
+            } else if second.is_empty() {
+                let first = std::mem::replace(first.as_mut(), DockNode::empty());
+                *self = first;
+            }
+        }
+    }
+
+    fn activate(&mut self, mut index: usize, key: &str) {
+        if let Some(DockNode::Leaf { tabs, active }) = self.leaf_mut(&mut index)
+            && let Some(position) = tabs.iter().position(|tab| tab == key)
+        {
+            *active = position;
+        }
+    }
+
+    /// Moves a tab out of its leaf into a new leaf on the given side of it.
+    fn split(&mut self, key: &str, side: Side) {
+        match self {
+            DockNode::Leaf { tabs, .. } if tabs.iter().any(|tab| tab == key) => {
+                if tabs.len() < 2 {
+                    return;
+                }
+                let mut old = std::mem::replace(self, DockNode::empty());
+                old.remove_tab(key);
+                let new = DockNode::Leaf { tabs: vec![key.to_string()], active: 0 };
+                let (first, second) = match side {
+                    Side::Left | Side::Top => (new, old),
+                    Side::Right | Side::Bottom => (old, new),
+                };
+                *self = DockNode::Split {
+                    horizontal: matches!(side, Side::Left | Side::Right),
+                    fraction: 0.5,
+                    first: Box::new(first),
+                    second: Box::new(second),
+                };
+            }
+            DockNode::Leaf { .. } => {}
+            DockNode::Split { first, second, .. } => {
+                first.split(key, side);
+                second.split(key, side);
+            }
+        }
+    }
+
+    /// Lays out the leaves with visible tabs in `rect`, and handles dragging the splitters.
+    fn layout(
+        &mut self,
+        ui: &mut egui::Ui,
+        rect: egui::Rect,
+        id: egui::Id,
+        visible: &dyn Fn(&str) -> bool,
+        next_leaf: &mut usize,
+        leaves: &mut Vec<LeafLayout>,
+    ) -> bool {
+        match self {
+            DockNode::Leaf { tabs, active } => {
+                let index = *next_leaf;
+                *next_leaf += 1;
+                let visible_tabs: Vec<String> =
+                    tabs.iter().filter(|tab| visible(tab)).cloned().collect();
+                let active = match tabs.get(*active) {
+                    Some(tab) if visible(tab) => tab.clone(),
+                    _ => match visible_tabs.first() {
+                        Some(tab) => tab.clone(),
+                        None => return false,
+                    },
+                };
+                leaves.push(LeafLayout { index, rect, tabs: visible_tabs, active });
+                false
+            }
+            DockNode::Split { horizontal, fraction, first, second } => {
+                // Give the whole rect to one side if the other has nothing to show
+                let first_id = id.with(0);
+                let second_id = id.with(1);
+                if !first.has_visible(visible) || !second.has_visible(visible) {
+                    let first_changed =
+                        first.layout(ui, rect, first_id, visible, next_leaf, leaves);
+                    let second_changed =
+                        second.layout(ui, rect, second_id, visible, next_leaf, leaves);
+                    return first_changed || second_changed;
+                }
+
+                let (first_rect, splitter_rect, second_rect) = if *horizontal {
+                    let first_width = (rect.width() - SPLITTER_WIDTH) * *fraction;
+                    let splitter_x = rect.min.x + first_width;
+                    (
+                        egui::Rect::from_x_y_ranges(rect.min.x..=splitter_x, rect.y_range()),
+                        egui::Rect::from_x_y_ranges(
+                            splitter_x..=splitter_x + SPLITTER_WIDTH,
+                            rect.y_range(),
+                        ),
+                        egui::Rect::from_x_y_ranges(
+                            splitter_x + SPLITTER_WIDTH..=rect.max.x,
+                            rect.y_range(),
+                        ),
+                    )
+                } else {
+                    let first_height = (rect.height() - SPLITTER_WIDTH) * *fraction;
+                    let splitter_y = rect.min.y + first_height;
+                    (
+                        egui::Rect::from_x_y_ranges(rect.x_range(), rect.min.y..=splitter_y),
+                        egui::Rect::from_x_y_ranges(
+                            rect.x_range(),
+                            splitter_y..=splitter_y + SPLITTER_WIDTH,
+                        ),
+                        egui::Rect::from_x_y_ranges(
+                            rect.x_range(),
+                            splitter_y + SPLITTER_WIDTH..=rect.max.y,
+                        ),
+                    )
+                };
+
+                let response = ui.interact(splitter_rect, id.with("splitter"), egui::Sense::drag());
+                if response.hovered() || response.dragged() {
+                    ui.ctx().set_cursor_icon(if *horizontal {
+                        egui::CursorIcon::ResizeHorizontal
+                    } else {
+                        egui::CursorIcon::ResizeVertical
+                    });
+                }
+                if response.dragged() {
+                    let (delta, total) = if *horizontal {
+                        (response.drag_delta().x, rect.width() - SPLITTER_WIDTH)
+                    } else {
+                        (response.drag_delta().y, rect.height() - SPLITTER_WIDTH)
+                    };
+                    *fraction = (*fraction + delta / total.max(1.0)).clamp(0.1, 0.9);
+                }
+                let stroke = if response.hovered() || response.dragged() {
+                    ui.visuals().widgets.hovered.fg_stroke
+                } else {
+                    ui.visuals().widgets.noninteractive.bg_stroke
+                };
+                if *horizontal {
+                    ui.painter().vline(splitter_rect.center().x, splitter_rect.y_range(), stroke);
+                } else {
+                    ui.painter().hline(splitter_rect.x_range(), splitter_rect.center().y, stroke);
+                }
+
+                let first_changed =
+                    first.layout(ui, first_rect, first_id, visible, next_leaf, leaves);
+                let second_changed =
+                    second.layout(ui, second_rect, second_id, visible, next_leaf, leaves);
+                response.drag_stopped() || first_changed || second_changed
+            }
+        }
+    }
+
+    fn to_toml(&self) -> toml::Table {
+        let mut table = toml::Table::new();
+        match self {
+            DockNode::Leaf { tabs, active } => {
+                let tabs: toml::value::Array = tabs.iter().map(|tab| tab.clone().into()).collect();
+                table.insert("tabs".into(), tabs.into());
+                table.insert("active".into(), (*active as i64).into());
+            }
+            DockNode::Split { horizontal, fraction, first, second } => {
+                let split = if *horizontal { "horizontal" } else { "vertical" };
+                table.insert("split".into(), split.into());
+                table.insert("fraction".into(), (*fraction as f64).into());
+                table.insert("first".into(), first.to_toml().into());
+                table.insert("second".into(), second.to_toml().into());
+            }
+        }
+        table
+    }
+
+    fn from_toml(table: &toml::Table) -> Option<Self> {
+        if let Some(tabs) = table.get("tabs").and_then(|v| v.as_array()) {
+            let tabs: Vec<String> =
+                tabs.iter().filter_map(|tab| Some(tab.as_str()?.to_string())).collect();
+            let active = table.get("active").and_then(|v| v.as_integer()).unwrap_or(0) as usize;
+            let active = active.min(tabs.len().saturating_sub(1));
+            return Some(DockNode::Leaf { tabs, active });
+        }
+        let horizontal = table.get("split")?.as_str()? == "horizontal";
+        let fraction = table.get("fraction").and_then(|v| v.as_float()).unwrap_or(0.5) as f32;
+        let first = DockNode::from_toml(table.get("first")?.as_table()?)?;
+        let second = DockNode::from_toml(table.get("second")?.as_table()?)?;
+        Some(DockNode::Split {
+            horizontal,
+            fraction: fraction.clamp(0.1, 0.9),
+            first: Box::new(first),
+            second: Box::new(second),
+        })
+    }
+}
+
+struct LeafLayout {
+    /// Index of the leaf in depth-first order
+    index: usize,
+    rect: egui::Rect,
+    tabs: Vec<String>,
+    active: String,
+}
+
+enum DockAction {
+    Activate(usize, String),
+    Move(String, usize),
+    Split(String, Side),
+    Float(String),
+}
+
+/// Payload of a tab being dragged to another group.
+struct DraggedTab(String);
+
+/// Where the windows of the current frame go, kept in egui memory between [`Dock::begin`] and
+/// [`Dock::end`] so that windows can be shown without access to the dock.
+#[derive(Clone, Default)]
+struct DockFrame {
+    enabled: bool,
+    floating: BTreeSet<String>,
+    /// Content rects of the active tabs
+    active: BTreeMap<String, egui::Rect>,
+    /// Tabs closed this frame
+    closed: BTreeSet<String>,
+    /// Windows shown this frame and their titles
+    shown: Vec<(String, String)>,
+    /// Windows which shouldn't be saved in the layout, like actor windows
+    transient: BTreeSet<String>,
+    /// Floating windows whose "Dock" button was clicked
+    dock_requests: Vec<String>,
+}
+
+impl DockFrame {
+    fn id() -> egui::Id {
+        egui::Id::new("dock_frame")
+    }
+}
+
+/// A tab layout for the windows of a view. Windows are shown with [`DockWindow`], which places them
+/// in a tab while docking is enabled and in a floating window otherwise.
+pub struct Dock {
+    root: DockNode,
+    enabled: bool,
+    /// Windows which stay floating while docking is enabled
+    floating: BTreeSet<String>,
+    /// Titles of the windows shown last frame by key, these are the tabs to show
+    titles: BTreeMap<String, String>,
+    transient: BTreeSet<String>,
+    loaded: bool,
+}
+
+impl Default for Dock {
+    fn default() -> Self {
+        Self {
+            root: DockNode::empty(),
+            enabled: false,
+            floating: BTreeSet::new(),
+            titles: BTreeMap::new(),
+            transient: BTreeSet::new(),
+            loaded: false,
+        }
+    }
+}
+
+impl Dock {
+    const CONFIG_KEY: &str = "dock";
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn load(&mut self, game_config: &toml::Table) {
+        self.loaded = true;
+        let Some(dock) = game_config.get(Self::CONFIG_KEY).and_then(|v| v.as_table()) else {
+            return;
+        };
+        self.enabled = dock.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false);
+        if let Some(floating) = dock.get("floating").and_then(|v| v.as_array()) {
+            self.floating =
+                floating.iter().filter_map(|key| Some(key.as_str()?.to_string())).collect();
+        }
+        if let Some(layout) = dock.get("layout").and_then(|v| v.as_table()) {
+            self.root = DockNode::from_toml(layout).unwrap_or_else(DockNode::empty);
+        }
+    }
+
+    fn store(&self, game_config: &mut toml::Table) {
+        let mut dock = toml::Table::new();
+        dock.insert("enabled".into(), self.enabled.into());
+        let floating: toml::value::Array =
+            self.floating.iter().map(|key| key.clone().into()).collect();
+        dock.insert("floating".into(), floating.into());
+        let mut root = self.root.clone();
+        for key in &self.transient {
+            root.remove_tab(key);
+        }
+        root.collapse();
+        dock.insert("layout".into(), root.to_toml().into());
+        game_config.insert(Self::CONFIG_KEY.into(), dock.into());
+    }
+
+    /// Enables or disables docking. Returns true if the config was changed.
+    pub fn set_enabled(&mut self, enabled: bool, game_config: &mut toml::Table) -> bool {
+        if self.enabled == enabled {
+            return false;
+        }
+        self.enabled = enabled;
+        self.store(game_config);
+        true
+    }
+
+    /// Lays out the tabs in the available space of `ui`. Windows shown after this are placed in
+    /// their tab until [`Self::end`].
+    pub fn begin(&mut self, ui: &mut egui::Ui, game_config: &toml::Table) -> bool {
+        if !self.loaded {
+            self.load(game_config);
+        }
+        let mut frame = DockFrame {
+            enabled: self.enabled,
+            floating: self.floating.clone(),
+            ..Default::default()
+        };
+        if !self.enabled {
+            ui.ctx().data_mut(|data| data.insert_temp(DockFrame::id(), frame));
+            return false;
+        }
+
+        let titles = &self.titles;
+        let visible = |key: &str| titles.contains_key(key);
+        let mut leaves = Vec::new();
+        let rect = ui.available_rect_before_wrap();
+        let mut changed =
+            self.root.layout(ui, rect, ui.id().with("dock"), &visible, &mut 0, &mut leaves);
+
+        let mut actions = Vec::new();
+        for leaf in &leaves {
+            let strip_rect = egui::Rect::from_min_size(
+                leaf.rect.min,
+                egui::vec2(leaf.rect.width(), TAB_STRIP_HEIGHT),
+            );
+            let content_rect = leaf.rect.with_min_y(strip_rect.max.y).shrink(2.0);
+            ui.painter().rect_filled(strip_rect, 0.0, ui.visuals().faint_bg_color);
+            ui.painter().rect_stroke(
+                leaf.rect,
+                0.0,
+                ui.visuals().widgets.noninteractive.bg_stroke,
+                egui::StrokeKind::Inside,
+            );
+            ui.scope_builder(
+                egui::UiBuilder::new()
+                    .id_salt(("dock_tabs", leaf.index))
+                    .max_rect(strip_rect)
+                    .layout(egui::Layout::left_to_right(egui::Align::Center)),
+                |ui| {
+                    egui::ScrollArea::horizontal().show(ui, |ui| {
+                        for key in &leaf.tabs {
+                            self.render_tab(ui, leaf, key, &mut frame, &mut actions);
+                        }
+                    });
+                },
+            );
+            frame.active.insert(leaf.active.clone(), content_rect);
+        }
+
+        // Dropping a tab on a group moves it there
+        if let Some(tab) = egui::DragAndDrop::payload::<DraggedTab>(ui.ctx())
+            && let Some(pos) = ui.ctx().pointer_interact_pos()
+            && let Some(leaf) = leaves.iter().find(|leaf| leaf.rect.contains(pos))
+        {
+            ui.painter().rect_stroke(
+                leaf.rect,
+                0.0,
+                ui.visuals().selection.stroke,
+                egui::StrokeKind::Inside,
+            );
+            if ui.input(|input| input.pointer.any_released()) {
+                actions.push(DockAction::Move(tab.0.clone(), leaf.index));
+            }
+        }
+
+        for action in actions {
+            changed = true;
+            match action {
+                DockAction::Activate(index, key) => self.root.activate(index, &key),
+                DockAction::Move(key, index) => {
+                    let mut target = index;
+                    if matches!(self.root.leaf_mut(&mut target), Some(leaf) if leaf.contains(&key))
+                    {
+                        continue;
+                    }
+                    self.root.remove_tab(&key);
+                    self.root.add_tab(index, &key);
+                    self.root.collapse();
+                }
+                DockAction::Split(key, side) => self.root.split(&key, side),
+                DockAction::Float(key) => {
+                    self.root.remove_tab(&key);
+                    self.root.collapse();
+                    self.floating.insert(key);
+                }
+            }
+        }
+        frame.floating = self.floating.clone();
+        ui.ctx().data_mut(|data| data.insert_temp(DockFrame::id(), frame));
+        changed
+    }
+
+    fn render_tab(
+        &self,
+        ui: &mut egui::Ui,
+        leaf: &LeafLayout,
+        key: &str,
+        frame: &mut DockFrame,
+        actions: &mut Vec<DockAction>,
+    ) {
+        let title = self.titles.get(key).map(String::as_str).unwrap_or(key);
+        let response = ui.add(
+            egui::Button::selectable(*key == leaf.active, title)
+                .sense(egui::Sense::click_and_drag()),
+        );
+        response.dnd_set_drag_payload(DraggedTab(key.to_string()));
+        if response.clicked() {
+            actions.push(DockAction::Activate(leaf.index, key.to_string()));
+        }
+        response.context_menu(|ui| {
+            let can_split = leaf.tabs.len() > 1;
+            for (side, text) in [
+                (Side::Left, "Split left"),
+                (Side::Right, "Split right"),
+                (Side::Top, "Split up"),
+                (Side::Bottom, "Split down"),
+            ] {
+                if ui.add_enabled(can_split, egui::Button::new(text)).clicked() {
+                    actions.push(DockAction::Split(key.to_string(), side));
+                    ui.close();
+                }
+            }
+            if ui.button("Float").clicked() {
+                actions.push(DockAction::Float(key.to_string()));
+                ui.close();
+            }
+        });
+        if ui.small_button("✖").on_hover_text("Close").clicked() {
+            frame.closed.insert(key.to_string());
+        }
+        ui.separator();
+    }
+
+    /// Adds tabs for windows opened this frame and saves the layout if it was changed, either here
+    /// or in [`Self::begin`]. Returns true if the config was changed.
+    pub fn end(
+        &mut self,
+        ctx: &egui::Context,
+        mut changed: bool,
+        game_config: &mut toml::Table,
+    ) -> bool {
+        let frame = ctx.data_mut(|data| data.remove_temp::<DockFrame>(DockFrame::id()));
+        let Some(frame) = frame else {
+            return false;
+        };
+        self.titles = frame.shown.into_iter().collect();
+        self.transient.extend(frame.transient);
+
+        for key in frame.dock_requests {
+            changed |= self.floating.remove(&key);
+        }
+        if self.enabled {
+            let mut keys = Vec::new();
+            self.root.keys(&mut keys);
+            let new_tabs: Vec<String> = self
+                .titles
+                .keys()
+                .filter(|key| !self.floating.contains(*key) && !keys.contains(&key.as_str()))
+                .cloned()
+                .collect();
+            for key in new_tabs {
+                self.root.add_tab(0, &key);
+                changed = true;
+            }
+        }
+        if changed {
+            self.store(game_config);
+        }
+        changed
+    }
+}
+
+/// A window which is shown in a dock tab or as a floating window, see [`Dock`].
+pub struct DockWindow {
+    key: String,
+    title: String,
+    id: egui::Id,
+    resizable: bool,
+    persist: bool,
+}
+
+impl DockWindow {
+    /// `key` identifies the window in the saved layout and is also used for its ID.
+    pub fn new(key: impl Into<String>, title: impl Into<String>) -> Self {
+        let key = key.into();
+        let id = egui::Id::new(key.as_str());
+        Self { key, title: title.into(), id, resizable: true, persist: true }
+    }
+
+    pub fn id(mut self, id: egui::Id) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Whether the tab of this window is saved in the layout.
+    pub fn persist(mut self, persist: bool) -> Self {
+        self.persist = persist;
+        self
+    }
+
+    pub fn show(
+        self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        add_contents: impl FnOnce(&mut egui::Ui),
+    ) {
+        if !*open {
+            return;
+        }
+        let frame = ctx.data_mut(|data| {
+            let frame = data.get_temp_mut_or_default::<DockFrame>(DockFrame::id());
+            if frame.closed.contains(&self.key) {
+                return None;
+            }
+            frame.shown.push((self.key.clone(), self.title.clone()));
+            if !self.persist {
+                frame.transient.insert(self.key.clone());
+            }
+            let docked = frame.enabled && !frame.floating.contains(&self.key);
+            Some((frame.enabled, docked, frame.active.get(&self.key).copied()))
+        });
+        let Some((dock_enabled, docked, tab_rect)) = frame else {
+            *open = false;
+            return;
+        };
+
+        if docked {
+            // Inactive tabs and windows which get a tab at the end of this frame aren't shown
+            let Some(rect) = tab_rect else {
+                return;
+            };
+            let mut ui = egui::Ui::new(
+                ctx.clone(),
+                self.id.with("dock_tab"),
+                egui::UiBuilder::new().layer_id(egui::LayerId::background()).max_rect(rect),
+            );
+            ui.set_clip_rect(rect);
+            safe_mode_banner(&mut ui);
+            add_contents(&mut ui);
+            return;
+        }
+
+        egui::Window::new(self.title).id(self.id).open(open).resizable(self.resizable).show(
+            ctx,
+            |ui| {
+                if dock_enabled && ui.small_button("Dock").clicked() {
+                    ctx.data_mut(|data| {
+                        data.get_temp_mut_or_default::<DockFrame>(DockFrame::id())
+                            .dock_requests
+                            .push(self.key.clone());
+                    });
+                }
+                safe_mode_banner(ui);
+                add_contents(ui);
+            },
+        );
+    }
+}
+
+/// Shows a banner at the top of every window while safe mode is enabled.
+pub fn set_safe_mode(ctx: &egui::Context, enabled: bool) {
+    ctx.data_mut(|data| data.insert_temp(egui::Id::new("safe_mode"), enabled));
+}
+
+fn safe_mode_banner(ui: &mut egui::Ui) {
+    let enabled = ui.ctx().data(|data| data.get_temp(egui::Id::new("safe_mode")).unwrap_or(false));
+    if enabled {
+        ui.colored_label(egui::Color32::LIGHT_GREEN, "Safe mode: memory is read-only");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(tabs: &[&str], active: usize) -> DockNode {
+        DockNode::Leaf { tabs: tabs.iter().map(|tab| tab.to_string()).collect(), active }
+    }
+
+    fn split(horizontal: bool, first: DockNode, second: DockNode) -> DockNode {
+        DockNode::Split {
+            horizontal,
+            fraction: 0.5,
+            first: Box::new(first),
+            second: Box::new(second),
+        }
+    }
+
+    fn assert_layout(node: &DockNode, expected: &DockNode) {
+        assert_eq!(node.to_toml(), expected.to_toml());
+    }
+
+    #[test]
+    fn test_split() {
+        let mut node = leaf(&["a", "b", "c"], 0);
+        node.split("b", Side::Right);
+        assert_layout(&node, &split(true, leaf(&["a", "c"], 0), leaf(&["b"], 0)));
+
+        // Splits the leaf holding the tab, with the new leaf first for the top and left sides
+        node.split("c", Side::Top);
+        let expected = split(true, split(false, leaf(&["c"], 0), leaf(&["a"], 0)), leaf(&["b"], 0));
+        assert_layout(&node, &expected);
+
+        // The only tab of a leaf can't be split off
+        node.split("b", Side::Bottom);
+        assert_layout(&node, &expected);
+    }
+
+    #[test]
+    fn test_remove_tab() {
+        let mut node = leaf(&["a", "b", "c"], 2);
+        node.remove_tab("a");
+        assert_layout(&node, &leaf(&["b", "c"], 1));
+        node.remove_tab("c");
+        assert_layout(&node, &leaf(&["b"], 0));
+        node.remove_tab("missing");
+        assert_layout(&node, &leaf(&["b"], 0));
+
+        // Emptied leaves are kept until collapsed
+        let mut node = split(false, leaf(&["a"], 0), leaf(&["b", "c"], 1));
+        node.remove_tab("a");
+        node.remove_tab("b");
+        assert_layout(&node, &split(false, leaf(&[], 0), leaf(&["c"], 0)));
+    }
+
+    #[test]
+    fn test_collapse() {
+        let mut node = split(true, split(false, leaf(&[], 0), leaf(&["a"], 0)), leaf(&["b"], 0));
+        node.collapse();
+        assert_layout(&node, &split(true, leaf(&["a"], 0), leaf(&["b"], 0)));
+
+        let mut node = split(true, split(false, leaf(&[], 0), leaf(&[], 0)), leaf(&["b"], 0));
+        node.collapse();
+        assert_layout(&node, &leaf(&["b"], 0));
+
+        let mut node = split(true, leaf(&[], 0), leaf(&[], 0));
+        node.collapse();
+        assert!(node.is_empty());
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let node = DockNode::Split {
+            horizontal: false,
+            fraction: 0.25,
+            first: Box::new(leaf(&["a", "b"], 1)),
+            second: Box::new(split(true, leaf(&["c"], 0), leaf(&["d"], 0))),
+        };
+        let table = node.to_toml();
+        let parsed = DockNode::from_toml(&table).unwrap();
+        assert_eq!(parsed.to_toml(), table);
+
+        let text = toml::to_string(&table).unwrap();
+        let reparsed = DockNode::from_toml(&toml::from_str(&text).unwrap()).unwrap();
+        assert_eq!(reparsed.to_toml(), table);
+    }
+
+    #[test]
+    fn test_from_toml_malformed() {
+        let parse = |text: &str| DockNode::from_toml(&toml::from_str(text).unwrap());
+
+        // Invalid tabs are skipped and values out of range are clamped
+        let node = parse("tabs = ['a', 1, 'b']\nactive = 5").unwrap();
+        assert_layout(&node, &leaf(&["a", "b"], 1));
+        let node = parse(
+            "split = 'horizontal'\nfraction = 2.0\nfirst = { tabs = ['a'] }\n\
+             second = { tabs = ['b'] }",
+        )
+        .unwrap();
+        let DockNode::Split { fraction, .. } = node else {
+            panic!("expected a split");
+        };
+        assert_eq!(fraction, 0.9);
+
+        assert!(parse("").is_none());
+        assert!(parse("split = 'horizontal'\nfirst = { tabs = ['a'] }").is_none());
+        assert!(parse("split = 1\nfirst = { tabs = ['a'] }\nsecond = { tabs = ['b'] }").is_none());
+        assert!(parse("split = 'vertical'\nfirst = 'a'\nsecond = { tabs = ['b'] }").is_none());
+        assert!(
+            parse("split = 'vertical'\nfirst = { split = 'horizontal' }\nsecond = { tabs = [] }")
+                .is_none()
+        );
+
+        // A malformed layout in the config leaves the dock empty
+        let mut dock = Dock::default();
+        dock.load(&toml::from_str("dock = { enabled = true, layout = { split = 3 } }").unwrap());
+        assert!(dock.is_enabled());
+        assert!(dock.root.is_empty());
+    }
+}
@@ -1,3 +1,6 @@
 pub mod columns;
+pub mod dock;
+pub mod refresh;
+pub mod struct_table;
 pub mod text_field_list;
 pub mod type_decl;
@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use dsv_core::state::{Freshness, RefreshRate, State};
+use eframe::egui;
+
+/// Default interval of the cached refresh rate, in seconds.
+const CACHED_INTERVAL: f32 = 10.0;
+
+/// Returns the refresh rate chosen for a window, see [`refresh_rate_selector`].
+pub fn window_refresh_rate(ctx: &egui::Context, window_id: egui::Id) -> RefreshRate {
+    ctx.data_mut(|data| data.get_temp(window_id.with("refresh_rate")).unwrap_or_default())
+}
+
+/// Shows buttons for choosing how often the contents of a window are read from memory, and how
+/// recent the shown data is.
+pub fn refresh_rate_selector(ui: &mut egui::Ui, window_id: egui::Id) {
+    let mut refresh_rate = window_refresh_rate(ui.ctx(), window_id);
+    let interval_id = window_id.with("refresh_interval");
+    let mut interval: f32 =
+        ui.ctx().data_mut(|data| data.get_temp(interval_id).unwrap_or(CACHED_INTERVAL));
+    ui.horizontal(|ui| {
+        ui.selectable_value(&mut refresh_rate, RefreshRate::Live, "Live");
+        ui.selectable_value(&mut refresh_rate, RefreshRate::SLOW, "1 Hz");
+        let cached =
+            matches!(refresh_rate, RefreshRate::Interval(_)) && refresh_rate != RefreshRate::SLOW;
+        if ui
+            .selectable_label(cached, "Cached")
+            .on_hover_text(
+                "Read again once the interval has passed, or after writing to the shown memory",
+            )
+            .clicked()
+        {
+            refresh_rate = RefreshRate::Interval(Duration::from_secs_f32(interval));
+        }
+        if cached {
+            let drag = egui::DragValue::new(&mut interval).range(2.0..=3600.0).suffix(" s");
+            if ui.add(drag).changed() {
+                refresh_rate = RefreshRate::Interval(Duration::from_secs_f32(interval));
+            }
+        }
+        ui.selectable_value(&mut refresh_rate, RefreshRate::Paused, "Paused");
+        freshness_indicator(ui, window_id);
+    });
+    ui.ctx().data_mut(|data| {
+        data.insert_temp(window_id.with("refresh_rate"), refresh_rate);
+        data.insert_temp(interval_id, interval);
+    });
+}
+
+/// Applies the refresh rate of a window to the requests made while rendering it, and summarizes
+/// how fresh their data is for [`freshness_indicator`].
+pub struct WindowRequests {
+    window_id: egui::Id,
+    previous_rate: RefreshRate,
+    previous_freshness: Option<Freshness>,
+}
+
+impl WindowRequests {
+    pub fn begin(ctx: &egui::Context, state: &mut State, window_id: egui::Id) -> Self {
+        let previous_rate = state.set_refresh_rate(window_refresh_rate(ctx, window_id));
+        let previous_freshness = state.track_freshness(Some(Freshness::default()));
+        Self { window_id, previous_rate, previous_freshness }
+    }
+
+    pub fn end(self, ctx: &egui::Context, state: &mut State) {
+        state.set_refresh_rate(self.previous_rate);
+        let freshness = state.track_freshness(self.previous_freshness).unwrap_or_default();
+        ctx.data_mut(|data| data.insert_temp(self.window_id.with("freshness"), freshness));
+    }
+}
+
+/// Shows a dot which is green if the data of a window was read within 100 ms, yellow within a
+/// second and red if it's older. The age is measured past the refresh interval of the window.
+pub fn freshness_indicator(ui: &mut egui::Ui, window_id: egui::Id) {
+    let freshness: Freshness =
+        ui.ctx().data_mut(|data| data.get_temp(window_id.with("freshness")).unwrap_or_default());
+    if freshness.requests == 0 {
+        return;
+    }
+    let refresh_rate = window_refresh_rate(ui.ctx(), window_id);
+    let (color, text) = match (freshness.oldest, refresh_rate) {
+        _ if freshness.failed > 0 => (
+            ui.visuals().error_fg_color,
+            format!("{} of {} reads failed", freshness.failed, freshness.requests),
+        ),
+        (None, _) => (egui::Color32::GRAY, "Not read yet".into()),
+        (Some(age), RefreshRate::Paused) => {
+            (egui::Color32::GRAY, format!("Paused, read {} ago", format_age(age)))
+        }
+        (Some(age), refresh_rate) => {
+            let interval = match refresh_rate {
+                RefreshRate::Interval(interval) => interval,
+                _ => Duration::ZERO,
+            };
+            let late = age.saturating_sub(interval);
+            let color = if late < Duration::from_millis(100) {
+                egui::Color32::GREEN
+            } else if late < Duration::from_secs(1) {
+                egui::Color32::YELLOW
+            } else {
+                egui::Color32::RED
+            };
+            (color, format!("Read {} ago", format_age(age)))
+        }
+    };
+    ui.label(egui::RichText::new("●").color(color).small()).on_hover_text(text);
+}
+
+fn format_age(age: Duration) -> String {
+    if age < Duration::from_secs(1) {
+        format!("{} ms", age.as_millis())
+    } else {
+        format!("{:.1} s", age.as_secs_f32())
+    }
+}
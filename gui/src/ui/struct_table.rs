@@ -0,0 +1,331 @@
+use std::{borrow::Cow, ops::Range};
+
+use dsv_core::{state::State, typed::layout::base_types};
+use eframe::egui;
+use type_crawler::Types;
+
+use crate::{
+    ui::type_decl::{IntoDataWidget, is_integer, resolve_type, summary_value},
+    util::{
+        read::{ReadIntValue, TypeInstance, TypeInstanceOptions},
+        widget_state::WidgetStates,
+    },
+};
+
+/// Number of fields shown until columns are chosen, as some structs have more than a hundred.
+const DEFAULT_COLUMNS: usize = 8;
+/// Number of elements behind a pointer which are requested together.
+const CHUNK_LENGTH: usize = 64;
+const MAX_VISIBLE_ROWS: f32 = 20.0;
+
+/// Where the elements of a [`StructTable`] are.
+pub enum TableElements<'s, 'a> {
+    /// An array which was read with its parent
+    Array(&'s TypeInstance<'a>),
+    /// Elements behind a pointer, which are requested as they are scrolled into view
+    Pointer(u32),
+}
+
+/// A field shown as a column, by its offset in the element.
+struct TableColumn<'a> {
+    name: &'a str,
+    ty: &'a type_crawler::TypeKind,
+    offset: usize,
+    bit_field_range: Option<Range<u8>>,
+}
+
+impl<'a> TableColumn<'a> {
+    /// Adds the fields of `struct_decl` and its base types, which is at `offset` in the element.
+    fn collect(
+        types: &'a Types,
+        struct_decl: &'a type_crawler::StructDecl,
+        offset: usize,
+        columns: &mut Vec<Self>,
+    ) {
+        for base_type in base_types(struct_decl, types) {
+            if let Some(base_struct) = base_type.ty.and_then(|ty| ty.as_struct(types)) {
+                Self::collect(types, base_struct, offset + base_type.range.start, columns);
+            }
+        }
+        for field in struct_decl.fields() {
+            let Some(name) = field.name() else {
+                continue;
+            };
+            let field_offset = field.offset_bytes();
+            let bit_field_range = field.bit_field_width().map(|width| {
+                let start = (field.offset_bits() - field_offset * 8) as u8;
+                start..start + width
+            });
+            columns.push(Self {
+                name,
+                ty: field.kind(),
+                offset: offset + field_offset,
+                bit_field_range,
+            });
+        }
+    }
+
+    fn is_numeric(&self, types: &Types) -> bool {
+        let ty = resolve_type(types, self.ty);
+        is_integer(ty)
+            || matches!(
+                ty,
+                type_crawler::TypeKind::F32
+                    | type_crawler::TypeKind::F64
+                    | type_crawler::TypeKind::Bool
+                    | type_crawler::TypeKind::Enum(_)
+            )
+    }
+
+    fn read<'e>(&self, types: &Types, element: &'e TypeInstance<'a>) -> TypeInstance<'e> {
+        element.slice(types, self.ty, self.offset, self.bit_field_range.clone())
+    }
+}
+
+/// Shows a list of structs with one row per element and one column per field. Numeric columns
+/// can be sorted, and cells are edited like the fields of a struct.
+pub struct StructTable<'a> {
+    element_type: &'a type_crawler::TypeKind,
+    struct_decl: &'a type_crawler::StructDecl,
+    count: usize,
+    id: egui::Id,
+}
+
+impl<'a> StructTable<'a> {
+    pub fn new(
+        ui: &mut egui::Ui,
+        element_type: &'a type_crawler::TypeKind,
+        struct_decl: &'a type_crawler::StructDecl,
+        count: usize,
+    ) -> Self {
+        let id = ui.make_persistent_id("struct_table");
+        Self { element_type, struct_decl, count, id }
+    }
+
+    /// Returns element `index` with its own copy of the data. Pointer elements are requested in
+    /// chunks, unless `whole` is set because the whole list is requested.
+    fn element(
+        &self,
+        types: &Types,
+        state: &mut State,
+        elements: &TableElements,
+        index: usize,
+        whole: bool,
+    ) -> Option<TypeInstance<'a>> {
+        let stride = self.element_type.stride(types);
+        let (address, data) = match elements {
+            TableElements::Array(instance) => {
+                let offset = index * stride;
+                let data = instance.data().get(offset..offset + stride)?.to_vec();
+                (instance.address() + offset as u32, data)
+            }
+            TableElements::Pointer(address) => {
+                if !whole {
+                    let chunk_start = index / CHUNK_LENGTH * CHUNK_LENGTH;
+                    let chunk_length = CHUNK_LENGTH.min(self.count - chunk_start);
+                    state.request(address + (chunk_start * stride) as u32, chunk_length * stride);
+                }
+                let address = address + (index * stride) as u32;
+                (address, state.get_data_range(address, stride)?.to_vec())
+            }
+        };
+        Some(TypeInstance::new(TypeInstanceOptions {
+            ty: self.element_type,
+            address,
+            bit_field_range: None,
+            data: Cow::Owned(data),
+        }))
+    }
+
+    /// Returns the element indices in the order of the sorted column. Sorting needs every
+    /// element, so pointer lists are requested as a whole while sorted.
+    fn sorted_order(
+        &self,
+        types: &Types,
+        state: &mut State,
+        elements: &TableElements,
+        column: &TableColumn,
+        descending: bool,
+    ) -> Vec<usize> {
+        if let TableElements::Pointer(address) = elements {
+            state.request(*address, self.count * self.element_type.stride(types));
+        }
+        let mut keys: Vec<(usize, Option<f64>)> = (0..self.count)
+            .map(|index| {
+                let element = self.element(types, state, elements, index, true);
+                let key =
+                    element.and_then(|element| sort_key(types, &column.read(types, &element)));
+                (index, key)
+            })
+            .collect();
+        // Unread elements go last either way
+        keys.sort_by(|(_, a), (_, b)| match (a, b) {
+            (Some(a), Some(b)) if descending => b.total_cmp(a),
+            (Some(a), Some(b)) => a.total_cmp(b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        });
+        keys.into_iter().map(|(index, _)| index).collect()
+    }
+
+    pub fn render(
+        &self,
+        ui: &mut egui::Ui,
+        types: &'a Types,
+        state: &mut State,
+        elements: TableElements,
+    ) {
+        let mut columns = Vec::new();
+        TableColumn::collect(types, self.struct_decl, 0, &mut columns);
+
+        // Chosen columns are shared by every table of the same struct
+        let shown_id =
+            egui::Id::new(("struct_table_columns", self.struct_decl.name().unwrap_or_default()));
+        let mut shown = ui
+            .ctx()
+            .data_mut(|data| data.get_persisted::<Vec<String>>(shown_id))
+            .unwrap_or_else(|| {
+                columns.iter().take(DEFAULT_COLUMNS).map(|column| column.name.to_string()).collect()
+            });
+        let shown_before = shown.clone();
+        ui.menu_button(format!("Columns ({}/{})", shown.len(), columns.len()), |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("All").clicked() {
+                    shown = columns.iter().map(|column| column.name.to_string()).collect();
+                }
+                if ui.button("None").clicked() {
+                    shown.clear();
+                }
+            });
+            egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                for column in &columns {
+                    let mut checked = shown.iter().any(|name| name == column.name);
+                    if ui.checkbox(&mut checked, column.name).changed() {
+                        if checked {
+                            shown.push(column.name.to_string());
+                        } else {
+                            shown.retain(|name| name != column.name);
+                        }
+                    }
+                }
+            });
+        });
+        if shown != shown_before {
+            ui.ctx().data_mut(|data| data.insert_persisted(shown_id, shown.clone()));
+        }
+        columns.retain(|column| shown.iter().any(|name| name == column.name));
+
+        let sort_id = self.id.with("sort");
+        let mut sort = ui.ctx().data_mut(|data| data.get_temp::<(String, bool)>(sort_id));
+        let sort_column = sort.as_ref().and_then(|(name, descending)| {
+            Some((columns.iter().find(|column| column.name == name)?, *descending))
+        });
+        let whole = sort_column.is_some();
+        let order = match sort_column {
+            Some((column, descending)) => {
+                self.sorted_order(types, state, &elements, column, descending)
+            }
+            None => (0..self.count).collect(),
+        };
+
+        let row_height = ui.spacing().interact_size.y;
+        egui::ScrollArea::horizontal().id_salt(self.id.with("scroll")).show(ui, |ui| {
+            let mut table = egui_extras::TableBuilder::new(ui)
+                .id_salt(self.id)
+                .striped(true)
+                .resizable(true)
+                .max_scroll_height(row_height * MAX_VISIBLE_ROWS)
+                .column(egui_extras::Column::auto().at_least(40.0));
+            for _ in &columns {
+                table = table.column(egui_extras::Column::initial(100.0).at_least(40.0).clip(true));
+            }
+            table
+                .header(row_height, |mut header| {
+                    header.col(|ui| {
+                        ui.strong("Index");
+                    });
+                    for column in &columns {
+                        header.col(|ui| {
+                            let sorted = sort.as_ref().filter(|(name, _)| name == column.name);
+                            let text = match sorted {
+                                Some((_, true)) => format!("{} ⏷", column.name),
+                                Some((_, false)) => format!("{} ⏶", column.name),
+                                None => column.name.to_string(),
+                            };
+                            if !column.is_numeric(types) {
+                                ui.strong(text);
+                                return;
+                            }
+                            let button =
+                                egui::Button::new(egui::RichText::new(text).strong()).frame(false);
+                            if ui.add(button).on_hover_text("Sort").clicked() {
+                                // Ascending, descending, then unsorted
+                                sort = match sorted {
+                                    None => Some((column.name.to_string(), false)),
+                                    Some((_, false)) => Some((column.name.to_string(), true)),
+                                    Some((_, true)) => None,
+                                };
+                            }
+                        });
+                    }
+                })
+                .body(|body| {
+                    body.rows(row_height, order.len(), |mut row| {
+                        let index = order[row.index()];
+                        let element = self.element(types, state, &elements, index, whole);
+                        row.col(|ui| {
+                            ui.label(format!("[{index}]"));
+                        });
+                        for column in &columns {
+                            row.col(|ui| {
+                                let Some(element) = &element else {
+                                    ui.weak("?");
+                                    return;
+                                };
+                                ui.push_id((index, column.name), |ui| {
+                                    WidgetStates::enter(ui.ctx(), &format!("[{index}]"));
+                                    WidgetStates::enter(ui.ctx(), column.name);
+                                    render_cell(ui, types, state, column, element);
+                                    WidgetStates::exit(ui.ctx());
+                                    WidgetStates::exit(ui.ctx());
+                                });
+                            });
+                        }
+                    });
+                });
+        });
+        ui.ctx().data_mut(|data| match sort {
+            Some(sort) => data.insert_temp(sort_id, sort),
+            None => data.remove::<(String, bool)>(sort_id),
+        });
+    }
+}
+
+/// Renders numbers with the same editable widgets as struct fields, and other fields as a summary.
+fn render_cell(
+    ui: &mut egui::Ui,
+    types: &Types,
+    state: &mut State,
+    column: &TableColumn,
+    element: &TypeInstance,
+) {
+    let field = column.read(types, element);
+    if column.is_numeric(types) {
+        field.into_data_widget(ui, types).render_value(ui, types, state);
+    } else if let Some(summary) = summary_value(types, &field) {
+        ui.label(summary);
+    } else {
+        ui.weak("…").on_hover_text("Open the element to see this field");
+    }
+}
+
+fn sort_key(types: &Types, instance: &TypeInstance) -> Option<f64> {
+    match resolve_type(types, instance.ty()) {
+        type_crawler::TypeKind::F32 => {
+            Some(f32::from_le_bytes(instance.data()[..].try_into().ok()?) as f64)
+        }
+        type_crawler::TypeKind::F64 => {
+            Some(f64::from_le_bytes(instance.data()[..].try_into().ok()?))
+        }
+        ty => ty.read_int_value(types, instance).map(|value| value as f64),
+    }
+}
@@ -1,15 +1,39 @@
-use std::borrow::Cow;
-
-use dsv_core::state::State;
+use std::{borrow::Cow, collections::BTreeMap, ops::Range};
+
+use dsv_core::{
+    disasm::Mode,
+    state::{
+        State,
+        watch::{Watch, WatchKind},
+    },
+    typed::{
+        input::{format_int, int_range, parse_int_input},
+        layout::{BaseType, NaturalAlignment, StructLayout, base_types},
+        member_pointer::MemberPointer,
+    },
+};
 use eframe::egui::{self, Widget};
 use type_crawler::Types;
 
 use crate::{
-    ui::columns,
-    util::read::{TypeInstance, TypeInstanceOptions},
+    ui::{
+        columns,
+        struct_table::{StructTable, TableElements},
+    },
+    util::{
+        baselines::Baselines,
+        list_lengths::ListLengths,
+        memory_map::with_memory_map,
+        pins::PinScope,
+        read::{ReadIntValue, TypeInstance, TypeInstanceOptions},
+        value_map::{ValueMap, ValueMaps},
+        widget_state::{WidgetOption, WidgetStates},
+    },
+    windows::disassembly::DisassemblyWindow,
 };
 
 const COLUMN_WIDTHS: &[f32] = &[75.0, 150.0, 100.0];
+const POINTER_TABLE_COLUMN_WIDTHS: &[f32] = &[50.0, 75.0, 50.0, 250.0];
 
 pub trait DataWidget {
     fn render_value(&mut self, ui: &mut egui::Ui, types: &Types, state: &mut State);
@@ -19,10 +43,21 @@ pub trait DataWidget {
     fn is_open(&self, _ui: &mut egui::Ui) -> bool {
         false
     }
+
+    /// Labels the values of integer widgets, other widgets ignore it.
+    fn set_value_map(&mut self, _value_map: ValueMap) {}
+
+    /// Tells the widget which struct field it shows. Pointer widgets use it to remember their
+    /// list length.
+    fn set_field(&mut self, _struct_name: &str, _field_name: &str) {}
 }
 
-impl<'a> TypeInstance<'a> {
-    pub fn into_data_widget(self, ui: &mut egui::Ui, types: &'a Types) -> Box<dyn DataWidget + 'a> {
+pub trait IntoDataWidget<'a> {
+    fn into_data_widget(self, ui: &mut egui::Ui, types: &'a Types) -> Box<dyn DataWidget + 'a>;
+}
+
+impl<'a> IntoDataWidget<'a> for TypeInstance<'a> {
+    fn into_data_widget(self, ui: &mut egui::Ui, types: &'a Types) -> Box<dyn DataWidget + 'a> {
         match self.ty() {
             type_crawler::TypeKind::USize { .. } => Box::new(IntegerWidget::new(ui, self)),
             type_crawler::TypeKind::SSize { .. } => Box::new(IntegerWidget::new(ui, self)),
@@ -44,17 +79,26 @@ impl<'a> TypeInstance<'a> {
             type_crawler::TypeKind::WChar { .. } => Box::new(WipWidget { data_type: "wchar" }),
             type_crawler::TypeKind::Bool => Box::new(BoolWidget { instance: self }),
             type_crawler::TypeKind::Void => Box::new(VoidWidget),
-            type_crawler::TypeKind::Reference { referenced_type: pointee_type, .. }
-            | type_crawler::TypeKind::Pointer { pointee_type, .. }
-            | type_crawler::TypeKind::MemberPointer { pointee_type, .. } => {
+            type_crawler::TypeKind::Reference { referenced_type, .. } => {
                 let address = u32::from_le_bytes(self.data()[..].try_into().unwrap_or([0; 4]));
-                Box::new(PointerWidget::new(ui, pointee_type, address))
+                Box::new(PointerWidget::new(ui, types, referenced_type, address).reference())
+            }
+            type_crawler::TypeKind::Pointer { pointee_type, .. } => {
+                let address = u32::from_le_bytes(self.data()[..].try_into().unwrap_or([0; 4]));
+                Box::new(PointerWidget::new(ui, types, pointee_type, address))
+            }
+            type_crawler::TypeKind::MemberPointer { record_name, .. } => {
+                Box::new(MemberPointerWidget { instance: self, record_name })
             }
             type_crawler::TypeKind::Array { element_type, size: Some(size) } => {
-                Box::new(ArrayWidget::new(ui, element_type, *size, self))
+                if let Some(pointee_type) = sized_pointee(types, element_type) {
+                    Box::new(PointerTableWidget::new(ui, element_type, pointee_type, *size, self))
+                } else {
+                    Box::new(ArrayWidget::new(ui, element_type, *size, self))
+                }
             }
             type_crawler::TypeKind::Array { element_type, size: None } => {
-                Box::new(PointerWidget::new(ui, element_type, self.address()))
+                Box::new(PointerWidget::new(ui, types, element_type, self.address()))
             }
             type_crawler::TypeKind::Function { .. } => Box::new(IntegerWidget::new(ui, self)),
             type_crawler::TypeKind::Struct(struct_decl) => {
@@ -67,7 +111,7 @@ impl<'a> TypeInstance<'a> {
                 Box::new(UnionWidget::new(ui, union_decl, self))
             }
             type_crawler::TypeKind::Enum(enum_decl) => {
-                Box::new(EnumWidget { enum_decl, instance: self })
+                Box::new(EnumWidget::new(ui, enum_decl, self))
             }
             type_crawler::TypeKind::Typedef(typedef) => {
                 self.with_type(typedef.underlying_type()).into_data_widget(ui, types)
@@ -86,6 +130,192 @@ impl<'a> TypeInstance<'a> {
     }
 }
 
+/// Widest name rendered in the name column, measured over a whole pass.
+#[derive(Clone, Default)]
+struct NameColumnWidth {
+    pass: u64,
+    widest: f32,
+    previous_widest: f32,
+}
+
+/// Loads the column widths of the current window, which are kept in egui memory.
+fn load_column_widths(ui: &egui::Ui, id: egui::Id, default_widths: &[f32]) -> Vec<f32> {
+    ui.ctx()
+        .data_mut(|data| data.get_persisted::<Vec<f32>>(id))
+        .filter(|widths| widths.len() == default_widths.len())
+        .unwrap_or_else(|| default_widths.to_vec())
+}
+
+/// Enables the tooltip which shows the raw bytes of a field when hovering its name.
+pub fn set_raw_bytes_tooltip(ctx: &egui::Context, enabled: bool) {
+    ctx.data_mut(|data| data.insert_temp(egui::Id::new("raw_bytes_tooltip"), enabled));
+}
+
+/// Renders the fields of base types in the same list as the fields of derived types, instead of
+/// a collapsible section per base type.
+pub fn set_flatten_base_types(ctx: &egui::Context, enabled: bool) {
+    ctx.data_mut(|data| data.insert_temp(egui::Id::new("flatten_base_types"), enabled));
+}
+
+/// Forgets which elements of each array are shown, after loading types which may have changed the
+/// sizes of the arrays.
+pub fn forget_array_ranges(ctx: &egui::Context) {
+    ctx.data_mut(|data| data.remove_by_type::<ArrayRange>());
+}
+
+/// Sets the number of decimals shown by float and fixed-point widgets.
+pub fn set_float_precision(ctx: &egui::Context, precision: usize) {
+    ctx.data_mut(|data| data.insert_temp(egui::Id::new("float_precision"), precision));
+}
+
+fn float_precision(ui: &egui::Ui) -> usize {
+    ui.ctx().data_mut(|data| data.get_temp::<usize>(egui::Id::new("float_precision")).unwrap_or(5))
+}
+
+/// Formats a float with `precision` decimals, spelling out the values which would otherwise be
+/// ambiguous: `NaN`, `+inf`, `-inf` and `-0.0`. These are also accepted as input.
+fn format_float(value: f64, precision: usize) -> String {
+    if value.is_nan() {
+        "NaN".into()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+inf".into() } else { "-inf".into() }
+    } else if value == 0.0 && value.is_sign_negative() {
+        "-0.0".into()
+    } else {
+        format!("{value:.precision$}")
+    }
+}
+
+/// The data of a row besides its value, captured before the instance is turned into a widget.
+struct FieldRow {
+    truncated: bool,
+    /// Set if the raw bytes tooltip is enabled
+    raw_bytes: Option<RawBytes>,
+    /// Set if the field overlaps another field of its struct
+    overlapping: bool,
+    /// Baseline and current value, if the field differs from the baseline of the window
+    baseline: Option<(String, String)>,
+}
+
+struct RawBytes {
+    offset: usize,
+    data: Vec<u8>,
+    bit_field_range: Option<Range<u8>>,
+}
+
+impl FieldRow {
+    /// `offset` is the offset of the instance in its parent.
+    fn new(ui: &egui::Ui, types: &Types, instance: &TypeInstance, offset: usize) -> Self {
+        let enabled = ui.ctx().data_mut(|data| {
+            data.get_temp::<bool>(egui::Id::new("raw_bytes_tooltip")).unwrap_or(false)
+        });
+        let raw_bytes = enabled.then(|| RawBytes {
+            offset,
+            data: instance.data().into_owned(),
+            bit_field_range: instance.bit_field_range().cloned(),
+        });
+        Self {
+            truncated: instance.is_truncated(),
+            raw_bytes,
+            overlapping: false,
+            baseline: Baselines::diff(ui.ctx(), types, instance),
+        }
+    }
+
+    fn overlapping(mut self, overlapping: bool) -> Self {
+        self.overlapping = overlapping;
+        self
+    }
+}
+
+impl RawBytes {
+    fn render(&self, ui: &mut egui::Ui) {
+        egui::Grid::new("raw_bytes").num_columns(2).show(ui, |ui| {
+            ui.label("Offset");
+            ui.monospace(format!("{:#x}", self.offset));
+            ui.end_row();
+            ui.label("Size");
+            ui.monospace(format!("{:#x}", self.data.len()));
+            ui.end_row();
+            if let Some(range) = &self.bit_field_range {
+                ui.label("Bits");
+                ui.monospace(format!("{}..{}", range.start, range.end));
+                ui.end_row();
+            }
+            ui.label("Bytes");
+            ui.add(egui::Label::new(egui::RichText::new(hex_bytes(&self.data)).monospace()).wrap());
+            ui.end_row();
+        });
+    }
+}
+
+/// Renders a row of type badge, name and value columns. Column widths are shared by every row in
+/// the window, and double-clicking the handle after the name column fits it to the widest name.
+/// Truncated rows are highlighted since part of their value wasn't read from memory, and rows
+/// which differ from the window's baseline in another color.
+fn field_columns(
+    ui: &mut egui::Ui,
+    name: &str,
+    row: FieldRow,
+    add_contents: impl FnOnce(&mut [egui::Ui]),
+) -> egui::Response {
+    let widths_id = ui.layer_id().id.with("field_column_widths");
+    let mut widths = load_column_widths(ui, widths_id, COLUMN_WIDTHS);
+
+    let mut name_width = 0.0;
+    let mut name_label = None;
+    let response = columns::fixed_columns(ui, &mut widths, |columns| {
+        let mut label = if row.truncated {
+            columns[1]
+                .colored_label(egui::Color32::ORANGE, name)
+                .on_hover_text("Truncated read, missing bytes are shown as zero")
+        } else if let Some((baseline, current)) = &row.baseline {
+            columns[1]
+                .colored_label(egui::Color32::LIGHT_BLUE, name)
+                .on_hover_text(format!("Changed from baseline: {baseline} → {current}"))
+        } else {
+            columns[1].label(name)
+        };
+        if let Some(raw_bytes) = &row.raw_bytes {
+            label = label.on_hover_ui(|ui| raw_bytes.render(ui));
+        }
+        if row.overlapping {
+            columns[0]
+                .colored_label(egui::Color32::ORANGE, "⚠")
+                .on_hover_text("Overlaps another field, the header may be broken");
+        }
+        if columns[1].is_rect_visible(label.rect) {
+            let font_id = egui::TextStyle::Body.resolve(columns[1].style());
+            name_width = columns[1].fonts(|fonts| {
+                fonts.layout_no_wrap(name.to_string(), font_id, egui::Color32::PLACEHOLDER).size().x
+            });
+        }
+        name_label = Some(label);
+        add_contents(columns);
+    });
+
+    let pass = ui.ctx().cumulative_pass_nr();
+    let widest = ui.ctx().data_mut(|data| {
+        let width = data.get_temp_mut_or_default::<NameColumnWidth>(widths_id);
+        if width.pass != pass {
+            width.previous_widest = width.widest;
+            width.widest = 0.0;
+            width.pass = pass;
+        }
+        width.widest = width.widest.max(name_width);
+        width.widest.max(width.previous_widest)
+    });
+
+    let auto_size = response.double_clicked == Some(1);
+    if auto_size {
+        widths[1] = widest + ui.spacing().item_spacing.x;
+    }
+    if response.resized || auto_size {
+        ui.ctx().data_mut(|data| data.insert_persisted(widths_id, widths));
+    }
+    name_label.expect("name column was rendered")
+}
+
 struct VoidWidget;
 
 impl DataWidget for VoidWidget {
@@ -96,66 +326,215 @@ impl DataWidget for VoidWidget {
 
 struct IntegerWidget<'a> {
     instance: TypeInstance<'a>,
-    show_hex_id: egui::Id,
+    show_hex: WidgetOption,
+    /// Decimal values are shown as signed, which defaults to the signedness of the type
+    show_signed: WidgetOption,
+    show_ascii: WidgetOption,
+    /// Little-endian ASCII is shown in memory order, big-endian reads like a fourcc tag
+    ascii_big_endian: WidgetOption,
+    show_bits: WidgetOption,
     text_id: egui::Id,
+    value_map: Option<ValueMap>,
 }
 
 impl<'a> IntegerWidget<'a> {
     fn new(ui: &mut egui::Ui, instance: TypeInstance<'a>) -> Self {
-        let show_hex_id = ui.make_persistent_id("show_hex");
+        let show_hex = WidgetOption::new(ui, "show_hex");
+        let show_signed = WidgetOption::new(ui, "show_signed");
+        let show_ascii = WidgetOption::new(ui, "show_ascii");
+        let ascii_big_endian = WidgetOption::new(ui, "ascii_big_endian");
+        let show_bits = WidgetOption::new(ui, "show_bits");
         let text_id = ui.make_persistent_id("value");
-        Self { instance, show_hex_id, text_id }
+        Self {
+            instance,
+            show_hex,
+            show_signed,
+            show_ascii,
+            ascii_big_endian,
+            show_bits,
+            text_id,
+            value_map: None,
+        }
+    }
+
+    fn render_ascii(&self, ui: &mut egui::Ui) {
+        let mut show_ascii = self.show_ascii.get(ui.ctx(), false);
+        let mut big_endian = self.ascii_big_endian.get(ui.ctx(), true);
+        if ui.selectable_label(show_ascii, "Aa").on_hover_text("Show as text").clicked() {
+            show_ascii = !show_ascii;
+            self.show_ascii.set(ui.ctx(), show_ascii);
+        }
+        if !show_ascii {
+            return;
+        }
+
+        let mut bytes = self.instance.data().into_owned();
+        if big_endian {
+            bytes.reverse();
+        }
+        ui.monospace(format!("'{}'", ascii_text(&bytes)));
+        let endian_text = if big_endian { "BE" } else { "LE" };
+        if ui.selectable_label(false, endian_text).on_hover_text("Byte order of the text").clicked()
+        {
+            big_endian = !big_endian;
+            self.ascii_big_endian.set(ui.ctx(), big_endian);
+        }
+    }
+
+    /// Shows each bit of flag fields as a box which toggles the bit when clicked, from the least
+    /// significant bit on. Bits with a single-bit value in the value map are labeled.
+    fn render_bits(&self, ui: &mut egui::Ui, types: &Types, state: &mut State) {
+        let size = self.instance.ty().size(types);
+        let bit_count = match self.instance.bit_field_range() {
+            Some(range) => range.len(),
+            None => size * 8,
+        };
+        if size > 4 || bit_count == 0 {
+            return;
+        }
+        let mut show_bits = self.show_bits.get(ui.ctx(), false);
+        if ui.selectable_label(show_bits, "01").on_hover_text("Show as bits").clicked() {
+            show_bits = !show_bits;
+            self.show_bits.set(ui.ctx(), show_bits);
+        }
+        if !show_bits {
+            return;
+        }
+
+        let value = self.instance.data_i64() as u64;
+        let entries = self.value_map.as_ref().map(|map| map.entries(types)).unwrap_or_default();
+        let names = bit_names(&entries);
+        ui.spacing_mut().item_spacing.x = 1.0;
+        for bit in 0..bit_count as u32 {
+            if bit > 0 && bit % 4 == 0 {
+                ui.add_space(4.0);
+            }
+            let mask = 1u64 << bit;
+            let set = value & mask != 0;
+            let text = egui::RichText::new(if set { "1" } else { "0" }).monospace();
+            let mut hover = format!("bit {bit}, {mask:#x}");
+            if let Some(name) = names.get(&bit) {
+                hover = format!("{name}: {hover}");
+            }
+            let button = egui::Button::new(text).small().selected(set);
+            if ui.add_enabled(!state.is_read_only(), button).on_hover_text(hover).clicked() {
+                let new_value = value ^ mask;
+                self.instance.write(state, new_value.to_le_bytes()[..size.max(1)].to_vec());
+            }
+        }
+        let set_names: Vec<_> = names
+            .iter()
+            .filter(|&(&bit, _)| value & (1 << bit) != 0)
+            .map(|(_, &name)| name)
+            .collect();
+        if !set_names.is_empty() {
+            ui.add_space(4.0);
+            ui.weak(set_names.join(" | "));
+        }
+    }
+
+    fn render_value_map(&self, ui: &mut egui::Ui, types: &Types, state: &mut State) {
+        let Some(value_map) = &self.value_map else {
+            return;
+        };
+        let value = self.instance.as_int::<i64>(types).unwrap_or(0);
+        let entries = value_map.entries(types);
+        let selected_text = match entries.iter().find(|(entry_value, _)| *entry_value == value) {
+            Some((_, label)) => label.clone(),
+            None => format!("{value:#x}"),
+        };
+        if state.is_read_only() {
+            ui.label(selected_text);
+            return;
+        }
+        egui::ComboBox::new("value_map", "").selected_text(selected_text).show_ui(ui, |ui| {
+            for (entry_value, label) in &entries {
+                if ui.selectable_label(*entry_value == value, label).clicked() {
+                    let size = self.instance.ty().size(types).clamp(1, 8);
+                    self.instance.write(state, entry_value.to_le_bytes()[..size].to_vec());
+                }
+            }
+        });
     }
 }
 
 impl<'a> DataWidget for IntegerWidget<'a> {
     fn render_value(&mut self, ui: &mut egui::Ui, types: &Types, state: &mut State) {
         ui.horizontal(|ui| {
-            let mut show_hex =
-                ui.ctx().data_mut(|data| data.get_temp::<bool>(self.show_hex_id).unwrap_or(false));
+            let size = self.instance.ty().size(types);
+            let error_id = self.text_id.with("range_error");
+            let mut show_hex = self.show_hex.get(ui.ctx(), false);
+            let mut show_signed =
+                self.show_signed.get(ui.ctx(), is_signed_integer(self.instance.ty()));
             let mut text =
                 ui.ctx().data_mut(|data| data.get_temp::<String>(self.text_id).unwrap_or_default());
 
-            let text_edit =
-                egui::TextEdit::singleline(&mut text).desired_width(70.0).show(ui).response;
+            let text_edit = egui::TextEdit::singleline(&mut text)
+                .desired_width(70.0)
+                .interactive(!state.is_read_only())
+                .background_color(input_background_color(ui, self.text_id))
+                .show(ui)
+                .response;
+            if text_edit.changed() {
+                ui.ctx().data_mut(|data| data.remove_temp::<String>(error_id));
+            }
 
             if text_edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                let value = if let Some(hex_text) = text.strip_prefix("0x") {
-                    u32::from_str_radix(hex_text, 16).unwrap_or(0)
-                } else {
-                    text.parse::<u32>().unwrap_or(0)
-                };
-                self.instance.write(state, value.to_le_bytes().to_vec());
+                let bytes = commit_input(ui, self.text_id, &text, |text| {
+                    parse_int_input(text, size, show_signed)
+                });
+                if let Some(bytes) = bytes {
+                    self.instance.write(state, bytes);
+                } else if !text.trim().is_empty()
+                    && let Some(range) = int_range(size, show_signed)
+                {
+                    let error = format!(
+                        "Expected a {size}-byte integer from {} to {}, or hex",
+                        range.start(),
+                        range.end()
+                    );
+                    ui.ctx().data_mut(|data| data.insert_temp(error_id, error));
+                }
             }
 
             if !text_edit.has_focus() {
-                let value = self.instance.as_int::<i64>(types).unwrap();
-                text = if show_hex {
-                    match self.instance.ty().size(types) {
-                        1 => format!("{:#x}", value as u8),
-                        2 => format!("{:#x}", value as u16),
-                        4 => format!("{:#x}", value as u32),
-                        8 => format!("{:#x}", value as u64),
-                        _ => format!("{:#x}", value),
-                    }
-                } else {
-                    value.to_string()
-                };
+                text = format_int(&self.instance.data(), show_signed, show_hex)
+                    .unwrap_or_else(|| "?".into());
             }
             ui.ctx().data_mut(|data| data.insert_temp(self.text_id, text));
 
             if ui.selectable_label(show_hex, "0x").clicked() {
                 show_hex = !show_hex;
-                ui.ctx().data_mut(|data| data.insert_temp(self.show_hex_id, show_hex));
+                self.show_hex.set(ui.ctx(), show_hex);
+            }
+            if !show_hex
+                && ui
+                    .selectable_label(show_signed, "±")
+                    .on_hover_text("Show and enter the value as signed")
+                    .clicked()
+            {
+                show_signed = !show_signed;
+                self.show_signed.set(ui.ctx(), show_signed);
             }
+            if let Some(error) = ui.ctx().data_mut(|data| data.get_temp::<String>(error_id)) {
+                ui.colored_label(ui.visuals().error_fg_color, "Invalid").on_hover_text(error);
+            }
+            self.render_ascii(ui);
+
+            self.render_value_map(ui, types, state);
+            self.render_bits(ui, types, state);
         });
     }
 
+    fn set_value_map(&mut self, value_map: ValueMap) {
+        self.value_map = Some(value_map);
+    }
+
     fn render_compound(&mut self, ui: &mut egui::Ui, types: &Types, state: &mut State) {
         ui.indent("integer_compound", |ui| {
-            columns::fixed_columns(ui, COLUMN_WIDTHS, |columns| {
+            let row = FieldRow::new(ui, types, &self.instance, 0);
+            field_columns(ui, "Value", row, |columns| {
                 ValueBadge::new(types, self.instance.ty()).render(&mut columns[0]);
-                columns[1].label("Value");
                 self.render_value(&mut columns[2], types, state);
             });
         });
@@ -164,62 +543,80 @@ impl<'a> DataWidget for IntegerWidget<'a> {
 
 struct FloatWidget<'a> {
     instance: TypeInstance<'a>,
-    show_hex_id: egui::Id,
+    show_hex: WidgetOption,
     text_id: egui::Id,
 }
 
 impl<'a> FloatWidget<'a> {
     fn new(ui: &mut egui::Ui, instance: TypeInstance<'a>) -> Self {
-        let show_hex_id = ui.make_persistent_id("show_hex");
+        let show_hex = WidgetOption::new(ui, "show_hex");
         let text_id = ui.make_persistent_id("value");
-        Self { instance, show_hex_id, text_id }
+        Self { instance, show_hex, text_id }
     }
 }
 
 impl<'a> DataWidget for FloatWidget<'a> {
     fn render_value(&mut self, ui: &mut egui::Ui, _types: &Types, state: &mut State) {
         ui.horizontal(|ui| {
-            let mut show_hex =
-                ui.ctx().data_mut(|data| data.get_temp::<bool>(self.show_hex_id).unwrap_or(false));
+            let mut show_hex = self.show_hex.get(ui.ctx(), false);
             let mut text =
                 ui.ctx().data_mut(|data| data.get_temp::<String>(self.text_id).unwrap_or_default());
 
-            let text_edit =
-                egui::TextEdit::singleline(&mut text).desired_width(70.0).show(ui).response;
-
-            if text_edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                let value = if let Some(hex_text) = text.strip_prefix("0x") {
-                    let raw_value = u32::from_str_radix(hex_text, 16).unwrap_or(0);
-                    f32::from_le_bytes(raw_value.to_le_bytes())
-                } else {
-                    text.parse::<f32>().unwrap_or(0.0)
-                };
-                self.instance.write(state, value.to_le_bytes().to_vec());
+            let text_edit = egui::TextEdit::singleline(&mut text)
+                .desired_width(70.0)
+                .interactive(!state.is_read_only())
+                .background_color(input_background_color(ui, self.text_id))
+                .show(ui)
+                .response;
+
+            let double = self.instance.data().len() == 8;
+            if text_edit.lost_focus()
+                && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                && let Some(bytes) =
+                    commit_input(ui, self.text_id, &text, |text| match text.strip_prefix("0x") {
+                        Some(hex_text) if double => {
+                            u64::from_str_radix(hex_text, 16).ok().map(|v| v.to_le_bytes().to_vec())
+                        }
+                        Some(hex_text) => {
+                            u32::from_str_radix(hex_text, 16).ok().map(|v| v.to_le_bytes().to_vec())
+                        }
+                        None if double => {
+                            text.parse::<f64>().ok().map(|v| v.to_le_bytes().to_vec())
+                        }
+                        None => text.parse::<f32>().ok().map(|v| v.to_le_bytes().to_vec()),
+                    })
+            {
+                self.instance.write(state, bytes);
             }
             if !text_edit.has_focus() {
-                let value =
-                    u32::from_le_bytes(self.instance.data()[..].try_into().unwrap_or([0; 4]));
+                let data = self.instance.data();
+                let (bits, float) = if double {
+                    let bits = u64::from_le_bytes(data[..].try_into().unwrap_or([0; 8]));
+                    (bits, f64::from_bits(bits))
+                } else {
+                    let bits = u32::from_le_bytes(data[..].try_into().unwrap_or([0; 4]));
+                    (bits as u64, f32::from_bits(bits) as f64)
+                };
                 text = if show_hex {
-                    format!("{:#x}", value)
+                    format!("{:#x}", bits)
                 } else {
-                    let float = f32::from_le_bytes(value.to_le_bytes());
-                    format!("{:.5}", float)
+                    format_float(float, float_precision(ui))
                 };
             }
             ui.ctx().data_mut(|data| data.insert_temp(self.text_id, text));
 
             if ui.selectable_label(show_hex, "0x").clicked() {
                 show_hex = !show_hex;
-                ui.ctx().data_mut(|data| data.insert_temp(self.show_hex_id, show_hex));
+                self.show_hex.set(ui.ctx(), show_hex);
             }
         });
     }
 
     fn render_compound(&mut self, ui: &mut egui::Ui, types: &Types, state: &mut State) {
         ui.indent("float_compound", |ui| {
-            columns::fixed_columns(ui, COLUMN_WIDTHS, |columns| {
+            let row = FieldRow::new(ui, types, &self.instance, 0);
+            field_columns(ui, "Value", row, |columns| {
                 ValueBadge::new(types, self.instance.ty()).render(&mut columns[0]);
-                columns[1].label("Value");
                 self.render_value(&mut columns[2], types, state);
             });
         });
@@ -240,27 +637,40 @@ impl<'a> DataWidget for BoolWidget<'a> {
         } else {
             "".into()
         };
-        if ui.checkbox(&mut checked, text).changed() {
+        if ui.add_enabled(!state.is_read_only(), egui::Checkbox::new(&mut checked, text)).changed()
+        {
             self.instance.write(state, if checked { vec![1] } else { vec![0] });
         }
     }
 
     fn render_compound(&mut self, ui: &mut egui::Ui, types: &Types, state: &mut State) {
         ui.indent("bool_compound", |ui| {
-            columns::fixed_columns(ui, COLUMN_WIDTHS, |columns| {
+            let row = FieldRow::new(ui, types, &self.instance, 0);
+            field_columns(ui, "Value", row, |columns| {
                 ValueBadge::new(types, &type_crawler::TypeKind::Bool).render(&mut columns[0]);
-                columns[1].label("Value");
                 self.render_value(&mut columns[2], types, state);
             });
         });
     }
 }
 
+/// First element and number of elements shown by an [`ArrayWidget`].
+#[derive(Clone, Copy)]
+struct ArrayRange {
+    start: usize,
+    count: usize,
+}
+
 struct ArrayWidget<'a> {
     element_type: &'a type_crawler::TypeKind,
     size: usize,
     instance: TypeInstance<'a>,
-    open_id: egui::Id,
+    open: WidgetOption,
+    /// Elements to show, see [`ArrayRange`]
+    range_id: egui::Id,
+    /// Element to scroll to, set by the index box
+    jump_id: egui::Id,
+    table: WidgetOption,
 }
 
 impl<'a> ArrayWidget<'a> {
@@ -270,44 +680,97 @@ impl<'a> ArrayWidget<'a> {
         size: usize,
         instance: TypeInstance<'a>,
     ) -> Self {
-        let open_id = ui.make_persistent_id("array_open");
-        Self { element_type, size, instance, open_id }
+        let open = WidgetOption::new(ui, "array_open");
+        let range_id = ui.make_persistent_id("array_range");
+        let jump_id = ui.make_persistent_id("array_jump");
+        let table = WidgetOption::new(ui, "array_table");
+        Self { element_type, size, instance, open, range_id, jump_id, table }
+    }
+
+    /// Returns the elements to show, clamped to the array size.
+    fn range(&self, ctx: &egui::Context) -> Range<usize> {
+        let ArrayRange { start, count } = ctx
+            .data_mut(|data| data.get_temp::<ArrayRange>(self.range_id))
+            .unwrap_or(ArrayRange { start: 0, count: self.size });
+        let start = start.min(self.size);
+        start..start.saturating_add(count).min(self.size)
+    }
+
+    fn render_range(&self, ui: &mut egui::Ui) {
+        let range = self.range(ui.ctx());
+        let (mut start, mut count) = (range.start, range.len());
+        let last = self.size.saturating_sub(1);
+        let start_changed = ui
+            .add(egui::DragValue::new(&mut start).range(0..=last).prefix("["))
+            .on_hover_text("First element to show")
+            .changed();
+        let count_changed = ui
+            .add(egui::DragValue::new(&mut count).range(1..=self.size - start).prefix("+"))
+            .on_hover_text("Number of elements to show")
+            .changed();
+        if start_changed || count_changed {
+            ui.ctx().data_mut(|data| data.insert_temp(self.range_id, ArrayRange { start, count }));
+        }
     }
 }
 
 impl<'a> DataWidget for ArrayWidget<'a> {
-    fn render_value(&mut self, ui: &mut egui::Ui, _types: &Types, _state: &mut State) {
-        let mut open = self.is_open(ui);
-        if ui.selectable_label(open, "Open").clicked() {
-            open = !open;
-            ui.ctx().data_mut(|data| data.insert_temp(self.open_id, open));
-        }
+    fn render_value(&mut self, ui: &mut egui::Ui, types: &Types, _state: &mut State) {
+        ui.horizontal(|ui| {
+            let mut open = self.is_open(ui);
+            if ui.selectable_label(open, "Open").clicked() {
+                open = !open;
+                self.open.set(ui.ctx(), open);
+            }
+            if open && self.size > 1 && table_struct(types, self.element_type).is_some() {
+                render_table_toggle(ui, &self.table);
+            }
+            if open && self.size > 1 && !self.table.get(ui.ctx(), false) {
+                self.render_range(ui);
+                if let Some(index) = jump_to_index_input(ui, self.jump_id, self.size) {
+                    // Move the range to the element if it's not shown
+                    let range = self.range(ui.ctx());
+                    if !range.contains(&index) {
+                        let range = ArrayRange { start: index, count: range.len() };
+                        ui.ctx().data_mut(|data| data.insert_temp(self.range_id, range));
+                    }
+                    ui.ctx().data_mut(|data| data.insert_temp(self.jump_id, index));
+                }
+            }
+        });
     }
 
     fn render_compound(&mut self, ui: &mut egui::Ui, types: &Types, state: &mut State) {
         ui.indent("array_compound", |ui| {
-            let stride = self.element_type.stride(types);
-            for i in 0..self.size {
-                let offset = i * stride;
-                let field_instance = self.instance.slice(types, self.element_type, offset, None);
-
-                ui.push_id(i, |ui| {
-                    let mut widget = field_instance.into_data_widget(ui, types);
-                    columns::fixed_columns(ui, COLUMN_WIDTHS, |columns| {
-                        ValueBadge::new(types, self.element_type).render(&mut columns[0]);
-                        columns[1].label(format!("[{i}]"));
-                        widget.render_value(&mut columns[2], types, state);
-                    });
-                    if widget.is_open(ui) {
-                        widget.render_compound(ui, types, state);
-                    }
-                });
+            if let Some(struct_decl) = table_struct(types, self.element_type)
+                && self.size > 1
+                && self.table.get(ui.ctx(), false)
+            {
+                StructTable::new(ui, self.element_type, struct_decl, self.size).render(
+                    ui,
+                    types,
+                    state,
+                    TableElements::Array(&self.instance),
+                );
+                return;
             }
+            let stride = self.element_type.stride(types);
+            let range = self.range(ui.ctx());
+            let jump = ui.ctx().data_mut(|data| data.remove_temp::<usize>(self.jump_id));
+            let scroll_to = jump.and_then(|index| index.checked_sub(range.start));
+            element_rows(ui, range.len(), scroll_to, |ui, rows| {
+                for i in rows.start + range.start..rows.end + range.start {
+                    let offset = i * stride;
+                    let field_instance =
+                        self.instance.slice(types, self.element_type, offset, None);
+                    render_element(ui, types, state, self.element_type, field_instance, i, offset);
+                }
+            });
         });
     }
 
     fn is_open(&self, ui: &mut egui::Ui) -> bool {
-        ui.ctx().data_mut(|data| data.get_temp::<bool>(self.open_id).unwrap_or(false))
+        self.open.get(ui.ctx(), false)
     }
 }
 
@@ -315,14 +778,82 @@ struct PointerWidget<'a> {
     pointee_type: &'a type_crawler::TypeKind,
     address: u32,
     list_length_id: egui::Id,
-    open_id: egui::Id,
+    /// Key of the saved list length, if the pointer is a struct field
+    list_length_key: Option<String>,
+    /// Element to scroll to, set by the index box
+    jump_id: egui::Id,
+    table: WidgetOption,
+    open: WidgetOption,
+    opened_address_id: egui::Id,
+    suspicion: Option<String>,
+    /// C++ references always point to one object and are open until closed
+    reference: bool,
 }
 
 impl<'a> PointerWidget<'a> {
-    fn new(ui: &mut egui::Ui, pointee_type: &'a type_crawler::TypeKind, address: u32) -> Self {
+    fn new(
+        ui: &mut egui::Ui,
+        types: &Types,
+        pointee_type: &'a type_crawler::TypeKind,
+        address: u32,
+    ) -> Self {
         let list_length_id = ui.make_persistent_id("pointer_list_length");
-        let open_id = ui.make_persistent_id("pointer_open");
-        Self { pointee_type, address, list_length_id, open_id }
+        let open = WidgetOption::new(ui, "pointer_open");
+        let opened_address_id = ui.make_persistent_id("pointer_opened_address");
+        let suspicion = Self::suspicion(ui.ctx(), types, pointee_type, address);
+        Self {
+            pointee_type,
+            address,
+            list_length_id,
+            list_length_key: None,
+            jump_id: ui.make_persistent_id("pointer_jump"),
+            table: WidgetOption::new(ui, "pointer_table"),
+            open,
+            opened_address_id,
+            suspicion,
+            reference: false,
+        }
+    }
+
+    fn reference(mut self) -> Self {
+        self.reference = true;
+        self
+    }
+
+    /// Returns a reason if the pointer value is obviously bogus.
+    fn suspicion(
+        ctx: &egui::Context,
+        types: &Types,
+        pointee_type: &type_crawler::TypeKind,
+        address: u32,
+    ) -> Option<String> {
+        if !with_memory_map(ctx, |map| map.is_readable(address)) {
+            return Some(format!("Pointer value {address:#x} is outside of known memory"));
+        }
+        let alignment = pointee_type.natural_alignment(types) as u32;
+        if alignment > 1 && !address.is_multiple_of(alignment) {
+            return Some(format!("Pointer value {address:#x} is not aligned to {alignment} bytes"));
+        }
+        None
+    }
+
+    fn list_length(&self, ctx: &egui::Context) -> usize {
+        if self.reference {
+            return 1;
+        }
+        match &self.list_length_key {
+            Some(key) => ListLengths::get(ctx, key),
+            None => ctx
+                .data_mut(|data| data.get_temp::<usize>(self.list_length_id))
+                .unwrap_or_else(|| ListLengths::default_length(ctx)),
+        }
+    }
+
+    fn set_list_length(&self, ctx: &egui::Context, list_length: usize) {
+        match &self.list_length_key {
+            Some(key) => ListLengths::set(ctx, key, list_length),
+            None => ctx.data_mut(|data| data.insert_temp(self.list_length_id, list_length)),
+        }
     }
 }
 
@@ -330,82 +861,894 @@ impl DataWidget for PointerWidget<'_> {
     fn render_value(&mut self, ui: &mut egui::Ui, types: &Types, _state: &mut State) {
         if self.pointee_type.size(types) == 0 {
             let mut str = format!("{:#010x}", self.address);
-            egui::TextEdit::singleline(&mut str).desired_width(70.0).show(ui);
+            let response =
+                egui::TextEdit::singleline(&mut str).desired_width(70.0).show(ui).response;
+            let function = matches!(
+                resolve_type(types, self.pointee_type),
+                type_crawler::TypeKind::Function { .. }
+            );
+            if function && self.address != 0 {
+                response.context_menu(|ui| {
+                    if ui.button("Disassemble").clicked() {
+                        let mode = Mode::of_code_pointer(self.address);
+                        DisassemblyWindow::disassemble(ui.ctx(), self.address & !1, mode);
+                    }
+                });
+            }
             return;
         }
         if self.address == 0 {
             ui.label("NULL");
-            ui.ctx().data_mut(|data| data.insert_temp(self.open_id, false));
+            if self.open.get(ui.ctx(), false) {
+                self.open.set(ui.ctx(), false);
+            }
             return;
         }
         ui.horizontal(|ui| {
             let mut open = self.is_open(ui);
-            let open_label = ui.selectable_label(open, "Open");
+            let open_text = egui::RichText::new(if self.reference { "Referent" } else { "Open" });
+            let open_text = if self.suspicion.is_some() {
+                open_text.color(egui::Color32::ORANGE)
+            } else {
+                open_text
+            };
+            let open_label = ui.selectable_label(open, open_text);
             if open_label.clicked() {
                 open = !open;
-                ui.ctx().data_mut(|data| data.insert_temp(self.open_id, open));
+                self.open.set(ui.ctx(), open);
+                ui.ctx().data_mut(|data| data.insert_temp(self.opened_address_id, self.address));
             }
             if open_label.hovered() {
                 egui::Tooltip::for_widget(&open_label).at_pointer().gap(12.0).show(|ui| {
-                    ui.label(format!("{:#x}", self.address));
+                    if self.reference {
+                        ui.label(format!("Reference to {:#x}", self.address));
+                    } else {
+                        ui.label(format!("{:#x}", self.address));
+                    }
+                    if let Some(suspicion) = &self.suspicion {
+                        ui.colored_label(egui::Color32::ORANGE, suspicion);
+                    }
                 });
             }
+            if self.reference {
+                return;
+            }
 
-            let mut list_length =
-                ui.ctx().data_mut(|data| data.get_temp::<usize>(self.list_length_id).unwrap_or(1));
+            let mut list_length = self.list_length(ui.ctx());
             if egui::DragValue::new(&mut list_length).ui(ui).changed() {
-                ui.ctx().data_mut(|data| data.insert_temp(self.list_length_id, list_length));
+                self.set_list_length(ui.ctx(), list_length);
+            }
+            let table = open && list_length > 1 && table_struct(types, self.pointee_type).is_some();
+            if table {
+                render_table_toggle(ui, &self.table);
+            }
+            if open
+                && list_length > 1
+                && sized_pointee(types, self.pointee_type).is_none()
+                && !(table && self.table.get(ui.ctx(), false))
+                && let Some(index) = jump_to_index_input(ui, self.jump_id, list_length)
+            {
+                ui.ctx().data_mut(|data| data.insert_temp(self.jump_id, index));
             }
         });
     }
 
     fn render_compound(&mut self, ui: &mut egui::Ui, types: &Types, state: &mut State) {
-        let list_length =
-            ui.ctx().data_mut(|data| data.get_temp::<usize>(self.list_length_id).unwrap_or(1));
+        let list_length = self.list_length(ui.ctx());
         let stride = self.pointee_type.stride(types);
         if stride == 0 {
             return;
         }
-        let size = stride * list_length;
-        state.request(self.address, size);
-        let Some(data) = state.get_data(self.address).map(|d| d.to_vec()) else {
+        let sized_pointee = sized_pointee(types, self.pointee_type);
+        if sized_pointee.is_some() || list_length == 1 {
+            let size = stride * list_length;
+            state.request(self.address, size);
+            let Some(data) = state.get_data(self.address).map(|d| d.to_vec()) else {
+                ui.label("Pointer data not found");
+                return;
+            };
+            let instance = TypeInstance::new(TypeInstanceOptions {
+                ty: self.pointee_type,
+                address: self.address,
+                bit_field_range: None,
+                data: Cow::Owned(data),
+            });
+            match sized_pointee {
+                Some(pointee_type) => {
+                    PointerTableWidget::new(
+                        ui,
+                        self.pointee_type,
+                        pointee_type,
+                        list_length,
+                        instance,
+                    )
+                    .render_compound(ui, types, state);
+                }
+                None => {
+                    // The pointee has its own options, apart from those of the pointer
+                    WidgetStates::enter(ui.ctx(), "*");
+                    instance.into_data_widget(ui, types).render_compound(ui, types, state);
+                    WidgetStates::exit(ui.ctx());
+                }
+            }
+            return;
+        }
+        if let Some(struct_decl) = table_struct(types, self.pointee_type)
+            && self.table.get(ui.ctx(), false)
+        {
+            ui.indent("pointer_compound", |ui| {
+                StructTable::new(ui, self.pointee_type, struct_decl, list_length).render(
+                    ui,
+                    types,
+                    state,
+                    TableElements::Pointer(self.address),
+                );
+            });
+            return;
+        }
+        let jump = ui.ctx().data_mut(|data| data.remove_temp::<usize>(self.jump_id));
+        ui.indent("pointer_compound", |ui| {
+            element_rows(ui, list_length, jump, |ui, rows| {
+                // Read in fixed chunks so that scrolling doesn't request a new range every frame
+                let first_chunk = rows.start / ELEMENT_CHUNK_LENGTH * ELEMENT_CHUNK_LENGTH;
+                for chunk_start in (first_chunk..rows.end).step_by(ELEMENT_CHUNK_LENGTH) {
+                    let chunk = chunk_start..(chunk_start + ELEMENT_CHUNK_LENGTH).min(list_length);
+                    let address = self.address + (chunk.start * stride) as u32;
+                    state.request(address, chunk.len() * stride);
+                    let Some(data) = state.get_data(address).map(|d| d.to_vec()) else {
+                        ui.label("Pointer data not found");
+                        continue;
+                    };
+                    let instance = TypeInstance::new(TypeInstanceOptions {
+                        ty: self.pointee_type,
+                        address,
+                        bit_field_range: None,
+                        data: Cow::Owned(data),
+                    });
+                    for i in chunk.start.max(rows.start)..chunk.end.min(rows.end) {
+                        let offset = (i - chunk.start) * stride;
+                        let field_instance = instance.slice(types, self.pointee_type, offset, None);
+                        render_element(
+                            ui,
+                            types,
+                            state,
+                            self.pointee_type,
+                            field_instance,
+                            i,
+                            i * stride,
+                        );
+                    }
+                }
+            });
+        });
+    }
+
+    fn is_open(&self, ui: &mut egui::Ui) -> bool {
+        let open = self.open.get(ui.ctx(), self.reference);
+        let opened_address = ui.ctx().data_mut(|data| data.get_temp::<u32>(self.opened_address_id));
+        // Don't keep following a pointer that turned into garbage, unless it was opened explicitly
+        open && (self.suspicion.is_none() || opened_address == Some(self.address))
+    }
+
+    fn set_field(&mut self, struct_name: &str, field_name: &str) {
+        self.list_length_key = Some(ListLengths::key(struct_name, field_name));
+    }
+}
+
+/// Lists with more elements than this only lay out the rows which are visible.
+const VIRTUAL_ROWS_MIN: usize = 64;
+/// Number of elements behind a pointer which are requested together.
+const ELEMENT_CHUNK_LENGTH: usize = 64;
+
+/// Calls `add_rows` with the range of elements to render. Long lists get a scroll area which only
+/// renders the visible rows, assuming that they all have the height of a closed row. The row at
+/// `scroll_to` is scrolled into view.
+fn element_rows(
+    ui: &mut egui::Ui,
+    count: usize,
+    scroll_to: Option<usize>,
+    mut add_rows: impl FnMut(&mut egui::Ui, Range<usize>),
+) {
+    let scroll_to = scroll_to.filter(|&index| index < count);
+    if count <= VIRTUAL_ROWS_MIN {
+        let Some(index) = scroll_to else {
+            add_rows(ui, 0..count);
+            return;
+        };
+        add_rows(ui, 0..index);
+        let top = ui.cursor().top();
+        add_rows(ui, index..index + 1);
+        let rect = egui::Rect::from_x_y_ranges(ui.max_rect().x_range(), top..=ui.cursor().top());
+        ui.scroll_to_rect(rect, Some(egui::Align::TOP));
+        add_rows(ui, index + 1..count);
+        return;
+    }
+    let row_height = ui.spacing().interact_size.y;
+    let mut scroll_area = egui::ScrollArea::vertical()
+        .id_salt("element_rows")
+        .max_height(row_height * 20.0)
+        .auto_shrink([false, true]);
+    if let Some(index) = scroll_to {
+        let row_height_with_spacing = row_height + ui.spacing().item_spacing.y;
+        scroll_area = scroll_area.vertical_scroll_offset(index as f32 * row_height_with_spacing);
+    }
+    scroll_area.show_rows(ui, row_height, count, add_rows);
+}
+
+/// Returns the struct which a list of `element_type` can be shown as a table of.
+fn table_struct<'a>(
+    types: &'a Types,
+    element_type: &'a type_crawler::TypeKind,
+) -> Option<&'a type_crawler::StructDecl> {
+    match resolve_type(types, element_type) {
+        type_crawler::TypeKind::Struct(struct_decl)
+        | type_crawler::TypeKind::Class(struct_decl) => Some(struct_decl),
+        _ => None,
+    }
+}
+
+/// Switches a list of structs between a row per element and a table, see [`StructTable`].
+fn render_table_toggle(ui: &mut egui::Ui, option: &WidgetOption) {
+    let table = option.get(ui.ctx(), false);
+    if ui
+        .selectable_label(table, "Table")
+        .on_hover_text("Show one row per element and one column per field")
+        .clicked()
+    {
+        option.set(ui.ctx(), !table);
+    }
+}
+
+/// Renders a box to enter an element index into. Returns the index when it's confirmed and less
+/// than `count`.
+fn jump_to_index_input(ui: &mut egui::Ui, id: egui::Id, count: usize) -> Option<usize> {
+    let text_id = id.with("text");
+    let parse = |text: &str| {
+        let index = match text.strip_prefix("0x") {
+            Some(hex) => usize::from_str_radix(hex, 16).ok(),
+            None => text.parse::<usize>().ok(),
+        };
+        index.filter(|&index| index < count)
+    };
+    let mut text = ui.ctx().data_mut(|data| data.get_temp::<String>(text_id).unwrap_or_default());
+    let text_color =
+        (!text.is_empty() && parse(&text).is_none()).then(|| ui.visuals().error_fg_color);
+    let response = egui::TextEdit::singleline(&mut text)
+        .desired_width(40.0)
+        .hint_text("index")
+        .text_color_opt(text_color)
+        .show(ui)
+        .response
+        .on_hover_text(format!("Scroll to an element from 0 to {}", count - 1));
+    let confirmed = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+    let index = parse(&text).filter(|_| confirmed);
+    ui.ctx().data_mut(|data| data.insert_temp(text_id, text));
+    index
+}
+
+/// Renders element `index` of an array or pointer list. `offset` is the offset from the start.
+fn render_element(
+    ui: &mut egui::Ui,
+    types: &Types,
+    state: &mut State,
+    element_type: &type_crawler::TypeKind,
+    instance: TypeInstance,
+    index: usize,
+    offset: usize,
+) {
+    ui.push_id(index, |ui| {
+        let name = format!("[{index}]");
+        WidgetStates::enter(ui.ctx(), &name);
+        let row = FieldRow::new(ui, types, &instance, offset);
+        let mut widget = instance.into_data_widget(ui, types);
+        field_columns(ui, &name, row, |columns| {
+            ValueBadge::new(types, element_type).render(&mut columns[0]);
+            widget.render_value(&mut columns[2], types, state);
+        });
+        if widget.is_open(ui) {
+            widget.render_compound(ui, types, state);
+        }
+        WidgetStates::exit(ui.ctx());
+    });
+}
+
+/// Renders the definition of a type without any data: its size and alignment, then the base
+/// types and fields of structs and unions with their offsets and sizes, or the constants of enums.
+pub fn render_type_definition(ui: &mut egui::Ui, types: &Types, ty: &type_crawler::TypeKind) {
+    ui.horizontal(|ui| {
+        ui.label(format!("Size {:#x}", ty.size(types)));
+        ui.separator();
+        ui.label(format!("Alignment {}", ty.natural_alignment(types)));
+    });
+    let fields = match resolve_type(types, ty) {
+        type_crawler::TypeKind::Struct(struct_decl)
+        | type_crawler::TypeKind::Class(struct_decl) => {
+            let base_types = base_types(struct_decl, types);
+            if !base_types.is_empty() {
+                ui.strong("Base types");
+                egui::Grid::new("type_definition_bases").num_columns(2).striped(true).show(
+                    ui,
+                    |ui| {
+                        for base_type in base_types {
+                            let range = base_type.range;
+                            ui.monospace(format!("{:#x}..{:#x}", range.start, range.end));
+                            if base_type.ty.is_some() {
+                                ui.label(base_type.name);
+                            } else {
+                                ui.colored_label(
+                                    ui.visuals().error_fg_color,
+                                    format!("{} (not found)", base_type.name),
+                                );
+                            }
+                            ui.end_row();
+                        }
+                    },
+                );
+            }
+            struct_decl.fields()
+        }
+        type_crawler::TypeKind::Union(union_decl) => union_decl.fields(),
+        type_crawler::TypeKind::Enum(enum_decl) => {
+            egui::Grid::new("type_definition_constants").striped(true).show(ui, |ui| {
+                for constant in enum_decl.constants() {
+                    ui.label(constant.name());
+                    ui.monospace(format!("{:#x}", constant.value()));
+                    ui.end_row();
+                }
+            });
+            return;
+        }
+        resolved => {
+            ValueBadge::new(types, resolved).render(ui);
+            return;
+        }
+    };
+    ui.strong("Fields");
+    egui::Grid::new("type_definition_fields").num_columns(5).striped(true).show(ui, |ui| {
+        ui.strong("Offset");
+        ui.strong("Name");
+        ui.strong("Type");
+        ui.strong("Size");
+        ui.strong("Bits");
+        ui.end_row();
+        for field in fields {
+            let offset = field.offset_bytes();
+            ui.monospace(format!("{offset:#x}"));
+            ui.label(field.name().unwrap_or(""));
+            ValueBadge::new(types, field.kind()).render(ui);
+            ui.monospace(format!("{:#x}", field.kind().size(types)));
+            match field.bit_field_width() {
+                Some(width) => {
+                    let start = field.offset_bits() - offset * 8;
+                    ui.monospace(format!("{start}..{}", start + width as usize))
+                }
+                None => ui.label(""),
+            };
+            ui.end_row();
+        }
+    });
+}
+
+/// Resolves typedefs and type names to the underlying type.
+pub fn resolve_type<'a>(
+    types: &'a Types,
+    ty: &'a type_crawler::TypeKind,
+) -> &'a type_crawler::TypeKind {
+    match ty {
+        type_crawler::TypeKind::Typedef(typedef) => resolve_type(types, typedef.underlying_type()),
+        type_crawler::TypeKind::Named(name) => match types.get(name) {
+            Some(named) => resolve_type(types, named),
+            None => ty,
+        },
+        _ => ty,
+    }
+}
+
+pub fn is_integer(ty: &type_crawler::TypeKind) -> bool {
+    matches!(
+        ty,
+        type_crawler::TypeKind::USize { .. }
+            | type_crawler::TypeKind::SSize { .. }
+            | type_crawler::TypeKind::U64
+            | type_crawler::TypeKind::U32
+            | type_crawler::TypeKind::U16
+            | type_crawler::TypeKind::U8
+            | type_crawler::TypeKind::S64
+            | type_crawler::TypeKind::S32
+            | type_crawler::TypeKind::S16
+            | type_crawler::TypeKind::S8
+    )
+}
+
+fn is_signed_integer(ty: &type_crawler::TypeKind) -> bool {
+    matches!(
+        ty,
+        type_crawler::TypeKind::SSize { .. }
+            | type_crawler::TypeKind::S64
+            | type_crawler::TypeKind::S32
+            | type_crawler::TypeKind::S16
+            | type_crawler::TypeKind::S8
+    )
+}
+
+/// Renders a row for bytes of a struct which no field covers, which can be opened to edit them.
+fn render_padding(
+    ui: &mut egui::Ui,
+    state: &mut State,
+    instance: &TypeInstance,
+    range: Range<usize>,
+) {
+    /// Bytes shown in the value column before the rest is cut off
+    const PREVIEW_LENGTH: usize = 8;
+
+    ui.push_id(("padding", range.start), |ui| {
+        let open_option = WidgetOption::new(ui, &format!("padding_open_{:#x}", range.start));
+        let data = instance.data();
+        let bytes = data.get(range.start..range.end.min(data.len())).unwrap_or_default();
+        let row = FieldRow {
+            truncated: instance.is_truncated() || bytes.len() < range.len(),
+            raw_bytes: None,
+            overlapping: false,
+            baseline: None,
+        };
+        let mut open = open_option.get(ui.ctx(), false);
+        field_columns(ui, &format!("pad_{:#x}", range.start), row, |columns| {
+            ValueBadge::new_padding(range.len()).render(&mut columns[0]);
+            columns[2].horizontal(|ui| {
+                if ui.selectable_label(open, "Edit").clicked() {
+                    open = !open;
+                    open_option.set(ui.ctx(), open);
+                }
+                let mut preview = hex_bytes(&bytes[..bytes.len().min(PREVIEW_LENGTH)]);
+                if bytes.len() > PREVIEW_LENGTH {
+                    preview.push_str(" …");
+                }
+                ui.monospace(preview);
+            });
+        });
+        if open {
+            ui.indent("padding_compound", |ui| {
+                let address = instance.address() + range.start as u32;
+                render_hex_editor(ui, state, address, bytes);
+            });
+        }
+    });
+}
+
+/// Renders bytes in rows of 16 with a text field each, which writes the byte when confirmed or
+/// flashes if it isn't hex.
+fn render_hex_editor(ui: &mut egui::Ui, state: &mut State, address: u32, bytes: &[u8]) {
+    const ROW_LENGTH: usize = 16;
+
+    egui::Grid::new("hex_editor").spacing([2.0, 2.0]).show(ui, |ui| {
+        for (row, chunk) in bytes.chunks(ROW_LENGTH).enumerate() {
+            ui.monospace(format!("+{:#04x}", row * ROW_LENGTH));
+            for (column, &byte) in chunk.iter().enumerate() {
+                let index = row * ROW_LENGTH + column;
+                let text_id = ui.id().with(("hex_byte", index));
+                // The text is only kept while editing, otherwise it follows the byte
+                let mut text = ui
+                    .ctx()
+                    .data_mut(|data| data.get_temp::<String>(text_id))
+                    .unwrap_or_else(|| format!("{byte:02x}"));
+                let text_edit = egui::TextEdit::singleline(&mut text)
+                    .font(egui::TextStyle::Monospace)
+                    .char_limit(2)
+                    .desired_width(16.0)
+                    .interactive(!state.is_read_only())
+                    .background_color(input_background_color(ui, text_id))
+                    .show(ui)
+                    .response;
+                if text_edit.lost_focus()
+                    && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                    && let Some(value) =
+                        commit_input(ui, text_id, &text, |text| u8::from_str_radix(text, 16).ok())
+                {
+                    state.request_write(address + index as u32, vec![value]);
+                }
+                ui.ctx().data_mut(|data| {
+                    if text_edit.has_focus() {
+                        data.insert_temp(text_id, text);
+                    } else {
+                        data.remove_temp::<String>(text_id);
+                    }
+                });
+            }
+            ui.end_row();
+        }
+    });
+}
+
+/// Seconds that a field is highlighted after input which couldn't be parsed.
+const INVALID_INPUT_FLASH_TIME: f64 = 1.0;
+
+/// Parses the text committed to the value field with `text_id`. Empty text means no change, and
+/// text which doesn't parse flashes the field instead of being written.
+fn commit_input<T>(
+    ui: &egui::Ui,
+    text_id: egui::Id,
+    text: &str,
+    parse: impl FnOnce(&str) -> Option<T>,
+) -> Option<T> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let value = parse(text);
+    if value.is_none() {
+        let time = ui.input(|i| i.time);
+        ui.ctx().data_mut(|data| data.insert_temp(text_id.with("invalid_input"), time));
+    }
+    value
+}
+
+/// Background of a value field, which flashes red after [`commit_input`] rejected its text.
+fn input_background_color(ui: &egui::Ui, text_id: egui::Id) -> egui::Color32 {
+    let background = ui.visuals().text_edit_bg_color();
+    let Some(invalid_time) =
+        ui.ctx().data_mut(|data| data.get_temp::<f64>(text_id.with("invalid_input")))
+    else {
+        return background;
+    };
+    let elapsed = ui.input(|i| i.time) - invalid_time;
+    if !(0.0..INVALID_INPUT_FLASH_TIME).contains(&elapsed) {
+        return background;
+    }
+    ui.ctx().request_repaint();
+    let t = 1.0 - elapsed / INVALID_INPUT_FLASH_TIME;
+    background.lerp_to_gamma(ui.visuals().error_fg_color, t as f32 * 0.6)
+}
+
+/// Returns the labels of the value map entries which have a single bit set, by bit index.
+fn bit_names(entries: &[(i64, String)]) -> BTreeMap<u32, &str> {
+    entries
+        .iter()
+        .filter(|(value, _)| value.count_ones() == 1)
+        .map(|(value, label)| (value.trailing_zeros(), label.as_str()))
+        .collect()
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect::<Vec<_>>().join(" ")
+}
+
+/// Shows printable ASCII bytes as text and every other byte as `.`.
+fn ascii_text(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        })
+        .collect()
+}
+
+/// Returns how changes to a field of this type are shown, if it can be watched.
+fn watch_kind(ty: &type_crawler::TypeKind) -> Option<WatchKind> {
+    match ty {
+        type_crawler::TypeKind::SSize { .. }
+        | type_crawler::TypeKind::S64
+        | type_crawler::TypeKind::S32
+        | type_crawler::TypeKind::S16
+        | type_crawler::TypeKind::S8
+        | type_crawler::TypeKind::Enum(_) => Some(WatchKind::Signed),
+        _ if is_integer(ty) => Some(WatchKind::Unsigned),
+        type_crawler::TypeKind::F32 | type_crawler::TypeKind::F64 => Some(WatchKind::Float),
+        type_crawler::TypeKind::Bool => Some(WatchKind::Bool),
+        _ => None,
+    }
+}
+
+/// Context menu toggle which adds or removes a watch of a field.
+fn watch_menu(ui: &mut egui::Ui, state: &mut State, watch: Watch) {
+    let mut watched = state.is_watched(watch.address, watch.size);
+    if ui.checkbox(&mut watched, "Break on change").changed() {
+        if watched {
+            state.add_watch(watch);
+        } else {
+            state.remove_watch(watch.address, watch.size);
+        }
+    }
+}
+
+/// Context menu of an integer field for labeling its values with an enum or a table.
+fn value_map_menu(
+    ui: &mut egui::Ui,
+    struct_name: &str,
+    field_name: &str,
+    value_map: Option<&ValueMap>,
+) {
+    let enum_id = ui.make_persistent_id("value_map_enum");
+    let table_id = ui.make_persistent_id("value_map_table");
+    let mut enum_name = ui.ctx().data_mut(|data| {
+        data.get_temp::<String>(enum_id).unwrap_or_else(|| match value_map {
+            Some(ValueMap::Enum(name)) => name.clone(),
+            _ => String::new(),
+        })
+    });
+    let mut table_text = ui.ctx().data_mut(|data| {
+        data.get_temp::<String>(table_id)
+            .unwrap_or_else(|| value_map.map(ValueMap::table_text).unwrap_or_default())
+    });
+
+    let mut new_value_map = None;
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(&mut enum_name).hint_text("Enum name"));
+        if ui.add_enabled(!enum_name.is_empty(), egui::Button::new("Use enum")).clicked() {
+            new_value_map = Some(Some(ValueMap::Enum(enum_name.trim().to_string())));
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(&mut table_text).hint_text("0=Idle, 1=Walk"));
+        let table = ValueMap::parse_table(&table_text);
+        if ui.add_enabled(table.is_some(), egui::Button::new("Use values")).clicked() {
+            new_value_map = Some(table);
+        }
+    });
+    if value_map.is_some() && ui.button("Remove value map").clicked() {
+        new_value_map = Some(None);
+    }
+
+    ui.ctx().data_mut(|data| {
+        data.insert_temp(enum_id, enum_name);
+        data.insert_temp(table_id, table_text);
+    });
+    if let Some(new_value_map) = new_value_map {
+        ValueMaps::set(ui.ctx(), struct_name, field_name, new_value_map);
+        ui.ctx().data_mut(|data| {
+            data.remove::<String>(enum_id);
+            data.remove::<String>(table_id);
+        });
+        ui.close();
+    }
+}
+
+/// Returns the pointee type if `ty` is a pointer to a non-empty type.
+fn sized_pointee<'a>(
+    types: &'a Types,
+    ty: &'a type_crawler::TypeKind,
+) -> Option<&'a type_crawler::TypeKind> {
+    let type_crawler::TypeKind::Pointer { pointee_type, .. } = resolve_type(types, ty) else {
+        return None;
+    };
+    let pointee_type = resolve_type(types, pointee_type);
+    (pointee_type.size(types) > 0).then_some(pointee_type)
+}
+
+/// Table of pointers where each row can be expanded into its pointee. Pointees are only read for
+/// rows which are visible or expanded.
+struct PointerTableWidget<'a> {
+    pointer_type: &'a type_crawler::TypeKind,
+    pointee_type: &'a type_crawler::TypeKind,
+    count: usize,
+    instance: TypeInstance<'a>,
+    open: WidgetOption,
+    display_field_id: egui::Id,
+}
+
+impl<'a> PointerTableWidget<'a> {
+    /// Number of fields shown in a row summary when no display field is set.
+    const SUMMARY_FIELDS: usize = 3;
+
+    fn new(
+        ui: &mut egui::Ui,
+        pointer_type: &'a type_crawler::TypeKind,
+        pointee_type: &'a type_crawler::TypeKind,
+        count: usize,
+        instance: TypeInstance<'a>,
+    ) -> Self {
+        let open = WidgetOption::new(ui, "pointer_table_open");
+        let display_field_id = ui.make_persistent_id("pointer_table_display_field");
+        Self { pointer_type, pointee_type, count, instance, open, display_field_id }
+    }
+
+    fn pointers(&self, types: &Types) -> Vec<u32> {
+        let stride = self.pointer_type.stride(types);
+        (0..self.count)
+            .map(|i| {
+                let element = self.instance.slice(types, self.pointer_type, i * stride, None);
+                element.as_int::<u32>(types).unwrap_or(0)
+            })
+            .collect()
+    }
+
+    fn summary(
+        &self,
+        types: &Types,
+        state: &mut State,
+        address: u32,
+        display_field: &str,
+    ) -> String {
+        state.request(address, self.pointee_type.size(types));
+        let Some(data) = state.get_data(address) else {
+            return "...".into();
+        };
+        let pointee = TypeInstance::new(TypeInstanceOptions {
+            ty: self.pointee_type,
+            address,
+            bit_field_range: None,
+            data: Cow::Owned(data.to_vec()),
+        });
+
+        if !display_field.is_empty() {
+            return match pointee.read_field(types, display_field) {
+                Some(field) => {
+                    let value = summary_value(types, &field).unwrap_or_else(|| "?".into());
+                    format!("{display_field} = {value}")
+                }
+                None => format!("No field '{display_field}'"),
+            };
+        }
+
+        let Some(struct_decl) = self.pointee_type.as_struct(types) else {
+            return summary_value(types, &pointee).unwrap_or_default();
+        };
+        struct_decl
+            .fields()
+            .iter()
+            .filter_map(|field| {
+                let name = field.name()?;
+                let value = summary_value(types, &pointee.read_field(types, name)?)?;
+                Some(format!("{name} = {value}"))
+            })
+            .take(Self::SUMMARY_FIELDS)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn pointee_compound(&self, ui: &mut egui::Ui, types: &Types, state: &mut State, address: u32) {
+        state.request(address, self.pointee_type.size(types));
+        let Some(data) = state.get_data(address).map(|d| d.to_vec()) else {
             ui.label("Pointer data not found");
             return;
         };
-        let instance = TypeInstance::new(TypeInstanceOptions {
+        let pointee = TypeInstance::new(TypeInstanceOptions {
             ty: self.pointee_type,
-            address: self.address,
+            address,
             bit_field_range: None,
             data: Cow::Owned(data),
         });
+        pointee.into_data_widget(ui, types).render_compound(ui, types, state);
+    }
+}
 
-        if list_length == 1 {
-            instance.into_data_widget(ui, types).render_compound(ui, types, state);
-            return;
+/// Formats a scalar value in one line, or returns `None` for compound types.
+pub fn summary_value(types: &Types, instance: &TypeInstance) -> Option<String> {
+    match resolve_type(types, instance.ty()) {
+        type_crawler::TypeKind::Pointer { .. } => {
+            Some(format!("{:#010x}", instance.as_int::<u32>(types)?))
         }
-        ui.indent("pointer_compound", |ui| {
-            for i in 0..list_length {
-                ui.push_id(i, |ui| {
-                    let offset = i * stride;
-                    let field_instance = instance.slice(types, self.pointee_type, offset, None);
+        type_crawler::TypeKind::Enum(enum_decl) => {
+            let value = instance.as_int::<i64>(types)?;
+            Some(match enum_decl.get_by_value(value) {
+                Some(constant) => constant.name().to_string(),
+                None => format!("{value:#x}"),
+            })
+        }
+        type_crawler::TypeKind::F32 => {
+            let bytes = instance.data()[..].try_into().ok()?;
+            Some(format_float(f32::from_le_bytes(bytes) as f64, 3))
+        }
+        ty => ty.read_int_value(types, instance).map(|value| value.to_string()),
+    }
+}
 
-                    let mut widget = field_instance.into_data_widget(ui, types);
-                    columns::fixed_columns(ui, COLUMN_WIDTHS, |columns| {
-                        ValueBadge::new(types, self.pointee_type).render(&mut columns[0]);
-                        columns[1].label(format!("[{i}]"));
-                        widget.render_value(&mut columns[2], types, state);
+impl DataWidget for PointerTableWidget<'_> {
+    fn render_value(&mut self, ui: &mut egui::Ui, _types: &Types, _state: &mut State) {
+        let mut open = self.is_open(ui);
+        if ui.selectable_label(open, "Open").clicked() {
+            open = !open;
+            self.open.set(ui.ctx(), open);
+        }
+    }
+
+    fn render_compound(&mut self, ui: &mut egui::Ui, types: &Types, state: &mut State) {
+        ui.indent("pointer_table_compound", |ui| {
+            let pointers = self.pointers(types);
+            let non_null = pointers.iter().filter(|&&pointer| pointer != 0).count();
+
+            let mut display_field = ui.ctx().data_mut(|data| {
+                data.get_temp::<String>(self.display_field_id).unwrap_or_default()
+            });
+            ui.horizontal(|ui| {
+                ui.label(format!("{non_null}/{} non-NULL", pointers.len()));
+                ui.label("Display field");
+                egui::TextEdit::singleline(&mut display_field)
+                    .hint_text("First fields")
+                    .desired_width(100.0)
+                    .show(ui);
+            });
+            ui.ctx()
+                .data_mut(|data| data.insert_temp(self.display_field_id, display_field.clone()));
+
+            let widths_id = ui.layer_id().id.with("pointer_table_column_widths");
+            let mut widths = load_column_widths(ui, widths_id, POINTER_TABLE_COLUMN_WIDTHS);
+            let mut resized = false;
+            for (i, &pointer) in pointers.iter().enumerate() {
+                ui.push_id(i, |ui| {
+                    let row_open_id = ui.make_persistent_id("row_open");
+                    let mut row_open = pointer != 0
+                        && ui
+                            .ctx()
+                            .data_mut(|data| data.get_temp::<bool>(row_open_id))
+                            .unwrap_or(false);
+                    let row_rect = egui::Rect::from_min_size(
+                        ui.cursor().min,
+                        egui::vec2(ui.available_width(), ui.spacing().interact_size.y),
+                    );
+                    let visible = ui.is_rect_visible(row_rect);
+
+                    let response = columns::fixed_columns(ui, &mut widths, |columns| {
+                        columns[0].label(format!("[{i}]"));
+                        columns[1].label(format!("{pointer:#010x}"));
+                        if pointer == 0 {
+                            columns[2].weak("NULL");
+                            return;
+                        }
+                        if columns[2].selectable_label(row_open, "Open").clicked() {
+                            row_open = !row_open;
+                            columns[2]
+                                .ctx()
+                                .data_mut(|data| data.insert_temp(row_open_id, row_open));
+                        }
+                        if visible {
+                            let summary = self.summary(types, state, pointer, &display_field);
+                            columns[3].add(egui::Label::new(summary).truncate());
+                        }
                     });
-                    if widget.is_open(ui) {
-                        widget.render_compound(ui, types, state);
+                    resized |= response.resized;
+
+                    if row_open {
+                        WidgetStates::enter(ui.ctx(), &format!("[{i}]"));
+                        self.pointee_compound(ui, types, state, pointer);
+                        WidgetStates::exit(ui.ctx());
                     }
                 });
             }
+            if resized {
+                ui.ctx().data_mut(|data| data.insert_persisted(widths_id, widths));
+            }
         });
     }
 
     fn is_open(&self, ui: &mut egui::Ui) -> bool {
-        ui.ctx().data_mut(|data| data.get_temp::<bool>(self.open_id).unwrap_or(false))
+        self.open.get(ui.ctx(), false)
+    }
+}
+
+/// Shows the offset or function of a pointer-to-member, which has no address to follow.
+struct MemberPointerWidget<'a> {
+    instance: TypeInstance<'a>,
+    record_name: &'a str,
+}
+
+impl DataWidget for MemberPointerWidget<'_> {
+    fn render_value(&mut self, ui: &mut egui::Ui, types: &Types, _state: &mut State) {
+        let data = self.instance.data();
+        let Some(member_pointer) = MemberPointer::decode(&data) else {
+            ui.label(egui::RichText::new(hex_bytes(&data)).monospace())
+                .on_hover_text(format!("Unknown member pointer layout of {} bytes", data.len()));
+            return;
+        };
+        let mut text = member_pointer.to_string();
+        if let MemberPointer::Data { offset } = member_pointer
+            && let Some(field) = types
+                .get(self.record_name)
+                .and_then(|record| record.as_struct(types))
+                .and_then(|record| {
+                    record.fields().iter().find(|field| field.offset_bytes() == offset as usize)
+                })
+            && let Some(name) = field.name()
+        {
+            text.push_str(&format!(" ({}::{name})", self.record_name));
+        }
+        ui.label(egui::RichText::new(text).monospace()).on_hover_text(hex_bytes(&data));
     }
+
+    fn render_compound(&mut self, _ui: &mut egui::Ui, _types: &Types, _state: &mut State) {}
 }
 
 struct WipWidget {
@@ -445,35 +1788,40 @@ impl DataWidget for NotFoundWidget {
 
 struct Fx32Widget<'a> {
     instance: TypeInstance<'a>,
-    show_hex_id: egui::Id,
+    show_hex: WidgetOption,
     text_id: egui::Id,
 }
 
 impl<'a> Fx32Widget<'a> {
     fn new(ui: &mut egui::Ui, instance: TypeInstance<'a>) -> Self {
-        let show_hex_id = ui.make_persistent_id("show_hex");
+        let show_hex = WidgetOption::new(ui, "show_hex");
         let text_id = ui.make_persistent_id("text");
-        Self { instance, show_hex_id, text_id }
+        Self { instance, show_hex, text_id }
     }
 }
 
 impl<'a> DataWidget for Fx32Widget<'a> {
     fn render_value(&mut self, ui: &mut egui::Ui, types: &Types, state: &mut State) {
         ui.horizontal(|ui| {
-            let mut show_hex =
-                ui.ctx().data_mut(|data| data.get_temp::<bool>(self.show_hex_id).unwrap_or(false));
+            let mut show_hex = self.show_hex.get(ui.ctx(), false);
             let mut text =
                 ui.ctx().data_mut(|data| data.get_temp::<String>(self.text_id).unwrap_or_default());
 
-            let text_edit =
-                egui::TextEdit::singleline(&mut text).desired_width(70.0).show(ui).response;
-
-            if text_edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                let value = if let Some(hex_text) = text.strip_prefix("0x") {
-                    i32::from_str_radix(hex_text, 16).unwrap_or(0)
-                } else {
-                    (text.parse::<f32>().unwrap_or(0.0) * 4096.0) as i32
-                };
+            let text_edit = egui::TextEdit::singleline(&mut text)
+                .desired_width(70.0)
+                .interactive(!state.is_read_only())
+                .background_color(input_background_color(ui, self.text_id))
+                .show(ui)
+                .response;
+
+            if text_edit.lost_focus()
+                && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                && let Some(value) =
+                    commit_input(ui, self.text_id, &text, |text| match text.strip_prefix("0x") {
+                        Some(hex_text) => i32::from_str_radix(hex_text, 16).ok(),
+                        None => text.parse::<f32>().ok().map(|value| (value * 4096.0) as i32),
+                    })
+            {
                 self.instance.write(state, value.to_le_bytes().to_vec());
             }
             if !text_edit.has_focus() {
@@ -481,25 +1829,25 @@ impl<'a> DataWidget for Fx32Widget<'a> {
                 text = if show_hex {
                     format!("{:#x}", value)
                 } else {
-                    let q20 = value as f32 / 4096.0;
-                    format!("{:.5}", q20)
+                    let q20 = value as f64 / 4096.0;
+                    format!("{q20:.0$}", float_precision(ui))
                 };
             }
             ui.ctx().data_mut(|data| data.insert_temp(self.text_id, text));
 
             if ui.selectable_label(show_hex, "0x").clicked() {
                 show_hex = !show_hex;
-                ui.ctx().data_mut(|data| data.insert_temp(self.show_hex_id, show_hex));
+                self.show_hex.set(ui.ctx(), show_hex);
             }
         });
     }
 
     fn render_compound(&mut self, ui: &mut egui::Ui, types: &Types, state: &mut State) {
         ui.indent("fx32_compound", |ui| {
-            columns::fixed_columns(ui, COLUMN_WIDTHS, |columns| {
+            let row = FieldRow::new(ui, types, &self.instance, 0);
+            field_columns(ui, "Value", row, |columns| {
                 ValueBadge::new(types, &type_crawler::TypeKind::Named("q20".to_string()))
                     .render(&mut columns[0]);
-                columns[1].label("Value");
                 self.render_value(&mut columns[2], types, state);
             });
         });
@@ -509,11 +1857,83 @@ impl<'a> DataWidget for Fx32Widget<'a> {
 struct EnumWidget<'a> {
     enum_decl: &'a type_crawler::EnumDecl,
     instance: TypeInstance<'a>,
+    /// Raw value being typed, for values without a constant
+    text_id: egui::Id,
+}
+
+impl<'a> EnumWidget<'a> {
+    fn new(
+        ui: &mut egui::Ui,
+        enum_decl: &'a type_crawler::EnumDecl,
+        instance: TypeInstance<'a>,
+    ) -> Self {
+        let text_id = ui.make_persistent_id("enum_raw_value");
+        Self { enum_decl, instance, text_id }
+    }
+
+    fn write_value(&self, state: &mut State, value: i64) {
+        let size = self.enum_decl.size().clamp(1, 8);
+        self.instance.write(state, value.to_le_bytes()[..size].to_vec());
+    }
+
+    /// Lets any value of the enum's size be entered, in decimal or with a 0x prefix.
+    fn render_raw_value(&self, ui: &mut egui::Ui, state: &mut State, value: i64) {
+        let size = self.enum_decl.size().clamp(1, 8);
+        let signed = is_signed_enum(self.enum_decl);
+        let error_id = self.text_id.with("range_error");
+        let mut text =
+            ui.ctx().data_mut(|data| data.get_temp::<String>(self.text_id).unwrap_or_default());
+        let text_edit = egui::TextEdit::singleline(&mut text)
+            .desired_width(70.0)
+            .background_color(input_background_color(ui, self.text_id))
+            .show(ui)
+            .response
+            .on_hover_text("Raw value");
+        if text_edit.changed() {
+            ui.ctx().data_mut(|data| data.remove_temp::<String>(error_id));
+        }
+        if text_edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            let bytes = commit_input(ui, self.text_id, &text, |text| {
+                parse_enum_input(self.enum_decl, text)
+            });
+            if let Some(bytes) = bytes {
+                self.instance.write(state, bytes);
+            } else if !text.trim().is_empty()
+                && let Some(range) = int_range(size, signed)
+            {
+                let error = format!(
+                    "Expected a {size}-byte value from {} to {}, or hex",
+                    range.start(),
+                    range.end()
+                );
+                ui.ctx().data_mut(|data| data.insert_temp(error_id, error));
+            }
+        }
+        if !text_edit.has_focus() {
+            let bits = size * 8;
+            let mask = if bits == 64 { u64::MAX } else { (1 << bits) - 1 };
+            text = format!("{:#x}", value as u64 & mask);
+        }
+        ui.ctx().data_mut(|data| data.insert_temp(self.text_id, text));
+        if let Some(error) = ui.ctx().data_mut(|data| data.get_temp::<String>(error_id)) {
+            ui.colored_label(ui.visuals().error_fg_color, "Invalid").on_hover_text(error);
+        }
+    }
+}
+
+/// Enums are treated as signed if any of their constants is negative.
+fn is_signed_enum(enum_decl: &type_crawler::EnumDecl) -> bool {
+    enum_decl.constants().iter().any(|constant| constant.value() < 0)
+}
+
+/// Parses a raw value typed for an enum into its bytes, see [`parse_int_input`]. Returns `None`
+/// if the value doesn't fit in the enum's size.
+fn parse_enum_input(enum_decl: &type_crawler::EnumDecl, text: &str) -> Option<Vec<u8>> {
+    parse_int_input(text, enum_decl.size().clamp(1, 8), is_signed_enum(enum_decl))
 }
 
 impl<'a> DataWidget for EnumWidget<'a> {
     fn render_value(&mut self, ui: &mut egui::Ui, types: &Types, state: &mut State) {
-        let size = self.enum_decl.size();
         let mut value = self.instance.as_int::<i64>(types).unwrap();
 
         let current_constant = self.enum_decl.get_by_value(value);
@@ -523,27 +1943,28 @@ impl<'a> DataWidget for EnumWidget<'a> {
             format!("{:#x}", value).into()
         };
 
-        egui::ComboBox::new("enum_value", "").selected_text(selected_text).show_ui(ui, |ui| {
-            for constant in self.enum_decl.constants() {
-                if ui.selectable_value(&mut value, constant.value(), constant.name()).clicked() {
-                    let constant_bytes = match size {
-                        1 => (constant.value() as u8).to_le_bytes().to_vec(),
-                        2 => (constant.value() as u16).to_le_bytes().to_vec(),
-                        4 => (constant.value() as u32).to_le_bytes().to_vec(),
-                        8 => (constant.value() as u64).to_le_bytes().to_vec(),
-                        _ => panic!("Unsupported enum size"),
-                    };
-                    self.instance.write(state, constant_bytes);
+        if state.is_read_only() {
+            ui.label(selected_text);
+            return;
+        }
+        ui.horizontal(|ui| {
+            self.render_raw_value(ui, state, value);
+            egui::ComboBox::new("enum_value", "").selected_text(selected_text).show_ui(ui, |ui| {
+                for constant in self.enum_decl.constants() {
+                    if ui.selectable_value(&mut value, constant.value(), constant.name()).clicked()
+                    {
+                        self.write_value(state, constant.value());
+                    }
                 }
-            }
+            });
         });
     }
 
     fn render_compound(&mut self, ui: &mut egui::Ui, types: &Types, state: &mut State) {
         ui.indent("enum_compound", |ui| {
-            columns::fixed_columns(ui, COLUMN_WIDTHS, |columns| {
+            let row = FieldRow::new(ui, types, &self.instance, 0);
+            field_columns(ui, "Value", row, |columns| {
                 ValueBadge::new_enum(self.enum_decl).render(&mut columns[0]);
-                columns[1].label("Value");
                 self.render_value(&mut columns[2], types, state);
             });
         });
@@ -553,7 +1974,7 @@ impl<'a> DataWidget for EnumWidget<'a> {
 struct StructWidget<'a> {
     struct_decl: &'a type_crawler::StructDecl,
     instance: TypeInstance<'a>,
-    open_id: egui::Id,
+    open: WidgetOption,
 }
 
 impl<'a> StructWidget<'a> {
@@ -562,18 +1983,59 @@ impl<'a> StructWidget<'a> {
         struct_decl: &'a type_crawler::StructDecl,
         instance: TypeInstance<'a>,
     ) -> Self {
-        let open_id = ui.make_persistent_id("struct_open");
-        Self { struct_decl, instance, open_id }
+        let open = WidgetOption::new(ui, "struct_open");
+        Self { struct_decl, instance, open }
+    }
+
+    fn base_types<'t>(&self, types: &'t Types) -> Vec<BaseType<'t>>
+    where
+        'a: 't,
+    {
+        base_types(self.struct_decl, types)
     }
 
-    fn render_fields(&self, ui: &mut egui::Ui, types: &type_crawler::Types, state: &mut State) {
+    fn layout(&self, types: &Types, size: usize) -> (StructLayout, usize) {
+        let mut ranges: Vec<Range<usize>> = self
+            .base_types(types)
+            .iter()
+            .map(|base_type| base_type.range.start * 8..base_type.range.end * 8)
+            .collect();
+        let base_count = ranges.len();
+        ranges.extend(self.struct_decl.fields().iter().map(|field| {
+            let start = field.offset_bits();
+            let width = match field.bit_field_width() {
+                Some(width) => width as usize,
+                None => field.kind().size(types) * 8,
+            };
+            start..start + width
+        }));
+        (StructLayout::new(&ranges, size), base_count)
+    }
+
+    /// `size` is the size of [`Self::struct_decl`], which is less than the size of the instance
+    /// when rendering a base type.
+    fn render_fields(
+        &self,
+        ui: &mut egui::Ui,
+        types: &type_crawler::Types,
+        state: &mut State,
+        size: usize,
+        heading: bool,
+    ) {
         let fields = self.struct_decl.fields();
-        if fields.is_empty() {
+        let (layout, base_count) = self.layout(types, size);
+        if fields.is_empty() && layout.holes.is_empty() {
             return;
         }
-        ui.heading(self.struct_decl.name().unwrap_or("Unnamed Struct"));
-        for field in fields {
+        if heading {
+            ui.heading(self.struct_decl.name().unwrap_or("Unnamed Struct"));
+        }
+        let mut holes = layout.holes.iter().peekable();
+        for (index, field) in fields.iter().enumerate() {
             let offset = field.offset_bytes();
+            while let Some(hole) = holes.next_if(|hole| hole.start < offset) {
+                render_padding(ui, state, &self.instance, hole.clone());
+            }
             let bit_field_range = if let Some(width) = field.bit_field_width() {
                 let start = (field.offset_bits() - offset * 8) as u8;
                 Some(start..start + width)
@@ -583,33 +2045,117 @@ impl<'a> StructWidget<'a> {
             let field_instance = self.instance.slice(types, field.kind(), offset, bit_field_range);
 
             ui.push_id(offset, |ui| {
+                let struct_name = self.struct_decl.name().unwrap_or("");
+                let field_name = field.name().unwrap_or("");
+                WidgetStates::enter(ui.ctx(), field_name);
+                let value_map = ValueMaps::get(ui.ctx(), struct_name, field_name);
+
+                let row = FieldRow::new(ui, types, &field_instance, offset)
+                    .overlapping(layout.is_overlapping(base_count + index));
+                let field_address = field_instance.address();
                 let mut widget = field_instance.into_data_widget(ui, types);
-                columns::fixed_columns(ui, COLUMN_WIDTHS, |columns| {
+                if let Some(value_map) = value_map.clone() {
+                    widget.set_value_map(value_map);
+                }
+                widget.set_field(struct_name, field_name);
+                let name_label = field_columns(ui, field_name, row, |columns| {
                     ValueBadge::new(types, field.kind()).render(&mut columns[0]);
-                    columns[1].label(field.name().unwrap_or(""));
                     widget.render_value(&mut columns[2], types, state);
                 });
+                let field_type = resolve_type(types, field.kind());
+                let pin = PinScope::pin(ui.ctx(), field_name);
+                let watch_kind =
+                    watch_kind(field_type).filter(|_| field.bit_field_width().is_none());
+                if pin.is_some() || watch_kind.is_some() {
+                    name_label.context_menu(|ui| {
+                        if let Some(pin) = pin.clone()
+                            && ui.button("Pin to dashboard").clicked()
+                        {
+                            PinScope::add_pin(ui.ctx(), pin);
+                            ui.close();
+                        }
+                        if let Some(kind) = watch_kind {
+                            let label = match &pin {
+                                Some(pin) => pin.label(),
+                                None => format!("{struct_name}.{field_name}"),
+                            };
+                            let size = field.kind().size(types);
+                            let mut watch = Watch::new(field_address, size, kind, label);
+                            if let Some(window) = PinScope::window(ui.ctx()) {
+                                watch = watch.with_window(&window);
+                            }
+                            watch_menu(ui, state, watch);
+                        }
+                        if is_integer(field_type) {
+                            value_map_menu(ui, struct_name, field_name, value_map.as_ref());
+                        }
+                    });
+                }
                 if widget.is_open(ui) {
+                    // Only struct fields can be followed by a pin path
+                    let path_field = match field_type {
+                        type_crawler::TypeKind::Struct(_) | type_crawler::TypeKind::Class(_) => {
+                            Some(field_name)
+                        }
+                        _ => None,
+                    };
+                    PinScope::enter_field(ui.ctx(), path_field);
                     widget.render_compound(ui, types, state);
+                    PinScope::exit_field(ui.ctx());
                 }
+                WidgetStates::exit(ui.ctx());
             });
         }
+        for hole in holes {
+            render_padding(ui, state, &self.instance, hole.clone());
+        }
     }
 
-    fn render_base_types_and_fields(&self, ui: &mut egui::Ui, types: &'a Types, state: &mut State) {
-        for base_type in self.struct_decl.base_types() {
-            let Some(base_struct) = types.get(base_type).and_then(|ty| ty.as_struct(types)) else {
-                ui.label(format!("Base type '{base_type}' not found"));
+    /// Renders each base type in a collapsible section, or every field in one list with a heading
+    /// per type if base types are flattened.
+    fn render_base_types_and_fields(
+        &self,
+        ui: &mut egui::Ui,
+        types: &'a Types,
+        state: &mut State,
+        size: usize,
+        heading: bool,
+    ) {
+        /// Base types with more fields than this start out collapsed
+        const MAX_OPEN_BASE_FIELDS: usize = 20;
+
+        let flatten = ui.ctx().data_mut(|data| {
+            data.get_temp::<bool>(egui::Id::new("flatten_base_types")).unwrap_or(false)
+        });
+        for base_type in self.base_types(types) {
+            let Some(ty) = base_type.ty else {
+                ui.label(format!("Base type '{}' not found", base_type.name));
+                continue;
+            };
+            let Some(base_struct) = ty.as_struct(types) else {
                 continue;
             };
-            Self {
+            // Bases after the first one don't start at the start of the derived struct
+            let base_widget = StructWidget {
                 struct_decl: base_struct,
-                instance: self.instance.clone(),
-                open_id: self.open_id,
+                instance: self.instance.slice(types, ty, base_type.range.start, None),
+                open: self.open.clone(),
+            };
+            let base_size = ty.size(types);
+            if flatten {
+                base_widget.render_base_types_and_fields(ui, types, state, base_size, true);
+                continue;
             }
-            .render_base_types_and_fields(ui, types, state);
+            let range = &base_type.range;
+            let header = format!("{} [{:#x}..{:#x}]", base_type.name, range.start, range.end);
+            egui::CollapsingHeader::new(egui::RichText::new(header).heading())
+                .id_salt(("base_type", base_type.name))
+                .default_open(base_struct.fields().len() <= MAX_OPEN_BASE_FIELDS)
+                .show(ui, |ui| {
+                    base_widget.render_base_types_and_fields(ui, types, state, base_size, false);
+                });
         }
-        self.render_fields(ui, types, state);
+        self.render_fields(ui, types, state, size, heading);
     }
 }
 
@@ -618,25 +2164,35 @@ impl<'a> DataWidget for StructWidget<'a> {
         let mut open = self.is_open(ui);
         if ui.selectable_label(open, "Open").clicked() {
             open = !open;
-            ui.ctx().data_mut(|data| data.insert_temp(self.open_id, open));
+            self.open.set(ui.ctx(), open);
         }
     }
 
     fn render_compound(&mut self, ui: &mut egui::Ui, types: &Types, state: &mut State) {
         ui.indent("struct_compound", |ui| {
-            self.render_base_types_and_fields(ui, types, state);
+            let size = self.instance.ty().size(types);
+            self.render_base_types_and_fields(ui, types, state, size, true);
         });
     }
 
     fn is_open(&self, ui: &mut egui::Ui) -> bool {
-        ui.ctx().data_mut(|data| data.get_temp::<bool>(self.open_id).unwrap_or(false))
+        self.open.get(ui.ctx(), false)
     }
 }
 
 struct UnionWidget<'a> {
     union_decl: &'a type_crawler::UnionDecl,
     instance: TypeInstance<'a>,
-    open_id: egui::Id,
+    open: WidgetOption,
+    /// Keyed by address so the selection follows the union, not its place in the tree
+    member_id: egui::Id,
+}
+
+/// Which members of a union to show
+#[derive(Clone, Copy, PartialEq)]
+enum UnionMembers {
+    One(usize),
+    All,
 }
 
 impl<'a> UnionWidget<'a> {
@@ -645,8 +2201,44 @@ impl<'a> UnionWidget<'a> {
         union_decl: &'a type_crawler::UnionDecl,
         instance: TypeInstance<'a>,
     ) -> Self {
-        let open_id = ui.make_persistent_id("union_open");
-        Self { union_decl, instance, open_id }
+        let open = WidgetOption::new(ui, "union_open");
+        let member_id = egui::Id::new(("union_member", instance.address(), union_decl.name()));
+        Self { union_decl, instance, open, member_id }
+    }
+
+    fn render_member_selector(&self, ui: &mut egui::Ui) -> UnionMembers {
+        let fields = self.union_decl.fields();
+        let mut members = ui.ctx().data_mut(|data| {
+            data.get_temp::<UnionMembers>(self.member_id).unwrap_or(UnionMembers::One(0))
+        });
+        let member_name = |i: usize| match fields.get(i).and_then(|field| field.name()) {
+            Some(name) => name.to_string(),
+            None => format!("Member {i}"),
+        };
+
+        let previous = members;
+        ui.horizontal(|ui| {
+            let selected_text = match members {
+                UnionMembers::One(i) => member_name(i),
+                UnionMembers::All => "All members".to_string(),
+            };
+            egui::ComboBox::from_id_salt("union_member").selected_text(selected_text).show_ui(
+                ui,
+                |ui| {
+                    for i in 0..fields.len() {
+                        ui.selectable_value(&mut members, UnionMembers::One(i), member_name(i));
+                    }
+                },
+            );
+            let mut all = members == UnionMembers::All;
+            if ui.toggle_value(&mut all, "All members").clicked() {
+                members = if all { UnionMembers::All } else { UnionMembers::One(0) };
+            }
+        });
+        if members != previous {
+            ui.ctx().data_mut(|data| data.insert_temp(self.member_id, members));
+        }
+        members
     }
 }
 
@@ -655,33 +2247,40 @@ impl<'a> DataWidget for UnionWidget<'a> {
         let mut open = self.is_open(ui);
         if ui.selectable_label(open, "Open").clicked() {
             open = !open;
-            ui.ctx().data_mut(|data| data.insert_temp(self.open_id, open));
+            self.open.set(ui.ctx(), open);
         }
     }
 
     fn render_compound(&mut self, ui: &mut egui::Ui, types: &Types, state: &mut State) {
         ui.indent("union_compound", |ui| {
+            let members = self.render_member_selector(ui);
             for (i, field) in self.union_decl.fields().iter().enumerate() {
+                if members != UnionMembers::All && members != UnionMembers::One(i) {
+                    continue;
+                }
                 let bit_field_range = field.bit_field_width().map(|width| 0..width);
                 let field_instance = self.instance.slice(types, field.kind(), 0, bit_field_range);
 
                 ui.push_id(i, |ui| {
+                    let name = field.name().unwrap_or("");
+                    WidgetStates::enter(ui.ctx(), name);
+                    let row = FieldRow::new(ui, types, &field_instance, 0);
                     let mut widget = field_instance.into_data_widget(ui, types);
-                    columns::fixed_columns(ui, COLUMN_WIDTHS, |columns| {
+                    field_columns(ui, name, row, |columns| {
                         ValueBadge::new(types, field.kind()).render(&mut columns[0]);
-                        columns[1].label(field.name().unwrap_or(""));
                         widget.render_value(&mut columns[2], types, state);
                     });
                     if widget.is_open(ui) {
                         widget.render_compound(ui, types, state);
                     }
+                    WidgetStates::exit(ui.ctx());
                 });
             }
         });
     }
 
     fn is_open(&self, ui: &mut egui::Ui) -> bool {
-        ui.ctx().data_mut(|data| data.get_temp::<bool>(self.open_id).unwrap_or(false))
+        self.open.get(ui.ctx(), false)
     }
 }
 
@@ -707,6 +2306,7 @@ impl<'a> ValueBadge<'a> {
             });
         }
     }
+
     fn new(types: &'a Types, kind: &'a type_crawler::TypeKind) -> Self {
         match kind {
             type_crawler::TypeKind::USize { .. } => ValueBadge {
@@ -824,7 +2424,7 @@ impl<'a> ValueBadge<'a> {
                 let (new_text, tooltip) = if text.len() <= 10 {
                     (format!("{text}&").into(), None)
                 } else {
-                    ("pointer".into(), Some(format!("{text}&")))
+                    ("reference".into(), Some(format!("{text}&")))
                 };
                 ValueBadge { text: new_text, tooltip, background, color }
             }
@@ -941,4 +2541,107 @@ impl<'a> ValueBadge<'a> {
         };
         ValueBadge { text, tooltip, background: "#ff8c00", color: "#ffffff" }
     }
+
+    fn new_padding(size: usize) -> Self {
+        ValueBadge {
+            text: "pad".into(),
+            tooltip: Some(format!("{size:#x} bytes not covered by any field")),
+            background: "#555555",
+            color: "#ffffff",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dsv_core::test_support::parse_header;
+
+    use super::*;
+
+    const FIXTURE_HEADER: &str = "
+        struct Position {
+            int x;
+            int y;
+        };
+
+        struct Flags {
+            unsigned short flags;
+        };
+
+        class Player : public Position, public Flags {
+            unsigned char health;
+        };
+    ";
+
+    fn fixture_types() -> Types {
+        parse_header("fixture.hpp", FIXTURE_HEADER)
+    }
+
+    #[test]
+    fn test_second_base_offset() {
+        let types = fixture_types();
+        let player_type = types.get("Player").expect("fixture should define Player");
+        let player_decl = player_type.as_struct(&types).unwrap();
+        let bases = base_types(player_decl, &types);
+        let ranges: Vec<_> = bases.iter().map(|base| base.range.clone()).collect();
+        assert_eq!(ranges, [0..8, 8..10]);
+
+        let mut data = Vec::new();
+        data.extend(1i32.to_le_bytes());
+        data.extend(2i32.to_le_bytes());
+        data.extend(0xabcd_u16.to_le_bytes());
+        data.extend([100, 0]);
+        let player = TypeInstance::new(TypeInstanceOptions {
+            ty: player_type,
+            address: 0x02000000,
+            bit_field_range: None,
+            data: Cow::Owned(data),
+        });
+
+        // Fields of each base are read from where that base is in the derived object
+        let position = player.slice(&types, bases[0].ty.unwrap(), bases[0].range.start, None);
+        assert_eq!(position.read_int_field::<i32>(&types, "y"), Some(2));
+        let flags = player.slice(&types, bases[1].ty.unwrap(), bases[1].range.start, None);
+        assert_eq!(flags.address(), 0x02000008);
+        assert_eq!(flags.read_int_field::<u16>(&types, "flags"), Some(0xabcd));
+    }
+
+    #[test]
+    fn test_enum_input_range() {
+        let types = parse_header(
+            "enums.hpp",
+            "
+            enum Small : unsigned char { SMALL_A, SMALL_B };
+            enum Signed : signed char { SIGNED_NEG = -1, SIGNED_POS = 1 };
+            ",
+        );
+        let enum_decl = |name: &str| match types.get(name) {
+            Some(type_crawler::TypeKind::Enum(enum_decl)) => enum_decl,
+            _ => panic!("fixture should define {name}"),
+        };
+
+        let small = enum_decl("Small");
+        assert_eq!(parse_enum_input(small, "255"), Some(vec![0xff]));
+        assert_eq!(parse_enum_input(small, "0x2a"), Some(vec![0x2a]));
+        assert_eq!(parse_enum_input(small, "300"), None);
+        assert_eq!(parse_enum_input(small, "0x100"), None);
+        assert_eq!(parse_enum_input(small, "-1"), None);
+
+        let signed = enum_decl("Signed");
+        assert_eq!(parse_enum_input(signed, "-128"), Some(vec![0x80]));
+        assert_eq!(parse_enum_input(signed, "-129"), None);
+        assert_eq!(parse_enum_input(signed, "128"), None);
+    }
+
+    #[test]
+    fn test_bit_names() {
+        let entries = [
+            (0, "None".to_string()),
+            (1, "Visible".to_string()),
+            (0x20, "Frozen".to_string()),
+            (0x21, "VisibleFrozen".to_string()),
+        ];
+        let names = bit_names(&entries);
+        assert_eq!(names.into_iter().collect::<Vec<_>>(), [(0, "Visible"), (5, "Frozen")]);
+    }
 }
@@ -0,0 +1,231 @@
+use std::{borrow::Cow, collections::BTreeMap};
+
+use eframe::egui;
+use serde_json::Value;
+
+use crate::{
+    ui::type_decl::resolve_type,
+    util::{
+        export::to_json,
+        read::{TypeInstance, TypeInstanceOptions},
+    },
+};
+
+/// Snapshots of the bytes of objects, saved per game and keyed by type name and label. A window
+/// compares against one while rendering, see [`Self::begin`], and fields which differ from it are
+/// highlighted. Kept in egui memory while rendering like [`super::value_map::ValueMaps`].
+#[derive(Clone, Default)]
+pub struct Baselines {
+    baselines: BTreeMap<(String, String), Vec<u8>>,
+    changed: bool,
+    /// Baseline of the window being rendered and the address of its object
+    active: Option<(u32, (String, String))>,
+}
+
+/// Baseline chosen in the header of a window, see [`Baselines::selector`].
+pub struct BaselineSelection {
+    /// Label of the baseline to compare against
+    pub label: Option<String>,
+    /// Set if the current bytes should be saved under this label
+    pub snapshot: Option<String>,
+}
+
+impl Baselines {
+    const CONFIG_KEY: &str = "baselines";
+
+    fn id() -> egui::Id {
+        egui::Id::new("baselines")
+    }
+
+    fn update<R>(ctx: &egui::Context, f: impl FnOnce(&mut Baselines) -> R) -> R {
+        ctx.data_mut(|data| f(data.get_temp_mut_or_default::<Baselines>(Self::id())))
+    }
+
+    /// Loads baselines from the game config, where they are hex strings under
+    /// `baselines.<type name>.<label>`.
+    pub fn load(ctx: &egui::Context, game_config: &toml::Table) {
+        let table = game_config.get(Self::CONFIG_KEY).and_then(|v| v.as_table());
+        let baselines = table
+            .into_iter()
+            .flatten()
+            .filter_map(|(type_name, labels)| Some((type_name, labels.as_table()?)))
+            .flat_map(|(type_name, labels)| {
+                labels.iter().filter_map(move |(label, hex)| {
+                    let data = parse_hex(hex.as_str()?)?;
+                    Some(((type_name.clone(), label.clone()), data))
+                })
+            })
+            .collect();
+        Self::update(ctx, |state| {
+            state.baselines = baselines;
+            state.changed = false;
+        });
+    }
+
+    /// Writes baselines changed since [`Self::load`] to the game config. Returns true if the
+    /// config was changed.
+    pub fn store(ctx: &egui::Context, game_config: &mut toml::Table) -> bool {
+        let baselines = Self::update(ctx, |state| state.changed.then(|| state.baselines.clone()));
+        let Some(baselines) = baselines else {
+            return false;
+        };
+        let mut table = toml::Table::new();
+        for ((type_name, label), data) in baselines {
+            let labels = table
+                .entry(type_name)
+                .or_insert_with(|| toml::Table::new().into())
+                .as_table_mut()
+                .expect("baselines of a type are a table");
+            let hex: String = data.iter().map(|byte| format!("{byte:02x}")).collect();
+            labels.insert(label, hex.into());
+        }
+        game_config.insert(Self::CONFIG_KEY.into(), table.into());
+        true
+    }
+
+    pub fn save(ctx: &egui::Context, type_name: &str, label: &str, instance: &TypeInstance) {
+        let data = instance.data().into_owned();
+        Self::update(ctx, |state| {
+            state.baselines.insert((type_name.to_string(), label.to_string()), data);
+            state.changed = true;
+        });
+    }
+
+    fn remove(ctx: &egui::Context, type_name: &str, label: &str) {
+        Self::update(ctx, |state| {
+            state.baselines.remove(&(type_name.to_string(), label.to_string()));
+            state.changed = true;
+        });
+    }
+
+    fn labels(ctx: &egui::Context, type_name: &str) -> Vec<String> {
+        Self::update(ctx, |state| {
+            state
+                .baselines
+                .keys()
+                .filter(|(name, _)| name == type_name)
+                .map(|(_, label)| label.clone())
+                .collect()
+        })
+    }
+
+    /// Starts comparing the fields of the object at `address` against the baseline `label`.
+    pub fn begin(ctx: &egui::Context, type_name: &str, label: Option<&str>, address: u32) {
+        Self::update(ctx, |state| {
+            state.active = label.map(|label| (address, (type_name.to_string(), label.to_string())));
+        });
+    }
+
+    pub fn end(ctx: &egui::Context) {
+        Self::update(ctx, |state| state.active = None);
+    }
+
+    /// Returns the baseline and current values of a field if they differ. Only fields without
+    /// other fields or elements are compared, so that a change is shown once.
+    pub fn diff(
+        ctx: &egui::Context,
+        types: &type_crawler::Types,
+        instance: &TypeInstance,
+    ) -> Option<(String, String)> {
+        if instance.is_truncated() {
+            return None;
+        }
+        let compound = matches!(
+            resolve_type(types, instance.ty()),
+            type_crawler::TypeKind::Struct(_)
+                | type_crawler::TypeKind::Class(_)
+                | type_crawler::TypeKind::Union(_)
+                | type_crawler::TypeKind::Array { .. }
+        );
+        if compound {
+            return None;
+        }
+        Self::update(ctx, |state| {
+            let (address, key) = state.active.as_ref()?;
+            let data = state.baselines.get(key)?;
+            let offset = instance.address().checked_sub(*address)? as usize;
+            let size = match instance.bit_field_range() {
+                Some(range) => range.end.div_ceil(8) as usize,
+                None => instance.ty().size(types),
+            };
+            let baseline = TypeInstance::new(TypeInstanceOptions {
+                ty: instance.ty(),
+                address: instance.address(),
+                bit_field_range: instance.bit_field_range().cloned(),
+                data: Cow::Borrowed(data.get(offset..offset + size)?),
+            });
+            if baseline.data() == instance.data() {
+                return None;
+            }
+            Some((value_text(to_json(types, &baseline)), value_text(to_json(types, instance))))
+        })
+    }
+
+    /// Lets the user snapshot the object of a window and choose a baseline to compare against.
+    /// The choice is kept per window in egui memory.
+    pub fn selector(ui: &mut egui::Ui, window_id: egui::Id, type_name: &str) -> BaselineSelection {
+        let selected_id = window_id.with("baseline");
+        let label_id = window_id.with("baseline_label");
+        let labels = Self::labels(ui.ctx(), type_name);
+        let mut selected = ui
+            .ctx()
+            .data_mut(|data| data.get_temp::<Option<String>>(selected_id))
+            .flatten()
+            .filter(|label| labels.contains(label));
+        let mut label_text =
+            ui.ctx().data_mut(|data| data.get_temp::<String>(label_id)).unwrap_or_default();
+
+        let mut snapshot = None;
+        ui.horizontal(|ui| {
+            ui.label("Baseline");
+            egui::ComboBox::from_id_salt(selected_id)
+                .selected_text(selected.as_deref().unwrap_or("None"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut selected, None, "None");
+                    for label in labels {
+                        ui.selectable_value(&mut selected, Some(label.clone()), label);
+                    }
+                });
+            if let Some(label) = &selected
+                && ui.small_button("Forget").clicked()
+            {
+                Self::remove(ui.ctx(), type_name, label);
+                selected = None;
+            }
+            ui.separator();
+            egui::TextEdit::singleline(&mut label_text)
+                .desired_width(80.0)
+                .hint_text("Label")
+                .show(ui);
+            let label = label_text.trim();
+            if ui
+                .add_enabled(!label.is_empty(), egui::Button::new("Snapshot").small())
+                .on_hover_text("Save the current bytes as a baseline")
+                .clicked()
+            {
+                snapshot = Some(label.to_string());
+                selected = Some(label.to_string());
+            }
+        });
+        ui.ctx().data_mut(|data| {
+            data.insert_temp(selected_id, selected.clone());
+            data.insert_temp(label_id, label_text);
+        });
+        BaselineSelection { label: selected, snapshot }
+    }
+}
+
+/// Shows strings like addresses and enum constants without quotes.
+fn value_text(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        value => value.to_string(),
+    }
+}
+
+fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len()).step_by(2).map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok()).collect()
+}
@@ -0,0 +1,462 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use dsv_core::{state::State, typed::layout::base_types};
+use serde_json::{Map, Value, json};
+use type_crawler::Types;
+
+use crate::{
+    ui::type_decl::resolve_type,
+    util::read::{ReadIntValue, TypeInstance},
+};
+
+/// Converts the current values of an instance to JSON, walking its type the same way as the data
+/// widgets. Structs and unions become objects of their fields, with base types nested under their
+/// names. Arrays become lists, enums the names of their constants and pointers hex addresses.
+/// Values which can't be shown, like `void`, become `null`.
+pub fn to_json(types: &Types, instance: &TypeInstance) -> Value {
+    match instance.ty() {
+        type_crawler::TypeKind::Named(name) if name == "q20" => {
+            let value = instance.data_i64() as i32;
+            float_value(value as f64 / 4096.0)
+        }
+        type_crawler::TypeKind::Typedef(_) | type_crawler::TypeKind::Named(_) => {
+            let ty = resolve_type(types, instance.ty());
+            if std::ptr::eq(ty, instance.ty()) {
+                // Type not found
+                return Value::Null;
+            }
+            to_json(types, &instance.borrowed().with_type(ty))
+        }
+        type_crawler::TypeKind::Struct(struct_decl)
+        | type_crawler::TypeKind::Class(struct_decl) => {
+            let mut object = Map::new();
+            for base_type in base_types(struct_decl, types) {
+                let Some(ty) = base_type.ty else {
+                    continue;
+                };
+                let base = instance.slice(types, ty, base_type.range.start, None);
+                object.insert(base_type.name.to_string(), to_json(types, &base));
+            }
+            for field in struct_decl.fields() {
+                let offset = field.offset_bytes();
+                let bit_field_range = field.bit_field_width().map(|width| {
+                    let start = (field.offset_bits() - offset * 8) as u8;
+                    start..start + width
+                });
+                let field_instance = instance.slice(types, field.kind(), offset, bit_field_range);
+                let name = match field.name() {
+                    Some(name) => name.to_string(),
+                    None => format!("unnamed_{offset:#x}"),
+                };
+                object.insert(name, to_json(types, &field_instance));
+            }
+            Value::Object(object)
+        }
+        type_crawler::TypeKind::Union(union_decl) => {
+            let mut object = Map::new();
+            for (i, field) in union_decl.fields().iter().enumerate() {
+                let bit_field_range = field.bit_field_width().map(|width| 0..width);
+                let field_instance = instance.slice(types, field.kind(), 0, bit_field_range);
+                let name = match field.name() {
+                    Some(name) => name.to_string(),
+                    None => format!("unnamed_{i}"),
+                };
+                object.insert(name, to_json(types, &field_instance));
+            }
+            Value::Object(object)
+        }
+        type_crawler::TypeKind::Array { element_type, size: Some(size) } => {
+            let stride = element_type.stride(types);
+            let elements = (0..*size)
+                .map(|i| to_json(types, &instance.slice(types, element_type, i * stride, None)))
+                .collect();
+            Value::Array(elements)
+        }
+        type_crawler::TypeKind::Array { size: None, .. } => address_value(instance.address()),
+        type_crawler::TypeKind::Pointer { .. } | type_crawler::TypeKind::Reference { .. } => {
+            address_value(instance.data_i64() as u32)
+        }
+        type_crawler::TypeKind::Enum(enum_decl) => {
+            let Some(value) = instance.as_int::<i64>(types) else {
+                return Value::Null;
+            };
+            match enum_decl.get_by_value(value) {
+                Some(constant) => constant.name().into(),
+                None => value.into(),
+            }
+        }
+        type_crawler::TypeKind::Bool => Value::Bool(instance.data_i64() != 0),
+        type_crawler::TypeKind::F32 => {
+            let bytes = instance.data()[..].try_into().unwrap_or([0; 4]);
+            float_value(f32::from_le_bytes(bytes) as f64)
+        }
+        type_crawler::TypeKind::F64 => {
+            let bytes = instance.data()[..].try_into().unwrap_or([0; 8]);
+            float_value(f64::from_le_bytes(bytes))
+        }
+        ty => ty.read_int_value(types, instance).map_or(Value::Null, Value::from),
+    }
+}
+
+/// NaN and infinities have no JSON number, so they become `null`.
+fn float_value(value: f64) -> Value {
+    serde_json::Number::from_f64(value).map_or(Value::Null, Value::Number)
+}
+
+fn address_value(address: u32) -> Value {
+    format!("{address:#010x}").into()
+}
+
+/// Asks where to save the values of `instance` and writes them there as JSON, along with
+/// `type_name` so that [`import_json`] can check it. `name` is the suggested file name, without
+/// extension.
+pub fn export_json(types: &Types, instance: &TypeInstance, type_name: &str, name: &str) {
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter("JSON", &["json"])
+        .set_file_name(format!("{name}.json"))
+        .save_file()
+    else {
+        return;
+    };
+    let file = json!({ "type": type_name, "value": to_json(types, instance) });
+    if let Err(err) = write_json(&path, &file) {
+        log::error!("Failed to export {}: {err}", path.display());
+    }
+}
+
+fn write_json(path: &Path, value: &Value) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+/// Asks for a file from [`export_json`] and writes its values back to `instance`. Nothing is
+/// written if the file was exported from another type than `type_name`.
+pub fn import_json(types: &Types, instance: &TypeInstance, type_name: &str, state: &mut State) {
+    let Some(path) = rfd::FileDialog::new().add_filter("JSON", &["json"]).pick_file() else {
+        return;
+    };
+    if let Err(err) = import_file(types, instance, type_name, state, &path) {
+        log::error!("Failed to import {}: {err}", path.display());
+    }
+}
+
+fn import_file(
+    types: &Types,
+    instance: &TypeInstance,
+    type_name: &str,
+    state: &mut State,
+    path: &Path,
+) -> Result<()> {
+    let text = std::fs::read_to_string(path)?;
+    let file: Value = serde_json::from_str(&text)?;
+    let file_type = file.get("type").and_then(Value::as_str).context("No type name in file")?;
+    if file_type != type_name {
+        bail!("File has values of {file_type}, not {type_name}");
+    }
+    let value = file.get("value").context("No values in file")?;
+
+    let mut import = JsonImport::new(instance);
+    import.apply(types, instance, value, type_name);
+    for error in &import.errors {
+        log::warn!("Skipped {error}");
+    }
+    let mut written = 0;
+    for (offset, data) in import.changes() {
+        written += data.len();
+        state.request_write(instance.address() + offset as u32, data);
+    }
+    log::info!("Imported {} ({written} bytes changed)", path.display());
+    Ok(())
+}
+
+/// Writes JSON values into a copy of an instance's data, so that bit-fields sharing a byte and
+/// overlapping union members are written in one go.
+pub struct JsonImport {
+    base: u32,
+    original: Vec<u8>,
+    data: Vec<u8>,
+    /// Values which didn't fit their field, by path
+    pub errors: Vec<String>,
+}
+
+impl JsonImport {
+    pub fn new(instance: &TypeInstance) -> Self {
+        let data = instance.data().into_owned();
+        Self {
+            base: instance.address(),
+            original: data.clone(),
+            data,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Writes `value` to the part of the data where `instance` is, the inverse of [`to_json`].
+    /// Fields which are missing from `value` are left as they are.
+    pub fn apply(&mut self, types: &Types, instance: &TypeInstance, value: &Value, path: &str) {
+        if instance.is_truncated() {
+            self.errors.push(format!("{path}: past the end of the data"));
+            return;
+        }
+        match instance.ty() {
+            type_crawler::TypeKind::Named(name) if name == "q20" => match value.as_f64() {
+                Some(value) => self.write_int(instance, path, (value * 4096.0).round() as i64),
+                None => self.mismatch(path, "a number", value),
+            },
+            type_crawler::TypeKind::Typedef(_) | type_crawler::TypeKind::Named(_) => {
+                let ty = resolve_type(types, instance.ty());
+                if std::ptr::eq(ty, instance.ty()) {
+                    self.errors.push(format!("{path}: type not found"));
+                    return;
+                }
+                self.apply(types, &instance.borrowed().with_type(ty), value, path)
+            }
+            type_crawler::TypeKind::Struct(struct_decl)
+            | type_crawler::TypeKind::Class(struct_decl) => {
+                let Some(object) = value.as_object() else {
+                    return self.mismatch(path, "an object", value);
+                };
+                for base_type in base_types(struct_decl, types) {
+                    let (Some(ty), Some(value)) = (base_type.ty, object.get(base_type.name)) else {
+                        continue;
+                    };
+                    let base = instance.slice(types, ty, base_type.range.start, None);
+                    self.apply(types, &base, value, &format!("{path}.{}", base_type.name));
+                }
+                for field in struct_decl.fields() {
+                    let offset = field.offset_bytes();
+                    let name = match field.name() {
+                        Some(name) => name.to_string(),
+                        None => format!("unnamed_{offset:#x}"),
+                    };
+                    let Some(value) = object.get(&name) else {
+                        continue;
+                    };
+                    let bit_field_range = field.bit_field_width().map(|width| {
+                        let start = (field.offset_bits() - offset * 8) as u8;
+                        start..start + width
+                    });
+                    let field_instance =
+                        instance.slice(types, field.kind(), offset, bit_field_range);
+                    self.apply(types, &field_instance, value, &format!("{path}.{name}"));
+                }
+            }
+            type_crawler::TypeKind::Union(union_decl) => {
+                let Some(object) = value.as_object() else {
+                    return self.mismatch(path, "an object", value);
+                };
+                for (i, field) in union_decl.fields().iter().enumerate() {
+                    let name = match field.name() {
+                        Some(name) => name.to_string(),
+                        None => format!("unnamed_{i}"),
+                    };
+                    let Some(value) = object.get(&name) else {
+                        continue;
+                    };
+                    let bit_field_range = field.bit_field_width().map(|width| 0..width);
+                    let field_instance = instance.slice(types, field.kind(), 0, bit_field_range);
+                    self.apply(types, &field_instance, value, &format!("{path}.{name}"));
+                }
+            }
+            type_crawler::TypeKind::Array { element_type, size: Some(size) } => {
+                let Some(elements) = value.as_array() else {
+                    return self.mismatch(path, "a list", value);
+                };
+                if elements.len() != *size {
+                    self.errors.push(format!(
+                        "{path}: {} elements in file, {size} in type",
+                        elements.len()
+                    ));
+                }
+                let stride = element_type.stride(types);
+                for (i, value) in elements.iter().take(*size).enumerate() {
+                    let element = instance.slice(types, element_type, i * stride, None);
+                    self.apply(types, &element, value, &format!("{path}[{i}]"));
+                }
+            }
+            // Only the address of the array was exported
+            type_crawler::TypeKind::Array { size: None, .. } => {}
+            type_crawler::TypeKind::Pointer { .. } | type_crawler::TypeKind::Reference { .. } => {
+                let address = value.as_str().and_then(|text| {
+                    u32::from_str_radix(text.strip_prefix("0x").unwrap_or(text), 16).ok()
+                });
+                match address {
+                    Some(address) => self.write_int(instance, path, address as i64),
+                    None => self.mismatch(path, "a hex address", value),
+                }
+            }
+            type_crawler::TypeKind::Enum(enum_decl) => {
+                let constant = value.as_str().map(|name| {
+                    enum_decl.constants().iter().find(|constant| constant.name() == name)
+                });
+                match (constant, value.as_i64()) {
+                    (Some(Some(constant)), _) => self.write_int(instance, path, constant.value()),
+                    (Some(None), _) => {
+                        self.errors.push(format!("{path}: no constant called {value}"));
+                    }
+                    (None, Some(value)) => self.write_int(instance, path, value),
+                    (None, None) => self.mismatch(path, "a constant name or number", value),
+                }
+            }
+            type_crawler::TypeKind::Bool => match value.as_bool() {
+                Some(value) => self.write_int(instance, path, value as i64),
+                None => self.mismatch(path, "a bool", value),
+            },
+            type_crawler::TypeKind::F32 => match value.as_f64() {
+                Some(value) => self.write_bytes(instance, &(value as f32).to_le_bytes()),
+                // NaN was exported as null
+                None if value.is_null() => {}
+                None => self.mismatch(path, "a number", value),
+            },
+            type_crawler::TypeKind::F64 => match value.as_f64() {
+                Some(value) => self.write_bytes(instance, &value.to_le_bytes()),
+                None if value.is_null() => {}
+                None => self.mismatch(path, "a number", value),
+            },
+            ty => {
+                if ty.read_int_value(types, instance).is_none() {
+                    // Not exported either
+                    return;
+                }
+                let int = value.as_i64().or_else(|| value.as_u64().map(|value| value as i64));
+                match int {
+                    Some(int) => self.write_int(instance, path, int),
+                    None => self.mismatch(path, "an integer", value),
+                }
+            }
+        }
+    }
+
+    fn mismatch(&mut self, path: &str, expected: &str, value: &Value) {
+        self.errors.push(format!("{path}: expected {expected}, found {value}"));
+    }
+
+    /// Writes the low bytes of `value`, if it fits in the field as a signed or unsigned integer.
+    fn write_int(&mut self, instance: &TypeInstance, path: &str, value: i64) {
+        let bits = match instance.bit_field_range() {
+            Some(range) => range.len() as u32,
+            None => instance.data().len() as u32 * 8,
+        };
+        let fits =
+            bits >= 64 || (bits > 0 && (-(1i64 << (bits - 1))..1i64 << bits).contains(&value));
+        if !fits {
+            self.errors.push(format!("{path}: {value} does not fit in {bits} bits"));
+            return;
+        }
+        self.write_bytes(instance, &value.to_le_bytes());
+    }
+
+    fn write_bytes(&mut self, instance: &TypeInstance, bytes: &[u8]) {
+        let offset = (instance.address() - self.base) as usize;
+        match instance.bit_field_range() {
+            Some(range) if !range.is_empty() => {
+                // Bit-fields start in the first byte and are at most 64 bits wide
+                let end = (offset + range.end.div_ceil(8) as usize).min(self.data.len());
+                let mut word = [0; 8];
+                word[..end - offset].copy_from_slice(&self.data[offset..end]);
+                let mut word = u64::from_le_bytes(word);
+                let mut value = [0; 8];
+                value[..bytes.len().min(8)].copy_from_slice(&bytes[..bytes.len().min(8)]);
+                let mask = (u64::MAX >> (64 - range.len())) << range.start;
+                word = word & !mask | (u64::from_le_bytes(value) << range.start) & mask;
+                self.data[offset..end].copy_from_slice(&word.to_le_bytes()[..end - offset]);
+            }
+            Some(_) => {}
+            None => {
+                let size = instance.data().len().min(bytes.len());
+                self.data[offset..offset + size].copy_from_slice(&bytes[..size]);
+            }
+        }
+    }
+
+    /// Returns the runs of bytes which differ from the original data, by offset.
+    pub fn changes(&self) -> Vec<(usize, Vec<u8>)> {
+        let mut changes: Vec<(usize, Vec<u8>)> = Vec::new();
+        for (offset, (&old, &new)) in self.original.iter().zip(&self.data).enumerate() {
+            if old == new {
+                continue;
+            }
+            match changes.last_mut() {
+                Some((start, run)) if *start + run.len() == offset => run.push(new),
+                _ => changes.push((offset, vec![new])),
+            }
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::util::read::TypeInstanceOptions;
+
+    fn instance<'a>(ty: &'a type_crawler::TypeKind, data: &[u8]) -> TypeInstance<'a> {
+        TypeInstance::new(TypeInstanceOptions {
+            ty,
+            address: 0x02000000,
+            bit_field_range: None,
+            data: Cow::Owned(data.to_vec()),
+        })
+    }
+
+    #[test]
+    fn test_to_json_scalars() {
+        let types = Types::new();
+        let s16 = type_crawler::TypeKind::S16;
+        assert_eq!(to_json(&types, &instance(&s16, &[0xfe, 0xff])), json!(-2));
+        let f32 = type_crawler::TypeKind::F32;
+        assert_eq!(to_json(&types, &instance(&f32, &1.5f32.to_le_bytes())), json!(1.5));
+        assert_eq!(to_json(&types, &instance(&f32, &f32::NAN.to_le_bytes())), Value::Null);
+        let q20 = type_crawler::TypeKind::Named("q20".into());
+        assert_eq!(to_json(&types, &instance(&q20, &0x1800i32.to_le_bytes())), json!(1.5));
+        let bool = type_crawler::TypeKind::Bool;
+        assert_eq!(to_json(&types, &instance(&bool, &[1])), json!(true));
+    }
+
+    #[test]
+    fn test_to_json_array_and_pointer() {
+        let types = Types::new();
+        let array = type_crawler::TypeKind::Array {
+            element_type: Box::new(type_crawler::TypeKind::U8),
+            size: Some(3),
+        };
+        assert_eq!(to_json(&types, &instance(&array, &[1, 2, 3])), json!([1, 2, 3]));
+        let pointer = type_crawler::TypeKind::Pointer {
+            pointee_type: Box::new(type_crawler::TypeKind::Void),
+            size: 4,
+        };
+        let data = 0x0210abcdu32.to_le_bytes();
+        assert_eq!(to_json(&types, &instance(&pointer, &data)), json!("0x0210abcd"));
+    }
+
+    #[test]
+    fn test_import_array() {
+        let types = Types::new();
+        let array = type_crawler::TypeKind::Array {
+            element_type: Box::new(type_crawler::TypeKind::S16),
+            size: Some(4),
+        };
+        let target = instance(&array, &[1, 0, 2, 0, 3, 0, 4, 0]);
+        let mut import = JsonImport::new(&target);
+        import.apply(&types, &target, &json!([1, -2, 3, "x", 5]), "array");
+        assert_eq!(import.changes(), [(2, vec![0xfe, 0xff])]);
+        assert_eq!(import.errors.len(), 2, "{:?}", import.errors);
+    }
+
+    #[test]
+    fn test_import_bit_field() {
+        let types = Types::new();
+        let u8 = type_crawler::TypeKind::U8;
+        let byte = instance(&u8, &[0b1110_0011]);
+        let target = byte.slice(&types, &u8, 0, Some(2..5));
+        let mut import = JsonImport::new(&byte);
+        import.apply(&types, &target, &json!(0b101), "flags");
+        assert_eq!(import.changes(), [(0, vec![0b1111_0111])]);
+        import.apply(&types, &target, &json!(8), "flags");
+        assert_eq!(import.errors, ["flags: 8 does not fit in 3 bits"]);
+    }
+}
@@ -0,0 +1,32 @@
+/// Matches the characters of `query` in order anywhere in `text`, ignoring case. Returns a score
+/// which is higher for consecutive matches and matches at the start of words, and the char indices
+/// of the matched characters in `text`.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(i32, Vec<usize>)> {
+    let mut query = query.chars().filter(|c| !c.is_whitespace()).peekable();
+    let mut indices = Vec::new();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    for (index, c) in text.chars().enumerate() {
+        let Some(&wanted) = query.peek() else {
+            break;
+        };
+        if c.to_lowercase().eq(wanted.to_lowercase()) {
+            score += 1;
+            if indices.last().is_some_and(|&last| last + 1 == index) {
+                score += 3;
+            }
+            if previous.is_none_or(|previous| !previous.is_alphanumeric()) {
+                score += 2;
+            }
+            indices.push(index);
+            query.next();
+        }
+        previous = Some(c);
+    }
+    if query.peek().is_some() {
+        return None;
+    }
+    // Prefer matches which start early
+    score -= indices.first().copied().unwrap_or(0).min(10) as i32;
+    Some((score, indices))
+}
@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+use eframe::egui;
+
+/// Action which opens the command palette. It isn't listed in the palette itself.
+pub const COMMAND_PALETTE: &str = "command_palette";
+
+/// Shortcuts of the actions which have one by default, by action ID. These are the IDs of
+/// [`crate::windows::palette::PaletteAction`]s, so any palette action can be bound.
+const DEFAULT_BINDINGS: &[(&str, &str)] = &[
+    (COMMAND_PALETTE, "Ctrl+P"),
+    ("Actors", "Ctrl+Shift+A"),
+    ("pause", "F5"),
+    ("safe_mode", "Ctrl+Shift+S"),
+];
+
+/// Returns the shortcut of every bound action. `overrides` comes from the config, where an empty
+/// string unbinds an action which has a default shortcut.
+pub fn bindings(overrides: &BTreeMap<String, String>) -> BTreeMap<String, egui::KeyboardShortcut> {
+    let mut bindings: BTreeMap<String, egui::KeyboardShortcut> = DEFAULT_BINDINGS
+        .iter()
+        .filter_map(|&(id, text)| Some((id.to_string(), parse_shortcut(text)?)))
+        .collect();
+    for (id, text) in overrides {
+        match parse_shortcut(text) {
+            Some(shortcut) => bindings.insert(id.clone(), shortcut),
+            None => bindings.remove(id),
+        };
+    }
+    bindings
+}
+
+/// Binds `id` to `shortcut` in the config overrides, or unbinds it if `None`. Overrides which
+/// match the default are left out.
+pub fn set_binding(
+    overrides: &mut BTreeMap<String, String>,
+    id: &str,
+    shortcut: Option<&egui::KeyboardShortcut>,
+) {
+    let text = shortcut.map(format_shortcut).unwrap_or_default();
+    let default = DEFAULT_BINDINGS.iter().find(|&&(default_id, _)| default_id == id);
+    match default {
+        Some(&(_, default_text)) if default_text == text => overrides.remove(id),
+        None if text.is_empty() => overrides.remove(id),
+        _ => overrides.insert(id.to_string(), text),
+    };
+}
+
+/// Returns the IDs of the actions which share a shortcut with another action.
+pub fn conflicts(bindings: &BTreeMap<String, egui::KeyboardShortcut>) -> Vec<String> {
+    bindings
+        .iter()
+        .filter(|&(id, shortcut)| bindings.iter().any(|(other, s)| other != id && s == shortcut))
+        .map(|(id, _)| id.clone())
+        .collect()
+}
+
+/// Parses shortcuts like `Ctrl+Shift+A` or `F5`. `Ctrl` is the command key on macOS.
+pub fn parse_shortcut(text: &str) -> Option<egui::KeyboardShortcut> {
+    let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+    let key = egui::Key::from_name(parts.pop()?)?;
+    let mut modifiers = egui::Modifiers::NONE;
+    for part in parts {
+        modifiers |= match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "cmd" => egui::Modifiers::COMMAND,
+            "shift" => egui::Modifiers::SHIFT,
+            "alt" => egui::Modifiers::ALT,
+            _ => return None,
+        };
+    }
+    Some(egui::KeyboardShortcut::new(modifiers, key))
+}
+
+pub fn format_shortcut(shortcut: &egui::KeyboardShortcut) -> String {
+    let modifiers = shortcut.modifiers;
+    let mut parts = Vec::new();
+    if modifiers.command || modifiers.ctrl {
+        parts.push("Ctrl");
+    }
+    if modifiers.shift {
+        parts.push("Shift");
+    }
+    if modifiers.alt {
+        parts.push("Alt");
+    }
+    parts.push(shortcut.logical_key.name());
+    parts.join("+")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shortcut() {
+        let shortcut = parse_shortcut("Ctrl+Shift+A").unwrap();
+        assert_eq!(shortcut.modifiers, egui::Modifiers::COMMAND | egui::Modifiers::SHIFT);
+        assert_eq!(shortcut.logical_key, egui::Key::A);
+        assert_eq!(format_shortcut(&shortcut), "Ctrl+Shift+A");
+        assert_eq!(format_shortcut(&parse_shortcut("F5").unwrap()), "F5");
+        assert!(parse_shortcut("").is_none());
+        assert!(parse_shortcut("Hyper+A").is_none());
+    }
+
+    #[test]
+    fn test_bindings() {
+        let mut overrides = BTreeMap::new();
+        set_binding(&mut overrides, "safe_mode", None);
+        set_binding(&mut overrides, "Dashboard", parse_shortcut("F5").as_ref());
+        // Rebinding to the default drops the override
+        set_binding(&mut overrides, COMMAND_PALETTE, parse_shortcut("Ctrl+P").as_ref());
+        assert_eq!(overrides.len(), 2);
+
+        let bindings = bindings(&overrides);
+        assert!(!bindings.contains_key("safe_mode"));
+        assert_eq!(conflicts(&bindings), ["Dashboard", "pause"]);
+    }
+}
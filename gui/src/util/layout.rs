@@ -0,0 +1 @@
+pub use dsv_core::typed::layout::NaturalAlignment;
@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+
+use eframe::egui;
+
+/// Number of elements shown behind pointer fields, keyed by `Struct.field`. Kept in egui memory
+/// while rendering like [`super::value_map::ValueMaps`].
+#[derive(Clone)]
+pub struct ListLengths {
+    lengths: BTreeMap<String, usize>,
+    /// Length of pointers which have no saved length
+    default_length: usize,
+    changed: bool,
+}
+
+impl Default for ListLengths {
+    fn default() -> Self {
+        Self { lengths: BTreeMap::new(), default_length: 1, changed: false }
+    }
+}
+
+impl ListLengths {
+    const CONFIG_KEY: &str = "list_lengths";
+
+    fn id() -> egui::Id {
+        egui::Id::new("list_lengths")
+    }
+
+    pub fn key(struct_name: &str, field_name: &str) -> String {
+        format!("{struct_name}.{field_name}")
+    }
+
+    pub fn load(ctx: &egui::Context, game_config: &toml::Table, default_length: usize) {
+        let lengths = game_config
+            .get(Self::CONFIG_KEY)
+            .and_then(|v| v.as_table())
+            .map(|table| {
+                table
+                    .iter()
+                    .filter_map(|(key, value)| {
+                        let length = usize::try_from(value.as_integer()?).ok()?;
+                        Some((key.clone(), length))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let list_lengths = ListLengths { lengths, default_length, changed: false };
+        ctx.data_mut(|data| data.insert_temp(Self::id(), list_lengths));
+    }
+
+    /// Writes lengths edited since [`Self::load`] to the game config. Returns true if the config
+    /// was changed.
+    pub fn store(ctx: &egui::Context, game_config: &mut toml::Table) -> bool {
+        let list_lengths = ctx.data_mut(|data| data.get_temp::<ListLengths>(Self::id()));
+        let Some(list_lengths) = list_lengths.filter(|list_lengths| list_lengths.changed) else {
+            return false;
+        };
+        let table: toml::Table = list_lengths
+            .lengths
+            .iter()
+            .map(|(key, &length)| (key.clone(), (length as i64).into()))
+            .collect();
+        game_config.insert(Self::CONFIG_KEY.into(), table.into());
+        true
+    }
+
+    pub fn default_length(ctx: &egui::Context) -> usize {
+        ctx.data_mut(|data| data.get_temp_mut_or_default::<ListLengths>(Self::id()).default_length)
+    }
+
+    /// Returns the saved length of a field, or the default length if it has none.
+    pub fn get(ctx: &egui::Context, key: &str) -> usize {
+        ctx.data_mut(|data| {
+            let list_lengths = data.get_temp_mut_or_default::<ListLengths>(Self::id());
+            list_lengths.lengths.get(key).copied().unwrap_or(list_lengths.default_length)
+        })
+    }
+
+    pub fn set(ctx: &egui::Context, key: &str, length: usize) {
+        ctx.data_mut(|data| {
+            let list_lengths = data.get_temp_mut_or_default::<ListLengths>(Self::id());
+            list_lengths.lengths.insert(key.to_string(), length);
+            list_lengths.changed = true;
+        });
+    }
+}
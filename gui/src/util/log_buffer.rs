@@ -0,0 +1,90 @@
+use std::{
+    collections::VecDeque,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+/// Number of records kept for the log window, the oldest are dropped first.
+const MAX_RECORDS: usize = 1000;
+
+pub struct LogRecord {
+    /// Time since the logger was installed
+    pub time: Duration,
+    pub level: log::Level,
+    pub target: String,
+    pub message: String,
+}
+
+struct LogBuffer {
+    records: VecDeque<LogRecord>,
+    /// Errors logged since the log window was last shown
+    unseen_errors: usize,
+}
+
+static BUFFER: Mutex<LogBuffer> =
+    Mutex::new(LogBuffer { records: VecDeque::new(), unseen_errors: 0 });
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// Forwards every record to the inner logger and keeps the enabled ones for the log window, so
+/// that errors from any thread can be seen without a terminal.
+struct BufferedLogger<L> {
+    inner: L,
+}
+
+impl<L: log::Log> log::Log for BufferedLogger<L> {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.inner.enabled(record.metadata()) {
+            return;
+        }
+        self.inner.log(record);
+        let start = START.get_or_init(Instant::now);
+        let mut buffer = BUFFER.lock().unwrap();
+        if buffer.records.len() >= MAX_RECORDS {
+            buffer.records.pop_front();
+        }
+        if record.level() == log::Level::Error {
+            buffer.unseen_errors += 1;
+        }
+        buffer.records.push_back(LogRecord {
+            time: start.elapsed(),
+            level: record.level(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        });
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Installs `logger` as the global logger, with records also kept for [`with_records`].
+pub fn init(logger: env_logger::Logger) {
+    START.get_or_init(Instant::now);
+    log::set_max_level(logger.filter());
+    log::set_boxed_logger(Box::new(BufferedLogger { inner: logger }))
+        .expect("Logger was already set");
+}
+
+/// Calls `f` with the kept records, oldest first.
+pub fn with_records<R>(f: impl FnOnce(&VecDeque<LogRecord>) -> R) -> R {
+    f(&BUFFER.lock().unwrap().records)
+}
+
+pub fn clear() {
+    let mut buffer = BUFFER.lock().unwrap();
+    buffer.records.clear();
+    buffer.unseen_errors = 0;
+}
+
+pub fn unseen_errors() -> usize {
+    BUFFER.lock().unwrap().unseen_errors
+}
+
+pub fn mark_errors_seen() {
+    BUFFER.lock().unwrap().unseen_errors = 0;
+}
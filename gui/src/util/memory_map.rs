@@ -0,0 +1,50 @@
+use dsv_core::memory::map::{Access, MemoryMap, MemoryRegion};
+use eframe::egui;
+
+const CONFIG_KEY: &str = "memory_map";
+
+fn id() -> egui::Id {
+    egui::Id::new("memory_map")
+}
+
+/// Loads the memory map of a game, which is the ARM9 map unless the game config has a
+/// `memory_map` array of regions like `{ name = "ARM7 WRAM", start = 0x03800000, end =
+/// 0x03810000, access = "rw", io = false }`. Returns true if the map came from the config.
+pub fn load_memory_map(ctx: &egui::Context, game_config: &toml::Table) -> bool {
+    let regions = game_config
+        .get(CONFIG_KEY)
+        .and_then(|v| v.as_array())
+        .map(|regions| regions.iter().filter_map(parse_region).collect::<Vec<_>>());
+    let (memory_map, custom) = match regions {
+        Some(regions) if !regions.is_empty() => (MemoryMap::new(regions), true),
+        _ => (MemoryMap::ds_arm9(), false),
+    };
+    ctx.data_mut(|data| data.insert_temp(id(), memory_map));
+    custom
+}
+
+fn parse_region(value: &toml::Value) -> Option<MemoryRegion> {
+    let table = value.as_table()?;
+    let address = |key: &str| u32::try_from(table.get(key)?.as_integer()?).ok();
+    let Some(((name, start), end)) =
+        table.get("name").and_then(|v| v.as_str()).zip(address("start")).zip(address("end"))
+    else {
+        log::warn!("Ignoring memory region without a name, start and end: {value}");
+        return None;
+    };
+    let access = match table.get("access").and_then(|v| v.as_str()) {
+        Some(access) => Access::parse(access).or_else(|| {
+            log::warn!("Invalid access '{access}' of memory region '{name}'");
+            None
+        })?,
+        None => Access::READ_WRITE,
+    };
+    let io = table.get("io").and_then(|v| v.as_bool()).unwrap_or(false);
+    let region = MemoryRegion::new(name, start..end, access);
+    Some(if io { region.io() } else { region })
+}
+
+/// Calls `f` with the memory map loaded by [`load_memory_map`].
+pub fn with_memory_map<R>(ctx: &egui::Context, f: impl FnOnce(&MemoryMap) -> R) -> R {
+    ctx.data_mut(|data| f(data.get_temp_mut_or_default::<MemoryMap>(id())))
+}
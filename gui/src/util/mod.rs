@@ -1,2 +1,14 @@
-pub mod bitvec;
+pub mod baselines;
+pub mod export;
+pub mod fuzzy;
+pub mod keybindings;
+pub mod layout;
+pub mod list_lengths;
+pub mod log_buffer;
+pub mod memory_map;
+pub mod pins;
 pub mod read;
+pub mod symbols;
+pub mod type_names;
+pub mod value_map;
+pub mod widget_state;
@@ -0,0 +1,124 @@
+use eframe::egui;
+
+/// A field shown on the dashboard, found by following `path` from the object of a base window.
+#[derive(Clone, PartialEq)]
+pub struct Pin {
+    /// Title of the window the field was pinned from
+    pub base: String,
+    pub path: Vec<String>,
+}
+
+impl Pin {
+    const CONFIG_KEY: &str = "pins";
+
+    pub fn label(&self) -> String {
+        self.path.join(".")
+    }
+
+    pub fn load(game_config: &toml::Table) -> Vec<Pin> {
+        let Some(pins) = game_config.get(Self::CONFIG_KEY).and_then(|v| v.as_array()) else {
+            return Vec::new();
+        };
+        pins.iter()
+            .filter_map(|pin| {
+                let pin = pin.as_table()?;
+                let base = pin.get("base")?.as_str()?.to_string();
+                let path = pin.get("path")?.as_str()?.split('.').map(str::to_string).collect();
+                Some(Pin { base, path })
+            })
+            .collect()
+    }
+
+    pub fn store(game_config: &mut toml::Table, pins: &[Pin]) {
+        let pins: toml::value::Array = pins
+            .iter()
+            .map(|pin| {
+                let mut table = toml::Table::new();
+                table.insert("base".into(), pin.base.clone().into());
+                table.insert("path".into(), pin.label().into());
+                table.into()
+            })
+            .collect();
+        game_config.insert(Self::CONFIG_KEY.into(), pins.into());
+    }
+}
+
+/// Tracks which window and field path is being rendered, so that fields can be pinned and watches
+/// can be traced back to their window from inside nested struct widgets. Kept in egui memory like
+/// [`super::value_map::ValueMaps`].
+#[derive(Clone, Default)]
+pub struct PinScope {
+    /// Key of the window being rendered
+    window: Option<String>,
+    /// Title of the base window being rendered, `None` if its fields can't be pinned
+    base: Option<String>,
+    /// Field names entered so far, `None` for fields which can't be part of a pin path
+    path: Vec<Option<String>>,
+    new_pins: Vec<Pin>,
+}
+
+impl PinScope {
+    fn id() -> egui::Id {
+        egui::Id::new("pin_scope")
+    }
+
+    fn update<R>(ctx: &egui::Context, f: impl FnOnce(&mut PinScope) -> R) -> R {
+        ctx.data_mut(|data| f(data.get_temp_mut_or_default::<PinScope>(Self::id())))
+    }
+
+    pub fn begin(ctx: &egui::Context, base: &str) {
+        Self::update(ctx, |scope| {
+            scope.window = Some(base.to_string());
+            scope.base = Some(base.to_string());
+            scope.path.clear();
+        });
+    }
+
+    /// Like [`Self::begin`] for windows which can't be the base of a pin, such as actor windows.
+    pub fn begin_window(ctx: &egui::Context, window: &str) {
+        Self::update(ctx, |scope| {
+            scope.window = Some(window.to_string());
+            scope.base = None;
+            scope.path.clear();
+        });
+    }
+
+    pub fn end(ctx: &egui::Context) {
+        Self::update(ctx, |scope| {
+            scope.window = None;
+            scope.base = None;
+            scope.path.clear();
+        });
+    }
+
+    /// Returns the key of the window being rendered.
+    pub fn window(ctx: &egui::Context) -> Option<String> {
+        Self::update(ctx, |scope| scope.window.clone())
+    }
+
+    pub fn enter_field(ctx: &egui::Context, field_name: Option<&str>) {
+        Self::update(ctx, |scope| scope.path.push(field_name.map(str::to_string)));
+    }
+
+    pub fn exit_field(ctx: &egui::Context) {
+        Self::update(ctx, |scope| scope.path.pop());
+    }
+
+    /// Returns a pin for a field of the struct being rendered, if it can be pinned.
+    pub fn pin(ctx: &egui::Context, field_name: &str) -> Option<Pin> {
+        Self::update(ctx, |scope| {
+            let base = scope.base.clone()?;
+            let mut path = scope.path.iter().cloned().collect::<Option<Vec<_>>>()?;
+            path.push(field_name.to_string());
+            Some(Pin { base, path })
+        })
+    }
+
+    pub fn add_pin(ctx: &egui::Context, pin: Pin) {
+        Self::update(ctx, |scope| scope.new_pins.push(pin));
+    }
+
+    pub fn take_new_pins(ctx: &egui::Context) -> Vec<Pin> {
+        Self::update(ctx, |scope| std::mem::take(&mut scope.new_pins))
+    }
+}
@@ -0,0 +1,133 @@
+use std::ops::Range;
+
+use dsv_core::memory::map::MemoryMap;
+
+use crate::views::actor_type::parse_address;
+
+/// Symbols further than this from an address aren't used to describe it.
+const MAX_SYMBOL_OFFSET: u32 = 0x1000;
+
+/// Describes an address by the closest symbol at or below it in the `symbols` table of the game
+/// config, or by the type of the vtable at it, e.g. `gGame+0x10`.
+pub fn describe_address(game_config: &toml::Table, address: u32) -> Option<String> {
+    let table = |key: &str| game_config.get(key).and_then(|v| v.as_table());
+    let vtable = table("vtables").and_then(|vtables| {
+        vtables.iter().find(|(key, _)| parse_address(key) == Some(address))?.1.as_str()
+    });
+    if let Some(type_name) = vtable {
+        return Some(format!("vtable for {type_name}"));
+    }
+
+    let (symbol_address, name) = table("symbols")?
+        .iter()
+        .filter_map(|(key, name)| Some((parse_address(key)?, name.as_str()?)))
+        .filter(|&(symbol_address, _)| symbol_address <= address)
+        .max_by_key(|&(symbol_address, _)| symbol_address)?;
+    match address - symbol_address {
+        0 => Some(name.to_string()),
+        offset if offset < MAX_SYMBOL_OFFSET => Some(format!("{name}+{offset:#x}")),
+        _ => None,
+    }
+}
+
+/// A symbol in a data section, see [`data_symbols`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataSymbol {
+    pub address: u32,
+    pub name: String,
+    /// Name of the section or memory region containing the symbol
+    pub section: String,
+}
+
+/// Returns the symbols in the `symbols` table of the game config which hold game state, sorted by
+/// section and address. The game config can list its data and bss sections in a `sections` array
+/// like `{ name = ".bss", start = 0x020f0000, end = 0x02110000 }`, otherwise every symbol in a
+/// region of the memory map which can hold objects is included. Vtables are left out.
+pub fn data_symbols(game_config: &toml::Table, memory_map: &MemoryMap) -> Vec<DataSymbol> {
+    let sections: Vec<(String, Range<u32>)> = match game_config.get("sections") {
+        Some(sections) => {
+            sections.as_array().into_iter().flatten().filter_map(parse_section).collect()
+        }
+        None => memory_map
+            .regions()
+            .iter()
+            .filter(|region| region.access.read && !region.io)
+            .map(|region| (region.name.clone(), region.range.clone()))
+            .collect(),
+    };
+    let symbols = game_config.get("symbols").and_then(|v| v.as_table());
+    let mut data_symbols: Vec<DataSymbol> = symbols
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, name)| Some((parse_address(key)?, name.as_str()?)))
+        .filter(|(_, name)| !name.starts_with("__vt__") && !name.starts_with("_ZTV"))
+        .filter_map(|(address, name)| {
+            let (section, _) = sections.iter().find(|(_, range)| range.contains(&address))?;
+            Some(DataSymbol { address, name: name.to_string(), section: section.clone() })
+        })
+        .collect();
+    let section_index =
+        |symbol: &DataSymbol| sections.iter().position(|(name, _)| *name == symbol.section);
+    data_symbols.sort_by_key(|symbol| (section_index(symbol), symbol.address));
+    data_symbols
+}
+
+fn parse_section(value: &toml::Value) -> Option<(String, Range<u32>)> {
+    let table = value.as_table()?;
+    let address = |key: &str| u32::try_from(table.get(key)?.as_integer()?).ok();
+    let name = table.get("name")?.as_str()?;
+    Some((name.to_string(), address("start")?..address("end")?))
+}
+
+/// Returns the type of a global variable when its name matches a type, either exactly or after
+/// a prefix like `g`, `g_` or `s_`, e.g. `gPlayerManager` is a `PlayerManager`.
+pub fn symbol_type_name<'a>(types: &type_crawler::Types, name: &'a str) -> Option<&'a str> {
+    let stripped = ["g_", "s_", "g", "s"].iter().filter_map(|prefix| {
+        name.strip_prefix(prefix).filter(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()))
+    });
+    std::iter::once(name).chain(stripped).find(|type_name| types.get(type_name).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_symbols() {
+        let mut game_config: toml::Table = toml::from_str(
+            r#"
+            [symbols]
+            "0x02000800" = "main"
+            "0x020f0100" = "gGame"
+            "0x020e0000" = "gSaveData"
+            "0x020e0040" = "__vt__7PhEnemy"
+            "0x027e0000" = "sStack"
+            "#,
+        )
+        .unwrap();
+        let names = |game_config: &toml::Table| {
+            data_symbols(game_config, &MemoryMap::ds_arm9())
+                .into_iter()
+                .map(|symbol| format!("{} {}", symbol.section, symbol.name))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&game_config), [
+            "Main RAM main",
+            "Main RAM gSaveData",
+            "Main RAM gGame",
+            "DTCM sStack"
+        ]);
+
+        let sections: toml::Table = toml::from_str(
+            r#"
+            sections = [
+                { name = ".bss", start = 0x020f0000, end = 0x02100000 },
+                { name = ".data", start = 0x020e0000, end = 0x020f0000 },
+            ]
+            "#,
+        )
+        .unwrap();
+        game_config.extend(sections);
+        assert_eq!(names(&game_config), [".bss gGame", ".data gSaveData"]);
+    }
+}
@@ -0,0 +1,61 @@
+/// Collects the names of structs, classes, unions and enums declared in a header. `Types` can only
+/// be queried by name, so this index is what lets the UI suggest names.
+pub fn scan_type_names(source: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut tokens = source
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|token| !token.is_empty());
+    while let Some(token) = tokens.next() {
+        if !matches!(token, "struct" | "class" | "union" | "enum") {
+            continue;
+        }
+        let Some(mut name) = tokens.next() else {
+            break;
+        };
+        if token == "enum" && matches!(name, "class" | "struct") {
+            let Some(next) = tokens.next() else {
+                break;
+            };
+            name = next;
+        }
+        if !name.starts_with(|c: char| c.is_ascii_digit()) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Returns up to `max` names similar to `name`, closest first.
+pub fn close_matches<'a>(name: &str, names: &'a [String], max: usize) -> Vec<&'a str> {
+    let name = name.to_ascii_lowercase();
+    let threshold = (name.len() / 3).max(2);
+    let mut matches: Vec<(usize, &str)> = names
+        .iter()
+        .filter_map(|candidate| {
+            let lower = candidate.to_ascii_lowercase();
+            let distance = if lower.contains(&name) || name.contains(&lower) {
+                lower.len().abs_diff(name.len()).min(threshold)
+            } else {
+                edit_distance(&name, &lower)
+            };
+            (distance <= threshold).then_some((distance, candidate.as_str()))
+        })
+        .collect();
+    matches.sort();
+    matches.into_iter().take(max).map(|(_, candidate)| candidate).collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.as_bytes();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, &ca) in a.as_bytes().iter().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous + usize::from(ca != cb);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+    row[b.len()]
+}
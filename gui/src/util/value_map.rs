@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+
+use eframe::egui;
+
+/// Labels for the values of an integer field which is used like an enum.
+#[derive(Clone, PartialEq)]
+pub enum ValueMap {
+    /// Name of an enum in the loaded types
+    Enum(String),
+    Table(BTreeMap<i64, String>),
+}
+
+impl ValueMap {
+    pub fn entries(&self, types: &type_crawler::Types) -> Vec<(i64, String)> {
+        match self {
+            ValueMap::Enum(name) => match types.get(name).and_then(|ty| Self::as_enum(ty)) {
+                Some(enum_decl) => enum_decl
+                    .constants()
+                    .iter()
+                    .map(|constant| (constant.value(), constant.name().to_string()))
+                    .collect(),
+                None => Vec::new(),
+            },
+            ValueMap::Table(table) => {
+                table.iter().map(|(&value, label)| (value, label.clone())).collect()
+            }
+        }
+    }
+
+    fn as_enum(ty: &type_crawler::TypeKind) -> Option<&type_crawler::EnumDecl> {
+        match ty {
+            type_crawler::TypeKind::Enum(enum_decl) => Some(enum_decl),
+            type_crawler::TypeKind::Typedef(typedef) => Self::as_enum(typedef.underlying_type()),
+            _ => None,
+        }
+    }
+
+    /// Parses a table like `0=Idle, 1=Walk, 0x10=Swim`.
+    pub fn parse_table(text: &str) -> Option<Self> {
+        let mut table = BTreeMap::new();
+        for entry in text.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (value, label) = entry.split_once('=')?;
+            table.insert(parse_value(value.trim())?, label.trim().to_string());
+        }
+        (!table.is_empty()).then_some(ValueMap::Table(table))
+    }
+
+    pub fn table_text(&self) -> String {
+        match self {
+            ValueMap::Enum(_) => String::new(),
+            ValueMap::Table(table) => table
+                .iter()
+                .map(|(value, label)| format!("{value}={label}"))
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+
+    fn from_config(value: &toml::Value) -> Option<Self> {
+        let table = value.as_table()?;
+        if let Some(name) = table.get("enum").and_then(|v| v.as_str()) {
+            return Some(ValueMap::Enum(name.to_string()));
+        }
+        let values = table.get("values")?.as_table()?;
+        let mut map = BTreeMap::new();
+        for (value, label) in values {
+            map.insert(parse_value(value)?, label.as_str()?.to_string());
+        }
+        Some(ValueMap::Table(map))
+    }
+
+    fn to_config(&self) -> toml::Value {
+        let mut table = toml::Table::new();
+        match self {
+            ValueMap::Enum(name) => {
+                table.insert("enum".into(), name.clone().into());
+            }
+            ValueMap::Table(map) => {
+                let values: toml::Table = map
+                    .iter()
+                    .map(|(value, label)| (value.to_string(), label.clone().into()))
+                    .collect();
+                table.insert("values".into(), values.into());
+            }
+        }
+        table.into()
+    }
+}
+
+fn parse_value(text: &str) -> Option<i64> {
+    match text.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/// Value maps of struct fields from the game config, keyed by `Struct.field`. Views put them in
+/// egui memory while rendering so that widgets can use and edit them.
+#[derive(Clone, Default)]
+pub struct ValueMaps {
+    maps: BTreeMap<String, ValueMap>,
+    changed: bool,
+}
+
+impl ValueMaps {
+    const CONFIG_KEY: &str = "value_maps";
+
+    fn id() -> egui::Id {
+        egui::Id::new("value_maps")
+    }
+
+    fn key(struct_name: &str, field_name: &str) -> String {
+        format!("{struct_name}.{field_name}")
+    }
+
+    pub fn load(ctx: &egui::Context, game_config: &toml::Table) {
+        let maps = game_config
+            .get(Self::CONFIG_KEY)
+            .and_then(|v| v.as_table())
+            .map(|table| {
+                table
+                    .iter()
+                    .filter_map(|(key, value)| Some((key.clone(), ValueMap::from_config(value)?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        ctx.data_mut(|data| data.insert_temp(Self::id(), ValueMaps { maps, changed: false }));
+    }
+
+    /// Writes value maps edited since [`Self::load`] to the game config. Returns true if the
+    /// config was changed.
+    pub fn store(ctx: &egui::Context, game_config: &mut toml::Table) -> bool {
+        let value_maps = ctx.data_mut(|data| data.get_temp::<ValueMaps>(Self::id()));
+        let Some(value_maps) = value_maps.filter(|value_maps| value_maps.changed) else {
+            return false;
+        };
+        let table: toml::Table =
+            value_maps.maps.iter().map(|(key, map)| (key.clone(), map.to_config())).collect();
+        game_config.insert(Self::CONFIG_KEY.into(), table.into());
+        true
+    }
+
+    pub fn get(ctx: &egui::Context, struct_name: &str, field_name: &str) -> Option<ValueMap> {
+        let key = Self::key(struct_name, field_name);
+        ctx.data_mut(|data| {
+            data.get_temp_mut_or_default::<ValueMaps>(Self::id()).maps.get(&key).cloned()
+        })
+    }
+
+    pub fn set(
+        ctx: &egui::Context,
+        struct_name: &str,
+        field_name: &str,
+        value_map: Option<ValueMap>,
+    ) {
+        let key = Self::key(struct_name, field_name);
+        ctx.data_mut(|data| {
+            let value_maps = data.get_temp_mut_or_default::<ValueMaps>(Self::id());
+            match value_map {
+                Some(value_map) => value_maps.maps.insert(key, value_map),
+                None => value_maps.maps.remove(&key),
+            };
+            value_maps.changed = true;
+        });
+    }
+}
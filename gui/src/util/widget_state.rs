@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+
+use eframe::egui;
+
+/// Display options of data widgets, like hex toggles and whether they are open, saved per game so
+/// that they survive the window being recreated. Options are keyed by the window title and the
+/// path of the field, see [`Self::begin`] and [`Self::enter`]. Kept in egui memory while rendering
+/// like [`super::value_map::ValueMaps`].
+#[derive(Clone, Default)]
+pub struct WidgetStates {
+    /// Values and when they were last changed, by `window/field.path#option`
+    options: BTreeMap<String, (bool, u64)>,
+    next_change: u64,
+    changed: bool,
+    /// Title of the window being rendered
+    scope: Option<String>,
+    path: Vec<String>,
+}
+
+impl WidgetStates {
+    const CONFIG_KEY: &str = "widget_states";
+    /// Most options to save. The ones changed longest ago are dropped first.
+    const MAX_OPTIONS: usize = 2000;
+
+    fn id() -> egui::Id {
+        egui::Id::new("widget_states")
+    }
+
+    fn update<R>(ctx: &egui::Context, f: impl FnOnce(&mut WidgetStates) -> R) -> R {
+        ctx.data_mut(|data| f(data.get_temp_mut_or_default::<WidgetStates>(Self::id())))
+    }
+
+    pub fn load(ctx: &egui::Context, game_config: &toml::Table) {
+        let table = game_config.get(Self::CONFIG_KEY).and_then(|v| v.as_table());
+        // Saved in the order they were changed
+        let options: BTreeMap<String, (bool, u64)> = table
+            .into_iter()
+            .flatten()
+            .filter_map(|(key, value)| Some((key, value.as_bool()?)))
+            .enumerate()
+            .map(|(index, (key, value))| (key.clone(), (value, index as u64)))
+            .collect();
+        Self::update(ctx, |states| {
+            states.next_change = options.len() as u64;
+            states.options = options;
+            states.changed = false;
+        });
+    }
+
+    /// Writes options changed since [`Self::load`] to the game config. Returns true if the config
+    /// was changed.
+    pub fn store(ctx: &egui::Context, game_config: &mut toml::Table) -> bool {
+        let options = Self::update(ctx, |states| {
+            states.changed.then(|| states.options.iter().map(|(k, &v)| (k.clone(), v)).collect())
+        });
+        let Some(mut options): Option<Vec<(String, (bool, u64))>> = options else {
+            return false;
+        };
+        options.sort_by_key(|(_, (_, changed))| *changed);
+        let excess = options.len().saturating_sub(Self::MAX_OPTIONS);
+        let table: toml::Table =
+            options.into_iter().skip(excess).map(|(key, (value, _))| (key, value.into())).collect();
+        game_config.insert(Self::CONFIG_KEY.into(), table.into());
+        true
+    }
+
+    /// Starts saving the options of widgets in the window called `title`.
+    pub fn begin(ctx: &egui::Context, title: &str) {
+        Self::update(ctx, |states| {
+            states.scope = Some(title.to_string());
+            states.path.clear();
+        });
+    }
+
+    pub fn end(ctx: &egui::Context) {
+        Self::update(ctx, |states| {
+            states.scope = None;
+            states.path.clear();
+        });
+    }
+
+    /// Adds a field name or element index to the path of the widgets created from now on.
+    pub fn enter(ctx: &egui::Context, segment: &str) {
+        Self::update(ctx, |states| states.path.push(segment.to_string()));
+    }
+
+    pub fn exit(ctx: &egui::Context) {
+        Self::update(ctx, |states| states.path.pop());
+    }
+
+    /// Returns the key of an option of the widget being created, if it's inside a window.
+    fn key(ctx: &egui::Context, option: &str) -> Option<String> {
+        Self::update(ctx, |states| {
+            let scope = states.scope.as_ref()?;
+            Some(format!("{scope}/{}#{option}", states.path.join(".")))
+        })
+    }
+}
+
+/// A display option of a widget, saved in [`WidgetStates`] if the widget has a field path or in
+/// egui temp memory otherwise.
+#[derive(Clone)]
+pub struct WidgetOption {
+    id: egui::Id,
+    key: Option<String>,
+}
+
+impl WidgetOption {
+    pub fn new(ui: &egui::Ui, name: &str) -> Self {
+        Self {
+            id: ui.make_persistent_id(name),
+            key: WidgetStates::key(ui.ctx(), name),
+        }
+    }
+
+    pub fn get(&self, ctx: &egui::Context, default: bool) -> bool {
+        match &self.key {
+            Some(key) => WidgetStates::update(ctx, |states| {
+                states.options.get(key).map_or(default, |&(value, _)| value)
+            }),
+            None => ctx.data_mut(|data| data.get_temp::<bool>(self.id).unwrap_or(default)),
+        }
+    }
+
+    pub fn set(&self, ctx: &egui::Context, value: bool) {
+        match &self.key {
+            Some(key) => WidgetStates::update(ctx, |states| {
+                if states.options.get(key).is_some_and(|&(old, _)| old == value) {
+                    return;
+                }
+                states.options.insert(key.clone(), (value, states.next_change));
+                states.next_change += 1;
+                states.changed = true;
+            }),
+            None => ctx.data_mut(|data| data.insert_temp(self.id, value)),
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::{borrow::Cow, path::Path};
+
+use anyhow::{Context, Result};
+use eframe::egui;
+
+use crate::util::type_names::close_matches;
+
+pub enum ActorTypeSource {
+    Vtable,
+    /// The vtable pointer points into a vtable symbol of the `symbols` table
+    Symbol,
+    FourCc,
+    Fallback,
+}
+
+pub struct ActorType<'a> {
+    pub name: Cow<'a, str>,
+    pub source: ActorTypeSource,
+}
+
+/// Resolves an actor's most derived type name from the game config. The `vtables` table (vtable
+/// address → type name) is consulted first, then vtable symbols in the `symbols` table, then the
+/// `actors` table (fourCC → type name), defaulting to the base `Actor`.
+pub fn resolve_actor_type<'a>(
+    game_config: &'a toml::Table,
+    vtable: u32,
+    fourcc: &str,
+) -> ActorType<'a> {
+    let table = |key: &str| game_config.get(key).and_then(|v| v.as_table());
+    let vtable_type = table("vtables").and_then(|vtables| {
+        vtables
+            .iter()
+            .find(|(address, _)| parse_address(address) == Some(vtable))
+            .and_then(|(_, name)| name.as_str())
+    });
+    if let Some(name) = vtable_type {
+        return ActorType { name: name.into(), source: ActorTypeSource::Vtable };
+    }
+
+    let symbol_type = table("symbols").and_then(|symbols| {
+        symbols.iter().find_map(|(address, symbol)| {
+            let address = parse_address(address)?;
+            let (type_name, vptr_offset) = parse_vtable_symbol(symbol.as_str()?)?;
+            (address.wrapping_add(vptr_offset) == vtable).then_some(type_name)
+        })
+    });
+    if let Some(name) = symbol_type {
+        return ActorType { name: name.into(), source: ActorTypeSource::Symbol };
+    }
+
+    let fourcc_type = game_config
+        .get("actors")
+        .and_then(|v| v.as_table())
+        .and_then(|actors| actors.get(fourcc))
+        .and_then(|v| v.as_str());
+    match fourcc_type {
+        Some(name) => ActorType { name: name.into(), source: ActorTypeSource::FourCc },
+        None => ActorType { name: "Actor".into(), source: ActorTypeSource::Fallback },
+    }
+}
+
+impl ActorType<'_> {
+    pub fn render_source(&self, ui: &mut egui::Ui, vtable: u32) {
+        match self.source {
+            ActorTypeSource::Vtable => ui.weak(format!("Type detected from vtable {vtable:#010x}")),
+            ActorTypeSource::Symbol => {
+                ui.weak(format!("Type detected from vtable symbol at {vtable:#010x}"))
+            }
+            ActorTypeSource::FourCc => ui.weak("Type from fourCC mapping"),
+            ActorTypeSource::Fallback => ui.weak(format!("Unknown vtable {vtable:#010x}")),
+        };
+    }
+}
+
+/// Explains that `type_name` is missing from the loaded types and suggests similar names.
+pub fn render_missing_type(ui: &mut egui::Ui, type_name: &str, type_names: &[String]) {
+    ui.label(format!("Actor type '{type_name}' not found"));
+    let matches = close_matches(type_name, type_names, 5);
+    if !matches.is_empty() {
+        ui.label(format!("Did you mean: {}?", matches.join(", ")));
+    }
+}
+
+/// Shows a button which imports vtable addresses from a symbol map into the game config. Returns
+/// true if the config was changed.
+pub fn render_vtable_import(ui: &mut egui::Ui, game_config: &mut toml::Table) -> bool {
+    if !ui.button("Import vtables...").clicked() {
+        return false;
+    }
+    let Some(path) = rfd::FileDialog::new().pick_file() else {
+        return false;
+    };
+    match import_vtables(&path, game_config) {
+        Ok(count) => {
+            log::info!("Imported {count} vtables from {}", path.display());
+            count > 0
+        }
+        Err(e) => {
+            log::error!("Failed to import vtables: {e}");
+            false
+        }
+    }
+}
+
+fn import_vtables(path: &Path, game_config: &mut toml::Table) -> Result<usize> {
+    let symbols = std::fs::read_to_string(path).context("Failed to read symbol map")?;
+    let vtables = game_config.entry("vtables").or_insert_with(|| toml::Table::new().into());
+    let vtables = vtables.as_table_mut().context("'vtables' config is not a table")?;
+
+    let mut count = 0;
+    for line in symbols.lines() {
+        let tokens = line.split(|c: char| c.is_whitespace() || c == '=' || c == ';');
+        let mut vtable = None;
+        let mut address = None;
+        for token in tokens.filter(|t| !t.is_empty()) {
+            if let Some(parsed) = parse_vtable_symbol(token) {
+                vtable = Some(parsed);
+            } else if let Some(parsed) = parse_address(token.trim_start_matches("addr:")) {
+                address = Some(parsed);
+            }
+        }
+        let (Some((type_name, vptr_offset)), Some(address)) = (vtable, address) else {
+            continue;
+        };
+        vtables.insert(format!("{:#010x}", address + vptr_offset), type_name.into());
+        count += 1;
+    }
+    Ok(count)
+}
+
+pub fn parse_address(text: &str) -> Option<u32> {
+    let hex = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X"))?;
+    u32::from_str_radix(hex, 16).ok()
+}
+
+/// Returns the class name of a vtable symbol and the offset from the symbol to where objects'
+/// vtable pointers point.
+fn parse_vtable_symbol(symbol: &str) -> Option<(String, u32)> {
+    if let Some(mangled) = symbol.strip_prefix("__vt__") {
+        // Metrowerks objects point to the start of the vtable
+        return Some((demangle_name(mangled)?, 0));
+    }
+    if let Some(mangled) = symbol.strip_prefix("_ZTV") {
+        // Itanium vtables start with the offset to top and the RTTI pointer
+        let mangled =
+            mangled.strip_prefix('N').and_then(|m| m.strip_suffix('E')).unwrap_or(mangled);
+        return Some((demangle_name(mangled)?, 8));
+    }
+    None
+}
+
+/// Demangles a sequence of length-prefixed names, optionally qualified like `Q2_3Foo3Bar`.
+fn demangle_name(mangled: &str) -> Option<String> {
+    let mut rest = mangled;
+    if let Some(qualified) = rest.strip_prefix('Q') {
+        let (_, names) = qualified.split_once('_')?;
+        rest = names;
+    }
+    let mut parts = Vec::new();
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let length: usize = rest[..digits].parse().ok()?;
+        let name = rest.get(digits..digits + length)?;
+        parts.push(name);
+        rest = &rest[digits + length..];
+    }
+    (!parts.is_empty()).then(|| parts.join("::"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_actor_type() {
+        let game_config: toml::Table = toml::from_str(
+            r#"
+            [vtables]
+            "0x02100000" = "PhBoss"
+
+            [symbols]
+            "0x02100100" = "__vt__7PhEnemy"
+            "0x02100200" = "_ZTVN2Ph6PlayerE"
+            "0x02100300" = "gGame"
+
+            [actors]
+            NPC_ = "PhNpc"
+            "#,
+        )
+        .unwrap();
+        let resolve = |vtable, fourcc| {
+            let actor_type = resolve_actor_type(&game_config, vtable, fourcc);
+            (actor_type.name.into_owned(), actor_type.source)
+        };
+
+        let (name, source) = resolve(0x02100000, "NPC_");
+        assert_eq!(name, "PhBoss");
+        assert!(matches!(source, ActorTypeSource::Vtable));
+        let (name, source) = resolve(0x02100100, "NPC_");
+        assert_eq!(name, "PhEnemy");
+        assert!(matches!(source, ActorTypeSource::Symbol));
+        // Itanium objects point past the offset to top and RTTI pointer
+        let (name, source) = resolve(0x02100208, "NPC_");
+        assert_eq!(name, "Ph::Player");
+        assert!(matches!(source, ActorTypeSource::Symbol));
+        let (name, source) = resolve(0x02100200, "NPC_");
+        assert_eq!(name, "PhNpc");
+        assert!(matches!(source, ActorTypeSource::FourCc));
+        let (name, source) = resolve(0x02100300, "BOSS");
+        assert_eq!(name, "Actor");
+        assert!(matches!(source, ActorTypeSource::Fallback));
+    }
+}
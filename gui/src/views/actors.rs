@@ -0,0 +1,709 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use dsv_core::state::{RefreshRate, State};
+use eframe::egui;
+
+use crate::{
+    ui::{
+        dock::DockWindow,
+        refresh::{WindowRequests, refresh_rate_selector, window_refresh_rate},
+        type_decl::IntoDataWidget,
+    },
+    util::{
+        baselines::Baselines,
+        export::{export_json, import_json},
+        pins::PinScope,
+        read::{TypeInstance, TypeInstanceOptions},
+        widget_state::WidgetStates,
+    },
+    views::{
+        actor_type::{ActorType, render_missing_type, render_vtable_import, resolve_actor_type},
+        compare::CompareWindow,
+        game::{ActorTableLength, ActorTypeId, GameProfile},
+        get_object, read_pointer_object, request_object, type_size_label,
+    },
+    windows::pointer_scan::PointerScanWindow,
+};
+
+#[derive(Default)]
+pub struct ActorManagerWindow {
+    pub open: bool,
+}
+
+impl ActorManagerWindow {
+    fn window_id() -> egui::Id {
+        egui::Id::new("Actor manager")
+    }
+
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        types: &type_crawler::Types,
+        state: &mut State,
+        actor_manager: &ActorManagerFrame,
+    ) {
+        let mut open = self.open;
+        let window_id = Self::window_id();
+        let requests = WindowRequests::begin(ctx, state, window_id);
+        DockWindow::new("Actor manager", "Actor manager").show(ctx, &mut open, |ui| {
+            let instance = match &actor_manager.actor_manager {
+                Ok(instance) => instance.borrowed(),
+                Err(err) => {
+                    refresh_rate_selector(ui, window_id);
+                    ui.label(err);
+                    return;
+                }
+            };
+            ui.horizontal(|ui| {
+                refresh_rate_selector(ui, window_id);
+                type_size_label(ui, types, instance.ty());
+            });
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                WidgetStates::begin(ui.ctx(), "Actor manager");
+                instance.into_data_widget(ui, types).render_compound(ui, types, state);
+                WidgetStates::end(ui.ctx());
+            });
+        });
+        requests.end(ctx, state);
+        self.open = open;
+    }
+}
+
+/// The actor manager and its actor table, read once per frame and shared by the actor windows.
+pub struct ActorManagerFrame<'a> {
+    profile: &'static GameProfile,
+    actor_manager: Result<TypeInstance<'a>, String>,
+    actor_table: Result<Vec<u32>, String>,
+    /// Actors resolved this frame by actor table index, see [`Self::read_actors`]. Actors which
+    /// haven't been read yet are missing.
+    actors: BTreeMap<usize, Result<ActorEntry, String>>,
+}
+
+/// An actor whose most derived type was resolved from the last data read.
+struct ActorEntry {
+    id: Result<i32, String>,
+    vtable: u32,
+    /// Four-character type ID
+    fourcc: String,
+    resolved_type: ActorType<'static>,
+    /// Size of the most derived type, or of `Actor` if that type isn't loaded
+    size: usize,
+}
+
+impl<'a> ActorManagerFrame<'a> {
+    /// Returns the fastest refresh rate of the open windows which show the actor manager frame.
+    /// The Actors window has no refresh rate selector and is always live.
+    pub fn refresh_rate(
+        ctx: &egui::Context,
+        manager_window: &ActorManagerWindow,
+        actors_window: &ActorsWindow,
+        actor_list: &BTreeSet<ActorWindow>,
+    ) -> RefreshRate {
+        if actors_window.open {
+            return RefreshRate::Live;
+        }
+        let manager_rate =
+            manager_window.open.then(|| window_refresh_rate(ctx, ActorManagerWindow::window_id()));
+        actor_list
+            .iter()
+            .map(|actor| window_refresh_rate(ctx, actor.window_id()))
+            .chain(manager_rate)
+            .reduce(RefreshRate::fastest)
+            .unwrap_or_default()
+    }
+
+    /// Reads the actor table if it has at most `max_actors` entries.
+    pub fn read(
+        types: &'a type_crawler::Types,
+        state: &mut State,
+        profile: &'static GameProfile,
+        max_actors: u32,
+    ) -> Self {
+        let actor_manager =
+            read_pointer_object(types, state, "ActorManager", profile.actor_manager_address);
+        let actor_table = match &actor_manager {
+            Ok(actor_manager) => get_actor_table(types, state, profile, actor_manager, max_actors),
+            Err(err) => Err(err.clone()),
+        };
+        Self { profile, actor_manager, actor_table, actors: BTreeMap::new() }
+    }
+
+    /// Resolves the type of every actor if `all` is set, or else of the actors in `actor_list`.
+    /// Each actor is requested once with the size of its most derived type, so that the Actors
+    /// window and the actor windows share one request. Without the Actors window, each actor is
+    /// requested with the refresh rate of its window, so that paused windows stay paused.
+    pub fn read_actors(
+        &mut self,
+        ctx: &egui::Context,
+        types: &type_crawler::Types,
+        state: &mut State,
+        game_config: &toml::Table,
+        all: bool,
+        actor_list: &BTreeSet<ActorWindow>,
+    ) {
+        let Ok(actor_table) = &self.actor_table else {
+            return;
+        };
+        let indices: Vec<(usize, Option<RefreshRate>)> = if all {
+            (0..actor_table.len()).map(|index| (index, None)).collect()
+        } else {
+            actor_list
+                .iter()
+                .map(|actor| {
+                    (actor.index as usize, Some(window_refresh_rate(ctx, actor.window_id())))
+                })
+                .collect()
+        };
+        for (index, refresh_rate) in indices {
+            let Some(&address) = actor_table.get(index).filter(|&&address| address != 0) else {
+                continue;
+            };
+            let previous_rate = refresh_rate.map(|rate| state.set_refresh_rate(rate));
+            let entry = self.read_actor(types, state, game_config, address);
+            if let Some(previous_rate) = previous_rate {
+                state.set_refresh_rate(previous_rate);
+            }
+            if let Some(entry) = entry {
+                self.actors.insert(index, entry);
+            }
+        }
+    }
+
+    /// Returns `None` if the actor hasn't been read yet.
+    fn read_actor(
+        &self,
+        types: &type_crawler::Types,
+        state: &mut State,
+        game_config: &toml::Table,
+        address: u32,
+    ) -> Option<Result<ActorEntry, String>> {
+        let Some(actor_type) = types.get("Actor") else {
+            return Some(Err("Actor struct not found".into()));
+        };
+        let base_size = actor_type.size(types);
+        let Some(actor_data) = state.get_data(address) else {
+            state.request(address, base_size);
+            return None;
+        };
+        let actor = TypeInstance::new(TypeInstanceOptions {
+            ty: actor_type,
+            address,
+            bit_field_range: None,
+            data: Cow::Borrowed(actor_data),
+        });
+        // Read the fields before the actor type, which needs to change the state
+        let vtable = u32::from_le_bytes(actor.data()[..4].try_into().unwrap_or([0; 4]));
+        let mtype = actor_type_field(types, &actor);
+        let id = match actor.read_field(types, "mRef") {
+            Some(actor_ref) => match actor_ref.read_int_field::<i32>(types, "id") {
+                Some(id) => Ok(id),
+                None => Err(format!("Actor ref does not have id field {:#?}", actor_ref.ty())),
+            },
+            None => Err("Actor does not have mRef field".to_string()),
+        };
+
+        let fourcc =
+            mtype.and_then(|mtype| self.actor_type_id(types, state, mtype)).and_then(|type_id| {
+                match str::from_utf8(&type_id.to_be_bytes()) {
+                    Ok(fourcc) => Ok(fourcc.to_string()),
+                    Err(_) => Err("Invalid actor type ID".to_string()),
+                }
+            });
+        let fourcc = match fourcc {
+            Ok(fourcc) => fourcc,
+            Err(err) => {
+                state.request(address, base_size);
+                return Some(Err(err));
+            }
+        };
+        let resolved = resolve_actor_type(game_config, vtable, &fourcc);
+        let resolved_type =
+            ActorType { name: Cow::Owned(resolved.name.into_owned()), source: resolved.source };
+        let size = match types.get(&resolved_type.name) {
+            Some(derived_type) => derived_type.size(types).max(base_size),
+            None => base_size,
+        };
+        state.request(address, size);
+        Some(Ok(ActorEntry { id, vtable, fourcc, resolved_type, size }))
+    }
+
+    /// Returns the bytes requested this frame for the actor manager, its table and the actors
+    /// which were resolved.
+    pub fn request_bytes(&self) -> usize {
+        let manager = self.actor_manager.as_ref().map_or(0, |manager| manager.data().len());
+        let table = self.actor_table.as_ref().map_or(0, |table| table.len() * 4);
+        let actors: usize =
+            self.actors.values().filter_map(|entry| entry.as_ref().ok()).map(|e| e.size).sum();
+        manager + table + actors
+    }
+
+    fn request_bytes_id() -> egui::Id {
+        egui::Id::new("actor_request_bytes")
+    }
+
+    /// Keeps [`Self::request_bytes`] for the connection window, or clears it if no window shows
+    /// actors.
+    pub fn store_request_bytes(ctx: &egui::Context, frame: Option<&Self>) {
+        ctx.data_mut(|data| match frame {
+            Some(frame) => data.insert_temp(Self::request_bytes_id(), frame.request_bytes()),
+            None => {
+                data.remove_temp::<usize>(Self::request_bytes_id());
+            }
+        });
+    }
+
+    /// Returns the bytes stored by [`Self::store_request_bytes`].
+    pub fn last_request_bytes(ctx: &egui::Context) -> Option<usize> {
+        ctx.data(|data| data.get_temp(Self::request_bytes_id()))
+    }
+
+    /// Returns the fourCCs of the actors resolved this frame.
+    pub fn fourccs(&self) -> impl Iterator<Item = &str> {
+        self.actors.values().filter_map(|entry| entry.as_ref().ok()).map(|e| e.fourcc.as_str())
+    }
+
+    /// Returns the four-character type ID of an actor from its `mType` field, see
+    /// [`actor_type_field`].
+    fn actor_type_id(
+        &self,
+        types: &type_crawler::Types,
+        state: &mut State,
+        actor_type: u32,
+    ) -> Result<u32, String> {
+        match self.profile.actor_type_id {
+            ActorTypeId::Inline => Ok(actor_type),
+            ActorTypeId::ActorType => {
+                request_object(types, state, "ActorType", actor_type)?;
+                let actor_type = get_object(types, state, "ActorType", actor_type)?;
+                let Some(actor_type_id) = actor_type.read_int_field::<u32>(types, "mActorId")
+                else {
+                    return Err("ActorType does not have mActorId field".into());
+                };
+                Ok(actor_type_id)
+            }
+        }
+    }
+}
+
+fn actor_type_field(types: &type_crawler::Types, actor: &TypeInstance<'_>) -> Result<u32, String> {
+    actor
+        .read_int_field::<u32>(types, "mType")
+        .ok_or_else(|| "Actor does not have mType field".into())
+}
+
+fn get_actor_table(
+    types: &type_crawler::Types,
+    state: &mut State,
+    profile: &GameProfile,
+    actor_manager: &TypeInstance<'_>,
+    max_actors: u32,
+) -> Result<Vec<u32>, String> {
+    let Some(actor_table) = actor_manager.read_int_field::<u32>(types, "mActorTable") else {
+        return Err("ActorManager does not have mActorTable field".into());
+    };
+    let length_field = match profile.actor_table_length {
+        ActorTableLength::MaxActors => "mMaxActors",
+        ActorTableLength::TableEnd => "mActorTableEnd",
+    };
+    let Some(length_value) = actor_manager.read_int_field::<u32>(types, length_field) else {
+        return Err(format!("ActorManager does not have {length_field} field"));
+    };
+    let count =
+        actor_table_count(&profile.actor_table_length, actor_table, length_value, max_actors)?;
+    state.request(actor_table, count as usize * 4);
+    let Some(actors_data) = state.get_data(actor_table) else {
+        return Err("Actors data not found".into());
+    };
+    let actors_data: Vec<u32> = actors_data
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap_or([0; 4])))
+        .collect();
+    Ok(actors_data)
+}
+
+/// Returns the number of actor table entries from the value of the length field, which is
+/// `mMaxActors` or `mActorTableEnd`. The fields hold garbage for a few frames while the game
+/// changes scenes, so counts above `max_actors` are rejected instead of being read.
+fn actor_table_count(
+    length: &ActorTableLength,
+    actor_table: u32,
+    length_value: u32,
+    max_actors: u32,
+) -> Result<u32, String> {
+    let count = match length {
+        ActorTableLength::MaxActors => length_value,
+        ActorTableLength::TableEnd => {
+            let Some(size) = length_value.checked_sub(actor_table) else {
+                return Err(format!(
+                    "Actor table looks invalid (ends at {length_value:#x}, before {actor_table:#x})"
+                ));
+            };
+            size / 4
+        }
+    };
+    if count > max_actors {
+        return Err(format!("Actor table looks invalid (count={count})"));
+    }
+    Ok(count)
+}
+
+#[derive(Default)]
+pub struct ActorsWindow {
+    pub open: bool,
+}
+
+impl ActorsWindow {
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        types: &type_crawler::Types,
+        actor_manager: &ActorManagerFrame,
+        actor_list: &mut BTreeSet<ActorWindow>,
+        config: &mut toml::Table,
+    ) -> bool {
+        let mut open = self.open;
+        let mut config_changed = false;
+        DockWindow::new("Actors", "Actors").show(ctx, &mut open, |ui| {
+            config_changed = render_vtable_import(ui, config);
+
+            let actors_table = match &actor_manager.actor_table {
+                Ok(data) => data,
+                Err(err) => {
+                    ui.label(err);
+                    return;
+                }
+            };
+
+            if types.get("Actor").is_none() {
+                ui.label("Actor struct not found");
+                return;
+            }
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (index, &actor_ptr) in actors_table.iter().enumerate() {
+                    if actor_ptr == 0 {
+                        continue;
+                    }
+                    let entry = match actor_manager.actors.get(&index) {
+                        Some(Ok(entry)) => entry,
+                        Some(Err(err)) => {
+                            ui.label(err);
+                            continue;
+                        }
+                        None => {
+                            ui.label(format!("Failed to read actor at {actor_ptr:#x}"));
+                            continue;
+                        }
+                    };
+                    let actor_id = match &entry.id {
+                        Ok(actor_id) => *actor_id,
+                        Err(err) => {
+                            ui.label(err);
+                            continue;
+                        }
+                    };
+                    let actor_type_id = &entry.fourcc;
+
+                    let actor_ref = ActorWindow { id: actor_id, index: index as i32 };
+                    let mut checked = actor_list.contains(&actor_ref);
+                    if ui
+                        .toggle_value(&mut checked, format!("{actor_id}: {actor_type_id}"))
+                        .clicked()
+                    {
+                        if checked {
+                            actor_list.insert(actor_ref);
+                        } else {
+                            actor_list.remove(&actor_ref);
+                        }
+                    }
+                }
+            });
+        });
+        self.open = open;
+        config_changed
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct ActorWindow {
+    id: i32,
+    index: i32,
+}
+
+impl ActorWindow {
+    /// Key of the window in the dock layout, also used for its ID.
+    fn key(&self) -> String {
+        format!("actor:{}", self.id)
+    }
+
+    pub fn window_id(&self) -> egui::Id {
+        egui::Id::new(self.key())
+    }
+
+    pub fn render(
+        &self,
+        ctx: &egui::Context,
+        types: &type_crawler::Types,
+        type_names: &[String],
+        state: &mut State,
+        actor_manager: &ActorManagerFrame,
+        config: &mut toml::Table,
+    ) -> bool {
+        config.entry("actors").or_insert_with(|| toml::Table::new().into());
+
+        let Ok(actor_table) = &actor_manager.actor_table else {
+            return true;
+        };
+
+        let actor_ptr = actor_table.get(self.index as usize).copied().unwrap_or(0);
+        if actor_ptr == 0 {
+            return false;
+        }
+        let entry = match actor_manager.actors.get(&(self.index as usize)) {
+            Some(Ok(entry)) => entry,
+            Some(Err(_)) => return false,
+            // Actor data not received yet
+            None => return true,
+        };
+        let vtable = entry.vtable;
+        let actor_type_id = &entry.fourcc;
+        let resolved_type = &entry.resolved_type;
+        let actor_type_name: &str = &resolved_type.name;
+
+        let mut open = true;
+        DockWindow::new(self.key(), format!("{actor_type_name} ({actor_type_id})"))
+            .persist(false)
+            .show(ctx, &mut open, |ui| {
+                let mut export = false;
+                let mut import = false;
+                ui.horizontal(|ui| {
+                    refresh_rate_selector(ui, self.window_id());
+                    if ui.small_button("Find pointers to…").clicked() {
+                        PointerScanWindow::find_pointers_to(ui.ctx(), actor_ptr);
+                    }
+                    if ui
+                        .small_button("Compare")
+                        .on_hover_text("Compare with another actor")
+                        .clicked()
+                    {
+                        CompareWindow::compare(ui.ctx(), actor_type_name, actor_ptr);
+                    }
+                    export = ui.small_button("Export JSON…").clicked();
+                    import = ui
+                        .small_button("Import JSON…")
+                        .on_hover_text("Write values from an exported file")
+                        .clicked();
+                });
+                let baseline = Baselines::selector(ui, self.window_id(), actor_type_name);
+                resolved_type.render_source(ui, vtable);
+                if let Some(actor_type) = types.get(actor_type_name) {
+                    type_size_label(ui, types, actor_type);
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    let Some(actor_type) = types.get(actor_type_name) else {
+                        render_missing_type(ui, actor_type_name, type_names);
+                        return;
+                    };
+                    // Same size as the request of the actor manager frame, so only the refresh
+                    // rate of this window is added
+                    state.request(actor_ptr, entry.size);
+                    let Some(actor_data) = state.get_data(actor_ptr) else {
+                        ui.label(format!("Failed to read actor at {actor_ptr:#x}"));
+                        return;
+                    };
+                    let actor = TypeInstance::new(TypeInstanceOptions {
+                        ty: actor_type,
+                        address: actor_ptr,
+                        bit_field_range: None,
+                        data: Cow::Owned(actor_data.to_vec()),
+                    });
+                    if export {
+                        export_json(types, &actor, actor_type_name, actor_type_name);
+                    }
+                    if import {
+                        import_json(types, &actor, actor_type_name, state);
+                    }
+                    if let Some(label) = &baseline.snapshot {
+                        Baselines::save(ui.ctx(), actor_type_name, label, &actor);
+                    }
+                    // Keyed by type so that options are kept when the actor respawns
+                    PinScope::begin_window(ui.ctx(), &self.key());
+                    WidgetStates::begin(ui.ctx(), &format!("Actor {actor_type_name}"));
+                    Baselines::begin(
+                        ui.ctx(),
+                        actor_type_name,
+                        baseline.label.as_deref(),
+                        actor_ptr,
+                    );
+                    actor.into_data_widget(ui, types).render_compound(ui, types, state);
+                    Baselines::end(ui.ctx());
+                    WidgetStates::end(ui.ctx());
+                    PinScope::end(ui.ctx());
+                });
+            });
+        open
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dsv_core::{memory::in_memory::InMemoryBackend, test_support::parse_header};
+
+    use super::*;
+
+    const FIXTURE_HEADER: &str = "
+        struct ActorManager {
+            unsigned int mActorTable;
+            unsigned int mMaxActors;
+        };
+
+        struct ActorRef {
+            int id;
+        };
+
+        struct Actor {
+            unsigned int vtable;
+            ActorRef mRef;
+            unsigned int mType;
+        };
+
+        struct Enemy : public Actor {
+            unsigned char mData[0x40];
+        };
+    ";
+
+    static PROFILE: GameProfile = GameProfile {
+        name: "Fixture",
+        config_key: "fixture",
+        title_id: "FXT",
+        gamecode_overrides: &[],
+        player_pos_address: None,
+        player_pos_type: "Vec3p",
+        actor_manager_address: 0x02000000,
+        actor_table_length: ActorTableLength::MaxActors,
+        actor_type_id: ActorTypeId::Inline,
+        actor_spawn: false,
+        basic_windows: &[],
+    };
+
+    const ACTOR_COUNT: u32 = 40;
+    const ACTOR_TABLE: u32 = 0x02000200;
+    const ACTORS: u32 = 0x02001000;
+    const ACTOR_STRIDE: u32 = 0x80;
+
+    /// Memory with an actor manager whose table points to `ACTOR_COUNT` actors of type `ENMY`.
+    fn fixture_backend() -> InMemoryBackend {
+        let mut data = vec![0; 0x3000];
+        let mut write = |address: u32, value: u32| {
+            let offset = (address - 0x02000000) as usize;
+            data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        };
+        write(0x02000000, 0x02000100);
+        write(0x02000100, ACTOR_TABLE);
+        write(0x02000104, ACTOR_COUNT);
+        for index in 0..ACTOR_COUNT {
+            let actor = ACTORS + index * ACTOR_STRIDE;
+            write(ACTOR_TABLE + index * 4, actor);
+            write(actor, 0x020c0000);
+            write(actor + 4, index);
+            write(actor + 8, u32::from_be_bytes(*b"ENMY"));
+        }
+        InMemoryBackend::from_bytes(0x02000000, data)
+    }
+
+    /// Updates `state` as the game view would with a window open for every actor at
+    /// `refresh_rate`. Returns the bytes read in the last update and the bytes requested for the
+    /// actors in the last frame. If `before` is set, the actors are requested like before they
+    /// were resolved once per frame: with the size of `Actor`, then with the size of the derived
+    /// type.
+    fn bytes_per_update(before: bool, refresh_rate: RefreshRate) -> (usize, usize) {
+        let types = parse_header("fixture.hpp", FIXTURE_HEADER);
+        let game_config: toml::Table = "actors = { ENMY = \"Enemy\" }".parse().unwrap();
+        let actor_list: BTreeSet<_> =
+            (0..ACTOR_COUNT as i32).map(|index| ActorWindow { id: index, index }).collect();
+        let actor_size = types.get("Actor").unwrap().size(&types);
+        let enemy_size = types.get("Enemy").unwrap().size(&types);
+        let ctx = egui::Context::default();
+        for actor in &actor_list {
+            ctx.data_mut(|data| {
+                data.insert_temp(actor.window_id().with("refresh_rate"), refresh_rate)
+            });
+        }
+
+        let mut backend = fixture_backend();
+        let mut state = State::default();
+        let mut request_bytes = 0;
+        for _ in 0..8 {
+            state.begin_group();
+            state.set_refresh_rate(refresh_rate);
+            let mut actor_manager = ActorManagerFrame::read(&types, &mut state, &PROFILE, 256);
+            if before {
+                for actor in &actor_list {
+                    let address = ACTORS + actor.index as u32 * ACTOR_STRIDE;
+                    state.request(address, actor_size);
+                    state.request(address, enemy_size);
+                }
+            } else {
+                actor_manager.read_actors(
+                    &ctx,
+                    &types,
+                    &mut state,
+                    &game_config,
+                    false,
+                    &actor_list,
+                );
+                // The actor windows share the request of the manager frame
+                for (index, entry) in &actor_manager.actors {
+                    let entry = entry.as_ref().unwrap();
+                    assert_eq!(entry.size, enemy_size);
+                    state.request(ACTORS + *index as u32 * ACTOR_STRIDE, entry.size);
+                }
+            }
+            request_bytes = actor_manager.request_bytes();
+            state.set_refresh_rate(RefreshRate::Live);
+            state.end_group();
+            state.update(&mut backend).unwrap();
+        }
+        (state.bytes_per_update(), request_bytes)
+    }
+
+    #[test]
+    fn test_bytes_per_update() {
+        let enemy_size = 12 + 0x40;
+        let actor_bytes = 8 + ACTOR_COUNT as usize * (4 + enemy_size);
+
+        // Live, each actor is read once in the size of its derived type, next to the manager and
+        // its table. The pointer to the manager is the only other read.
+        let (live_bytes, request_bytes) = bytes_per_update(false, RefreshRate::Live);
+        assert_eq!(request_bytes, actor_bytes);
+        assert!(live_bytes <= 4 + actor_bytes, "{live_bytes} bytes per update");
+
+        // The length of every actor request changed twice per frame, so every actor was read on
+        // every update even though every window is paused
+        let (paused_before, _) = bytes_per_update(true, RefreshRate::Paused);
+        assert_eq!(paused_before, ACTOR_COUNT as usize * enemy_size);
+        let (paused_bytes, request_bytes) = bytes_per_update(false, RefreshRate::Paused);
+        assert_eq!(paused_bytes, 0);
+        assert_eq!(request_bytes, actor_bytes);
+    }
+
+    #[test]
+    fn test_actor_table_count() {
+        let max_actors = ActorTableLength::MaxActors;
+        assert_eq!(actor_table_count(&max_actors, 0x02100000, 64, 256), Ok(64));
+        assert_eq!(actor_table_count(&max_actors, 0x02100000, 0, 256), Ok(0));
+        assert!(actor_table_count(&max_actors, 0x02100000, 0xffff, 256).is_err());
+        assert!(actor_table_count(&max_actors, 0x02100000, u32::MAX, 256).is_err());
+
+        let table_end = ActorTableLength::TableEnd;
+        assert_eq!(actor_table_count(&table_end, 0x02100000, 0x02100100, 256), Ok(64));
+        assert_eq!(actor_table_count(&table_end, 0x02100000, 0x02100000, 256), Ok(0));
+        // End before start, like when the manager is only partly initialized
+        assert!(actor_table_count(&table_end, 0x02100000, 0, 256).is_err());
+        assert!(actor_table_count(&table_end, 0, 0x02100000, 256).is_err());
+        assert!(actor_table_count(&table_end, 0, u32::MAX, u32::MAX).is_ok());
+    }
+}
@@ -0,0 +1,135 @@
+use dsv_core::state::State;
+use eframe::egui;
+
+use crate::{
+    ui::{
+        dock::DockWindow,
+        refresh::{WindowRequests, refresh_rate_selector},
+        type_decl::IntoDataWidget,
+    },
+    views::{compare::CompareWindow, read_object, type_size_label},
+};
+
+/// Number of bytes shown by the hex view.
+const HEX_VIEW_LENGTH: usize = 0x100;
+
+/// Shows the memory at an address found elsewhere, like by a pointer scan, either as hex or as
+/// an object of a chosen type.
+pub struct AddressWindow {
+    address: u32,
+    type_name: String,
+    typed: bool,
+}
+
+impl AddressWindow {
+    fn id() -> egui::Id {
+        egui::Id::new("open_address_windows")
+    }
+
+    /// Opens a window for `address` in the next frame, see [`Self::take_opened`].
+    pub fn open(ctx: &egui::Context, address: u32, typed: bool) {
+        Self::push(ctx, address, typed, String::new());
+    }
+
+    /// Opens a window which shows `address` as an object of type `type_name`.
+    pub fn open_as(ctx: &egui::Context, address: u32, type_name: &str) {
+        Self::push(ctx, address, true, type_name.to_string());
+    }
+
+    fn push(ctx: &egui::Context, address: u32, typed: bool, type_name: String) {
+        ctx.data_mut(|data| {
+            data.get_temp_mut_or_default::<Vec<(u32, bool, String)>>(Self::id())
+                .push((address, typed, type_name))
+        });
+    }
+
+    pub fn take_opened(ctx: &egui::Context) -> Vec<AddressWindow> {
+        let opened = ctx.data_mut(|data| data.remove_temp::<Vec<(u32, bool, String)>>(Self::id()));
+        opened
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(address, typed, type_name)| AddressWindow { address, type_name, typed })
+            .collect()
+    }
+
+    pub fn address(&self) -> u32 {
+        self.address
+    }
+
+    fn window_id(&self) -> egui::Id {
+        egui::Id::new(("address_window", self.address))
+    }
+
+    /// Returns false once the window was closed.
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        types: &type_crawler::Types,
+        state: &mut State,
+    ) -> bool {
+        let mut open = true;
+        let window_id = self.window_id();
+        let requests = WindowRequests::begin(ctx, state, window_id);
+        DockWindow::new(format!("address:{:08x}", self.address), format!("{:#010x}", self.address))
+            .id(window_id)
+            .persist(false)
+            .show(ctx, &mut open, |ui| {
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.typed, false, "Hex");
+                    ui.selectable_value(&mut self.typed, true, "Typed");
+                    if self.typed {
+                        egui::TextEdit::singleline(&mut self.type_name)
+                            .desired_width(150.0)
+                            .hint_text("Type name")
+                            .show(ui);
+                        if !self.type_name.is_empty() && ui.small_button("Compare").clicked() {
+                            CompareWindow::compare(ui.ctx(), &self.type_name, self.address);
+                        }
+                    }
+                });
+                refresh_rate_selector(ui, window_id);
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    if self.typed {
+                        self.render_typed(ui, types, state);
+                    } else {
+                        self.render_hex(ui, state);
+                    }
+                });
+            });
+        requests.end(ctx, state);
+        open
+    }
+
+    fn render_typed(&self, ui: &mut egui::Ui, types: &type_crawler::Types, state: &mut State) {
+        if self.type_name.is_empty() {
+            ui.weak("Enter a type to show the memory as");
+            return;
+        }
+        match read_object(types, state, &self.type_name, self.address) {
+            Ok(instance) => {
+                type_size_label(ui, types, instance.ty());
+                instance.into_data_widget(ui, types).render_compound(ui, types, state)
+            }
+            Err(err) => {
+                ui.label(err);
+            }
+        }
+    }
+
+    fn render_hex(&self, ui: &mut egui::Ui, state: &mut State) {
+        state.request(self.address, HEX_VIEW_LENGTH);
+        let Some(data) = state.get_data(self.address) else {
+            ui.label("Data not found");
+            return;
+        };
+        for (row, bytes) in data.chunks(16).enumerate() {
+            let hex = bytes.iter().map(|byte| format!("{byte:02x}")).collect::<Vec<_>>().join(" ");
+            let ascii: String = bytes
+                .iter()
+                .map(|&byte| if byte.is_ascii_graphic() { byte as char } else { '.' })
+                .collect();
+            let address = self.address + row as u32 * 16;
+            ui.monospace(format!("{address:08x}  {hex:<47}  {ascii}"));
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use dsv_core::{state::State, typed::layout::base_types};
+use eframe::egui;
+
+use crate::{
+    ui::{
+        dock::DockWindow,
+        refresh::{WindowRequests, refresh_rate_selector},
+        type_decl::{resolve_type, summary_value},
+    },
+    util::read::TypeInstance,
+    views::{actor_type::parse_address, get_object, request_object, type_size_label},
+};
+
+/// Nested structs and arrays deeper than this are compared as a whole.
+const MAX_DEPTH: usize = 6;
+/// Arrays longer than this are compared as a whole.
+const MAX_ARRAY_ELEMENTS: usize = 256;
+const NAME_WIDTH: f32 = 200.0;
+const VALUE_WIDTH: f32 = 120.0;
+
+/// Compares two objects of the same type field by field, without following pointers.
+#[derive(Default)]
+pub struct CompareWindow {
+    pub open: bool,
+    type_name: String,
+    addresses: [String; 2],
+    only_differences: bool,
+}
+
+impl CompareWindow {
+    fn id() -> egui::Id {
+        egui::Id::new("compare_requests")
+    }
+
+    /// Fills the first free side of the window in the next frame, or starts over if both are
+    /// taken, see [`Self::take_requests`].
+    pub fn compare(ctx: &egui::Context, type_name: &str, address: u32) {
+        ctx.data_mut(|data| {
+            data.get_temp_mut_or_default::<Vec<(String, u32)>>(Self::id())
+                .push((type_name.to_string(), address))
+        });
+    }
+
+    fn take_requests(&mut self, ctx: &egui::Context) {
+        let requests = ctx.data_mut(|data| data.remove_temp::<Vec<(String, u32)>>(Self::id()));
+        for (type_name, address) in requests.into_iter().flatten() {
+            let address = format!("{address:#010x}");
+            let free = self.addresses.iter().position(|address| address.is_empty());
+            let side = match free {
+                Some(side) if self.type_name == type_name => side,
+                _ => {
+                    self.addresses = Default::default();
+                    0
+                }
+            };
+            self.type_name = type_name;
+            self.addresses[side] = address;
+            self.open = true;
+        }
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context, types: &type_crawler::Types, state: &mut State) {
+        self.take_requests(ctx);
+        let mut open = self.open;
+        let window_id = egui::Id::new("Compare");
+        let requests = WindowRequests::begin(ctx, state, window_id);
+        DockWindow::new("Compare", "Compare").id(window_id).show(ctx, &mut open, |ui| {
+            egui::Grid::new("compare_inputs").num_columns(2).show(ui, |ui| {
+                ui.label("Type");
+                egui::TextEdit::singleline(&mut self.type_name).desired_width(150.0).show(ui);
+                ui.end_row();
+                for (side, address) in ["Left", "Right"].into_iter().zip(&mut self.addresses) {
+                    ui.label(side);
+                    egui::TextEdit::singleline(address)
+                        .desired_width(150.0)
+                        .hint_text("Address")
+                        .show(ui);
+                    ui.end_row();
+                }
+            });
+            ui.horizontal(|ui| {
+                refresh_rate_selector(ui, window_id);
+                ui.checkbox(&mut self.only_differences, "Only differences");
+            });
+            ui.separator();
+
+            let [Some(left), Some(right)] = self.addresses.each_ref().map(|a| parse_address(a))
+            else {
+                ui.weak("Enter two addresses, or use \"Compare\" in the header of a window");
+                return;
+            };
+            let requested = request_object(types, state, &self.type_name, left)
+                .and_then(|_| request_object(types, state, &self.type_name, right));
+            let state = &*state;
+            let instances = requested
+                .and_then(|_| get_object(types, state, &self.type_name, left))
+                .and_then(|left| Ok((left, get_object(types, state, &self.type_name, right)?)));
+            let (left, right) = match instances {
+                Ok(instances) => instances,
+                Err(err) => {
+                    ui.label(err);
+                    return;
+                }
+            };
+            type_size_label(ui, types, left.ty());
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let mut comparison = Comparison { types, only_differences: self.only_differences };
+                if !comparison.render_children(ui, &left, &right, 0) {
+                    ui.weak("No differences");
+                }
+            });
+        });
+        requests.end(ctx, state);
+        self.open = open;
+    }
+}
+
+struct Comparison<'t> {
+    types: &'t type_crawler::Types,
+    only_differences: bool,
+}
+
+impl Comparison<'_> {
+    /// Renders the fields or elements of two compound instances. Returns false if nothing was
+    /// rendered, which is the case for instances without differences if only those are shown.
+    fn render_children(
+        &mut self,
+        ui: &mut egui::Ui,
+        left: &TypeInstance,
+        right: &TypeInstance,
+        depth: usize,
+    ) -> bool {
+        let types = self.types;
+        let mut rendered = false;
+        match resolve_type(types, left.ty()) {
+            type_crawler::TypeKind::Struct(struct_decl)
+            | type_crawler::TypeKind::Class(struct_decl) => {
+                for base_type in base_types(struct_decl, types) {
+                    let Some(ty) = base_type.ty else {
+                        continue;
+                    };
+                    let offset = base_type.range.start;
+                    let (left, right) =
+                        (left.slice(types, ty, offset, None), right.slice(types, ty, offset, None));
+                    rendered |= self.render_row(ui, base_type.name, &left, &right, depth);
+                }
+                for field in struct_decl.fields() {
+                    rendered |= self.render_field(ui, field, left, right, depth);
+                }
+            }
+            type_crawler::TypeKind::Union(union_decl) => {
+                for field in union_decl.fields() {
+                    rendered |= self.render_field(ui, field, left, right, depth);
+                }
+            }
+            type_crawler::TypeKind::Array { element_type, size: Some(count) } => {
+                let stride = element_type.stride(types);
+                for index in 0..*count {
+                    let offset = index * stride;
+                    let (left, right) = (
+                        left.slice(types, element_type, offset, None),
+                        right.slice(types, element_type, offset, None),
+                    );
+                    rendered |= self.render_row(ui, &format!("[{index}]"), &left, &right, depth);
+                }
+            }
+            _ => {}
+        }
+        rendered
+    }
+
+    fn render_field(
+        &mut self,
+        ui: &mut egui::Ui,
+        field: &type_crawler::Field,
+        left: &TypeInstance,
+        right: &TypeInstance,
+        depth: usize,
+    ) -> bool {
+        let offset = field.offset_bytes();
+        let bit_field_range = field.bit_field_width().map(|width| {
+            let start = (field.offset_bits() - offset * 8) as u8;
+            start..start + width
+        });
+        let types = self.types;
+        let left = left.slice(types, field.kind(), offset, bit_field_range.clone());
+        let right = right.slice(types, field.kind(), offset, bit_field_range);
+        self.render_row(ui, field.name().unwrap_or(""), &left, &right, depth)
+    }
+
+    /// Renders one field of both instances, as a collapsible section if it's compound.
+    fn render_row(
+        &mut self,
+        ui: &mut egui::Ui,
+        name: &str,
+        left: &TypeInstance,
+        right: &TypeInstance,
+        depth: usize,
+    ) -> bool {
+        // Pointers are plain values here, so they differ by address and not by what they point to
+        let differs = left.data() != right.data();
+        if self.only_differences && !differs {
+            return false;
+        }
+        let types = self.types;
+        let compound = match resolve_type(types, left.ty()) {
+            type_crawler::TypeKind::Struct(_)
+            | type_crawler::TypeKind::Class(_)
+            | type_crawler::TypeKind::Union(_) => true,
+            type_crawler::TypeKind::Array { size: Some(count), .. } => *count <= MAX_ARRAY_ELEMENTS,
+            _ => false,
+        };
+        if compound && depth < MAX_DEPTH {
+            let text = egui::RichText::new(name);
+            let text = if differs { text.color(ui.visuals().warn_fg_color) } else { text };
+            egui::CollapsingHeader::new(text)
+                .id_salt((name, left.address()))
+                .default_open(differs && self.only_differences)
+                .show(ui, |ui| {
+                    self.render_children(ui, left, right, depth + 1);
+                });
+            return true;
+        }
+
+        ui.horizontal(|ui| {
+            ui.add_sized([NAME_WIDTH, 0.0], egui::Label::new(name).truncate());
+            for instance in [left, right] {
+                let value = summary_value(types, instance).unwrap_or_else(|| {
+                    let data = instance.data();
+                    let bytes = data.iter().take(8).map(|byte| format!("{byte:02x}"));
+                    bytes.collect::<Vec<_>>().join(" ")
+                });
+                let text = egui::RichText::new(value).monospace();
+                let text = if differs {
+                    text.background_color(ui.visuals().warn_fg_color.gamma_multiply(0.3))
+                } else {
+                    text
+                };
+                ui.add_sized([VALUE_WIDTH, 0.0], egui::Label::new(text).truncate());
+            }
+        });
+        true
+    }
+}
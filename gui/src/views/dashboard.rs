@@ -0,0 +1,193 @@
+use std::ops::Range;
+
+use dsv_core::{
+    memory::address::{AddressWarning, check_address},
+    state::State,
+};
+use eframe::egui;
+
+use crate::{
+    ui::{
+        dock::DockWindow,
+        refresh::{WindowRequests, refresh_rate_selector},
+        type_decl::IntoDataWidget,
+    },
+    util::{
+        layout::NaturalAlignment,
+        pins::{Pin, PinScope},
+        read::TypeInstance,
+    },
+    views::{read_object, read_pointer_object},
+};
+
+/// An object which fields can be pinned from, usually a global shown in a basic window.
+#[derive(Clone, Copy)]
+pub struct PinBase<'b> {
+    pub title: &'b str,
+    pub type_name: &'b str,
+    pub address: u32,
+    pub pointer: bool,
+}
+
+impl PinBase<'_> {
+    pub fn read<'a>(
+        &self,
+        types: &'a type_crawler::Types,
+        state: &mut State,
+    ) -> Result<TypeInstance<'a>, String> {
+        if self.pointer {
+            read_pointer_object(types, state, self.type_name, self.address)
+        } else {
+            read_object(types, state, self.type_name, self.address)
+        }
+    }
+
+    /// Returns the address of the object and the reasons why it may not be there. For pointers,
+    /// the pointer itself is checked before the object it points to.
+    pub fn check_address(
+        &self,
+        types: &type_crawler::Types,
+        state: &mut State,
+        ranges: &[Range<u32>],
+    ) -> (u32, Vec<AddressWarning>) {
+        let Some(ty) = types.get(self.type_name) else {
+            return (self.address, Vec::new());
+        };
+        let mut address = self.address;
+        if self.pointer {
+            let warnings = check_address(address, 4, 4, ranges);
+            if !warnings.is_empty() {
+                return (address, warnings);
+            }
+            state.request(address, 4);
+            let Some(data) = state.get_data(address) else {
+                return (address, Vec::new());
+            };
+            address = u32::from_le_bytes(data.try_into().unwrap_or([0; 4]));
+        }
+        (address, check_address(address, ty.size(types), ty.natural_alignment(types), ranges))
+    }
+}
+
+#[derive(Default)]
+pub struct DashboardWindow {
+    pub open: bool,
+}
+
+impl DashboardWindow {
+    /// Shows the pinned fields of the game config. Returns true if the config was changed.
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        types: &type_crawler::Types,
+        state: &mut State,
+        bases: &[PinBase],
+        game_config: &mut toml::Table,
+    ) -> bool {
+        let mut pins = Pin::load(game_config);
+        let mut changed = false;
+        for pin in PinScope::take_new_pins(ctx) {
+            if !pins.contains(&pin) {
+                pins.push(pin);
+                changed = true;
+            }
+        }
+
+        let mut open = self.open;
+        let window_id = egui::Id::new("dashboard");
+        let requests = WindowRequests::begin(ctx, state, window_id);
+        DockWindow::new("Dashboard", "Dashboard").id(window_id).show(ctx, &mut open, |ui| {
+            refresh_rate_selector(ui, window_id);
+            if pins.is_empty() {
+                ui.weak("Right-click a field and pin it to show it here");
+                return;
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let mut remove = None;
+                let mut moved = None;
+                for (index, pin) in pins.iter().enumerate() {
+                    ui.push_id((&pin.base, &pin.path), |ui| {
+                        let row = ui.horizontal(|ui| {
+                            ui.dnd_drag_source(ui.id().with("drag"), index, |ui| {
+                                ui.label("☰");
+                            });
+                            if ui.small_button("✖").on_hover_text("Remove pin").clicked() {
+                                remove = Some(index);
+                            }
+                            ui.label(pin.label());
+                            ui.weak(&pin.base);
+                        });
+                        if let Some(from) = row.response.dnd_release_payload::<usize>() {
+                            moved = Some((*from, index));
+                        }
+                        render_pin(ui, types, state, bases, pin);
+                    });
+                    ui.separator();
+                }
+
+                if let Some(index) = remove {
+                    pins.remove(index);
+                    changed = true;
+                }
+                if let Some((from, to)) = moved
+                    && from != to
+                {
+                    let pin = pins.remove(from);
+                    pins.insert(to, pin);
+                    changed = true;
+                }
+            });
+        });
+        requests.end(ctx, state);
+        self.open = open;
+
+        if changed {
+            Pin::store(game_config, &pins);
+        }
+        changed
+    }
+}
+
+fn render_pin(
+    ui: &mut egui::Ui,
+    types: &type_crawler::Types,
+    state: &mut State,
+    bases: &[PinBase],
+    pin: &Pin,
+) {
+    let Some(base) = bases.iter().find(|base| base.title == pin.base) else {
+        ui.colored_label(ui.visuals().error_fg_color, format!("Unknown window '{}'", pin.base));
+        return;
+    };
+    let object = match base.read(types, state) {
+        Ok(object) => object,
+        Err(err) => {
+            ui.colored_label(ui.visuals().error_fg_color, err);
+            return;
+        }
+    };
+    if let Err(err) = render_field_path(ui, types, state, &object, &pin.path) {
+        ui.colored_label(ui.visuals().error_fg_color, err);
+    }
+}
+
+fn render_field_path(
+    ui: &mut egui::Ui,
+    types: &type_crawler::Types,
+    state: &mut State,
+    instance: &TypeInstance,
+    path: &[String],
+) -> Result<(), String> {
+    let Some((field_name, rest)) = path.split_first() else {
+        let mut widget = instance.borrowed().into_data_widget(ui, types);
+        widget.render_value(ui, types, state);
+        if widget.is_open(ui) {
+            widget.render_compound(ui, types, state);
+        }
+        return Ok(());
+    };
+    let Some(field) = instance.read_field(types, field_name) else {
+        return Err(format!("Field '{field_name}' not found"));
+    };
+    render_field_path(ui, types, state, &field, rest)
+}
@@ -0,0 +1,651 @@
+use std::{collections::BTreeSet, ops::Range};
+
+use anyhow::Result;
+use dsv_core::{
+    disasm::Mode,
+    memory::address::{Addr, check_address},
+    state::State,
+};
+use eframe::egui;
+
+use crate::{
+    client::{Client, Command},
+    config::Config,
+    ui::{
+        dock::{Dock, DockWindow},
+        refresh::{WindowRequests, freshness_indicator, refresh_rate_selector},
+        type_decl::IntoDataWidget,
+    },
+    util::{
+        baselines::Baselines,
+        export::{export_json, import_json},
+        layout::NaturalAlignment,
+        list_lengths::ListLengths,
+        memory_map::{load_memory_map, with_memory_map},
+        pins::PinScope,
+        value_map::ValueMaps,
+        widget_state::WidgetStates,
+    },
+    views::{
+        actor_type::parse_address,
+        actors::{ActorManagerFrame, ActorManagerWindow, ActorWindow, ActorsWindow},
+        address::AddressWindow,
+        address_banner,
+        compare::CompareWindow,
+        dashboard::{DashboardWindow, PinBase},
+        ph, read_object, st, type_size_label,
+    },
+    windows::{
+        actor_spawn::ActorSpawnWindow, actor_types::ActorTypesWindow,
+        disassembly::DisassemblyWindow, game_data::GameDataWindow, globals::GlobalsWindow,
+        memory_map::MemoryMapWindow, object_scan::ObjectScanWindow, palette::PaletteAction,
+        pointer_scan::PointerScanWindow, stack::StackWindow,
+    },
+};
+
+/// Everything that differs between the supported games.
+pub struct GameProfile {
+    pub name: &'static str,
+    /// Key of the game's table in the config
+    pub config_key: &'static str,
+    /// First three characters of the gamecode, which are the same in every region
+    pub title_id: &'static str,
+    /// Full gamecodes which belong to this game whatever their title ID, for releases which
+    /// don't follow it. Checked before the title IDs of every profile.
+    pub gamecode_overrides: &'static [&'static str],
+    /// Address of the player position, if known. The `player_pos_address` key of the game config
+    /// overrides it, see [`GameProfile::player_pos_address`].
+    pub player_pos_address: Option<u32>,
+    /// Type of the player position
+    pub player_pos_type: &'static str,
+    /// Address of the pointer to the actor manager
+    pub actor_manager_address: u32,
+    pub actor_table_length: ActorTableLength,
+    pub actor_type_id: ActorTypeId,
+    /// Whether actors can be spawned through the pending-spawn fields of the actor manager
+    pub actor_spawn: bool,
+    /// Global objects which get a window each
+    pub basic_windows: &'static [PinBase<'static>],
+}
+
+/// How the number of actor table entries is found in the actor manager.
+pub enum ActorTableLength {
+    /// From the `mMaxActors` field
+    MaxActors,
+    /// From the `mActorTableEnd` pointer
+    TableEnd,
+}
+
+/// How the four-character type ID of an actor is found.
+pub enum ActorTypeId {
+    /// The `mType` field holds the ID
+    Inline,
+    /// The `mType` field points to an `ActorType`, which holds the ID in `mActorId`
+    ActorType,
+}
+
+pub const PROFILES: &[&GameProfile] = &[&ph::PROFILE, &st::PROFILE];
+
+impl GameProfile {
+    pub fn for_gamecode(gamecode: &str) -> Option<&'static GameProfile> {
+        let gamecode = gamecode.get(..4).unwrap_or(gamecode);
+        let overridden =
+            PROFILES.iter().find(|profile| profile.gamecode_overrides.contains(&gamecode));
+        let title_id = gamecode.get(..3)?;
+        overridden.or_else(|| PROFILES.iter().find(|profile| profile.title_id == title_id)).copied()
+    }
+
+    /// Returns true if `gamecode` is detected as this game, see [`Self::for_gamecode`].
+    pub fn matches(&self, gamecode: &str) -> bool {
+        Self::for_gamecode(gamecode).is_some_and(|profile| profile.config_key == self.config_key)
+    }
+
+    /// Returns the address of the player position from the game config, or else from the profile.
+    pub fn player_pos_address(&self, game_config: &toml::Table) -> Option<u32> {
+        let configured = game_config.get(PLAYER_POS_KEY).and_then(|value| match value {
+            toml::Value::Integer(address) => u32::try_from(*address).ok(),
+            toml::Value::String(address) => parse_address(address),
+            _ => None,
+        });
+        configured.or(self.player_pos_address)
+    }
+
+    /// Gamecode of sessions whose gamecode couldn't be read, that of the Japanese release.
+    pub fn default_gamecode(&self) -> String {
+        format!("{}J", self.title_id)
+    }
+
+    pub fn for_config_key(config_key: &str) -> Option<&'static GameProfile> {
+        PROFILES.iter().copied().find(|profile| profile.config_key == config_key)
+    }
+}
+
+pub struct GameView {
+    client: Client,
+    profile: &'static GameProfile,
+    windows: Windows,
+    dock: Dock,
+}
+
+struct Windows {
+    player_pos: PlayerPosWindow,
+    actor_manager: ActorManagerWindow,
+    actors: ActorsWindow,
+    actor_list: BTreeSet<ActorWindow>,
+    actor_spawn: ActorSpawnWindow,
+    actor_types: ActorTypesWindow,
+    dashboard: DashboardWindow,
+    basic_windows: Vec<BasicWindow>,
+    game_data: GameDataWindow,
+    pointer_scan: PointerScanWindow,
+    object_scan: ObjectScanWindow,
+    globals: GlobalsWindow,
+    disassembly: DisassemblyWindow,
+    stack: StackWindow,
+    memory_map: MemoryMapWindow,
+    compare: CompareWindow,
+    address_windows: Vec<AddressWindow>,
+}
+
+impl GameView {
+    pub fn new(client: Client, profile: &'static GameProfile) -> Self {
+        let windows = Windows {
+            player_pos: PlayerPosWindow::default(),
+            actor_manager: ActorManagerWindow::default(),
+            actors: ActorsWindow::default(),
+            actor_list: BTreeSet::new(),
+            actor_spawn: ActorSpawnWindow::default(),
+            actor_types: ActorTypesWindow::default(),
+            dashboard: DashboardWindow::default(),
+            basic_windows: profile
+                .basic_windows
+                .iter()
+                .map(|&base| BasicWindow { open: false, base })
+                .collect(),
+            game_data: GameDataWindow::default(),
+            pointer_scan: PointerScanWindow::default(),
+            object_scan: ObjectScanWindow::default(),
+            globals: GlobalsWindow::default(),
+            disassembly: DisassemblyWindow::default(),
+            stack: StackWindow::default(),
+            memory_map: MemoryMapWindow::default(),
+            compare: CompareWindow::default(),
+            address_windows: Vec::new(),
+        };
+        GameView { client, profile, windows, dock: Dock::default() }
+    }
+}
+
+impl GameView {
+    /// Returns the titles of the windows listed in the side panel.
+    fn window_titles(&self) -> Vec<&'static str> {
+        let mut titles = vec!["Player position"];
+        titles.extend(["Actor manager", "Actors", "Actor types"]);
+        if self.profile.actor_spawn {
+            titles.push("Spawn actor");
+        }
+        titles.push("Dashboard");
+        titles.extend(self.windows.basic_windows.iter().map(|window| window.base.title));
+        titles.extend([
+            "Compare",
+            "Memory map",
+            "Find pointers",
+            "Find objects",
+            "Globals",
+            "Disassembly",
+            "Stack",
+            "Game data",
+        ]);
+        titles
+    }
+
+    fn window_open(&mut self, title: &str) -> Option<&mut bool> {
+        let windows = &mut self.windows;
+        let open = match title {
+            "Player position" => &mut windows.player_pos.open,
+            "Actor manager" => &mut windows.actor_manager.open,
+            "Actors" => &mut windows.actors.open,
+            "Actor types" => &mut windows.actor_types.open,
+            "Spawn actor" => &mut windows.actor_spawn.open,
+            "Dashboard" => &mut windows.dashboard.open,
+            "Compare" => &mut windows.compare.open,
+            "Memory map" => &mut windows.memory_map.open,
+            "Find pointers" => &mut windows.pointer_scan.open,
+            "Find objects" => &mut windows.object_scan.open,
+            "Globals" => &mut windows.globals.open,
+            "Disassembly" => &mut windows.disassembly.open,
+            "Stack" => &mut windows.stack.open,
+            "Game data" => &mut windows.game_data.open,
+            _ => {
+                let window = windows.basic_windows.iter_mut().find(|w| w.base.title == title)?;
+                &mut window.open
+            }
+        };
+        Some(open)
+    }
+}
+
+impl super::View for GameView {
+    fn render_side_panel(
+        &mut self,
+        _ctx: &egui::Context,
+        ui: &mut egui::Ui,
+        _types: &type_crawler::Types,
+        config: &mut Config,
+    ) -> Result<()> {
+        let game_config = game_config(&mut config.games, self.profile)?;
+        let mut docked = self.dock.is_enabled();
+        if ui.checkbox(&mut docked, "Docked").on_hover_text("Show windows as tabs").changed() {
+            config.unsaved_changes |= self.dock.set_enabled(docked, game_config);
+        }
+        egui::ScrollArea::vertical().max_width(100.0).show(ui, |ui| {
+            ui.with_layout(
+                egui::Layout::top_down(egui::Align::LEFT).with_cross_justify(true),
+                |ui| {
+                    ui.toggle_value(&mut self.windows.player_pos.open, "Player position");
+                    ui.toggle_value(&mut self.windows.actor_manager.open, "Actor manager");
+                    ui.toggle_value(&mut self.windows.actors.open, "Actors");
+                    ui.toggle_value(&mut self.windows.actor_types.open, "Actor types");
+                    if self.profile.actor_spawn {
+                        ui.toggle_value(&mut self.windows.actor_spawn.open, "Spawn actor");
+                    }
+                    ui.toggle_value(&mut self.windows.dashboard.open, "Dashboard");
+                    for window in &mut self.windows.basic_windows {
+                        ui.toggle_value(&mut window.open, window.base.title);
+                    }
+                    ui.separator();
+                    ui.toggle_value(&mut self.windows.compare.open, "Compare");
+                    ui.toggle_value(&mut self.windows.memory_map.open, "Memory map");
+                    ui.toggle_value(&mut self.windows.pointer_scan.open, "Find pointers");
+                    ui.toggle_value(&mut self.windows.object_scan.open, "Find objects");
+                    ui.toggle_value(&mut self.windows.globals.open, "Globals");
+                    ui.toggle_value(&mut self.windows.disassembly.open, "Disassembly");
+                    ui.toggle_value(&mut self.windows.stack.open, "Stack");
+                    ui.toggle_value(&mut self.windows.game_data.open, "Game data");
+                },
+            );
+        });
+        Ok(())
+    }
+
+    fn render_central_panel(
+        &mut self,
+        ctx: &egui::Context,
+        ui: &mut egui::Ui,
+        types: &type_crawler::Types,
+        type_names: &[String],
+        config: &mut Config,
+    ) -> Result<()> {
+        let mut state = self.client.state.lock().unwrap();
+
+        let game_config = game_config(&mut config.games, self.profile)?;
+        ValueMaps::load(ctx, game_config);
+        ListLengths::load(ctx, game_config, config.ui.default_list_length);
+        WidgetStates::load(ctx, game_config);
+        Baselines::load(ctx, game_config);
+        let dock_changed = self.dock.begin(ui, game_config);
+
+        let custom_memory_map = load_memory_map(ctx, game_config);
+        let memory_map = with_memory_map(ctx, |map| map.clone());
+        let mut valid_ranges = memory_map.object_ranges();
+        valid_ranges.extend(config.memory.valid_ranges());
+        config.unsaved_changes |= self.windows.player_pos.render(
+            ctx,
+            types,
+            &mut state,
+            self.profile,
+            &valid_ranges,
+            game_config,
+        );
+        let actor_windows_open = self.windows.actor_manager.open
+            || self.windows.actors.open
+            || !self.windows.actor_list.is_empty();
+        if actor_windows_open {
+            // The actor table must agree with the actor manager and the actors it points to
+            state.begin_group();
+            let refresh_rate = ActorManagerFrame::refresh_rate(
+                ctx,
+                &self.windows.actor_manager,
+                &self.windows.actors,
+                &self.windows.actor_list,
+            );
+            let previous_rate = state.set_refresh_rate(refresh_rate);
+            let mut actor_manager =
+                ActorManagerFrame::read(types, &mut state, self.profile, config.memory.max_actors);
+            actor_manager.read_actors(
+                ctx,
+                types,
+                &mut state,
+                game_config,
+                self.windows.actors.open,
+                &self.windows.actor_list,
+            );
+            state.set_refresh_rate(previous_rate);
+            ActorManagerFrame::store_request_bytes(ctx, Some(&actor_manager));
+            self.windows.actor_types.observe(actor_manager.fourccs());
+            self.windows.actor_manager.render(ctx, types, &mut state, &actor_manager);
+            config.unsaved_changes |= self.windows.actors.render(
+                ctx,
+                types,
+                &actor_manager,
+                &mut self.windows.actor_list,
+                game_config,
+            );
+
+            let mut remove_actor = None;
+            for actor in &self.windows.actor_list {
+                let requests = WindowRequests::begin(ctx, &mut state, actor.window_id());
+                let keep_open =
+                    actor.render(ctx, types, type_names, &mut state, &actor_manager, game_config);
+                requests.end(ctx, &mut state);
+                if !keep_open {
+                    remove_actor = Some(actor.clone());
+                }
+            }
+            state.end_group();
+            if let Some(actor) = remove_actor {
+                self.windows.actor_list.remove(&actor);
+            }
+        } else {
+            ActorManagerFrame::store_request_bytes(ctx, None);
+        }
+
+        config.unsaved_changes |=
+            self.windows.actor_types.render(ctx, types, type_names, game_config);
+
+        if self.profile.actor_spawn {
+            self.windows.actor_spawn.render(
+                ctx,
+                &mut state,
+                game_config,
+                self.profile.actor_manager_address,
+                self.profile.player_pos_address(game_config),
+            );
+        }
+
+        for window in &mut self.windows.basic_windows {
+            window.render(ctx, types, &mut state, &valid_ranges);
+        }
+
+        let pin_bases: Vec<_> =
+            self.windows.basic_windows.iter().map(|window| window.base).collect();
+        config.unsaved_changes |=
+            self.windows.dashboard.render(ctx, types, &mut state, &pin_bases, game_config);
+
+        self.windows.compare.render(ctx, types, &mut state);
+        self.windows.memory_map.render(ctx, &state, &memory_map, custom_memory_map);
+        self.windows.pointer_scan.render(ctx, &mut state, game_config, &memory_map);
+        self.windows.object_scan.render(ctx, &mut state, game_config, &memory_map);
+        self.windows.globals.render(ctx, types, game_config, &memory_map);
+        let stopped_at = self.client.stopped_at();
+        self.windows.disassembly.render(ctx, &mut state, game_config, stopped_at);
+        self.windows.stack.render(ctx, &mut state, game_config, &memory_map, stopped_at);
+        for window in AddressWindow::take_opened(ctx) {
+            if !self.windows.address_windows.iter().any(|w| w.address() == window.address()) {
+                self.windows.address_windows.push(window);
+            }
+        }
+        self.windows.address_windows.retain_mut(|window| window.render(ctx, types, &mut state));
+
+        config.unsaved_changes |= ValueMaps::store(ctx, game_config);
+        config.unsaved_changes |= ListLengths::store(ctx, game_config);
+        config.unsaved_changes |= WidgetStates::store(ctx, game_config);
+        config.unsaved_changes |= Baselines::store(ctx, game_config);
+        config.unsaved_changes |= self.dock.end(ctx, dock_changed, game_config);
+        // Imports are merged after the tables above were stored, so that they aren't overwritten
+        config.unsaved_changes |=
+            self.windows.game_data.render(ctx, types, game_config, self.profile.config_key);
+        Ok(())
+    }
+
+    fn actions(&self) -> Vec<PaletteAction> {
+        let mut actions: Vec<PaletteAction> = self
+            .window_titles()
+            .into_iter()
+            .map(|title| PaletteAction::new(title, format!("Open window: {title}")))
+            .collect();
+        actions
+            .push(PaletteAction::new("address", "Open address…").with_address("Address to show"));
+        actions.push(
+            PaletteAction::new("find_pointers", "Find pointers to…")
+                .with_address("Address to find pointers to"),
+        );
+        actions.push(
+            PaletteAction::new("disassemble", "Disassemble…")
+                .with_address("Address of the code, odd for Thumb"),
+        );
+        actions
+    }
+
+    fn run_action(&mut self, ctx: &egui::Context, action: &PaletteAction, address: Option<Addr>) {
+        match (action.id.as_str(), address) {
+            ("address", Some(address)) => AddressWindow::open(ctx, address, false),
+            ("find_pointers", Some(address)) => PointerScanWindow::find_pointers_to(ctx, address),
+            ("disassemble", Some(address)) => {
+                DisassemblyWindow::disassemble(ctx, address & !1, Mode::of_code_pointer(address))
+            }
+            (title, _) => {
+                if let Some(open) = self.window_open(title) {
+                    *open = true;
+                }
+            }
+        }
+    }
+
+    fn types_changed(&mut self, _ctx: &egui::Context) -> usize {
+        let mut open = 0;
+        for title in self.window_titles() {
+            open += self.window_open(title).is_some_and(|open| *open) as usize;
+        }
+        open + self.windows.actor_list.len() + self.windows.address_windows.len()
+    }
+
+    fn exit(&mut self) -> Result<()> {
+        if !self.client.is_running() {
+            return Ok(());
+        }
+        self.client.send_command(Command::Disconnect)?;
+        self.client.join_update_thread(Client::JOIN_TIMEOUT);
+        Ok(())
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+/// Returns the table of the game in the `games` config, adding it if there is none.
+fn game_config<'c>(
+    games: &'c mut toml::Table,
+    profile: &GameProfile,
+) -> Result<&'c mut toml::Table> {
+    let config_key = profile.config_key;
+    let game_config = games.entry(config_key).or_insert_with(|| toml::Table::new().into());
+    game_config
+        .as_table_mut()
+        .ok_or_else(|| anyhow::anyhow!("Failed to get '{config_key}' config as a table"))
+}
+
+/// Key of the game config which overrides the player position address of the profile.
+const PLAYER_POS_KEY: &str = "player_pos_address";
+
+#[derive(Default)]
+struct PlayerPosWindow {
+    open: bool,
+    address_text: String,
+}
+
+impl PlayerPosWindow {
+    /// Returns true if the config was changed.
+    fn render(
+        &mut self,
+        ctx: &egui::Context,
+        types: &type_crawler::Types,
+        state: &mut State,
+        profile: &GameProfile,
+        valid_ranges: &[Range<u32>],
+        game_config: &mut toml::Table,
+    ) -> bool {
+        let type_name = profile.player_pos_type;
+        let address = profile.player_pos_address(game_config);
+        let mut open = self.open;
+        let mut config_changed = false;
+        let title = "Player position";
+        let window_id = egui::Id::new(title);
+        let requests = WindowRequests::begin(ctx, state, window_id);
+        DockWindow::new(title, title).resizable(false).show(ctx, &mut open, |ui| {
+            let Some(address) = address else {
+                config_changed = self.render_address_input(ui, game_config);
+                return;
+            };
+            freshness_indicator(ui, window_id);
+            if let Some(ty) = types.get(type_name) {
+                type_size_label(ui, types, ty);
+                let warnings = check_address(
+                    address,
+                    ty.size(types),
+                    ty.natural_alignment(types),
+                    valid_ranges,
+                );
+                if !address_banner(ui, window_id, address, &warnings) {
+                    return;
+                }
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let player_pos = match read_object(types, state, type_name, address) {
+                    Ok(instance) => instance,
+                    Err(err) => {
+                        ui.label(err);
+                        return;
+                    }
+                };
+                WidgetStates::begin(ui.ctx(), "Player position");
+                player_pos.into_data_widget(ui, types).render_compound(ui, types, state);
+                WidgetStates::end(ui.ctx());
+            });
+        });
+        requests.end(ctx, state);
+        self.open = open;
+        config_changed
+    }
+
+    /// Asks for the address of the player position if the profile doesn't know it, and saves it
+    /// in the game config. Returns true if the config was changed.
+    fn render_address_input(&mut self, ui: &mut egui::Ui, game_config: &mut toml::Table) -> bool {
+        ui.label("The address of the player position isn't known for this game");
+        let address = parse_address(self.address_text.trim());
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            egui::TextEdit::singleline(&mut self.address_text)
+                .desired_width(90.0)
+                .hint_text("Address")
+                .show(ui);
+            if ui.add_enabled(address.is_some(), egui::Button::new("Set")).clicked()
+                && let Some(address) = address
+            {
+                game_config.insert(PLAYER_POS_KEY.into(), format!("{address:#010x}").into());
+                changed = true;
+            }
+        });
+        ui.weak(format!("Saved as '{PLAYER_POS_KEY}' in the game config"));
+        changed
+    }
+}
+
+struct BasicWindow {
+    open: bool,
+    base: PinBase<'static>,
+}
+
+impl BasicWindow {
+    fn render(
+        &mut self,
+        ctx: &egui::Context,
+        types: &type_crawler::Types,
+        state: &mut State,
+        valid_ranges: &[Range<u32>],
+    ) {
+        let mut open = self.open;
+        let title = self.base.title;
+        let window_id = egui::Id::new(title);
+        let requests = WindowRequests::begin(ctx, state, window_id);
+        DockWindow::new(title, title).show(ctx, &mut open, |ui| {
+            let (address, warnings) = self.base.check_address(types, state, valid_ranges);
+            if !address_banner(ui, window_id, address, &warnings) {
+                return;
+            }
+            let mut export = false;
+            let mut import = false;
+            ui.horizontal(|ui| {
+                refresh_rate_selector(ui, window_id);
+                if ui.small_button("Find pointers to…").clicked() {
+                    PointerScanWindow::find_pointers_to(ui.ctx(), address);
+                }
+                if ui.small_button("Compare").clicked() {
+                    CompareWindow::compare(ui.ctx(), self.base.type_name, address);
+                }
+                export = ui.small_button("Export JSON…").clicked();
+                import = ui
+                    .small_button("Import JSON…")
+                    .on_hover_text("Write values from an exported file")
+                    .clicked();
+                if let Some(ty) = types.get(self.base.type_name) {
+                    type_size_label(ui, types, ty);
+                }
+            });
+            let baseline = Baselines::selector(ui, window_id, self.base.type_name);
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let instance = match self.base.read(types, state) {
+                    Ok(instance) => instance,
+                    Err(err) => {
+                        ui.label(err);
+                        return;
+                    }
+                };
+                if export {
+                    export_json(types, &instance, self.base.type_name, title);
+                }
+                if import {
+                    import_json(types, &instance, self.base.type_name, state);
+                }
+                let type_name = self.base.type_name;
+                if let Some(label) = &baseline.snapshot {
+                    Baselines::save(ui.ctx(), type_name, label, &instance);
+                }
+                PinScope::begin(ui.ctx(), title);
+                WidgetStates::begin(ui.ctx(), title);
+                Baselines::begin(
+                    ui.ctx(),
+                    type_name,
+                    baseline.label.as_deref(),
+                    instance.address(),
+                );
+                instance.into_data_widget(ui, types).render_compound(ui, types, state);
+                Baselines::end(ui.ctx());
+                WidgetStates::end(ui.ctx());
+                PinScope::end(ui.ctx());
+            });
+        });
+        requests.end(ctx, state);
+        self.open = open;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_gamecode() {
+        let name = |gamecode| GameProfile::for_gamecode(gamecode).map(|profile| profile.name);
+        assert_eq!(name("AZEE"), Some("Phantom Hourglass"));
+        assert_eq!(name("BKIJ"), Some("Spirit Tracks"));
+        // Regions without a release yet are detected too
+        assert_eq!(name("BKIK"), Some("Spirit Tracks"));
+        assert_eq!(name("BKIJ01"), Some("Spirit Tracks"));
+        assert_eq!(name("AZ"), None);
+        assert_eq!(name("ABCD"), None);
+        assert!(st::PROFILE.matches("BKIE"));
+        assert!(!st::PROFILE.matches("AZEE"));
+    }
+}
@@ -1,14 +1,28 @@
 use std::borrow::Cow;
 
 use anyhow::Result;
-use dsv_core::state::State;
+use dsv_core::{
+    memory::address::{Addr, AddressWarning},
+    state::State,
+};
 use eframe::egui;
 
 use crate::{
+    client::Client,
     config::Config,
-    util::read::{TypeInstance, TypeInstanceOptions},
+    util::{
+        layout::NaturalAlignment,
+        read::{TypeInstance, TypeInstanceOptions},
+    },
+    windows::palette::PaletteAction,
 };
 
+pub mod actor_type;
+pub mod actors;
+pub mod address;
+pub mod compare;
+pub mod dashboard;
+pub mod game;
 pub mod ph;
 pub mod st;
 
@@ -26,23 +40,32 @@ pub trait View {
         ctx: &egui::Context,
         ui: &mut egui::Ui,
         types: &type_crawler::Types,
+        type_names: &[String],
         config: &mut Config,
     ) -> Result<()>;
 
+    /// Returns the actions of this view for the command palette.
+    fn actions(&self) -> Vec<PaletteAction>;
+
+    /// Runs one of the [`Self::actions`], with an address if it takes one.
+    fn run_action(&mut self, ctx: &egui::Context, action: &PaletteAction, address: Option<Addr>);
+
+    /// Called when new types were loaded. Returns the number of open windows, which resolve their
+    /// types again from the next frame on.
+    fn types_changed(&mut self, ctx: &egui::Context) -> usize;
+
     fn exit(&mut self) -> Result<()>;
+
+    fn client(&self) -> &Client;
 }
 
 fn read_object<'a>(
     types: &'a type_crawler::Types,
     state: &mut State,
     type_name: &str,
-    address: u32,
+    address: Addr,
 ) -> Result<TypeInstance<'a>, String> {
-    let Some(ty) = types.get(type_name) else {
-        return Err(format!("{} struct not found", type_name));
-    };
-
-    state.request(address, ty.size(types));
+    let ty = request_object(types, state, type_name, address)?;
     let Some(game_data) = state.get_data(address).map(|d| d.to_vec()) else {
         return Err(format!("{} data not found", type_name));
     };
@@ -56,11 +79,48 @@ fn read_object<'a>(
     Ok(instance)
 }
 
+/// Requests the data of an object for [`get_object`].
+fn request_object<'a>(
+    types: &'a type_crawler::Types,
+    state: &mut State,
+    type_name: &str,
+    address: Addr,
+) -> Result<&'a type_crawler::TypeKind, String> {
+    let Some(ty) = types.get(type_name) else {
+        return Err(format!("{} struct not found", type_name));
+    };
+    state.request(address, ty.size(types));
+    Ok(ty)
+}
+
+/// Like [`read_object`], but borrows the data from `state` instead of copying it. The state can't
+/// be changed while the object is alive, so this is for objects which are only read, not rendered
+/// as widgets. The data must have been requested with [`request_object`].
+fn get_object<'a>(
+    types: &'a type_crawler::Types,
+    state: &'a State,
+    type_name: &str,
+    address: Addr,
+) -> Result<TypeInstance<'a>, String> {
+    let Some(ty) = types.get(type_name) else {
+        return Err(format!("{} struct not found", type_name));
+    };
+    let Some(game_data) = state.get_data(address) else {
+        return Err(format!("{} data not found", type_name));
+    };
+    Ok(TypeInstance::new(TypeInstanceOptions {
+        ty,
+        address,
+        bit_field_range: None,
+        data: Cow::Borrowed(game_data),
+    }))
+}
+
 fn read_pointer_object<'a>(
     types: &'a type_crawler::Types,
     state: &mut State,
     type_name: &str,
-    address: u32,
+    address: Addr,
 ) -> Result<TypeInstance<'a>, String> {
     state.request(address, 4);
     let Some(data) = state.get_data(address) else {
@@ -70,3 +130,40 @@ fn read_pointer_object<'a>(
 
     read_object(types, state, type_name, ptr)
 }
+
+/// Shows the size and alignment of the type shown by a window, for finding neighbouring objects.
+fn type_size_label(ui: &mut egui::Ui, types: &type_crawler::Types, ty: &type_crawler::TypeKind) {
+    let size = ty.size(types);
+    let alignment = ty.natural_alignment(types);
+    ui.weak(format!("Size {size:#x}, alignment {alignment}"));
+}
+
+/// Shows the warnings about the address of a window in a banner, until they're acknowledged for
+/// that address. Returns true if the contents of the window should be shown.
+fn address_banner(
+    ui: &mut egui::Ui,
+    window_id: egui::Id,
+    address: Addr,
+    warnings: &[AddressWarning],
+) -> bool {
+    if warnings.is_empty() {
+        return true;
+    }
+    let acknowledged_id = window_id.with("acknowledged_address");
+    if ui.ctx().data_mut(|data| data.get_temp::<u32>(acknowledged_id)) == Some(address) {
+        return true;
+    }
+    egui::Frame::new()
+        .fill(egui::Color32::from_rgb(70, 60, 0))
+        .inner_margin(4)
+        .corner_radius(2)
+        .show(ui, |ui| {
+            for warning in warnings {
+                ui.colored_label(egui::Color32::YELLOW, format!("{address:#010x}: {warning}"));
+            }
+            if ui.button("Proceed anyway").clicked() {
+                ui.ctx().data_mut(|data| data.insert_temp(acknowledged_id, address));
+            }
+        });
+    false
+}
@@ -0,0 +1,205 @@
+use dsv_core::state::State;
+use eframe::egui;
+
+const CONFIG_KEY: &str = "actor_spawn";
+
+/// Queues spawn requests by writing into the pending-spawn fields of the actor manager. The
+/// offsets of those fields come from the `actor_spawn` table of the game config, like
+/// `{ type = 0x40, params = 0x44, pos = 0x48, request = 0x54, request_value = 1 }`, so that they
+/// can be corrected without a rebuild.
+#[derive(Default)]
+pub struct ActorSpawnWindow {
+    pub open: bool,
+    fourcc: String,
+    params: u32,
+    /// Position in world units, or none to spawn at the player
+    pos: Option<[f32; 3]>,
+    status: Option<Result<String, String>>,
+}
+
+/// Offsets of the pending-spawn fields, relative to the actor manager.
+struct SpawnFields {
+    type_offset: u32,
+    params_offset: u32,
+    pos_offset: u32,
+    request_offset: u32,
+    request_value: u32,
+}
+
+impl SpawnFields {
+    fn parse(game_config: &toml::Table) -> Result<Self, String> {
+        let Some(table) = game_config.get(CONFIG_KEY).and_then(|v| v.as_table()) else {
+            return Err(format!("The game config has no '{CONFIG_KEY}' table"));
+        };
+        let offset = |key: &str| {
+            table
+                .get(key)
+                .and_then(|v| v.as_integer())
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| format!("'{CONFIG_KEY}.{key}' is missing or not an offset"))
+        };
+        Ok(Self {
+            type_offset: offset("type")?,
+            params_offset: offset("params")?,
+            pos_offset: offset("pos")?,
+            request_offset: offset("request")?,
+            request_value: offset("request_value").unwrap_or(1),
+        })
+    }
+}
+
+impl ActorSpawnWindow {
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        state: &mut State,
+        game_config: &toml::Table,
+        actor_manager_address: u32,
+        player_pos_address: Option<u32>,
+    ) {
+        let mut open = self.open;
+        egui::Window::new("Spawn actor").open(&mut open).resizable(false).show(ctx, |ui| {
+            let fields = SpawnFields::parse(game_config);
+            if let Err(err) = &fields {
+                ui.colored_label(ui.visuals().error_fg_color, err);
+            }
+
+            let player_pos = player_pos_address.and_then(|address| read_pos(state, address));
+            egui::Grid::new("actor_spawn_options").num_columns(2).show(ui, |ui| {
+                ui.label("Type");
+                self.render_type(ui, game_config);
+                ui.end_row();
+
+                ui.label("Parameters");
+                ui.add(egui::DragValue::new(&mut self.params).hexadecimal(8, false, false));
+                ui.end_row();
+
+                ui.label("Position");
+                self.render_pos(ui, player_pos);
+                ui.end_row();
+            });
+
+            let fourcc = parse_fourcc(&self.fourcc);
+            let pos = self.pos.or(player_pos);
+            let ready = fields.is_ok() && fourcc.is_some() && pos.is_some();
+            if ui.add_enabled(ready, egui::Button::new("Spawn")).clicked()
+                && let (Ok(fields), Some(fourcc), Some(pos)) = (&fields, fourcc, pos)
+            {
+                self.status = Some(self.spawn(state, fields, actor_manager_address, fourcc, pos));
+            }
+            match &self.status {
+                Some(Ok(status)) => {
+                    ui.label(status);
+                }
+                Some(Err(err)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, err);
+                }
+                None => {}
+            }
+        });
+        self.open = open;
+    }
+
+    fn render_type(&mut self, ui: &mut egui::Ui, game_config: &toml::Table) {
+        ui.horizontal(|ui| {
+            egui::TextEdit::singleline(&mut self.fourcc)
+                .desired_width(50.0)
+                .char_limit(4)
+                .hint_text("fourCC")
+                .show(ui);
+            let actors = game_config.get("actors").and_then(|v| v.as_table());
+            let selected = actors
+                .and_then(|actors| actors.get(&self.fourcc))
+                .and_then(|v| v.as_str())
+                .unwrap_or("Known actors");
+            egui::ComboBox::from_id_salt("actor_spawn_type").selected_text(selected).show_ui(
+                ui,
+                |ui| {
+                    for (fourcc, name) in actors.into_iter().flatten() {
+                        let name = name.as_str().unwrap_or_default();
+                        if ui
+                            .selectable_label(*fourcc == self.fourcc, format!("{fourcc} {name}"))
+                            .clicked()
+                        {
+                            self.fourcc = fourcc.clone();
+                        }
+                    }
+                },
+            );
+        });
+    }
+
+    fn render_pos(&mut self, ui: &mut egui::Ui, player_pos: Option<[f32; 3]>) {
+        ui.vertical(|ui| {
+            let mut at_player = self.pos.is_none();
+            ui.checkbox(&mut at_player, "At the player");
+            if at_player {
+                self.pos = None;
+                match player_pos {
+                    Some([x, y, z]) => ui.weak(format!("{x:.3}, {y:.3}, {z:.3}")),
+                    None => ui.weak("Player position not found"),
+                };
+                return;
+            }
+            let pos = self.pos.get_or_insert(player_pos.unwrap_or_default());
+            ui.horizontal(|ui| {
+                for value in pos {
+                    ui.add(egui::DragValue::new(value).speed(0.1).max_decimals(3));
+                }
+            });
+        });
+    }
+
+    fn spawn(
+        &self,
+        state: &mut State,
+        fields: &SpawnFields,
+        actor_manager_address: u32,
+        fourcc: u32,
+        pos: [f32; 3],
+    ) -> Result<String, String> {
+        state.request(actor_manager_address, 4);
+        let Some(actor_manager) = state.get_data(actor_manager_address) else {
+            return Err("Actor manager pointer not read yet".into());
+        };
+        let actor_manager = u32::from_le_bytes(actor_manager.try_into().unwrap_or([0; 4]));
+        if actor_manager == 0 {
+            return Err("Actor manager is NULL".into());
+        }
+
+        let pos_bytes: Vec<u8> =
+            pos.iter().flat_map(|&value| ((value * 4096.0) as i32).to_le_bytes()).collect();
+        let writes = [
+            (fields.type_offset, fourcc.to_le_bytes().to_vec()),
+            (fields.params_offset, self.params.to_le_bytes().to_vec()),
+            (fields.pos_offset, pos_bytes),
+            // Written last so that the game doesn't see a half-written request
+            (fields.request_offset, fields.request_value.to_le_bytes().to_vec()),
+        ];
+        let fourcc_name = self.fourcc.as_str();
+        for (offset, data) in writes {
+            let address = actor_manager.wrapping_add(offset);
+            log::info!("Spawn {fourcc_name}: writing {data:02x?} to {address:#010x}");
+            state.request_write(address, data);
+        }
+        let [x, y, z] = pos;
+        Ok(format!("Requested {fourcc_name} with {:#010x} at {x:.3}, {y:.3}, {z:.3}", self.params))
+    }
+}
+
+/// Reads a `Vec3p` of fixed-point coordinates as world units.
+fn read_pos(state: &mut State, address: u32) -> Option<[f32; 3]> {
+    state.request(address, 12);
+    let data = state.get_data(address)?;
+    let mut pos = [0.0; 3];
+    for (value, bytes) in pos.iter_mut().zip(data.chunks_exact(4)) {
+        *value = i32::from_le_bytes(bytes.try_into().ok()?) as f32 / 4096.0;
+    }
+    Some(pos)
+}
+
+/// Returns the fourCC as the game compares it, with the first character in the highest byte.
+fn parse_fourcc(text: &str) -> Option<u32> {
+    let bytes: [u8; 4] = text.as_bytes().try_into().ok()?;
+    bytes.iter().all(|byte| byte.is_ascii_graphic()).then(|| u32::from_be_bytes(bytes))
+}
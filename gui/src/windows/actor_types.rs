@@ -0,0 +1,215 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use eframe::egui;
+
+use crate::util::type_names::close_matches;
+
+const CONFIG_KEY: &str = "actors";
+
+/// Edits the `actors` table of the game config, which maps the fourCC of an actor to the name of
+/// its type. Lists the fourCCs seen in the actor table this session next to the mapped ones, and
+/// highlights the ones without a mapping.
+#[derive(Default)]
+pub struct ActorTypesWindow {
+    pub open: bool,
+    /// FourCCs of the actors read this session
+    observed: BTreeSet<String>,
+    /// Type names being typed, by fourCC
+    editing: BTreeMap<String, String>,
+}
+
+impl ActorTypesWindow {
+    pub fn observe<'a>(&mut self, fourccs: impl IntoIterator<Item = &'a str>) {
+        for fourcc in fourccs {
+            if !self.observed.contains(fourcc) {
+                self.observed.insert(fourcc.to_string());
+            }
+        }
+    }
+
+    /// Returns true if the config was changed.
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        types: &type_crawler::Types,
+        type_names: &[String],
+        game_config: &mut toml::Table,
+    ) -> bool {
+        let mut open = self.open;
+        let mut changed = false;
+        egui::Window::new("Actor types").open(&mut open).resizable(true).show(ctx, |ui| {
+            let mapping = actor_mapping(game_config);
+            // Owned so that the edited text can be updated while listing them
+            let fourccs: BTreeSet<String> = self
+                .observed
+                .iter()
+                .cloned()
+                .chain(mapping.keys().map(|fourcc| fourcc.to_string()))
+                .collect();
+            let unmapped: Vec<&str> = fourccs
+                .iter()
+                .map(String::as_str)
+                .filter(|fourcc| !mapping.contains_key(fourcc))
+                .collect();
+
+            let mut updates: Vec<(String, String)> = Vec::new();
+            ui.horizontal(|ui| {
+                ui.label(format!("{} fourCCs, {} unmapped", fourccs.len(), unmapped.len()));
+                if ui
+                    .add_enabled(!unmapped.is_empty(), egui::Button::new("Guess unmapped"))
+                    .on_hover_text("Map fourCCs to the types whose names equal or contain them")
+                    .clicked()
+                {
+                    for &fourcc in &unmapped {
+                        if let Some(type_name) = guess_actor_type(fourcc, type_names) {
+                            updates.push((fourcc.to_string(), type_name.to_string()));
+                        }
+                    }
+                    if updates.is_empty() {
+                        log::info!("No type names match the unmapped fourCCs");
+                    }
+                }
+            });
+            if fourccs.is_empty() {
+                ui.weak("Open the Actors window to collect the fourCCs of the loaded actors");
+            }
+            ui.separator();
+
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                egui::Grid::new("actor_types").num_columns(2).striped(true).show(ui, |ui| {
+                    for fourcc in &fourccs {
+                        let mapped = mapping.get(fourcc.as_str()).copied();
+                        let label = egui::RichText::new(fourcc).monospace();
+                        if mapped.is_some() {
+                            ui.label(label);
+                        } else {
+                            ui.colored_label(egui::Color32::ORANGE, label).on_hover_text(
+                                "Not mapped, shown as Actor unless its vtable is known",
+                            );
+                        }
+                        let update = self.render_type_name(ui, types, type_names, fourcc, mapped);
+                        updates.extend(update.map(|type_name| (fourcc.clone(), type_name)));
+                        ui.end_row();
+                    }
+                });
+            });
+
+            for (fourcc, type_name) in updates {
+                self.editing.remove(&fourcc);
+                changed |= set_actor_type(game_config, &fourcc, &type_name);
+            }
+        });
+        self.open = open;
+        changed
+    }
+
+    /// Shows the type name of a fourCC with suggestions from the loaded types if it isn't one of
+    /// them. Returns the new type name once editing is done, empty to remove the mapping.
+    fn render_type_name(
+        &mut self,
+        ui: &mut egui::Ui,
+        types: &type_crawler::Types,
+        type_names: &[String],
+        fourcc: &str,
+        mapped: Option<&str>,
+    ) -> Option<String> {
+        let mut update = None;
+        ui.vertical(|ui| {
+            let mut text = match self.editing.get(fourcc) {
+                Some(text) => text.clone(),
+                None => mapped.unwrap_or_default().to_string(),
+            };
+            let response = egui::TextEdit::singleline(&mut text)
+                .id_salt(("actor_type", fourcc))
+                .desired_width(200.0)
+                .hint_text("Type name")
+                .show(ui)
+                .response;
+            if response.changed() {
+                self.editing.insert(fourcc.to_string(), text.clone());
+            }
+            let name = text.trim();
+            if !name.is_empty() && types.get(name).is_none() {
+                response.on_hover_text("Not found in the loaded types");
+                ui.horizontal_wrapped(|ui| {
+                    ui.colored_label(ui.visuals().error_fg_color, "Not found");
+                    for suggestion in close_matches(name, type_names, 5) {
+                        if ui.small_button(suggestion).clicked() {
+                            update = Some(suggestion.to_string());
+                        }
+                    }
+                });
+            } else if response.lost_focus() {
+                self.editing.remove(fourcc);
+                if name != mapped.unwrap_or_default() {
+                    update = Some(name.to_string());
+                }
+            }
+        });
+        update
+    }
+}
+
+/// Returns the type names of the `actors` table of the game config, by fourCC.
+fn actor_mapping(game_config: &toml::Table) -> BTreeMap<&str, &str> {
+    let table = game_config.get(CONFIG_KEY).and_then(|v| v.as_table());
+    table
+        .into_iter()
+        .flatten()
+        .filter_map(|(fourcc, type_name)| Some((fourcc.as_str(), type_name.as_str()?)))
+        .collect()
+}
+
+/// Maps `fourcc` to `type_name`, or removes its mapping if the name is empty. Returns true if the
+/// config was changed.
+fn set_actor_type(game_config: &mut toml::Table, fourcc: &str, type_name: &str) -> bool {
+    let table = game_config.entry(CONFIG_KEY).or_insert_with(|| toml::Table::new().into());
+    let Some(table) = table.as_table_mut() else {
+        log::error!("'{CONFIG_KEY}' config is not a table");
+        return false;
+    };
+    if type_name.is_empty() {
+        return table.remove(fourcc).is_some();
+    }
+    let previous = table.insert(fourcc.to_string(), type_name.into());
+    previous.and_then(|previous| previous.as_str().map(|p| p != type_name)).unwrap_or(true)
+}
+
+/// Finds a type for an actor by name: one called like the fourCC, or else the shortest one which
+/// contains it. Case is ignored.
+fn guess_actor_type<'a>(fourcc: &str, type_names: &'a [String]) -> Option<&'a str> {
+    let fourcc = fourcc.to_ascii_lowercase();
+    let lower = |name: &String| name.to_ascii_lowercase();
+    if let Some(name) = type_names.iter().find(|name| lower(name) == fourcc) {
+        return Some(name);
+    }
+    type_names
+        .iter()
+        .filter(|name| lower(name).contains(&fourcc))
+        .min_by_key(|name| name.len())
+        .map(String::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guess_actor_type() {
+        let names: Vec<String> =
+            ["Actor", "ActorNpcBig", "ActorNpc", "Npca", "ActorBomb"].map(String::from).into();
+        assert_eq!(guess_actor_type("NPCA", &names), Some("Npca"));
+        assert_eq!(guess_actor_type("BOMB", &names), Some("ActorBomb"));
+        assert_eq!(guess_actor_type("ANPC", &names), None);
+    }
+
+    #[test]
+    fn test_set_actor_type() {
+        let mut game_config = toml::Table::new();
+        assert!(set_actor_type(&mut game_config, "BOMB", "ActorBomb"));
+        assert!(!set_actor_type(&mut game_config, "BOMB", "ActorBomb"));
+        assert_eq!(actor_mapping(&game_config).get("BOMB"), Some(&"ActorBomb"));
+        assert!(set_actor_type(&mut game_config, "BOMB", ""));
+        assert!(actor_mapping(&game_config).is_empty());
+    }
+}
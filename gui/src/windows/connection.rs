@@ -0,0 +1,89 @@
+use dsv_core::gdb::quirks::StubInfo;
+use eframe::egui;
+
+use crate::{client::Client, views::actors::ActorManagerFrame};
+
+#[derive(Default)]
+pub struct ConnectionWindow {
+    pub open: bool,
+}
+
+impl ConnectionWindow {
+    /// `stub` is `None` for traces.
+    pub fn render(&mut self, ctx: &egui::Context, client: &Client, stub: Option<&StubInfo>) {
+        let mut open = self.open;
+        egui::Window::new("Connection").open(&mut open).resizable(false).show(ctx, |ui| {
+            if let Some(stub) = stub {
+                Self::render_stub(ui, stub);
+                ui.separator();
+            }
+            let Some(stats) = client.connection_stats.lock().unwrap().clone() else {
+                ui.label("No connection statistics available");
+                return;
+            };
+            egui::Grid::new("connection_stats").num_columns(2).striped(true).show(ui, |ui| {
+                ui.label("Packets sent");
+                ui.label(stats.packets_sent.to_string());
+                ui.end_row();
+                ui.label("Packets received");
+                ui.label(stats.packets_received.to_string());
+                ui.end_row();
+                ui.label("Latency").on_hover_text("Average time from a request to its reply");
+                match stats.average_round_trip() {
+                    Some(latency) => ui.label(format!("{:.2} ms", latency.as_secs_f64() * 1000.0)),
+                    None => ui.label("-"),
+                };
+                ui.end_row();
+                ui.label("Retransmits");
+                ui.label(stats.retransmits.to_string());
+                ui.end_row();
+                ui.label("NAKs sent");
+                ui.label(stats.naks_sent.to_string());
+                ui.end_row();
+                ui.label("Unchanged by CRC")
+                    .on_hover_text("Large reads skipped because qCRC matched the last data");
+                ui.label(format!("{} of {}", stats.crc_hits, stats.crc_hits + stats.crc_misses));
+                ui.end_row();
+                let state = client.state.lock().unwrap();
+                ui.label("Bytes per update")
+                    .on_hover_text("Bytes of the requests which were due in the last update");
+                ui.label(state.bytes_per_update().to_string());
+                ui.end_row();
+                if let Some(bytes) = ActorManagerFrame::last_request_bytes(ctx) {
+                    ui.label("Actor bytes").on_hover_text(
+                        "Bytes requested for the actor manager, its table and the resolved actors",
+                    );
+                    ui.label(bytes.to_string());
+                    ui.end_row();
+                }
+                let write_conflicts = state.write_conflicts();
+                ui.label("Write conflicts");
+                if write_conflicts > 0 {
+                    ui.colored_label(egui::Color32::ORANGE, write_conflicts.to_string())
+                        .on_hover_text("Overlapping writes were queued in the same frame");
+                } else {
+                    ui.label("0");
+                }
+                ui.end_row();
+            });
+        });
+        self.open = open;
+    }
+
+    fn render_stub(ui: &mut egui::Ui, stub: &StubInfo) {
+        ui.horizontal(|ui| {
+            ui.label("GDB stub");
+            let text = egui::RichText::new(stub.identification()).monospace();
+            if ui
+                .add(egui::Label::new(text).sense(egui::Sense::click()))
+                .on_hover_text("Click to copy")
+                .clicked()
+            {
+                ui.ctx().copy_text(stub.identification());
+            }
+        });
+        for limitation in &stub.limitations {
+            ui.colored_label(egui::Color32::YELLOW, *limitation);
+        }
+    }
+}
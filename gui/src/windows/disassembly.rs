@@ -0,0 +1,174 @@
+use dsv_core::{
+    disasm::{self, Instruction, Mode},
+    state::State,
+};
+use eframe::egui::{self, Widget};
+
+use crate::{client::StoppedAt, util::symbols::describe_address, views::actor_type::parse_address};
+
+const DEFAULT_LENGTH: usize = 0x80;
+const MAX_LENGTH: usize = 0x1000;
+
+/// Shows the code at an address as ARM or Thumb instructions.
+pub struct DisassemblyWindow {
+    pub open: bool,
+    address_text: String,
+    mode: Mode,
+    /// Number of bytes to disassemble
+    length: usize,
+}
+
+impl Default for DisassemblyWindow {
+    fn default() -> Self {
+        Self {
+            open: false,
+            address_text: String::new(),
+            mode: Mode::Arm,
+            length: DEFAULT_LENGTH,
+        }
+    }
+}
+
+impl DisassemblyWindow {
+    fn id() -> egui::Id {
+        egui::Id::new("disassemble")
+    }
+
+    /// Opens the window at `address`, from a function pointer or another window.
+    pub fn disassemble(ctx: &egui::Context, address: u32, mode: Mode) {
+        ctx.data_mut(|data| data.insert_temp(Self::id(), Some((address, mode))));
+    }
+
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        state: &mut State,
+        game_config: &toml::Table,
+        stopped_at: Option<StoppedAt>,
+    ) {
+        let opened = ctx.data_mut(|data| data.remove_temp::<Option<(u32, Mode)>>(Self::id()));
+        if let Some((address, mode)) = opened.flatten() {
+            self.go_to(address, mode);
+        }
+
+        let mut open = self.open;
+        egui::Window::new("Disassembly").open(&mut open).resizable(true).show(ctx, |ui| {
+            let address = parse_address(self.address_text.trim());
+            self.render_options(ui, address.is_none(), stopped_at);
+            let Some(address) = address else {
+                return;
+            };
+            ui.separator();
+            // Instructions are aligned, also in case a function pointer with the Thumb bit is
+            // pasted
+            let address = address & !(self.mode.instruction_size() as u32 - 1);
+            state.request(address, self.length);
+            let Some(data) = state.get_data_range(address, self.length) else {
+                ui.label("Data not found");
+                return;
+            };
+            let instructions = disasm::disassemble(data, address, self.mode);
+            let pc = stopped_at.map(|stopped_at| stopped_at.pc);
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                self.render_instructions(ui, state, game_config, &instructions, pc);
+            });
+        });
+        self.open = open;
+    }
+
+    fn go_to(&mut self, address: u32, mode: Mode) {
+        self.open = true;
+        self.address_text = format!("{address:#010x}");
+        self.mode = mode;
+    }
+
+    fn render_options(&mut self, ui: &mut egui::Ui, invalid: bool, stopped_at: Option<StoppedAt>) {
+        ui.horizontal(|ui| {
+            let text_color = invalid.then(|| ui.visuals().error_fg_color);
+            egui::TextEdit::singleline(&mut self.address_text)
+                .desired_width(90.0)
+                .hint_text("0x02000000")
+                .text_color_opt(text_color)
+                .show(ui);
+            ui.selectable_value(&mut self.mode, Mode::Arm, "ARM");
+            ui.selectable_value(&mut self.mode, Mode::Thumb, "Thumb");
+            egui::DragValue::new(&mut self.length)
+                .range(4..=MAX_LENGTH)
+                .hexadecimal(1, false, false)
+                .prefix("0x")
+                .suffix(" bytes")
+                .ui(ui);
+            if let Some(stopped_at) = stopped_at
+                && ui.button("Go to PC").clicked()
+            {
+                let mode = match stopped_at.thumb {
+                    Some(true) => Mode::Thumb,
+                    Some(false) => Mode::Arm,
+                    None => self.mode,
+                };
+                self.go_to(stopped_at.pc, mode);
+            }
+        });
+    }
+
+    fn render_instructions(
+        &mut self,
+        ui: &mut egui::Ui,
+        state: &mut State,
+        game_config: &toml::Table,
+        instructions: &[Instruction],
+        pc: Option<u32>,
+    ) {
+        let mut go_to = None;
+        egui::Grid::new("disassembly").num_columns(4).striped(true).show(ui, |ui| {
+            for instruction in instructions {
+                let address = format!("{:08x}", instruction.address);
+                if pc == Some(instruction.address) {
+                    ui.label(egui::RichText::new(address).monospace().strong())
+                        .on_hover_text("Current PC");
+                } else {
+                    ui.monospace(address);
+                }
+                let raw = match instruction.size {
+                    2 => format!("{:04x}", instruction.raw),
+                    _ => format!("{:08x}", instruction.raw),
+                };
+                ui.weak(egui::RichText::new(raw).monospace());
+                ui.monospace(format!("{:<8}{}", instruction.mnemonic, instruction.operands));
+
+                ui.horizontal(|ui| {
+                    if let Some(target) = instruction.branch_target {
+                        let text = describe_address(game_config, target)
+                            .unwrap_or_else(|| format!("{target:#010x}"));
+                        if ui.link(text).on_hover_text("Go to branch target").clicked() {
+                            go_to = Some((target, self.branch_mode(instruction)));
+                        }
+                    }
+                    if let Some(literal) = instruction.literal_address {
+                        state.request(literal, 4);
+                        if let Some(bytes) = state.get_data_range(literal, 4) {
+                            let value = u32::from_le_bytes(bytes.try_into().unwrap());
+                            ui.weak(format!("= {value:#010x}"));
+                            if let Some(name) = describe_address(game_config, value) {
+                                ui.weak(name);
+                            }
+                        }
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        if let Some((address, mode)) = go_to {
+            self.go_to(address, mode);
+        }
+    }
+
+    /// Returns the state which the target of a branch runs in, as `blx` switches it.
+    fn branch_mode(&self, instruction: &Instruction) -> Mode {
+        match (instruction.mnemonic.as_str(), self.mode) {
+            ("blx", Mode::Arm) => Mode::Thumb,
+            ("blx", Mode::Thumb) => Mode::Arm,
+            (_, mode) => mode,
+        }
+    }
+}
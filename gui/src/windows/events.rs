@@ -0,0 +1,106 @@
+use std::time::{Duration, Instant};
+
+use dsv_core::state::State;
+use eframe::egui;
+
+use crate::client::Client;
+
+/// How long a window is outlined after one of its watches changed.
+const FLASH_DURATION: Duration = Duration::from_millis(750);
+
+#[derive(Default)]
+pub struct EventsWindow {
+    pub open: bool,
+}
+
+impl EventsWindow {
+    pub fn render(&mut self, ctx: &egui::Context, client: &Client) {
+        let mut open = self.open;
+        egui::Window::new("Events").open(&mut open).resizable(true).show(ctx, |ui| {
+            let mut state = client.state.lock().unwrap();
+            ui.horizontal(|ui| {
+                let mut halt_on_change = state.halt_on_change();
+                if ui.checkbox(&mut halt_on_change, "Halt on change").changed() {
+                    state.set_halt_on_change(halt_on_change);
+                }
+                if state.is_halted() {
+                    ui.colored_label(egui::Color32::ORANGE, "Halted");
+                    if ui.button("Continue").clicked() {
+                        state.continue_updates();
+                    }
+                }
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.strong("Watches");
+                if ui.button("Clear").clicked() {
+                    state.clear_watches();
+                }
+            });
+            if state.watches().is_empty() {
+                ui.weak("Right-click a field and enable \"Break on change\" to watch it");
+            }
+            let mut remove = None;
+            egui::Grid::new("watches").num_columns(3).striped(true).show(ui, |ui| {
+                for watch in state.watches() {
+                    if ui.small_button("✖").on_hover_text("Remove watch").clicked() {
+                        remove = Some((watch.address, watch.size));
+                    }
+                    ui.label(&watch.label);
+                    ui.monospace(format!("{:#010x}, {} bytes", watch.address, watch.size));
+                    ui.end_row();
+                }
+            });
+            if let Some((address, size)) = remove {
+                state.remove_watch(address, size);
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.strong("Changes");
+                if ui.button("Clear").clicked() {
+                    state.clear_watch_events();
+                }
+            });
+            egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+                egui::Grid::new("watch_events").num_columns(4).striped(true).show(ui, |ui| {
+                    for event in state.watch_events() {
+                        let time = event.time.saturating_duration_since(client.connected_at());
+                        ui.monospace(format!("{:.3}s", time.as_secs_f32()));
+                        ui.label(&event.label);
+                        ui.monospace(event.kind.format(&event.old));
+                        ui.monospace(format!("→ {}", event.kind.format(&event.new)));
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+        self.open = open;
+    }
+}
+
+/// Outlines the windows whose watches changed recently.
+pub fn flash_windows(ctx: &egui::Context, state: &State) {
+    let now = Instant::now();
+    let recent_events = state
+        .watch_events()
+        .rev()
+        .take_while(|event| now.saturating_duration_since(event.time) < FLASH_DURATION);
+    for event in recent_events {
+        let Some(window) = &event.window else {
+            continue;
+        };
+        let window_id = egui::Id::new(window.as_str());
+        let Some(rect) = ctx.memory(|memory| memory.area_rect(window_id)) else {
+            continue;
+        };
+        let layer_id = egui::LayerId::new(egui::Order::Middle, window_id);
+        ctx.layer_painter(layer_id).rect_stroke(
+            rect,
+            ctx.style().visuals.window_corner_radius,
+            egui::Stroke::new(3.0, egui::Color32::ORANGE),
+            egui::StrokeKind::Inside,
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use dsv_core::disasm::Mode;
+use eframe::egui::{self, Widget};
+
+use crate::{
+    client::{Client, Command},
+    config::Config,
+    windows::disassembly::DisassemblyWindow,
+};
+
+#[derive(Default)]
+pub struct ExecutionWindow {
+    pub open: bool,
+    /// Text of the frame counter address once it has been edited
+    frame_counter_text: Option<String>,
+}
+
+impl ExecutionWindow {
+    /// Read budget in bytes when it is first enabled
+    const DEFAULT_READ_BUDGET: usize = 0x4000;
+
+    pub fn render(&mut self, ctx: &egui::Context, client: &Client, config: &mut Config) {
+        let mut open = self.open;
+        egui::Window::new("Execution").open(&mut open).resizable(false).show(ctx, |ui| {
+            let paused = client.is_paused();
+            ui.horizontal(|ui| {
+                if paused {
+                    if ui.button("Resume").clicked() {
+                        send(client, Command::Resume);
+                    }
+                } else if ui.button("Pause").clicked() {
+                    send(client, Command::Pause);
+                }
+                let step = ui
+                    .add_enabled(paused, egui::Button::new("Step frame"))
+                    .on_disabled_hover_text("Pause the target first");
+                if step.clicked() {
+                    let frame_counter = config.memory.frame_counter;
+                    send(client, Command::StepFrame { frame_counter });
+                }
+            });
+            if paused {
+                ui.colored_label(egui::Color32::ORANGE, "Paused, memory is still read");
+                if let Some(stopped_at) = client.stopped_at() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("PC: {:#010x}", stopped_at.pc));
+                        if stopped_at.thumb == Some(true) {
+                            ui.weak("Thumb");
+                        }
+                        if ui.small_button("Disassemble").clicked() {
+                            let mode = if stopped_at.thumb == Some(true) {
+                                Mode::Thumb
+                            } else {
+                                Mode::Arm
+                            };
+                            DisassemblyWindow::disassemble(ctx, stopped_at.pc, mode);
+                        }
+                    });
+                }
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label("Frame counter");
+                let mut text = self.frame_counter_text.clone().unwrap_or_else(|| {
+                    config.memory.frame_counter.map(|a| format!("{a:#010x}")).unwrap_or_default()
+                });
+                let response = egui::TextEdit::singleline(&mut text)
+                    .desired_width(90.0)
+                    .hint_text("None")
+                    .show(ui)
+                    .response
+                    .on_hover_text("Step until the u32 at this address changes");
+                let address = parse_address(text.trim());
+                if response.changed() {
+                    if let Some(address) = address {
+                        config.memory.frame_counter = address;
+                        config.unsaved_changes = true;
+                    }
+                    self.frame_counter_text = Some(text);
+                }
+                if address.is_none() {
+                    ui.colored_label(ui.visuals().error_fg_color, "Invalid address");
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Read interval");
+                let response = egui::DragValue::new(&mut config.memory.read_interval_ms)
+                    .range(1.0..=1000.0)
+                    .speed(0.5)
+                    .max_decimals(2)
+                    .suffix(" ms")
+                    .ui(ui)
+                    .on_hover_text("Time between reads of memory, 16.67 ms reads once per frame");
+                if response.changed() {
+                    client.set_read_interval(config.memory.read_interval());
+                    config.unsaved_changes = true;
+                }
+            });
+            ui.horizontal(|ui| {
+                let mut limited = config.memory.read_budget.is_some();
+                let mut changed = ui
+                    .checkbox(&mut limited, "Read budget")
+                    .on_hover_text(
+                        "Most bytes read per update, windows over the budget are read in later \
+                         updates",
+                    )
+                    .changed();
+                let mut budget = config.memory.read_budget.unwrap_or(Self::DEFAULT_READ_BUDGET);
+                if limited {
+                    changed |= egui::DragValue::new(&mut budget)
+                        .range(0x100..=0x100000)
+                        .speed(64)
+                        .suffix(" bytes")
+                        .ui(ui)
+                        .changed();
+                }
+                if changed {
+                    config.memory.read_budget = limited.then_some(budget);
+                    client.state.lock().unwrap().set_read_budget(config.memory.read_budget);
+                    config.unsaved_changes = true;
+                }
+            });
+        });
+        self.open = open;
+    }
+}
+
+fn send(client: &Client, cmd: Command) {
+    if let Err(e) = client.send_command(cmd) {
+        log::error!("Failed to control execution: {e}");
+    }
+}
+
+/// Parses an optional hex address, where an empty string means none.
+fn parse_address(text: &str) -> Option<Option<u32>> {
+    if text.is_empty() {
+        return Some(None);
+    }
+    let hex = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")).unwrap_or(text);
+    u32::from_str_radix(hex, 16).ok().map(Some)
+}
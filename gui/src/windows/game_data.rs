@@ -0,0 +1,281 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use eframe::egui;
+
+/// Tables of a game config which are worth sharing, unlike window layouts and pins.
+const SHARED_TABLES: &[&str] = &["actors", "vtables", "value_maps", "list_lengths", "symbols"];
+
+/// Exports and imports the shared tables of a game config as standalone TOML files.
+#[derive(Default)]
+pub struct GameDataWindow {
+    pub open: bool,
+    import: Option<Import>,
+    /// Outcome of the last export or import
+    status: Option<String>,
+}
+
+/// A file which was compared to the game config and waits to be merged into it.
+struct Import {
+    path: PathBuf,
+    added: Vec<Entry>,
+    conflicts: Vec<Conflict>,
+    unchanged: usize,
+    warnings: Vec<String>,
+}
+
+struct Entry {
+    table: String,
+    key: String,
+    value: toml::Value,
+}
+
+struct Conflict {
+    entry: Entry,
+    current: toml::Value,
+}
+
+enum ImportChoice {
+    /// Add new entries and keep the current value of conflicting entries
+    Keep,
+    /// Add new entries and replace conflicting entries
+    Replace,
+    Cancel,
+}
+
+impl GameDataWindow {
+    /// Returns true if the config was changed.
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        types: &type_crawler::Types,
+        game_config: &mut toml::Table,
+        game_key: &str,
+    ) -> bool {
+        let mut changed = false;
+        let mut open = self.open;
+        egui::Window::new("Game data").open(&mut open).resizable(true).show(ctx, |ui| {
+            ui.label("Share actor names, vtables, value maps, list lengths and symbols");
+            egui::Grid::new("game_data_tables").num_columns(2).show(ui, |ui| {
+                for &table in SHARED_TABLES {
+                    let count = game_config.get(table).and_then(|v| v.as_table()).map(|t| t.len());
+                    ui.label(table);
+                    ui.label(format!("{} entries", count.unwrap_or(0)));
+                    ui.end_row();
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Export...").clicked()
+                    && let Some(path) = rfd::FileDialog::new()
+                        .add_filter("TOML", &["toml"])
+                        .set_file_name(format!("{game_key}.toml"))
+                        .save_file()
+                {
+                    self.status = Some(match export(&path, game_config, game_key) {
+                        Ok(()) => format!("Exported to {}", path.display()),
+                        Err(e) => format!("Failed to export: {e:#}"),
+                    });
+                }
+                if ui.button("Import...").clicked()
+                    && let Some(path) =
+                        rfd::FileDialog::new().add_filter("TOML", &["toml"]).pick_file()
+                {
+                    match Import::read(path, types, game_config, game_key) {
+                        Ok(import) => {
+                            self.import = Some(import);
+                            self.status = None;
+                        }
+                        Err(e) => self.status = Some(format!("Failed to import: {e:#}")),
+                    }
+                }
+            });
+            if let Some(status) = &self.status {
+                ui.label(status);
+            }
+
+            let Some(import) = &self.import else {
+                return;
+            };
+            ui.separator();
+            let Some(choice) = import.render(ui) else {
+                return;
+            };
+            let import = self.import.take().unwrap();
+            match choice {
+                ImportChoice::Keep => self.status = Some(import.apply(game_config, false)),
+                ImportChoice::Replace => self.status = Some(import.apply(game_config, true)),
+                ImportChoice::Cancel => return,
+            }
+            changed = true;
+        });
+        self.open = open;
+        changed
+    }
+}
+
+fn export(path: &Path, game_config: &toml::Table, game_key: &str) -> Result<()> {
+    let mut file = toml::Table::new();
+    file.insert("game".into(), game_key.into());
+    for &table in SHARED_TABLES {
+        if let Some(value) = game_config.get(table) {
+            file.insert(table.into(), value.clone());
+        }
+    }
+    let text = toml::to_string(&file).context("Failed to serialize game data")?;
+    std::fs::write(path, text).context("Failed to write file")
+}
+
+impl Import {
+    fn read(
+        path: PathBuf,
+        types: &type_crawler::Types,
+        game_config: &toml::Table,
+        game_key: &str,
+    ) -> Result<Self> {
+        let text = std::fs::read_to_string(&path).context("Failed to read file")?;
+        let file: toml::Table = toml::from_str(&text).context("Failed to parse file")?;
+
+        let mut import = Import {
+            path,
+            added: Vec::new(),
+            conflicts: Vec::new(),
+            unchanged: 0,
+            warnings: Vec::new(),
+        };
+        for (table, value) in file {
+            if table == "game" {
+                if let Some(game) = value.as_str()
+                    && game != game_key
+                {
+                    import.warnings.push(format!("File was exported for '{game}'"));
+                }
+                continue;
+            }
+            if !SHARED_TABLES.contains(&table.as_str()) {
+                import.warnings.push(format!("Ignored unknown table '{table}'"));
+                continue;
+            }
+            let toml::Value::Table(entries) = value else {
+                import.warnings.push(format!("Ignored '{table}', which is not a table"));
+                continue;
+            };
+            let current = game_config.get(&table).and_then(|v| v.as_table());
+            for (key, value) in entries {
+                let entry = Entry { table: table.clone(), key, value };
+                match current.and_then(|current| current.get(&entry.key)) {
+                    None => import.added.push(entry),
+                    Some(current) if *current == entry.value => import.unchanged += 1,
+                    Some(current) => {
+                        import.conflicts.push(Conflict { entry, current: current.clone() })
+                    }
+                }
+            }
+        }
+        import.check_types(types);
+        Ok(import)
+    }
+
+    /// Warns about type names in new entries which aren't in the loaded types.
+    fn check_types(&mut self, types: &type_crawler::Types) {
+        let entries = self.added.iter().chain(self.conflicts.iter().map(|c| &c.entry));
+        if types.is_empty() {
+            if entries.count() > 0 {
+                self.warnings.push("Types are not loaded, so type names were not checked".into());
+            }
+            return;
+        }
+        let mut warnings = Vec::new();
+        for entry in entries {
+            let type_name = match entry.table.as_str() {
+                "actors" | "vtables" => entry.value.as_str(),
+                "value_maps" => entry.value.get("enum").and_then(|v| v.as_str()),
+                _ => None,
+            };
+            if let Some(type_name) = type_name
+                && types.get(type_name).is_none()
+            {
+                warnings
+                    .push(format!("{}.{}: type '{type_name}' not found", entry.table, entry.key));
+            }
+        }
+        self.warnings.extend(warnings);
+    }
+
+    /// Shows what the import would change and returns the choice of the user, if any.
+    fn render(&self, ui: &mut egui::Ui) -> Option<ImportChoice> {
+        ui.strong(format!("Import {}", self.path.display()));
+        ui.label(format!(
+            "{} new, {} unchanged, {} conflicting",
+            self.added.len(),
+            self.unchanged,
+            self.conflicts.len()
+        ));
+        for warning in &self.warnings {
+            ui.colored_label(egui::Color32::YELLOW, warning);
+        }
+        if !self.conflicts.is_empty() {
+            egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                egui::Grid::new("game_data_conflicts").num_columns(3).striped(true).show(
+                    ui,
+                    |ui| {
+                        for conflict in &self.conflicts {
+                            ui.label(format!("{}.{}", conflict.entry.table, conflict.entry.key));
+                            ui.monospace(conflict.current.to_string());
+                            ui.monospace(format!("→ {}", conflict.entry.value));
+                            ui.end_row();
+                        }
+                    },
+                );
+            });
+        }
+
+        let mut result = None;
+        ui.horizontal(|ui| {
+            if self.conflicts.is_empty() {
+                if ui.button("Import").clicked() {
+                    result = Some(ImportChoice::Keep);
+                }
+            } else {
+                if ui.button("Keep existing").clicked() {
+                    result = Some(ImportChoice::Keep);
+                }
+                if ui.button("Replace existing").clicked() {
+                    result = Some(ImportChoice::Replace);
+                }
+            }
+            if ui.button("Cancel").clicked() {
+                result = Some(ImportChoice::Cancel);
+            }
+        });
+        result
+    }
+
+    /// Merges the import into the game config and returns a summary.
+    fn apply(self, game_config: &mut toml::Table, replace: bool) -> String {
+        let conflicts = self.conflicts.len();
+        let mut entries = self.added;
+        let added = entries.len();
+        if replace {
+            entries.extend(self.conflicts.into_iter().map(|conflict| conflict.entry));
+        }
+        let mut skipped = 0;
+        for entry in entries {
+            let table = game_config
+                .entry(entry.table.as_str())
+                .or_insert_with(|| toml::Table::new().into());
+            let Some(table) = table.as_table_mut() else {
+                log::error!("'{}' config is not a table, skipping '{}'", entry.table, entry.key);
+                skipped += 1;
+                continue;
+            };
+            table.insert(entry.key, entry.value);
+        }
+        let (replaced, kept) = if replace { (conflicts, 0) } else { (0, conflicts) };
+        log::info!("Imported game data from {}", self.path.display());
+        let mut summary = format!("Added {added}, replaced {replaced}, kept {kept}");
+        if skipped > 0 {
+            summary.push_str(&format!(", skipped {skipped}"));
+        }
+        summary
+    }
+}
@@ -0,0 +1,81 @@
+use dsv_core::memory::map::MemoryMap;
+use eframe::egui;
+
+use crate::{
+    util::symbols::{DataSymbol, data_symbols, symbol_type_name},
+    views::address::AddressWindow,
+};
+
+/// Lists the global variables in the `symbols` table of the game config by section, see
+/// [`data_symbols`].
+#[derive(Default)]
+pub struct GlobalsWindow {
+    pub open: bool,
+    filter: String,
+}
+
+impl GlobalsWindow {
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        types: &type_crawler::Types,
+        game_config: &toml::Table,
+        memory_map: &MemoryMap,
+    ) {
+        let mut open = self.open;
+        egui::Window::new("Globals").open(&mut open).resizable(true).show(ctx, |ui| {
+            let symbols = data_symbols(game_config, memory_map);
+            if symbols.is_empty() {
+                ui.weak("No data symbols, add them to the symbols table of the game config");
+                return;
+            }
+            egui::TextEdit::singleline(&mut self.filter)
+                .desired_width(200.0)
+                .hint_text("Filter by name")
+                .show(ui);
+            let filter = self.filter.trim().to_lowercase();
+            let symbols: Vec<_> = symbols
+                .iter()
+                .filter(|symbol| symbol.name.to_lowercase().contains(&filter))
+                .collect();
+
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                for section in symbols.chunk_by(|a, b| a.section == b.section) {
+                    let name = &section[0].section;
+                    egui::CollapsingHeader::new(format!("{name} ({})", section.len()))
+                        .id_salt(("globals_section", name))
+                        .default_open(true)
+                        .show(ui, |ui| render_section(ui, types, name, section));
+                }
+            });
+        });
+        self.open = open;
+    }
+}
+
+fn render_section(
+    ui: &mut egui::Ui,
+    types: &type_crawler::Types,
+    name: &str,
+    symbols: &[&DataSymbol],
+) {
+    egui::Grid::new(("globals", name)).num_columns(3).striped(true).show(ui, |ui| {
+        for symbol in symbols {
+            ui.monospace(format!("{:#010x}", symbol.address));
+            let type_name = symbol_type_name(types, &symbol.name);
+            let hover = match type_name {
+                Some(type_name) => format!("Open as {type_name}"),
+                None => "Open with the address filled in".into(),
+            };
+            let button = ui.add(egui::Button::new(&symbol.name).frame(false));
+            if button.on_hover_text(hover).clicked() {
+                match type_name {
+                    Some(type_name) => AddressWindow::open_as(ui.ctx(), symbol.address, type_name),
+                    None => AddressWindow::open(ui.ctx(), symbol.address, true),
+                }
+            }
+            ui.weak(type_name.unwrap_or_default());
+            ui.end_row();
+        }
+    });
+}
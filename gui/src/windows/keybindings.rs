@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+
+use eframe::egui;
+
+use crate::{
+    util::keybindings::{self, COMMAND_PALETTE, format_shortcut},
+    windows::palette::PaletteAction,
+};
+
+/// Lists the shortcuts of the command palette actions and lets the user rebind them.
+#[derive(Default)]
+pub struct KeybindingsWindow {
+    pub open: bool,
+    /// ID of the action waiting for its new shortcut
+    capturing: Option<String>,
+}
+
+impl KeybindingsWindow {
+    /// Returns true while waiting for a shortcut, when shortcuts shouldn't run their actions.
+    pub fn is_capturing(&self) -> bool {
+        self.open && self.capturing.is_some()
+    }
+
+    /// Renders the actions which can be bound, which are those without an address. Returns true if
+    /// `overrides` was changed.
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        actions: &[PaletteAction],
+        overrides: &mut BTreeMap<String, String>,
+    ) -> bool {
+        let mut changed = false;
+        if let Some(id) = self.capturing.clone() {
+            match capture_shortcut(ctx) {
+                Some(Capture::Cancel) => self.capturing = None,
+                Some(Capture::Shortcut(shortcut)) => {
+                    keybindings::set_binding(overrides, &id, Some(&shortcut));
+                    self.capturing = None;
+                    changed = true;
+                }
+                None => {}
+            }
+        }
+
+        let bindings = keybindings::bindings(overrides);
+        let conflicts = keybindings::conflicts(&bindings);
+        let mut rows = vec![(COMMAND_PALETTE, "Open command palette")];
+        rows.extend(
+            actions
+                .iter()
+                .filter(|action| action.address_hint.is_none())
+                .map(|action| (action.id.as_str(), action.label.as_str())),
+        );
+
+        let mut open = self.open;
+        egui::Window::new("Keyboard shortcuts").open(&mut open).resizable(true).show(ctx, |ui| {
+            ui.weak("Click a shortcut, then press the new keys. Escape cancels.");
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                egui::Grid::new("keybindings").num_columns(3).striped(true).show(ui, |ui| {
+                    for (id, label) in rows {
+                        ui.label(label);
+                        let capturing = self.capturing.as_deref() == Some(id);
+                        let text = match bindings.get(id) {
+                            _ if capturing => "Press keys…".to_string(),
+                            Some(shortcut) => format_shortcut(shortcut),
+                            None => "None".to_string(),
+                        };
+                        let text = if conflicts.iter().any(|conflict| conflict == id) {
+                            egui::RichText::new(text).color(egui::Color32::ORANGE)
+                        } else {
+                            egui::RichText::new(text)
+                        };
+                        let mut response = ui.selectable_label(capturing, text);
+                        if conflicts.iter().any(|conflict| conflict == id) {
+                            response =
+                                response.on_hover_text("Another action has the same shortcut");
+                        }
+                        if response.clicked() {
+                            self.capturing = if capturing { None } else { Some(id.to_string()) };
+                        }
+                        if bindings.contains_key(id) && ui.small_button("Clear").clicked() {
+                            keybindings::set_binding(overrides, id, None);
+                            changed = true;
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+        if !open {
+            self.capturing = None;
+        }
+        self.open = open;
+        changed
+    }
+}
+
+enum Capture {
+    Cancel,
+    Shortcut(egui::KeyboardShortcut),
+}
+
+/// Takes the next key press, so that it doesn't also reach other widgets.
+fn capture_shortcut(ctx: &egui::Context) -> Option<Capture> {
+    ctx.input_mut(|i| {
+        let (key, modifiers) = i.events.iter().find_map(|event| match event {
+            egui::Event::Key { key, pressed: true, modifiers, .. } => Some((*key, *modifiers)),
+            _ => None,
+        })?;
+        i.consume_key(modifiers, key);
+        if key == egui::Key::Escape {
+            return Some(Capture::Cancel);
+        }
+        let modifiers = egui::Modifiers {
+            // Shortcuts are stored with the command modifier so that they work on macOS too
+            command: modifiers.command || modifiers.ctrl,
+            ctrl: false,
+            mac_cmd: false,
+            ..modifiers
+        };
+        Some(Capture::Shortcut(egui::KeyboardShortcut::new(modifiers, key)))
+    })
+}
@@ -0,0 +1,41 @@
+use dsv_core::types::mirror::LayoutReport;
+use eframe::egui;
+
+/// Compares the structs which dsv-core reads by itself to the types of the same name in the loaded
+/// headers, see [`dsv_core::types::mirror`].
+#[derive(Default)]
+pub struct LayoutChecksWindow {
+    pub open: bool,
+}
+
+impl LayoutChecksWindow {
+    pub fn render(&mut self, ctx: &egui::Context, reports: &[LayoutReport]) {
+        let mut open = self.open;
+        egui::Window::new("Layout checks").open(&mut open).resizable(false).show(ctx, |ui| {
+            ui.weak("Structs built into dsv compared to the loaded headers");
+            egui::Grid::new("layout_checks").num_columns(3).striped(true).show(ui, |ui| {
+                ui.strong("Struct");
+                ui.strong("Header type");
+                ui.strong("Result");
+                ui.end_row();
+                for report in reports {
+                    ui.monospace(report.rust_name);
+                    ui.monospace(report.type_name);
+                    if !report.found {
+                        ui.weak("Not in headers");
+                    } else if report.is_ok() {
+                        ui.colored_label(egui::Color32::GREEN, "OK");
+                    } else {
+                        ui.vertical(|ui| {
+                            for mismatch in &report.mismatches {
+                                ui.colored_label(ui.visuals().error_fg_color, mismatch.to_string());
+                            }
+                        });
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        self.open = open;
+    }
+}
@@ -0,0 +1,87 @@
+use eframe::egui;
+
+use crate::util::log_buffer::{self, LogRecord};
+
+pub struct LogWindow {
+    pub open: bool,
+    /// Least severe level which is listed
+    level: log::LevelFilter,
+    auto_scroll: bool,
+}
+
+impl Default for LogWindow {
+    fn default() -> Self {
+        Self { open: false, level: log::LevelFilter::Info, auto_scroll: true }
+    }
+}
+
+impl LogWindow {
+    pub fn render(&mut self, ctx: &egui::Context) {
+        let mut open = self.open;
+        egui::Window::new("Log").open(&mut open).resizable(true).show(ctx, |ui| {
+            log_buffer::mark_errors_seen();
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_salt("log_level")
+                    .selected_text(self.level.as_str())
+                    .show_ui(ui, |ui| {
+                        for level in log::LevelFilter::iter().skip(1) {
+                            ui.selectable_value(&mut self.level, level, level.as_str());
+                        }
+                    });
+                ui.checkbox(&mut self.auto_scroll, "Auto-scroll");
+                if ui.button("Copy all").clicked() {
+                    let text = log_buffer::with_records(|records| {
+                        records
+                            .iter()
+                            .filter(|record| record.level <= self.level)
+                            .map(format_record)
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    });
+                    ui.ctx().copy_text(text);
+                }
+                if ui.button("Clear").clicked() {
+                    log_buffer::clear();
+                }
+            });
+            ui.separator();
+            egui::ScrollArea::vertical().stick_to_bottom(self.auto_scroll).show(ui, |ui| {
+                log_buffer::with_records(|records| {
+                    egui::Grid::new("log_records").num_columns(3).striped(true).show(ui, |ui| {
+                        for record in records.iter().filter(|record| record.level <= self.level) {
+                            ui.monospace(format!("{:.3}s", record.time.as_secs_f32()));
+                            ui.colored_label(level_color(ui, record.level), record.level.as_str());
+                            let message = ui
+                                .add(egui::Label::new(&record.message).sense(egui::Sense::click()))
+                                .on_hover_text(format!("{}\nClick to copy", record.target));
+                            if message.clicked() {
+                                ui.ctx().copy_text(format_record(record));
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        });
+        self.open = open;
+    }
+}
+
+fn format_record(record: &LogRecord) -> String {
+    format!(
+        "[{:.3}s {} {}] {}",
+        record.time.as_secs_f32(),
+        record.level,
+        record.target,
+        record.message
+    )
+}
+
+fn level_color(ui: &egui::Ui, level: log::Level) -> egui::Color32 {
+    match level {
+        log::Level::Error => ui.visuals().error_fg_color,
+        log::Level::Warn => ui.visuals().warn_fg_color,
+        log::Level::Info => ui.visuals().text_color(),
+        log::Level::Debug | log::Level::Trace => ui.visuals().weak_text_color(),
+    }
+}
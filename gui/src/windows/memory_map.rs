@@ -0,0 +1,85 @@
+use dsv_core::{memory::map::MemoryMap, state::State};
+use eframe::egui;
+
+use crate::views::address::AddressWindow;
+
+/// Lists the regions of the memory map and how much of each is being read.
+#[derive(Default)]
+pub struct MemoryMapWindow {
+    pub open: bool,
+}
+
+impl MemoryMapWindow {
+    /// `custom` is set if the map was overridden by the game config.
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        state: &State,
+        memory_map: &MemoryMap,
+        custom: bool,
+    ) {
+        let mut open = self.open;
+        egui::Window::new("Memory map").open(&mut open).resizable(true).show(ctx, |ui| {
+            if custom {
+                ui.weak("From the memory_map of the game config");
+            } else {
+                ui.weak("Default ARM9 map, override it with memory_map in the game config");
+            }
+            let mut usage = vec![(0, 0); memory_map.regions().len()];
+            let mut unmapped = (0, 0);
+            for (address, length) in state.requested_ranges() {
+                let index = memory_map.regions().iter().position(|region| region.contains(address));
+                let (requests, bytes) = match index {
+                    Some(index) => &mut usage[index],
+                    None => &mut unmapped,
+                };
+                *requests += 1;
+                *bytes += length;
+            }
+
+            egui::Grid::new("memory_map_regions").num_columns(6).striped(true).show(ui, |ui| {
+                ui.strong("Region");
+                ui.strong("Range");
+                ui.strong("Size");
+                ui.strong("Access");
+                ui.strong("Requests");
+                ui.end_row();
+                for (region, (requests, bytes)) in memory_map.regions().iter().zip(usage) {
+                    let name = ui.add(egui::Button::new(&region.name).frame(false));
+                    if name.on_hover_text("Open a hex view at the start").clicked() {
+                        AddressWindow::open(ctx, region.range.start, false);
+                    }
+                    ui.monospace(format!(
+                        "{:#010x}..{:#010x}",
+                        region.range.start, region.range.end
+                    ));
+                    ui.label(format_size(region.range.len()));
+                    let access = region.access.to_string();
+                    ui.monospace(if region.io { format!("{access} I/O") } else { access });
+                    if requests > 0 {
+                        ui.label(format!("{requests} ({})", format_size(bytes)));
+                    } else {
+                        ui.weak("-");
+                    }
+                    ui.end_row();
+                }
+            });
+            let (requests, bytes) = unmapped;
+            if requests > 0 {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    format!("{requests} requests ({}) outside of the map", format_size(bytes)),
+                );
+            }
+        });
+        self.open = open;
+    }
+}
+
+fn format_size(size: usize) -> String {
+    match size {
+        0..0x400 => format!("{size} B"),
+        0x400..0x100000 => format!("{} KB", size / 0x400),
+        _ => format!("{} MB", size / 0x100000),
+    }
+}
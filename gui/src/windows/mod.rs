@@ -0,0 +1,18 @@
+pub mod actor_spawn;
+pub mod actor_types;
+pub mod connection;
+pub mod disassembly;
+pub mod events;
+pub mod execution;
+pub mod game_data;
+pub mod globals;
+pub mod keybindings;
+pub mod layout_checks;
+pub mod log;
+pub mod memory_map;
+pub mod monitor;
+pub mod object_scan;
+pub mod palette;
+pub mod pointer_scan;
+pub mod stack;
+pub mod types;
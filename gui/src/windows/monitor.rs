@@ -0,0 +1,61 @@
+use eframe::egui;
+
+use crate::client::{Client, Command, ConsoleEntry};
+
+#[derive(Default)]
+pub struct MonitorWindow {
+    pub open: bool,
+    command: String,
+}
+
+impl MonitorWindow {
+    pub fn render(&mut self, ctx: &egui::Context, client: &Client) {
+        let mut open = self.open;
+        egui::Window::new("Monitor").open(&mut open).resizable(true).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let response = egui::TextEdit::singleline(&mut self.command)
+                    .desired_width(200.0)
+                    .hint_text("Monitor command")
+                    .show(ui)
+                    .response;
+                let submitted =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if (ui.button("Send").clicked() || submitted) && !self.command.is_empty() {
+                    let command = std::mem::take(&mut self.command);
+                    if let Err(e) = client.send_command(Command::Monitor(command)) {
+                        log::error!("Failed to send monitor command: {e}");
+                    }
+                }
+                if ui.button("Clear").clicked() {
+                    client.console.lock().unwrap().clear();
+                }
+            });
+            ui.separator();
+            egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+                for entry in client.console.lock().unwrap().iter() {
+                    match entry {
+                        ConsoleEntry::Monitor { command, result } => {
+                            ui.label(egui::RichText::new(format!("> {command}")).monospace());
+                            match result {
+                                Ok(output) => {
+                                    ui.label(egui::RichText::new(output).monospace());
+                                }
+                                Err(err) => {
+                                    ui.colored_label(egui::Color32::RED, err);
+                                }
+                            }
+                        }
+                        ConsoleEntry::Output(output) => {
+                            ui.label(
+                                egui::RichText::new(output.trim_end())
+                                    .monospace()
+                                    .color(egui::Color32::GRAY),
+                            );
+                        }
+                    }
+                }
+            });
+        });
+        self.open = open;
+    }
+}
@@ -0,0 +1,207 @@
+use dsv_core::{
+    memory::{
+        map::{MemoryMap, MemoryRegion},
+        scan::ValueScan,
+    },
+    state::State,
+};
+use eframe::egui::{self, Widget};
+
+use crate::{
+    util::symbols::describe_address,
+    views::{actor_type::parse_address, address::AddressWindow},
+    windows::pointer_scan::{region_selector, scannable_regions},
+};
+
+/// Finds objects of a type by a value they all hold at the same offset, usually their vtable
+/// address, so that objects outside of any manager or table can be opened. See [`ValueScan`].
+pub struct ObjectScanWindow {
+    pub open: bool,
+    type_name: String,
+    value_text: String,
+    offset: u32,
+    /// Alignment of the objects
+    stride: u32,
+    region_index: usize,
+    scan: Option<ValueScan>,
+}
+
+impl Default for ObjectScanWindow {
+    fn default() -> Self {
+        Self {
+            open: false,
+            type_name: String::new(),
+            value_text: String::new(),
+            offset: 0,
+            stride: 4,
+            region_index: 0,
+            scan: None,
+        }
+    }
+}
+
+impl ObjectScanWindow {
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        state: &mut State,
+        game_config: &toml::Table,
+        memory_map: &MemoryMap,
+    ) {
+        let mut open = self.open;
+        egui::Window::new("Find objects").open(&mut open).resizable(true).show(ctx, |ui| {
+            let regions = scannable_regions(memory_map);
+            self.render_options(ui, state, game_config, &regions);
+            let Some(scan) = &mut self.scan else {
+                return;
+            };
+            if !scan.step(state) {
+                ctx.request_repaint();
+            }
+            ui.separator();
+            self.render_results(ui, game_config);
+        });
+        if !open && let Some(scan) = &mut self.scan {
+            scan.cancel(state);
+        }
+        self.open = open;
+    }
+
+    fn render_options(
+        &mut self,
+        ui: &mut egui::Ui,
+        state: &mut State,
+        game_config: &toml::Table,
+        regions: &[&MemoryRegion],
+    ) {
+        let running = self.scan.as_ref().is_some_and(|scan| !scan.is_done());
+        let value = parse_address(self.value_text.trim());
+        egui::Grid::new("object_scan_options").num_columns(2).show(ui, |ui| {
+            ui.label("Type");
+            ui.horizontal(|ui| {
+                egui::TextEdit::singleline(&mut self.type_name)
+                    .desired_width(150.0)
+                    .hint_text("Type to open objects as")
+                    .show(ui);
+                let vtable = find_vtable(game_config, self.type_name.trim());
+                if ui
+                    .add_enabled(vtable.is_some(), egui::Button::new("Use vtable").small())
+                    .on_hover_text("Search for the vtable of this type")
+                    .on_disabled_hover_text("No vtable of this type in the game config")
+                    .clicked()
+                    && let Some(vtable) = vtable
+                {
+                    self.value_text = format!("{vtable:#010x}");
+                    self.offset = 0;
+                }
+            });
+            ui.end_row();
+
+            ui.label("Value");
+            let text_color = value.is_none().then(|| ui.visuals().error_fg_color);
+            egui::TextEdit::singleline(&mut self.value_text)
+                .desired_width(90.0)
+                .hint_text("0x020c1234")
+                .text_color_opt(text_color)
+                .show(ui)
+                .response
+                .on_hover_text("Vtable address or magic number, as a 32-bit word");
+            ui.end_row();
+
+            ui.label("Offset");
+            egui::DragValue::new(&mut self.offset)
+                .range(0..=0x1000)
+                .hexadecimal(1, false, false)
+                .prefix("0x")
+                .ui(ui)
+                .on_hover_text("Offset of the value in the object");
+            ui.end_row();
+
+            ui.label("Alignment");
+            egui::DragValue::new(&mut self.stride)
+                .range(1..=0x100)
+                .ui(ui)
+                .on_hover_text("Objects start at multiples of this many bytes");
+            ui.end_row();
+
+            ui.label("Region");
+            region_selector(ui, "object_scan_region", regions, &mut self.region_index);
+            ui.end_row();
+        });
+
+        ui.horizontal(|ui| {
+            if running {
+                if ui.button("Cancel").clicked()
+                    && let Some(scan) = &mut self.scan
+                {
+                    scan.cancel(state);
+                }
+                return;
+            }
+            let range = regions.get(self.region_index).map(|region| &region.range);
+            if ui
+                .add_enabled(value.is_some() && range.is_some(), egui::Button::new("Scan"))
+                .clicked()
+                && let (Some(value), Some(range)) = (value, range)
+            {
+                self.scan = Some(ValueScan::new(value, self.offset, self.stride, range.clone()));
+            }
+        });
+    }
+
+    fn render_results(&self, ui: &mut egui::Ui, game_config: &toml::Table) {
+        let Some(scan) = &self.scan else {
+            return;
+        };
+        if scan.is_done() {
+            ui.label(format!("{} objects found", scan.matches().len()));
+        } else {
+            ui.add(egui::ProgressBar::new(scan.progress()).show_percentage());
+        }
+        if scan.matches().len() >= ValueScan::MAX_MATCHES {
+            ui.colored_label(egui::Color32::YELLOW, "Too many matches, stopped scanning");
+        }
+        if scan.failed_chunks() > 0 {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                format!("{} chunks could not be read", scan.failed_chunks()),
+            );
+        }
+
+        let type_name = self.type_name.trim();
+        let row_height = ui.spacing().interact_size.y;
+        egui::ScrollArea::vertical().max_height(300.0).show_rows(
+            ui,
+            row_height,
+            scan.matches().len(),
+            |ui, rows| {
+                for &address in &scan.matches()[rows] {
+                    ui.horizontal(|ui| {
+                        ui.monospace(format!("{address:#010x}"));
+                        if let Some(name) = describe_address(game_config, address) {
+                            ui.label(name);
+                        }
+                        if !type_name.is_empty() && ui.small_button("Open").clicked() {
+                            AddressWindow::open_as(ui.ctx(), address, type_name);
+                        }
+                        if ui.small_button("Hex").clicked() {
+                            AddressWindow::open(ui.ctx(), address, false);
+                        }
+                    });
+                }
+            },
+        );
+    }
+}
+
+/// Returns the address of the vtable of `type_name` from the `vtables` table of the game config.
+fn find_vtable(game_config: &toml::Table, type_name: &str) -> Option<u32> {
+    if type_name.is_empty() {
+        return None;
+    }
+    let vtables = game_config.get("vtables")?.as_table()?;
+    vtables
+        .iter()
+        .find(|(_, name)| name.as_str() == Some(type_name))
+        .and_then(|(address, _)| parse_address(address))
+}
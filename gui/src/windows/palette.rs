@@ -0,0 +1,194 @@
+use eframe::egui::{self, text::LayoutJob};
+
+use crate::{util::fuzzy::fuzzy_match, views::actor_type::parse_address};
+
+/// Maximum number of matches listed at once.
+const MAX_MATCHES: usize = 50;
+
+/// An action which can be run from the command palette.
+pub struct PaletteAction {
+    /// Identifies the action to whoever registered it
+    pub id: String,
+    pub label: String,
+    /// Set for actions which take an address, entered in the palette after choosing the action
+    pub address_hint: Option<&'static str>,
+}
+
+impl PaletteAction {
+    pub fn new(id: impl Into<String>, label: impl Into<String>) -> Self {
+        Self { id: id.into(), label: label.into(), address_hint: None }
+    }
+
+    pub fn with_address(mut self, hint: &'static str) -> Self {
+        self.address_hint = Some(hint);
+        self
+    }
+}
+
+/// An action chosen in the palette, see [`CommandPalette::render`].
+pub struct ChosenAction {
+    /// Index of the action in the list it was chosen from
+    pub index: usize,
+    pub action: PaletteAction,
+    pub address: Option<u32>,
+}
+
+/// An overlay which finds actions by fuzzy search and runs them with the keyboard. Opened with
+/// Ctrl+P by default, see [`crate::util::keybindings`].
+#[derive(Default)]
+pub struct CommandPalette {
+    open: bool,
+    query: String,
+    selected: usize,
+    /// Action waiting for its address, by index
+    pending: Option<usize>,
+    address: String,
+}
+
+impl CommandPalette {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.query.clear();
+        self.selected = 0;
+        self.pending = None;
+    }
+
+    /// Lists the actions while the palette is open. Returns the action to run.
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        actions: impl FnOnce() -> Vec<PaletteAction>,
+    ) -> Option<ChosenAction> {
+        if !self.open {
+            return None;
+        }
+
+        let mut actions = actions();
+        let mut result = None;
+        let response = egui::Modal::new(egui::Id::new("command_palette")).show(ctx, |ui| {
+            ui.set_width(400.0);
+            match self.pending {
+                Some(index) => result = self.render_address(ui, &actions[index], index),
+                None => result = self.render_search(ui, &actions),
+            }
+        });
+        if response.should_close() || result.is_some() {
+            self.open = false;
+        }
+        let (index, address) = result?;
+        Some(ChosenAction { index, action: actions.swap_remove(index), address })
+    }
+
+    fn render_search(
+        &mut self,
+        ui: &mut egui::Ui,
+        actions: &[PaletteAction],
+    ) -> Option<(usize, Option<u32>)> {
+        let mut matches: Vec<(usize, i32, Vec<usize>)> = actions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, action)| {
+                let (score, indices) = fuzzy_match(&self.query, &action.label)?;
+                Some((index, score, indices))
+            })
+            .collect();
+        matches.sort_by_key(|&(_, score, _)| std::cmp::Reverse(score));
+        matches.truncate(MAX_MATCHES);
+
+        // Take the navigation keys before the text box moves its cursor with them
+        let (up, down, enter) = ui.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+            )
+        });
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        if down {
+            self.selected += 1;
+        }
+        let response = egui::TextEdit::singleline(&mut self.query)
+            .desired_width(f32::INFINITY)
+            .hint_text("Search actions")
+            .show(ui)
+            .response;
+        response.request_focus();
+        if response.changed() {
+            self.selected = 0;
+        }
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+
+        let mut chosen = enter.then(|| matches.get(self.selected).map(|&(index, ..)| index));
+        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            if matches.is_empty() {
+                ui.weak("No matching actions");
+            }
+            for (row, (index, _, indices)) in matches.iter().enumerate() {
+                let selected = row == self.selected;
+                let label = highlight(ui, &actions[*index].label, indices);
+                let response = ui.add(egui::Button::selectable(selected, label));
+                if selected && (up || down) {
+                    response.scroll_to_me(None);
+                }
+                if response.clicked() {
+                    chosen = Some(Some(*index));
+                }
+            }
+        });
+
+        let index = chosen.flatten()?;
+        if actions[index].address_hint.is_some() {
+            self.pending = Some(index);
+            self.address.clear();
+            return None;
+        }
+        Some((index, None))
+    }
+
+    fn render_address(
+        &mut self,
+        ui: &mut egui::Ui,
+        action: &PaletteAction,
+        index: usize,
+    ) -> Option<(usize, Option<u32>)> {
+        ui.strong(&action.label);
+        let enter = ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter));
+        let response = egui::TextEdit::singleline(&mut self.address)
+            .desired_width(f32::INFINITY)
+            .hint_text(action.address_hint.unwrap_or("Address"))
+            .show(ui)
+            .response;
+        response.request_focus();
+        let text = self.address.trim();
+        let address = parse_address(text).or_else(|| u32::from_str_radix(text, 16).ok());
+        if address.is_none() && !text.is_empty() {
+            ui.colored_label(ui.visuals().error_fg_color, "Invalid address");
+        }
+        let address = address.filter(|_| enter)?;
+        Some((index, Some(address)))
+    }
+}
+
+/// Lays out `text` with the chars at `indices` highlighted.
+fn highlight(ui: &egui::Ui, text: &str, indices: &[usize]) -> LayoutJob {
+    let normal = egui::TextFormat {
+        font_id: egui::TextStyle::Button.resolve(ui.style()),
+        color: ui.visuals().text_color(),
+        ..Default::default()
+    };
+    let strong = ui.visuals().strong_text_color();
+    let matched = egui::TextFormat {
+        color: strong,
+        underline: egui::Stroke::new(1.0, strong),
+        ..normal.clone()
+    };
+    let mut job = LayoutJob::default();
+    let mut indices = indices.iter().peekable();
+    for (index, c) in text.chars().enumerate() {
+        let format = if indices.next_if_eq(&&index).is_some() { &matched } else { &normal };
+        job.append(c.encode_utf8(&mut [0; 4]), 0.0, format.clone());
+    }
+    job
+}
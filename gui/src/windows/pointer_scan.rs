@@ -0,0 +1,188 @@
+use dsv_core::{
+    memory::{
+        map::{MemoryMap, MemoryRegion},
+        scan::PointerScan,
+    },
+    state::State,
+};
+use eframe::egui::{self, Widget};
+
+use crate::{
+    util::symbols::describe_address,
+    views::{actor_type::parse_address, address::AddressWindow},
+};
+
+/// Searches memory for pointers to an address, see [`PointerScan`].
+#[derive(Default)]
+pub struct PointerScanWindow {
+    pub open: bool,
+    target_text: String,
+    tolerance: u32,
+    /// Index of the memory region to scan, among those which can hold objects
+    region_index: usize,
+    scan: Option<PointerScan>,
+}
+
+impl PointerScanWindow {
+    fn id() -> egui::Id {
+        egui::Id::new("find_pointers_to")
+    }
+
+    /// Opens the window with `target` filled in, from the header of another window.
+    pub fn find_pointers_to(ctx: &egui::Context, target: u32) {
+        ctx.data_mut(|data| data.insert_temp(Self::id(), target));
+    }
+
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        state: &mut State,
+        game_config: &toml::Table,
+        memory_map: &MemoryMap,
+    ) {
+        if let Some(target) = ctx.data_mut(|data| data.remove_temp::<u32>(Self::id())) {
+            self.open = true;
+            self.target_text = format!("{target:#010x}");
+            if let Some(scan) = &mut self.scan {
+                scan.cancel(state);
+            }
+            self.scan = None;
+        }
+
+        let mut open = self.open;
+        egui::Window::new("Find pointers").open(&mut open).resizable(true).show(ctx, |ui| {
+            let regions = scannable_regions(memory_map);
+            self.render_options(ui, state, &regions);
+            let Some(scan) = &mut self.scan else {
+                return;
+            };
+            if !scan.step(state) {
+                ctx.request_repaint();
+            }
+            ui.separator();
+            self.render_results(ui, game_config);
+        });
+        if !open && let Some(scan) = &mut self.scan {
+            scan.cancel(state);
+        }
+        self.open = open;
+    }
+
+    fn render_options(&mut self, ui: &mut egui::Ui, state: &mut State, regions: &[&MemoryRegion]) {
+        let running = self.scan.as_ref().is_some_and(|scan| !scan.is_done());
+        let target = parse_address(self.target_text.trim());
+        egui::Grid::new("pointer_scan_options").num_columns(2).show(ui, |ui| {
+            ui.label("Target");
+            let text_color = target.is_none().then(|| ui.visuals().error_fg_color);
+            egui::TextEdit::singleline(&mut self.target_text)
+                .desired_width(90.0)
+                .hint_text("0x02000000")
+                .text_color_opt(text_color)
+                .show(ui);
+            ui.end_row();
+
+            ui.label("Tolerance");
+            egui::DragValue::new(&mut self.tolerance)
+                .range(0..=0x1000)
+                .hexadecimal(1, false, false)
+                .prefix("±0x")
+                .ui(ui)
+                .on_hover_text("Also find pointers to within this many bytes of the target");
+            ui.end_row();
+
+            ui.label("Region");
+            region_selector(ui, "pointer_scan_region", regions, &mut self.region_index);
+            ui.end_row();
+        });
+
+        ui.horizontal(|ui| {
+            if running {
+                if ui.button("Cancel").clicked()
+                    && let Some(scan) = &mut self.scan
+                {
+                    scan.cancel(state);
+                }
+                return;
+            }
+            let range = regions.get(self.region_index).map(|region| &region.range);
+            if ui
+                .add_enabled(target.is_some() && range.is_some(), egui::Button::new("Scan"))
+                .clicked()
+                && let (Some(target), Some(range)) = (target, range)
+            {
+                self.scan = Some(PointerScan::new(target, self.tolerance, range.clone()));
+            }
+        });
+    }
+
+    fn render_results(&self, ui: &mut egui::Ui, game_config: &toml::Table) {
+        let Some(scan) = &self.scan else {
+            return;
+        };
+        if scan.is_done() {
+            ui.label(format!("{} pointers found", scan.matches().len()));
+        } else {
+            ui.add(egui::ProgressBar::new(scan.progress()).show_percentage());
+        }
+        if scan.matches().len() >= PointerScan::MAX_MATCHES {
+            ui.colored_label(egui::Color32::YELLOW, "Too many matches, stopped scanning");
+        }
+        if scan.failed_chunks() > 0 {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                format!("{} chunks could not be read", scan.failed_chunks()),
+            );
+        }
+
+        let row_height = ui.spacing().interact_size.y;
+        egui::ScrollArea::vertical().max_height(300.0).show_rows(
+            ui,
+            row_height,
+            scan.matches().len(),
+            |ui, rows| {
+                for found in &scan.matches()[rows] {
+                    ui.horizontal(|ui| {
+                        ui.monospace(format!("{:#010x}", found.address));
+                        if found.offset != 0 {
+                            let sign = if found.offset < 0 { '-' } else { '+' };
+                            ui.weak(format!("→ {sign}{:#x}", found.offset.unsigned_abs()));
+                        }
+                        if let Some(name) = describe_address(game_config, found.address) {
+                            ui.label(name);
+                        }
+                        if ui.small_button("Hex").clicked() {
+                            AddressWindow::open(ui.ctx(), found.address, false);
+                        }
+                        if ui.small_button("Typed").clicked() {
+                            AddressWindow::open(ui.ctx(), found.address, true);
+                        }
+                    });
+                }
+            },
+        );
+    }
+}
+
+/// Returns the memory regions which can hold objects, which are the ones worth scanning.
+pub fn scannable_regions(memory_map: &MemoryMap) -> Vec<&MemoryRegion> {
+    memory_map.regions().iter().filter(|region| region.access.read && !region.io).collect()
+}
+
+/// Chooses one of `regions` by index.
+pub fn region_selector(
+    ui: &mut egui::Ui,
+    id_salt: &str,
+    regions: &[&MemoryRegion],
+    region_index: &mut usize,
+) {
+    *region_index = (*region_index).min(regions.len().saturating_sub(1));
+    let region_text = |region: &MemoryRegion| {
+        format!("{} ({:#010x}..{:#010x})", region.name, region.range.start, region.range.end)
+    };
+    let selected = regions.get(*region_index).map(|r| region_text(r)).unwrap_or_default();
+    egui::ComboBox::new(id_salt, "").selected_text(selected).show_ui(ui, |ui| {
+        for (index, region) in regions.iter().enumerate() {
+            ui.selectable_value(region_index, index, region_text(region));
+        }
+    });
+}
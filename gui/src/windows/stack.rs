@@ -0,0 +1,96 @@
+use dsv_core::{disasm::Mode, memory::map::MemoryMap, state::State};
+use eframe::egui::{self, Widget};
+
+use crate::{
+    client::StoppedAt, util::symbols::describe_address, windows::disassembly::DisassemblyWindow,
+};
+
+const DEFAULT_WORDS: usize = 64;
+const MAX_WORDS: usize = 0x400;
+
+/// Dumps the words above the stack pointer of a paused target, to see which functions called
+/// into the current one. Words which point into code are candidates for return addresses.
+pub struct StackWindow {
+    pub open: bool,
+    words: usize,
+}
+
+impl Default for StackWindow {
+    fn default() -> Self {
+        Self { open: false, words: DEFAULT_WORDS }
+    }
+}
+
+impl StackWindow {
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        state: &mut State,
+        game_config: &toml::Table,
+        memory_map: &MemoryMap,
+        stopped_at: Option<StoppedAt>,
+    ) {
+        let mut open = self.open;
+        egui::Window::new("Stack").open(&mut open).resizable(true).show(ctx, |ui| {
+            let Some(stopped_at) = stopped_at else {
+                ui.weak("Pause the game to read the stack");
+                return;
+            };
+            let Some(sp) = stopped_at.sp else {
+                ui.label("The GDB stub doesn't report the stack pointer");
+                return;
+            };
+            ui.horizontal(|ui| {
+                ui.label(format!("SP: {sp:#010x}"));
+                if let Some(lr) = stopped_at.lr {
+                    ui.separator();
+                    ui.label("LR:");
+                    code_pointer(ui, game_config, memory_map, lr);
+                }
+                ui.separator();
+                egui::DragValue::new(&mut self.words).range(1..=MAX_WORDS).suffix(" words").ui(ui);
+            });
+            ui.separator();
+
+            let length = self.words * 4;
+            state.request(sp, length);
+            let Some(data) = state.get_data_range(sp, length) else {
+                ui.label("Data not found");
+                return;
+            };
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                egui::Grid::new("stack_words").num_columns(3).striped(true).show(ui, |ui| {
+                    for (index, bytes) in data.chunks_exact(4).enumerate() {
+                        let offset = index as u32 * 4;
+                        let value = u32::from_le_bytes(bytes.try_into().unwrap());
+                        ui.monospace(format!("sp+{offset:#05x}"))
+                            .on_hover_text(format!("{:#010x}", sp + offset));
+                        ui.monospace(format!("{value:08x}"));
+                        code_pointer(ui, game_config, memory_map, value);
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+        self.open = open;
+    }
+}
+
+/// Shows a value which may be a return address as a link to its disassembly, if it points into
+/// executable memory.
+fn code_pointer(ui: &mut egui::Ui, game_config: &toml::Table, memory_map: &MemoryMap, value: u32) {
+    let executable = memory_map.region_at(value).is_some_and(|region| region.access.execute);
+    if !executable {
+        ui.label("");
+        return;
+    }
+    let mode = Mode::of_code_pointer(value);
+    let address = value & !1;
+    let link = match describe_address(game_config, address) {
+        Some(name) => ui.link(name),
+        None => ui.link(egui::RichText::new(format!("{value:#010x}")).weak()),
+    };
+    if link.on_hover_text("Disassemble").clicked() {
+        DisassemblyWindow::disassemble(ui.ctx(), address, mode);
+    }
+}
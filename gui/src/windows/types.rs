@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+
+use eframe::egui;
+
+use crate::ui::type_decl::render_type_definition;
+
+/// Lists the loaded types by name and shows the definition of the selected one.
+#[derive(Default)]
+pub struct TypesWindow {
+    pub open: bool,
+    search: String,
+    selected: Option<String>,
+}
+
+impl TypesWindow {
+    /// Returns a header which the user chose to reload.
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        types: &type_crawler::Types,
+        type_names: &[String],
+        project_root: &str,
+    ) -> Option<PathBuf> {
+        let mut reload = None;
+        let mut open = self.open;
+        egui::Window::new("Types").open(&mut open).resizable(true).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                egui::TextEdit::singleline(&mut self.search)
+                    .desired_width(200.0)
+                    .hint_text("Search")
+                    .show(ui);
+                ui.label(format!("{} types loaded", types.len()));
+                if ui
+                    .button("Reload file...")
+                    .on_hover_text("Parse one header again after editing it")
+                    .clicked()
+                {
+                    reload = rfd::FileDialog::new()
+                        .add_filter("Header", &["h", "hpp"])
+                        .set_directory(project_root)
+                        .pick_file();
+                }
+            });
+            let search = self.search.to_ascii_lowercase();
+            let matches: Vec<&String> = type_names
+                .iter()
+                .filter(|name| name.to_ascii_lowercase().contains(&search))
+                .collect();
+
+            ui.columns(2, |columns| {
+                columns[0].weak(format!("{} names", matches.len()));
+                egui::ScrollArea::vertical().id_salt("type_names").show_rows(
+                    &mut columns[0],
+                    18.0,
+                    matches.len(),
+                    |ui, rows| {
+                        for &name in &matches[rows] {
+                            let selected = self.selected.as_ref() == Some(name);
+                            if ui.selectable_label(selected, name).clicked() {
+                                self.selected = Some(name.clone());
+                            }
+                        }
+                    },
+                );
+
+                let ui = &mut columns[1];
+                let Some(name) = &self.selected else {
+                    ui.weak("Select a type to show its definition");
+                    return;
+                };
+                ui.heading(name);
+                let Some(ty) = types.get(name) else {
+                    ui.label("Type not found, it may have been unloaded");
+                    return;
+                };
+                egui::ScrollArea::vertical().id_salt("type_definition").show(ui, |ui| {
+                    render_type_definition(ui, types, ty);
+                });
+            });
+        });
+        self.open = open;
+        reload
+    }
+}